@@ -0,0 +1,246 @@
+use crossterm::event::KeyCode;
+
+use crate::topology::topology_item::TopologyItem;
+
+mod router;
+pub use router::route;
+
+/// Volume change applied by a single `+`/`-` key press
+pub const VOLUME_STEP: i8 = 5;
+
+/// Speaker action triggered from the control view
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Toggle between playing and paused on a group coordinator
+    PlayPause { coordinator_ip: String },
+    /// Raise or lower the volume of one speaker by `delta`
+    VolumeAdjust { ip: String, delta: i8 },
+    /// Raise or lower the volume of a whole group through its coordinator
+    GroupVolumeAdjust { coordinator_ip: String, delta: i8 },
+    /// Mute the speaker if it is unmuted and vice versa
+    ToggleMute { ip: String, muted: bool },
+    /// Join each `(ip, uuid)` member to the group led by the `coordinator` UUID
+    GroupSpeakers {
+        coordinator: String,
+        members: Vec<(String, String)>,
+    },
+}
+
+impl Command {
+    /// Map a key press on the selected item to a command, if the key is bound
+    pub fn from_key(key: KeyCode, item: &TopologyItem) -> Option<Self> {
+        match key {
+            KeyCode::Char(' ') | KeyCode::Char('p') => Self::play_pause(item),
+            KeyCode::Char('+') | KeyCode::Char('=') => Self::volume_adjust(item, VOLUME_STEP),
+            KeyCode::Char('-') => Self::volume_adjust(item, -VOLUME_STEP),
+            KeyCode::Char('m') => match item {
+                TopologyItem::Speaker { ip, muted, .. } => Some(Command::ToggleMute {
+                    ip: ip.clone(),
+                    muted: *muted,
+                }),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Group the marked speakers under the first one; needs at least two speakers
+    pub fn group_from_selection(items: &[TopologyItem]) -> Option<Self> {
+        let mut speakers = items.iter().filter_map(|item| match item {
+            TopologyItem::Speaker { ip, uuid, .. } => Some((ip.clone(), uuid.clone())),
+            _ => None,
+        });
+
+        let (_, coordinator) = speakers.next()?;
+        let members: Vec<(String, String)> = speakers.collect();
+        if members.is_empty() {
+            return None;
+        }
+
+        Some(Command::GroupSpeakers { coordinator, members })
+    }
+
+    fn play_pause(item: &TopologyItem) -> Option<Self> {
+        // Playback belongs to the group, so members are routed to their coordinator
+        match item {
+            TopologyItem::Group { ip, .. } => Some(Command::PlayPause {
+                coordinator_ip: ip.clone(),
+            }),
+            TopologyItem::Speaker { coordinator_ip, .. } => Some(Command::PlayPause {
+                coordinator_ip: coordinator_ip.clone(),
+            }),
+            TopologyItem::Satellite { .. } => None,
+        }
+    }
+
+    fn volume_adjust(item: &TopologyItem, delta: i8) -> Option<Self> {
+        // A group row changes the group volume, a speaker row the speaker alone
+        match item {
+            TopologyItem::Group { ip, .. } => Some(Command::GroupVolumeAdjust {
+                coordinator_ip: ip.clone(),
+                delta,
+            }),
+            TopologyItem::Speaker { ip, .. } => Some(Command::VolumeAdjust {
+                ip: ip.clone(),
+                delta,
+            }),
+            TopologyItem::Satellite { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sonos::PlayState;
+
+    use super::*;
+
+    fn group_item() -> TopologyItem {
+        TopologyItem::Group {
+            ip: "192.168.1.100".to_string(),
+            name: "Living Room".to_string(),
+            uuid: "RINCON_LIVING_ROOM_001".to_string(),
+            children: vec![("192.168.1.101".to_string(), "RINCON_KITCHEN_001".to_string())],
+            is_last: false,
+            play_state: PlayState::Paused,
+            volume: Some(20),
+            children_count: 1,
+        }
+    }
+
+    fn speaker_item() -> TopologyItem {
+        TopologyItem::Speaker {
+            ip: "192.168.1.101".to_string(),
+            coordinator_ip: "192.168.1.100".to_string(),
+            group_uuid: "GROUP:RINCON_LIVING_ROOM_001".to_string(),
+            uuid: "RINCON_KITCHEN_001".to_string(),
+            name: "Kitchen".to_string(),
+            model: None,
+            is_last: true,
+            volume: Some(15),
+            muted: true,
+        }
+    }
+
+    #[test]
+    fn test_space_on_group_toggles_its_playback() {
+        assert_eq!(
+            Command::from_key(KeyCode::Char(' '), &group_item()),
+            Some(Command::PlayPause {
+                coordinator_ip: "192.168.1.100".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_space_on_speaker_targets_its_coordinator() {
+        assert_eq!(
+            Command::from_key(KeyCode::Char(' '), &speaker_item()),
+            Some(Command::PlayPause {
+                coordinator_ip: "192.168.1.100".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_space_on_satellite_emits_nothing() {
+        let satellite = TopologyItem::Satellite {
+            uuid: "RINCON_SAT_001".to_string(),
+            is_last: true,
+        };
+
+        assert_eq!(Command::from_key(KeyCode::Char(' '), &satellite), None);
+    }
+
+    #[test]
+    fn test_unbound_key_emits_nothing() {
+        assert_eq!(Command::from_key(KeyCode::Char('x'), &group_item()), None);
+    }
+
+    #[test]
+    fn test_volume_keys_on_group_adjust_the_group() {
+        assert_eq!(
+            Command::from_key(KeyCode::Char('+'), &group_item()),
+            Some(Command::GroupVolumeAdjust {
+                coordinator_ip: "192.168.1.100".to_string(),
+                delta: VOLUME_STEP,
+            })
+        );
+        assert_eq!(
+            Command::from_key(KeyCode::Char('-'), &group_item()),
+            Some(Command::GroupVolumeAdjust {
+                coordinator_ip: "192.168.1.100".to_string(),
+                delta: -VOLUME_STEP,
+            })
+        );
+    }
+
+    #[test]
+    fn test_volume_keys_on_speaker_adjust_only_that_speaker() {
+        assert_eq!(
+            Command::from_key(KeyCode::Char('+'), &speaker_item()),
+            Some(Command::VolumeAdjust {
+                ip: "192.168.1.101".to_string(),
+                delta: 5,
+            })
+        );
+    }
+
+    #[test]
+    fn test_m_toggles_mute_on_speakers_only() {
+        assert_eq!(
+            Command::from_key(KeyCode::Char('m'), &speaker_item()),
+            Some(Command::ToggleMute {
+                ip: "192.168.1.101".to_string(),
+                muted: true,
+            })
+        );
+        assert_eq!(Command::from_key(KeyCode::Char('m'), &group_item()), None);
+    }
+
+    #[test]
+    fn test_group_from_selection_uses_first_speaker_as_coordinator() {
+        let bedroom = TopologyItem::Speaker {
+            ip: "192.168.1.102".to_string(),
+            coordinator_ip: "192.168.1.102".to_string(),
+            group_uuid: "GROUP:RINCON_BEDROOM_001".to_string(),
+            uuid: "RINCON_BEDROOM_001".to_string(),
+            name: "Bedroom".to_string(),
+            model: None,
+            is_last: true,
+            volume: None,
+            muted: false,
+        };
+        let office = TopologyItem::Speaker {
+            ip: "192.168.1.103".to_string(),
+            coordinator_ip: "192.168.1.103".to_string(),
+            group_uuid: "GROUP:RINCON_OFFICE_001".to_string(),
+            uuid: "RINCON_OFFICE_001".to_string(),
+            name: "Office".to_string(),
+            model: None,
+            is_last: true,
+            volume: None,
+            muted: false,
+        };
+
+        // Group rows in the selection are skipped
+        let selection = vec![group_item(), speaker_item(), bedroom, office];
+
+        assert_eq!(
+            Command::group_from_selection(&selection),
+            Some(Command::GroupSpeakers {
+                coordinator: "RINCON_KITCHEN_001".to_string(),
+                members: vec![
+                    ("192.168.1.102".to_string(), "RINCON_BEDROOM_001".to_string()),
+                    ("192.168.1.103".to_string(), "RINCON_OFFICE_001".to_string()),
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_group_from_selection_needs_two_speakers() {
+        assert_eq!(Command::group_from_selection(&[speaker_item()]), None);
+        assert_eq!(Command::group_from_selection(&[group_item(), speaker_item()]), None);
+    }
+}
@@ -0,0 +1,41 @@
+use sonos::{SonosError, SpeakerController};
+
+use super::Command;
+
+/**
+ * Execute a command against the speakers on the network
+ */
+pub fn route(command: Command) -> Result<(), SonosError> {
+    log::debug!("Routing command: {:?}", command);
+    let controller = SpeakerController::new();
+
+    match command {
+        Command::PlayPause { coordinator_ip } => controller.toggle_play_state(&coordinator_ip),
+        // The list picks up the new level on the next playback poll
+        Command::VolumeAdjust { ip, delta } => controller.adjust_volume(&ip, delta).map(|_| ()),
+        Command::GroupVolumeAdjust { coordinator_ip, delta } => {
+            controller.adjust_group_volume(&coordinator_ip, delta).map(|_| ())
+        }
+        // As with volume, the glyph follows the next playback poll
+        Command::ToggleMute { ip, muted } => controller.set_mute(&ip, !muted),
+        Command::GroupSpeakers { coordinator, members } => {
+            // Keep going after a failure so one offline speaker doesn't block the rest
+            let failed: Vec<String> = members
+                .into_iter()
+                .filter_map(|(ip, uuid)| match controller.join_group(&ip, &coordinator) {
+                    Ok(()) => None,
+                    Err(e) => {
+                        log::debug!("Failed to join {} to {}: {}", uuid, coordinator, e);
+                        Some(uuid)
+                    }
+                })
+                .collect();
+
+            if failed.is_empty() {
+                Ok(())
+            } else {
+                Err(SonosError::GroupJoinFailed(failed))
+            }
+        }
+    }
+}
@@ -1,3 +1,6 @@
+mod use_playback_events;
+pub use use_playback_events::use_playback_events;
+
 mod use_speakers;
 pub use use_speakers::use_speakers;
 
@@ -0,0 +1,265 @@
+use std::sync::Arc;
+
+use sonos_stream::model::TrackInfo;
+use sonos_stream::{ActiveEventStream, EventStreamBuilder, ServiceType, Speaker, SpeakerId, StateChange};
+
+use crate::state::reducers::{normalize_uuid, AppAction};
+use crate::state::store::{AppState, Store, Track};
+use crate::topology::topology_item::TopologyItem;
+
+/// Port every Sonos speaker serves UPnP on
+const SONOS_PORT: u16 = 1400;
+
+/**
+ * Event hook to stream playback, volume and mute changes into the store.
+ *
+ * Subscribes to AVTransport and RenderingControl on every speaker in the
+ * topology already in the store, writes each `StateChange` into the store and
+ * calls `redraw_fn`. `configure` can adjust the builder before it starts.
+ * Start it whenever discovery replaces the topology and shut the returned
+ * stream down on exit. Returns None when there is nothing to subscribe to or
+ * the stream could not start.
+ */
+pub fn use_playback_events(
+    store: &Arc<Store>,
+    redraw_fn: impl Fn() + Send + Sync + 'static,
+    configure: impl FnOnce(EventStreamBuilder) -> EventStreamBuilder,
+) -> Option<ActiveEventStream> {
+    let speakers = store.with_state(|state| {
+        state.topology.as_ref().map_or_else(Vec::new, |topology| {
+            topology.items.iter().filter_map(to_speaker).collect::<Vec<_>>()
+        })
+    });
+    if speakers.is_empty() {
+        log::debug!("No speakers in the topology, not starting the event stream");
+        return None;
+    }
+
+    let handler_store = Arc::clone(store);
+    let stream = EventStreamBuilder::new(speakers).and_then(|builder| {
+        let builder = builder
+            .with_services(&[ServiceType::AVTransport, ServiceType::RenderingControl])
+            .with_event_handler(move |change| {
+                if apply_state_change(&handler_store, change) {
+                    redraw_fn();
+                }
+            });
+        configure(builder).start()
+    });
+
+    match stream {
+        Ok(stream) => Some(stream),
+        Err(e) => {
+            log::debug!("Failed to start event stream: {}", e);
+            None
+        }
+    }
+}
+
+/**
+ * Write a single state change into the store, returning whether anything changed
+ */
+pub fn apply_state_change(store: &Store, change: StateChange) -> bool {
+    match change {
+        StateChange::VolumeChanged { speaker_id, volume } => {
+            let uuid = normalize_uuid(speaker_id.as_str());
+            store.dispatch(AppAction::UpdateSpeakerVolume(uuid.clone(), volume));
+
+            // RenderingControl doesn't event group volume, so follow it from the members
+            if let Some((group_uuid, group_volume)) = store.with_state(|state| group_volume(state, &uuid)) {
+                store.dispatch(AppAction::UpdateSpeakerVolume(group_uuid, group_volume));
+            }
+            true
+        }
+        StateChange::MuteChanged { speaker_id, muted } => {
+            store.dispatch(AppAction::UpdateSpeakerMute(normalize_uuid(speaker_id.as_str()), muted));
+            true
+        }
+        StateChange::TrackChanged { speaker_id, track_info } => {
+            store.dispatch(AppAction::UpdateTrack(
+                speaker_id.as_str().to_string(),
+                track_info.and_then(to_track),
+            ));
+            true
+        }
+        _ => false,
+    }
+}
+
+/**
+ * Average volume of the group `uuid` belongs to, as Sonos reports group volume
+ */
+fn group_volume(state: &AppState, uuid: &str) -> Option<(String, u8)> {
+    let items = &state.topology.as_ref()?.items;
+    let group_uuid = items.iter().find_map(|item| match item {
+        TopologyItem::Speaker { uuid: speaker_uuid, group_uuid, .. } if speaker_uuid == uuid => {
+            Some(group_uuid.clone())
+        }
+        _ => None,
+    })?;
+
+    let volumes: Vec<u32> = items
+        .iter()
+        .filter_map(|item| match item {
+            TopologyItem::Speaker { group_uuid: member_group, volume, .. } if *member_group == group_uuid => {
+                volume.map(u32::from)
+            }
+            _ => None,
+        })
+        .collect();
+    if volumes.is_empty() {
+        return None;
+    }
+
+    let average = volumes.iter().sum::<u32>() / volumes.len() as u32;
+    Some((group_uuid, average as u8))
+}
+
+fn to_speaker(item: &TopologyItem) -> Option<Speaker> {
+    match item {
+        TopologyItem::Speaker { ip, uuid, name, model, .. } => Some(Speaker {
+            id: SpeakerId::new(uuid.as_str()),
+            name: name.clone(),
+            room_name: name.clone(),
+            ip_address: ip.clone(),
+            port: SONOS_PORT,
+            model_name: model.clone().unwrap_or_default(),
+            satellites: Vec::new(),
+            supported_services: Vec::new(),
+            software_version: None,
+            hardware_version: None,
+        }),
+        _ => None,
+    }
+}
+
+fn to_track(track_info: TrackInfo) -> Option<Track> {
+    Some(Track {
+        title: track_info.title?,
+        artist: track_info.artist,
+        album: track_info.album,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use sonos_stream::model::TrackSourceKind;
+
+    use super::*;
+    use crate::topology::topology_list::TopologyList;
+
+    fn speaker_item(uuid: &str, group_uuid: &str, volume: u8) -> TopologyItem {
+        TopologyItem::Speaker {
+            ip: "192.168.1.101".to_string(),
+            coordinator_ip: "192.168.1.101".to_string(),
+            group_uuid: group_uuid.to_string(),
+            uuid: uuid.to_string(),
+            name: uuid.to_string(),
+            model: None,
+            is_last: true,
+            volume: Some(volume),
+            muted: false,
+        }
+    }
+
+    fn store_with_kitchen() -> Store {
+        let store = Store::new();
+        store.dispatch(AppAction::UpdateTopology(TopologyList::from_items(vec![speaker_item(
+            "RINCON_KITCHEN_001",
+            "GROUP:RINCON_KITCHEN_001",
+            10,
+        )])));
+        store
+    }
+
+    #[test]
+    fn test_volume_and_mute_events_update_the_speaker() {
+        let store = store_with_kitchen();
+        let speaker_id = SpeakerId::new("uuid:RINCON_KITCHEN_001::1");
+
+        assert!(apply_state_change(&store, StateChange::VolumeChanged { speaker_id: speaker_id.clone(), volume: 42 }));
+        assert!(apply_state_change(&store, StateChange::MuteChanged { speaker_id, muted: true }));
+
+        store.with_state(|state| match &state.topology.as_ref().unwrap().items[0] {
+            TopologyItem::Speaker { volume, muted, .. } => {
+                assert_eq!(*volume, Some(42));
+                assert!(*muted);
+            }
+            other => panic!("Expected speaker, got {:?}", other),
+        });
+    }
+
+    #[test]
+    fn test_member_volume_event_updates_the_group_volume() {
+        let store = Store::new();
+        store.dispatch(AppAction::UpdateTopology(TopologyList::from_items(vec![
+            TopologyItem::Group {
+                ip: "192.168.1.101".to_string(),
+                name: "Kitchen".to_string(),
+                uuid: "GROUP:RINCON_KITCHEN_001".to_string(),
+                children: Vec::new(),
+                is_last: true,
+                play_state: sonos::PlayState::Stopped,
+                volume: Some(20),
+                children_count: 2,
+            },
+            speaker_item("RINCON_KITCHEN_001", "GROUP:RINCON_KITCHEN_001", 20),
+            speaker_item("RINCON_DINING_001", "GROUP:RINCON_KITCHEN_001", 20),
+        ])));
+
+        apply_state_change(
+            &store,
+            StateChange::VolumeChanged { speaker_id: SpeakerId::new("RINCON_DINING_001"), volume: 40 },
+        );
+
+        store.with_state(|state| match &state.topology.as_ref().unwrap().items[0] {
+            TopologyItem::Group { volume, .. } => assert_eq!(*volume, Some(30)),
+            other => panic!("Expected group, got {:?}", other),
+        });
+    }
+
+    #[test]
+    fn test_track_event_sets_now_playing_for_the_group() {
+        let store = store_with_kitchen();
+        let track_info = TrackInfo {
+            title: Some("Borderline".to_string()),
+            artist: Some("Tame Impala".to_string()),
+            album: None,
+            duration_ms: None,
+            uri: None,
+            album_art_uri: None,
+            source_kind: TrackSourceKind::Library,
+            duration: None,
+        };
+
+        apply_state_change(
+            &store,
+            StateChange::TrackChanged {
+                speaker_id: SpeakerId::new("uuid:RINCON_KITCHEN_001::1"),
+                track_info: Some(track_info),
+            },
+        );
+
+        store.with_state(|state| {
+            let item = &state.topology.as_ref().unwrap().items[0];
+            assert_eq!(state.track_for(item).map(|track| track.title.as_str()), Some("Borderline"));
+        });
+    }
+
+    #[test]
+    fn test_other_changes_do_not_redraw() {
+        let store = store_with_kitchen();
+
+        assert!(!apply_state_change(
+            &store,
+            StateChange::CrossfadeChanged { speaker_id: SpeakerId::new("RINCON_KITCHEN_001"), crossfade: true },
+        ));
+    }
+
+    #[test]
+    fn test_no_stream_without_topology() {
+        let store = Arc::new(Store::new());
+
+        assert!(use_playback_events(&store, || {}, |builder| builder).is_none());
+    }
+}
@@ -18,7 +18,13 @@ pub fn use_speakers(
         render_callback().ok();
     }
 
-    store.dispatch(AppAction::SetControlView);
+    // Stay on the startup view so it can report that nothing was found
+    let found_speakers = store.with_state(|state| {
+        state.topology.as_ref().map_or(false, |topology| !topology.items.is_empty())
+    });
+    if found_speakers {
+        store.dispatch(AppAction::SetControlView);
+    }
 
     Ok(())
 }
@@ -1,29 +1,59 @@
 use std::io;
+use std::sync::Arc;
+use std::thread;
 
-use sonos::discover_topology;
+use sonos::{discover_topology, SonosError};
 
+use crate::hooks::use_speakers;
+use crate::state::cache;
 use crate::state::reducers::AppAction;
 use crate::state::store::Store;
 use crate::topology::topology_list::TopologyList;
 
 /**
  * Event hook to return the topology
+ *
+ * With a usable cache from the last run, renders it straight away and returns
+ * `true`: discovery then runs on a background thread, which updates the store
+ * and calls `redraw_fn` as the fresh topology and speakers come in. Otherwise
+ * discovers the topology before returning `false`.
  */
 pub fn use_topology(
-  store: &Store,
+  store: &Arc<Store>,
   mut render_callback: impl FnMut() -> io::Result<()>,
-) -> io::Result<()> {
-  match discover_topology() {
-    Ok(sonos_topology) => {
-      let topology_list = TopologyList::new(sonos_topology);
-      store.dispatch(AppAction::UpdateTopology(topology_list));
-    }
-    Err(e) => {
-      eprintln!("Failed to discover topology: {}", e);
-    }
+  redraw_fn: impl Fn() + Send + 'static,
+) -> io::Result<bool> {
+  if let Some(cached_topology) = cache::load_topology() {
+    store.dispatch(AppAction::UpdateTopology(TopologyList::new(cached_topology)));
+    store.dispatch(AppAction::SetControlView);
+    render_callback().ok();
+
+    let store = Arc::clone(store);
+    thread::spawn(move || {
+      // Printing would garble the screen the cached topology is already on
+      if let Err(e) = refresh_topology(&store) {
+        log::debug!("Failed to refresh topology: {}", e);
+      }
+      redraw_fn();
+      use_speakers(&store, || {
+        redraw_fn();
+        Ok(())
+      })
+      .ok();
+    });
+    return Ok(true);
   }
 
+  if let Err(e) = refresh_topology(store) {
+    eprintln!("Failed to discover topology: {}", e);
+  }
   render_callback().ok();
 
+  Ok(false)
+}
+
+fn refresh_topology(store: &Store) -> Result<(), SonosError> {
+  let sonos_topology = discover_topology()?;
+  store.dispatch(AppAction::UpdateTopology(TopologyList::new(sonos_topology)));
   Ok(())
 }
@@ -1,3 +1,4 @@
+mod commands;
 mod hooks;
 mod state;
 mod topology;
@@ -9,11 +10,16 @@ use ratatui::DefaultTerminal;
 use simplelog::*;
 use std::fs::File;
 use std::io;
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
 
+use sonos_stream::{ActiveEventStream, EventStreamBuilder};
+
+use crate::state::cache;
+use crate::state::reducers::AppAction;
 use crate::state::store::Store;
 
-use crate::hooks::{use_speakers, use_topology};
+use crate::hooks::{use_playback_events, use_speakers, use_topology};
 use crate::views::{control::ControlView, startup::StartupView, View, ViewType};
 
 pub struct App {
@@ -21,17 +27,28 @@ pub struct App {
     exit: bool,
     current_view: Box<dyn View>,
     current_view_type: ViewType,
+    // Background work asks the run loop to redraw through this channel
+    redraw_tx: mpsc::Sender<()>,
+    redraw_rx: mpsc::Receiver<()>,
+    playback_events: Option<ActiveEventStream>,
+    // Topology version the playback stream was started for
+    playback_topology_version: u64,
 }
 
 impl App {
     pub fn new() -> io::Result<Self> {
         let store = Arc::new(Store::new());
+        let (redraw_tx, redraw_rx) = mpsc::channel();
 
         Ok(Self {
             store: store.clone(),
             current_view: Box::new(StartupView::new(store.clone())),
             current_view_type: ViewType::Startup,
             exit: false,
+            redraw_tx,
+            redraw_rx,
+            playback_events: None,
+            playback_topology_version: 0,
         })
     }
 
@@ -54,33 +71,112 @@ impl App {
         }
     }
 
-    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
-      use_topology(&self.store, || {
-        terminal
-          .draw(|frame| self.current_view.render(frame))
-          .map(|_| ())
-      })?;
+    fn discover(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+      self.store.dispatch(AppAction::StartDiscovery);
+
+      let redraw_tx = self.redraw_tx.clone();
+      let refreshing_in_background = use_topology(
+        &self.store,
+        || {
+          terminal
+            .draw(|frame| self.current_view.render(frame))
+            .map(|_| ())
+        },
+        move || {
+          let _ = redraw_tx.send(());
+        },
+      )?;
+      if refreshing_in_background {
+        return Ok(());
+      }
 
       use_speakers(&self.store, || {
         terminal
           .draw(|frame| self.current_view.render(frame))
           .map(|_| ())
-      })?;
+      })
+    }
+
+    /// Restart the playback event stream if discovery replaced the topology since it started
+    ///
+    /// Covers retried discovery and background refreshes of a cached topology,
+    /// which can add speakers or move them to new addresses.
+    fn sync_playback_events(&mut self, configure: impl FnOnce(EventStreamBuilder) -> EventStreamBuilder) {
+        let topology_version = self.store.with_state(|state| state.topology_version);
+        if topology_version == self.playback_topology_version {
+            return;
+        }
+        self.playback_topology_version = topology_version;
+        self.stop_playback_events();
+
+        let redraw_tx = self.redraw_tx.clone();
+        self.playback_events = use_playback_events(
+            &self.store,
+            move || {
+                let _ = redraw_tx.send(());
+            },
+            configure,
+        );
+    }
 
+    fn stop_playback_events(&mut self) {
+        if let Some(stream) = self.playback_events.take() {
+            if let Err(e) = stream.shutdown() {
+                log::debug!("Failed to shut down event stream: {}", e);
+            }
+        }
+    }
+
+    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+      self.discover(terminal)?;
+
+      let mut redraw = true;
       while !self.exit {
-          self.update_current_view();
-          terminal.draw(|frame| self.current_view.render(frame))?;
+          if self.store.with_state(|state| state.discovery_requested) {
+              self.discover(terminal)?;
+              redraw = true;
+          }
+          self.sync_playback_events(|builder| builder);
+
+          // The startup view counts down its discovery timeout, so it redraws every tick
+          let playback_changed = self.redraw_rx.try_iter().count() > 0;
+          if redraw || playback_changed || self.current_view_type == ViewType::Startup {
+              self.update_current_view();
+              terminal.draw(|frame| self.current_view.render(frame))?;
+              redraw = false;
+          }
+
+          // Poll so speaker changes land on screen without waiting for a key
+          if !event::poll(Duration::from_millis(100))? {
+              continue;
+          }
 
           if let event::Event::Key(key_event) = event::read()? {
               self.handle_input(key_event)?;
+              redraw = true;
           }
       }
+
+      self.stop_playback_events();
+      self.save_topology();
       Ok(())
     }
 
+    /// Cache the topology so the next launch can render it before discovery finishes
+    fn save_topology(&self) {
+        let topology = self.store.with_state(|state| {
+            state.topology.as_ref().and_then(|list| list.topology().cloned())
+        });
+        if let Some(topology) = topology {
+            if let Err(e) = cache::save_topology(&topology) {
+                log::debug!("Failed to cache topology: {}", e);
+            }
+        }
+    }
+
     fn handle_input(&mut self, key_event: KeyEvent) -> io::Result<()> {
         match key_event.code {
-            KeyCode::Char('q') => {
+            KeyCode::Char('q') if !self.current_view.is_capturing_input() => {
                 self.exit = true;
                 return Ok(());
             }
@@ -104,3 +200,75 @@ fn main() -> io::Result<()> {
     ratatui::restore();
     app_result
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use sonos_stream::mock::MockSubscription;
+    use sonos_stream::{ServiceType, SpeakerId};
+
+    use super::*;
+    use crate::topology::topology_item::TopologyItem;
+    use crate::topology::topology_list::TopologyList;
+
+    fn kitchen_topology(ip: &str) -> TopologyList {
+        TopologyList::from_items(vec![TopologyItem::Speaker {
+            ip: ip.to_string(),
+            coordinator_ip: ip.to_string(),
+            group_uuid: "GROUP:RINCON_KITCHEN_001".to_string(),
+            uuid: "RINCON_KITCHEN_001".to_string(),
+            name: "Kitchen".to_string(),
+            model: None,
+            is_last: true,
+            volume: Some(10),
+            muted: false,
+        }])
+    }
+
+    fn mock_subscriptions(builder: EventStreamBuilder) -> EventStreamBuilder {
+        let template = MockSubscription::new(ServiceType::AVTransport, SpeakerId::new("RINCON_KITCHEN_001"));
+        builder
+            .with_callback_bind_address(IpAddr::V4(Ipv4Addr::LOCALHOST))
+            .with_subscription_factory(MockSubscription::factory(template))
+    }
+
+    fn subscribed_ips(app: &App) -> Vec<String> {
+        let stream = app.playback_events.as_ref().unwrap();
+        stream.speakers().iter().map(|speaker| speaker.ip_address.clone()).collect()
+    }
+
+    #[test]
+    fn test_retry_after_empty_discovery_starts_playback_events() {
+        let mut app = App::new().unwrap();
+
+        // The first discovery found nothing, so there is nothing to stream
+        app.store.dispatch(AppAction::StartDiscovery);
+        app.sync_playback_events(mock_subscriptions);
+        assert!(app.playback_events.is_none());
+
+        // Retrying finds the kitchen
+        app.store.dispatch(AppAction::RequestDiscovery);
+        app.store.dispatch(AppAction::StartDiscovery);
+        app.store.dispatch(AppAction::UpdateTopology(kitchen_topology("192.168.1.101")));
+        app.sync_playback_events(mock_subscriptions);
+
+        assert!(app.playback_events.is_some());
+        app.stop_playback_events();
+    }
+
+    #[test]
+    fn test_refreshed_topology_restarts_playback_events() {
+        let mut app = App::new().unwrap();
+        app.store.dispatch(AppAction::UpdateTopology(kitchen_topology("192.168.1.101")));
+        app.sync_playback_events(mock_subscriptions);
+        assert_eq!(subscribed_ips(&app), ["192.168.1.101"]);
+
+        // A background refresh found the kitchen at a new address
+        app.store.dispatch(AppAction::UpdateTopology(kitchen_topology("192.168.1.150")));
+        app.sync_playback_events(mock_subscriptions);
+
+        assert_eq!(subscribed_ips(&app), ["192.168.1.150"]);
+        app.stop_playback_events();
+    }
+}
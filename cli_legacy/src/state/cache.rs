@@ -0,0 +1,140 @@
+use std::fs;
+use std::io;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use sonos::Topology;
+
+/// How long each cached speaker gets to accept a connection before the cache is ignored
+const REACHABLE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// `~/.cache/sonos-cli/topology.json`, or None when there is no home directory
+pub fn cache_path() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME")?;
+    Some(PathBuf::from(home).join(".cache").join("sonos-cli").join("topology.json"))
+}
+
+/// Write the last discovered topology so the next launch can render it straight away
+pub fn save_topology(topology: &Topology) -> io::Result<()> {
+    match cache_path() {
+        Some(path) => save_topology_to(&path, topology),
+        None => Ok(()),
+    }
+}
+
+/// Read the cached topology, if every speaker in it still answers
+pub fn load_topology() -> Option<Topology> {
+    load_topology_from(&cache_path()?, REACHABLE_TIMEOUT)
+}
+
+pub fn save_topology_to(path: &Path, topology: &Topology) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string(topology).map_err(io::Error::other)?;
+    fs::write(path, json)
+}
+
+pub fn load_topology_from(path: &Path, timeout: Duration) -> Option<Topology> {
+    let json = fs::read_to_string(path).ok()?;
+    let topology: Topology = match serde_json::from_str(&json) {
+        Ok(topology) => topology,
+        Err(e) => {
+            log::debug!("Ignoring unreadable topology cache {}: {}", path.display(), e);
+            return None;
+        }
+    };
+
+    if topology.get_groups().is_empty() || !all_speakers_reachable(&topology, timeout) {
+        log::debug!("Ignoring stale topology cache {}", path.display());
+        return None;
+    }
+
+    Some(topology)
+}
+
+fn all_speakers_reachable(topology: &Topology, timeout: Duration) -> bool {
+    topology
+        .get_groups()
+        .iter()
+        .flat_map(|group| group.get_speakers())
+        .all(|member| is_reachable(member.get_location(), timeout))
+}
+
+/// Whether the host in a device description URL accepts a TCP connection
+fn is_reachable(location: &str, timeout: Duration) -> bool {
+    let without_scheme = location.split_once("://").map_or(location, |(_, rest)| rest);
+    let host = without_scheme.split('/').next().unwrap_or(without_scheme);
+    host.to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .map_or(false, |addr| TcpStream::connect_timeout(&addr, timeout).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use sonos::{ZoneGroup, ZoneGroupMember};
+
+    use super::*;
+
+    fn topology_at(port: u16) -> Topology {
+        Topology {
+            zone_groups: vec![ZoneGroup {
+                coordinator: "RINCON_KITCHEN_001".to_string(),
+                id: "RINCON_KITCHEN_001:1".to_string(),
+                members: vec![ZoneGroupMember {
+                    uuid: "RINCON_KITCHEN_001".to_string(),
+                    location: format!("http://127.0.0.1:{}/xml/device_description.xml", port),
+                    zone_name: "Kitchen".to_string(),
+                    software_version: String::new(),
+                    configuration: String::new(),
+                    icon: String::new(),
+                    satellites: vec![],
+                }],
+            }],
+            vanished_devices: None,
+        }
+    }
+
+    fn temp_cache_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("sonos-cli-test-{}-{}", std::process::id(), name))
+            .join("topology.json")
+    }
+
+    #[test]
+    fn test_cached_topology_loads_when_speakers_are_reachable() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let path = temp_cache_path("reachable");
+
+        save_topology_to(&path, &topology_at(port)).unwrap();
+        let loaded = load_topology_from(&path, REACHABLE_TIMEOUT).expect("cache should load");
+
+        assert_eq!(loaded.get_groups().len(), 1);
+        assert_eq!(loaded.get_groups()[0].get_coordinator().get_uuid(), "RINCON_KITCHEN_001");
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_cached_topology_is_ignored_when_a_speaker_is_gone() {
+        let port = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let path = temp_cache_path("unreachable");
+
+        save_topology_to(&path, &topology_at(port)).unwrap();
+
+        assert!(load_topology_from(&path, REACHABLE_TIMEOUT).is_none());
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_missing_cache_file_loads_nothing() {
+        assert!(load_topology_from(&temp_cache_path("missing"), REACHABLE_TIMEOUT).is_none());
+    }
+}
@@ -1,2 +1,3 @@
+pub mod cache;
 pub mod reducers;
 pub mod store;
\ No newline at end of file
@@ -7,7 +7,7 @@ use crate::{
     views::ViewType,
 };
 
-use super::store::AppState;
+use super::store::{AppState, Track};
 
 pub enum AppAction {
     SetStatusMessage(String),
@@ -17,6 +17,11 @@ pub enum AppAction {
     SetControlView,
     HydrateSpeakerTopology(SpeakerInfo),
     UpdateSpeakerVolume(String, u8),
+    UpdateSpeakerMute(String, bool),
+    UpdateTrack(String, Option<Track>),
+    SetFilter(String),
+    RequestDiscovery,
+    StartDiscovery,
 }
 
 impl std::fmt::Debug for AppAction {
@@ -42,6 +47,19 @@ impl std::fmt::Debug for AppAction {
                 .field(uuid)
                 .field(volume)
                 .finish(),
+            AppAction::UpdateSpeakerMute(uuid, muted) => f
+                .debug_tuple("UpdateSpeakerMute")
+                .field(uuid)
+                .field(muted)
+                .finish(),
+            AppAction::UpdateTrack(coordinator_uuid, track) => f
+                .debug_tuple("UpdateTrack")
+                .field(coordinator_uuid)
+                .field(track)
+                .finish(),
+            AppAction::SetFilter(query) => f.debug_tuple("SetFilter").field(query).finish(),
+            AppAction::RequestDiscovery => f.debug_tuple("RequestDiscovery").finish(),
+            AppAction::StartDiscovery => f.debug_tuple("StartDiscovery").finish(),
         }
     }
 }
@@ -51,11 +69,16 @@ pub fn app_reducer(state: &mut AppState, action: AppAction) {
         AppAction::SetStatusMessage(message) => {
             state.status_message = message;
         }
-        AppAction::UpdateTopology(topology) => {
+        AppAction::UpdateTopology(mut topology) => {
             log::debug!("SetTopology action received, switching to Control view");
+            // Keep the active filter across topology refreshes
+            if let Some(previous) = &state.topology {
+                topology.set_filter(previous.filter());
+            }
             let topology_map = create_uuid_to_index_map(&topology);
             state.topology = Some(topology);
             state.topology_ref = Some(topology_map);
+            state.topology_version += 1;
         }
         AppAction::SetHighlight(item) => {
             log::debug!("SetHighlight action received: {:?}", item.get_type());
@@ -142,6 +165,45 @@ pub fn app_reducer(state: &mut AppState, action: AppAction) {
                 log::debug!("No topology available");
             }
         }
+        AppAction::UpdateSpeakerMute(uuid, muted) => {
+            log::debug!("UpdateSpeakerMute action received for UUID: {}, muted: {}", uuid, muted);
+
+            if let (Some(topology), Some(topology_ref)) = (&mut state.topology, &state.topology_ref) {
+                if let Some(item) = topology_ref.get(&uuid).and_then(|&index| topology.items.get_mut(index)) {
+                    item.set_muted(muted);
+                } else {
+                    log::debug!("UUID {} not found in topology", uuid);
+                }
+            }
+        }
+        AppAction::UpdateTrack(coordinator_uuid, track) => {
+            log::debug!("UpdateTrack action received for coordinator: {}", coordinator_uuid);
+
+            let coordinator_uuid = normalize_uuid(&coordinator_uuid);
+            match track {
+                Some(track) => {
+                    state.now_playing.insert(coordinator_uuid, track);
+                }
+                None => {
+                    state.now_playing.remove(&coordinator_uuid);
+                }
+            }
+        }
+        AppAction::SetFilter(query) => {
+            log::debug!("SetFilter action received: {:?}", query);
+
+            if let Some(ref mut topology) = state.topology {
+                topology.set_filter(&query);
+                state.highlight = topology.visible_items().next().cloned();
+            }
+        }
+        AppAction::RequestDiscovery => {
+            state.discovery_requested = true;
+        }
+        AppAction::StartDiscovery => {
+            state.discovery_requested = false;
+            state.status_message = "loading...".to_owned();
+        }
     }
 }
 
@@ -153,7 +215,7 @@ fn create_uuid_to_index_map(list: &TopologyList) -> HashMap<String, usize> {
         .collect()
 }
 
-fn normalize_uuid(uuid: &str) -> String {
+pub(crate) fn normalize_uuid(uuid: &str) -> String {
     // Remove "uuid:" prefix if present
     let without_prefix = if uuid.starts_with("uuid:") {
         &uuid[5..]
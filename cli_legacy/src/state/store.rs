@@ -18,6 +18,14 @@ pub enum SpeakerDisplayState {
     ActiveAndLocked,
 }
 
+/// Track playing on a group, as reported by its coordinator
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track {
+    pub title: String,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+}
+
 pub struct AppState {
     pub view: ViewType,
     pub status_message: String,
@@ -25,6 +33,12 @@ pub struct AppState {
     pub topology_ref: Option<HashMap<String, usize>>,
     pub highlight: Option<TopologyItem>,
     pub selected_speaker_ip: Option<String>,
+    /// Current track per group, keyed by coordinator UUID
+    pub now_playing: HashMap<String, Track>,
+    /// Set when the user asks to run discovery again
+    pub discovery_requested: bool,
+    /// Bumped each time the topology is replaced, so the app can tell discovery finished
+    pub topology_version: u64,
 }
 
 impl Default for AppState {
@@ -36,6 +50,9 @@ impl Default for AppState {
             topology_ref: None,
             highlight: None,
             selected_speaker_ip: None,
+            now_playing: HashMap::new(),
+            discovery_requested: false,
+            topology_version: 0,
         }
     }
 }
@@ -49,6 +66,12 @@ impl AppState {
         self.selected_speaker_ip.as_ref().map(|s| s.as_str()) == Some(uuid)
     }
 
+    /// Track playing on the group that `item` belongs to
+    pub fn track_for(&self, item: &TopologyItem) -> Option<&Track> {
+        item.coordinator_uuid()
+            .and_then(|uuid| self.now_playing.get(uuid))
+    }
+
     pub fn get_speaker_display_state(&self, uuid: &str) -> SpeakerDisplayState {
         match (
             self.is_speaker_highlighted(uuid),
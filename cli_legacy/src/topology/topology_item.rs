@@ -27,6 +27,7 @@ pub enum TopologyItem {
     model: Option<String>,
     is_last: bool,
     volume: Option<u8>,
+    muted: bool,
   },
   Satellite {
     uuid: String,
@@ -58,6 +59,15 @@ impl TopologyItem {
     }
   }
 
+  /// UUID of the coordinator playing for this item's group
+  pub fn coordinator_uuid(&self) -> Option<&str> {
+    match self {
+      TopologyItem::Group { uuid, .. } => Some(uuid.trim_start_matches("GROUP:")),
+      TopologyItem::Speaker { group_uuid, .. } => Some(group_uuid.trim_start_matches("GROUP:")),
+      TopologyItem::Satellite { .. } => None,
+    }
+  }
+
   pub fn set_is_last(&mut self, is_last: bool) {
     match self {
       TopologyItem::Group {
@@ -86,6 +96,12 @@ impl TopologyItem {
     }
   }
 
+  pub fn set_muted(&mut self, is_muted: bool) {
+    if let TopologyItem::Speaker { muted, .. } = self {
+      *muted = is_muted;
+    }
+  }
+
   pub fn set_volume(&mut self, volume: u8) {
     match self {
       TopologyItem::Group {
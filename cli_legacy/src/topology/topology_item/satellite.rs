@@ -16,15 +16,19 @@ impl TopologyItem {
 
     /// Converts a Satellite variant to a ListItem
     pub(super) fn satellite_to_list_item(&self, _highlighted: bool) -> ListItem<'static> {
+        ListItem::new(self.satellite_line())
+    }
+
+    /// Satellite row, indented beneath its parent speaker
+    pub(crate) fn satellite_line(&self) -> Line<'static> {
         if let TopologyItem::Satellite { uuid, .. } = self {
-            let line = Line::from(vec![
-                Span::raw("  "),
+            Line::from(vec![
+                Span::raw("    "),
                 Span::styled("Satellite: ", Style::default().fg(Color::Yellow)),
                 Span::raw(uuid.clone()),
-            ]);
-            ListItem::new(line)
+            ])
         } else {
-            panic!("satellite_to_list_item called on non-Satellite variant")
+            panic!("satellite_line called on non-Satellite variant")
         }
     }
 }
@@ -12,6 +12,7 @@ impl TopologyItem {
     let ip = speaker.get_ip();
     let controller = SpeakerController::new();
     let volume = controller.get_volume(&ip).ok();
+    let muted = controller.get_mute(&ip).unwrap_or(false);
 
     TopologyItem::Speaker {
       ip,
@@ -22,6 +23,7 @@ impl TopologyItem {
       model: None,
       is_last: false,
       volume,
+      muted,
     }
   }
 
@@ -31,6 +33,7 @@ impl TopologyItem {
       model,
       is_last,
       volume,
+      muted,
       ..
     } = self
     {
@@ -41,6 +44,10 @@ impl TopologyItem {
         Span::raw(name.clone())
       ];
 
+      if *muted {
+        left_spans.push(Span::raw(" 🔇"));
+      }
+
       if let Some(model_name) = model {
         let style = if highlighted {
           Style::default()
@@ -108,6 +115,7 @@ mod tests {
       model: Some("Connect:Amp".to_string()),
       is_last: false,
       volume: Some(10),
+      muted: false,
     };
 
     let list_item = speaker.to_list_item(false);
@@ -126,9 +134,39 @@ mod tests {
       model: Some("Connect:Amp".to_string()),
       is_last: true,
       volume: Some(10),
+      muted: false,
     };
 
     let list_item = speaker.to_list_item(false);
     drop(list_item);
   }
+
+  fn render_to_string(item: ListItem<'static>) -> String {
+    use ratatui::{buffer::Buffer, layout::Rect, widgets::{List, Widget}};
+
+    let area = Rect::new(0, 0, 40, 1);
+    let mut buffer = Buffer::empty(area);
+    List::new(vec![item]).render(area, &mut buffer);
+    buffer.content.iter().map(|cell| cell.symbol()).collect()
+  }
+
+  fn kitchen(muted: bool) -> TopologyItem {
+    TopologyItem::Speaker {
+      ip: "192.168.1.101".to_string(),
+      coordinator_ip: "10.0.0.1".to_string(),
+      group_uuid: "RINCON_789012:123".to_string(),
+      name: "Kitchen".to_string(),
+      uuid: "RINCON_789012".to_string(),
+      model: None,
+      is_last: false,
+      volume: None,
+      muted,
+    }
+  }
+
+  #[test]
+  fn test_muted_speaker_shows_mute_glyph() {
+    assert!(render_to_string(kitchen(true).to_list_item(false)).contains("🔇"));
+    assert!(!render_to_string(kitchen(false).to_list_item(false)).contains("🔇"));
+  }
 }
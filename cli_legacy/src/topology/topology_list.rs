@@ -2,40 +2,330 @@ use sonos::Topology;
 
 use crate::topology::topology_item::TopologyItem;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct TopologyList {
     pub items: Vec<TopologyItem>,
+    filter: String,
+    // Indexes into `items` that match the filter, None while unfiltered
+    visible: Option<Vec<usize>>,
+    // Topology the items were built from, kept so it can be cached on exit
+    topology: Option<Topology>,
 }
 
 impl TopologyList {
     pub fn new(topology: Topology) -> Self {
-        let mut items: Vec<TopologyItem> = Vec::new();
-
         if topology.len() == 0 {
-            return TopologyList { items };
+            return TopologyList::from_items(Vec::new());
         }
 
-        for group in topology.get_groups() {
-            let group_uuid = group.get_coordinator().get_uuid();
-            let group_ip = group.get_coordinator().get_ip();
-            let group_item = TopologyItem::from_group(&group);
+        let groups = topology
+            .get_groups()
+            .iter()
+            .map(|group| {
+                let group_uuid = group.get_coordinator().get_uuid();
+                let group_ip = group.get_coordinator().get_ip();
+
+                let speakers = group
+                    .get_speakers()
+                    .iter()
+                    .map(|speaker| {
+                        let speaker_item = TopologyItem::from_speaker(&group_ip, &group_uuid, speaker);
+                        let satellite_items = speaker.satellites.iter().map(TopologyItem::from_satellite).collect();
+                        (speaker_item, satellite_items)
+                    })
+                    .collect();
+
+                (TopologyItem::from_group(group), speakers)
+            })
+            .collect();
+
+        let mut list = TopologyList::flatten(groups);
+        list.topology = Some(topology);
+        list
+    }
+
+    /// Flatten groups into display order: each group, then its speakers with
+    /// their satellites directly underneath
+    pub fn flatten(groups: Vec<(TopologyItem, Vec<(TopologyItem, Vec<TopologyItem>)>)>) -> Self {
+        let mut items: Vec<TopologyItem> = Vec::new();
+
+        for (group_item, speakers) in groups {
             items.push(group_item);
 
-            for speaker in group.get_speakers() {
-                let speaker_item = TopologyItem::from_speaker(&group_ip, &group_uuid, &speaker);
+            let speaker_count = speakers.len();
+            for (index, (mut speaker_item, satellites)) in speakers.into_iter().enumerate() {
+                // The last speaker closes the tree even when satellites follow it
+                speaker_item.set_is_last(index + 1 == speaker_count);
                 items.push(speaker_item);
-            }
 
-            // Set is_last to true for the last item
-            if let Some(last_item) = items.last_mut() {
-                last_item.set_is_last(true);
+                let satellite_count = satellites.len();
+                for (index, mut satellite_item) in satellites.into_iter().enumerate() {
+                    satellite_item.set_is_last(index + 1 == satellite_count);
+                    items.push(satellite_item);
+                }
             }
         }
 
-        TopologyList { items }
+        TopologyList::from_items(items)
+    }
+
+    pub fn from_items(items: Vec<TopologyItem>) -> Self {
+        Self {
+            items,
+            filter: String::new(),
+            visible: None,
+            topology: None,
+        }
+    }
+
+    /// The discovered topology behind this list, if it was built from one
+    pub fn topology(&self) -> Option<&Topology> {
+        self.topology.as_ref()
     }
 
     pub fn get_by_index(&self, index: u8) -> Option<&TopologyItem> {
-        self.items.get(index as usize)
+        self.visible_items().nth(index as usize)
+    }
+
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    /// Narrow the visible items to groups and speakers whose name contains
+    /// `query`, ignoring case. A matching group keeps all of its members and
+    /// a matching speaker keeps its group row. An empty query clears the filter.
+    pub fn set_filter(&mut self, query: &str) {
+        self.filter = query.to_string();
+
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            self.visible = None;
+            return;
+        }
+
+        let mut visible = Vec::new();
+        let mut group: Option<usize> = None;
+        let mut group_matches = false;
+        let mut speaker_visible = false;
+
+        for (index, item) in self.items.iter().enumerate() {
+            match item {
+                TopologyItem::Group { name, .. } => {
+                    group = Some(index);
+                    group_matches = name.to_lowercase().contains(&query);
+                    if group_matches {
+                        visible.push(index);
+                    }
+                }
+                TopologyItem::Speaker { name, .. } => {
+                    speaker_visible = group_matches || name.to_lowercase().contains(&query);
+                    if group_matches {
+                        visible.push(index);
+                    } else if speaker_visible {
+                        if let Some(group_index) = group {
+                            if !visible.contains(&group_index) {
+                                visible.push(group_index);
+                            }
+                        }
+                        visible.push(index);
+                    }
+                }
+                TopologyItem::Satellite { .. } => {
+                    if speaker_visible {
+                        visible.push(index);
+                    }
+                }
+            }
+        }
+
+        self.visible = Some(visible);
+    }
+
+    /// Items currently shown, in display order
+    pub fn visible_items(&self) -> impl Iterator<Item = &TopologyItem> {
+        let indexes: Box<dyn Iterator<Item = usize>> = match &self.visible {
+            Some(visible) => Box::new(visible.iter().copied()),
+            None => Box::new(0..self.items.len()),
+        };
+        indexes.filter_map(move |index| self.items.get(index))
+    }
+
+    #[cfg(test)]
+    pub fn visible_len(&self) -> usize {
+        self.visible.as_ref().map_or(self.items.len(), Vec::len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sonos::PlayState;
+
+    use super::*;
+
+    fn group(name: &str, uuid: &str) -> TopologyItem {
+        TopologyItem::Group {
+            ip: "192.168.1.100".to_string(),
+            name: name.to_string(),
+            uuid: format!("GROUP:{}", uuid),
+            children: vec![],
+            is_last: false,
+            play_state: PlayState::Stopped,
+            volume: None,
+            children_count: 1,
+        }
+    }
+
+    fn speaker(name: &str, uuid: &str, group_uuid: &str) -> TopologyItem {
+        TopologyItem::Speaker {
+            ip: "192.168.1.101".to_string(),
+            coordinator_ip: "192.168.1.100".to_string(),
+            group_uuid: group_uuid.to_string(),
+            uuid: uuid.to_string(),
+            name: name.to_string(),
+            model: None,
+            is_last: false,
+            volume: None,
+            muted: false,
+        }
+    }
+
+    fn create_test_list() -> TopologyList {
+        TopologyList::from_items(vec![
+            group("Living Room", "RINCON_LIVING"),
+            speaker("Living Room", "RINCON_LIVING", "RINCON_LIVING"),
+            speaker("Kitchen", "RINCON_KITCHEN", "RINCON_LIVING"),
+            group("Bedroom", "RINCON_BEDROOM"),
+            speaker("Bedroom", "RINCON_BEDROOM", "RINCON_BEDROOM"),
+            group("Office", "RINCON_OFFICE"),
+            speaker("Office", "RINCON_OFFICE", "RINCON_OFFICE"),
+            speaker("Kitchen Nook", "RINCON_NOOK", "RINCON_OFFICE"),
+        ])
+    }
+
+    fn visible_uuids(list: &TopologyList) -> Vec<String> {
+        list.visible_items().map(|item| item.get_uuid()).collect()
+    }
+
+    fn satellite(uuid: &str) -> TopologyItem {
+        TopologyItem::Satellite {
+            uuid: uuid.to_string(),
+            is_last: false,
+        }
+    }
+
+    fn create_surround_list() -> TopologyList {
+        TopologyList::flatten(vec![
+            (
+                group("Living Room", "RINCON_SOUNDBAR"),
+                vec![
+                    (
+                        speaker("Living Room", "RINCON_SOUNDBAR", "RINCON_SOUNDBAR"),
+                        vec![satellite("RINCON_SURROUND_LEFT"), satellite("RINCON_SURROUND_RIGHT")],
+                    ),
+                    (speaker("Kitchen", "RINCON_KITCHEN", "RINCON_SOUNDBAR"), vec![]),
+                ],
+            ),
+            (
+                group("Office", "RINCON_OFFICE"),
+                vec![(speaker("Office", "RINCON_OFFICE", "RINCON_OFFICE"), vec![])],
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_flatten_places_satellites_under_their_speaker() {
+        let list = create_surround_list();
+
+        assert_eq!(list.items.len(), 7);
+        assert_eq!(
+            visible_uuids(&list),
+            vec![
+                "GROUP:RINCON_SOUNDBAR",
+                "RINCON_SOUNDBAR",
+                "RINCON_SURROUND_LEFT",
+                "RINCON_SURROUND_RIGHT",
+                "RINCON_KITCHEN",
+                "GROUP:RINCON_OFFICE",
+                "RINCON_OFFICE",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flatten_marks_last_speaker_and_satellite() {
+        let list = create_surround_list();
+
+        let is_last: Vec<bool> = list
+            .items
+            .iter()
+            .map(|item| match item {
+                TopologyItem::Group { is_last, .. }
+                | TopologyItem::Speaker { is_last, .. }
+                | TopologyItem::Satellite { is_last, .. } => *is_last,
+            })
+            .collect();
+        assert_eq!(is_last, vec![false, false, false, true, true, false, true]);
+    }
+
+    #[test]
+    fn test_satellites_render_indented() {
+        let list = create_surround_list();
+
+        let line = match &list.items[2] {
+            satellite @ TopologyItem::Satellite { .. } => satellite.satellite_line(),
+            other => panic!("Expected satellite, got {:?}", other),
+        };
+        let text: String = line.spans.iter().map(|span| span.content.as_ref()).collect();
+        assert!(text.starts_with("    "));
+        assert!(text.contains("RINCON_SURROUND_LEFT"));
+    }
+
+    #[test]
+    fn test_filter_matching_nothing_hides_everything() {
+        let mut list = create_test_list();
+        list.set_filter("garage");
+
+        assert_eq!(list.visible_len(), 0);
+        assert!(list.get_by_index(0).is_none());
+    }
+
+    #[test]
+    fn test_filter_matching_one_speaker_keeps_its_group() {
+        let mut list = create_test_list();
+        list.set_filter("NOOK");
+
+        assert_eq!(visible_uuids(&list), vec!["GROUP:RINCON_OFFICE", "RINCON_NOOK"]);
+    }
+
+    #[test]
+    fn test_filter_matching_several_items() {
+        let mut list = create_test_list();
+        list.set_filter("kitchen");
+
+        assert_eq!(
+            visible_uuids(&list),
+            vec!["GROUP:RINCON_LIVING", "RINCON_KITCHEN", "GROUP:RINCON_OFFICE", "RINCON_NOOK"]
+        );
+    }
+
+    #[test]
+    fn test_filter_matching_group_keeps_all_members() {
+        let mut list = create_test_list();
+        list.set_filter("living");
+
+        assert_eq!(
+            visible_uuids(&list),
+            vec!["GROUP:RINCON_LIVING", "RINCON_LIVING", "RINCON_KITCHEN"]
+        );
+    }
+
+    #[test]
+    fn test_empty_filter_shows_everything() {
+        let mut list = create_test_list();
+        list.set_filter("kitchen");
+        list.set_filter("");
+
+        assert_eq!(list.visible_len(), list.items.len());
+        assert_eq!(list.filter(), "");
     }
 }
@@ -7,10 +7,12 @@ use sonos::SpeakerController;
 use std::io;
 use std::sync::Arc;
 
+use crate::commands::{self, Command};
 use crate::state::reducers::AppAction;
 use crate::state::store::Store;
 use crate::topology::topology_item::TopologyItem;
 use crate::topology::topology_list::TopologyList;
+use crate::widgets::now_playing::now_playing;
 use crate::widgets::speaker_list::SpeakerList;
 
 use super::View;
@@ -18,6 +20,10 @@ use super::View;
 pub struct ControlView {
     store: Arc<Store>,
     list_widget: SpeakerList,
+    /// Error from the last command, cleared on the next key press
+    status: Option<String>,
+    /// Filter query being typed after `/`, None when not editing
+    filter_input: Option<String>,
 }
 
 impl ControlView {
@@ -26,12 +32,75 @@ impl ControlView {
             if let Some(topology) = &state.topology {
                 SpeakerList::new(topology)
             } else {
-                let empty_topology = TopologyList { items: vec![] };
-                SpeakerList::new(&empty_topology)
+                SpeakerList::new(&TopologyList::default())
             }
         });
 
-        Self { store, list_widget }
+        Self {
+            store,
+            list_widget,
+            status: None,
+            filter_input: None,
+        }
+    }
+
+    fn apply_filter(&mut self, store: &Store, query: &str) {
+        store.dispatch(AppAction::SetFilter(query.to_string()));
+        self.list_widget.reset_selection();
+    }
+
+    /// Handle a key while the filter box is open
+    fn handle_filter_input(&mut self, key_event: KeyEvent, store: &Store) {
+        let Some(mut query) = self.filter_input.take() else {
+            return;
+        };
+
+        match key_event.code {
+            KeyCode::Esc => {
+                self.apply_filter(store, "");
+                return;
+            }
+            KeyCode::Enter => return,
+            KeyCode::Backspace => {
+                query.pop();
+            }
+            KeyCode::Char(c) => query.push(c),
+            _ => {}
+        }
+
+        self.apply_filter(store, &query);
+        self.filter_input = Some(query);
+    }
+
+    fn filter_line(&self) -> Option<String> {
+        match &self.filter_input {
+            Some(query) => Some(format!("/{}", query)),
+            None => self.store.with_state(|state| {
+                state
+                    .topology
+                    .as_ref()
+                    .map(|topology| topology.filter())
+                    .filter(|filter| !filter.is_empty())
+                    .map(|filter| format!("Filter: {} (Esc to clear)", filter))
+            }),
+        }
+    }
+
+    fn selected_item(&self, store: &Store) -> Option<TopologyItem> {
+        store.with_state(|state| {
+            if let Some(topology) = &state.topology {
+                self.list_widget.selected(topology).cloned()
+            } else {
+                None
+            }
+        })
+    }
+
+    fn run_command(&mut self, command: Command) {
+        if let Err(e) = commands::route(command.clone()) {
+            log::debug!("Command {:?} failed: {}", command, e);
+            self.status = Some(format!("Command failed: {}", e));
+        }
     }
 
     fn get_selected_list(&self) -> String {
@@ -49,7 +118,12 @@ impl View for ControlView {
     fn render(&mut self, frame: &mut Frame) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(0),
+                Constraint::Length(2),
+                Constraint::Length(1),
+            ])
             .split(frame.area());
         let body = Text::from(self.get_selected_list());
         let body_paragraph = Paragraph::new(body).alignment(Alignment::Center);
@@ -60,13 +134,86 @@ impl View for ControlView {
             if let Some(topology) = &state.topology {
                 self.list_widget.draw(frame, chunks[1], topology);
             } else {
-                let empty_topology = TopologyList { items: vec![] };
-                self.list_widget.draw(frame, chunks[1], &empty_topology);
+                self.list_widget.draw(frame, chunks[1], &TopologyList::default());
             }
         });
+
+        let track_text = self.store.with_state(|state| {
+            let track = state
+                .topology
+                .as_ref()
+                .and_then(|topology| self.list_widget.selected(topology))
+                .and_then(|item| state.track_for(item));
+            now_playing(track)
+        });
+        frame.render_widget(Paragraph::new(track_text), chunks[2]);
+
+        if let Some(status) = self.status.clone().or_else(|| self.filter_line()) {
+            let status_paragraph = Paragraph::new(Text::from(status)).alignment(Alignment::Center);
+            frame.render_widget(status_paragraph, chunks[3]);
+        }
+    }
+
+    fn is_capturing_input(&self) -> bool {
+        self.filter_input.is_some()
     }
 
     fn handle_input(&mut self, key_event: KeyEvent, store: &Store) -> io::Result<()> {
+        self.status = None;
+
+        if self.filter_input.is_some() {
+            self.handle_filter_input(key_event, store);
+            return Ok(());
+        }
+
+        match key_event.code {
+            KeyCode::Char('v') => {
+                self.list_widget.toggle_multi_select();
+                return Ok(());
+            }
+            KeyCode::Char(' ') if self.list_widget.is_multi_select() => {
+                self.list_widget.toggle_mark();
+                return Ok(());
+            }
+            KeyCode::Char('g') if self.list_widget.is_multi_select() => {
+                let marked: Vec<TopologyItem> = store.with_state(|state| {
+                    state
+                        .topology
+                        .as_ref()
+                        .map(|topology| self.list_widget.marked(topology).into_iter().cloned().collect())
+                        .unwrap_or_default()
+                });
+                match Command::group_from_selection(&marked) {
+                    Some(command) => {
+                        self.run_command(command);
+                        self.list_widget.toggle_multi_select();
+                    }
+                    None => self.status = Some("Mark at least two speakers to group".to_string()),
+                }
+                return Ok(());
+            }
+            KeyCode::Char('/') => {
+                let current = store.with_state(|state| {
+                    state.topology.as_ref().map(|topology| topology.filter().to_string())
+                });
+                self.filter_input = Some(current.unwrap_or_default());
+                return Ok(());
+            }
+            KeyCode::Esc => {
+                self.apply_filter(store, "");
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        if let Some(command) = self
+            .selected_item(store)
+            .and_then(|item| Command::from_key(key_event.code, &item))
+        {
+            self.run_command(command);
+            return Ok(());
+        }
+
         match key_event.code {
             KeyCode::Up => {
                 self.list_widget.previous();
@@ -153,46 +300,10 @@ impl View for ControlView {
                     }
                 }
             }
-            KeyCode::Char(' ') => {
+            KeyCode::Enter => {
                 // Toggle lock for the currently highlighted item if it's a speaker
-                let selected_item = store.with_state(|state| {
-                    if let Some(topology) = &state.topology {
-                        self.list_widget.selected(topology).cloned()
-                    } else {
-                        None
-                    }
-                });
-                if let Some(selected_item) = selected_item {
-                    match selected_item {
-                        TopologyItem::Speaker { ip, .. } => {
-                            store.dispatch(AppAction::SetSelectSpeaker(ip));
-                        }
-                        TopologyItem::Group { .. } => {
-                            // TODO: Handle group toggle
-                        }
-                        TopologyItem::Satellite { .. } => {
-                            // TODO: Handle satellite toggle
-                        }
-                    }
-                }
-            }
-            KeyCode::Char('p') => {
-                let selected_item = store.with_state(|state| {
-                    if let Some(topology) = &state.topology {
-                        self.list_widget.selected(topology).cloned()
-                    } else {
-                        None
-                    }
-                });
-                if let Some(selected_item) = selected_item {
-                    match selected_item {
-                        TopologyItem::Group { ip, .. } => {
-                            let controller = SpeakerController::new();
-                            let _ = controller.toggle_play_state(&ip);
-                        }
-                        TopologyItem::Speaker { .. } => todo!(),
-                        TopologyItem::Satellite { .. } => todo!(),
-                    }
+                if let Some(TopologyItem::Speaker { ip, .. }) = self.selected_item(store) {
+                    store.dispatch(AppAction::SetSelectSpeaker(ip));
                 }
             }
             _ => {}
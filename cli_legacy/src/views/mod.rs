@@ -15,4 +15,9 @@ pub enum ViewType {
 pub trait View {
   fn render(&mut self, frame: &mut Frame);
   fn handle_input(&mut self, key_event: KeyEvent, store: &Store) -> io::Result<()>;
+
+  /// True while the view is taking text input and global shortcuts should be ignored
+  fn is_capturing_input(&self) -> bool {
+    false
+  }
 }
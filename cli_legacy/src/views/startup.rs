@@ -1,6 +1,7 @@
 use std::io;
 use std::sync::Arc;
-use crossterm::event::KeyEvent;
+use std::time::{Duration, Instant};
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
   layout::{ Alignment, Constraint, Direction, Layout },
   text::Text,
@@ -8,29 +9,78 @@ use ratatui::{
   Frame,
 };
 
+use crate::state::reducers::AppAction;
 use crate::state::store::Store;
 use crate::widgets::{ logo::logo, util };
 
 use super::View;
 
+/// How long discovery may run before the view reports that nothing was found
+pub const DEFAULT_DISCOVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+const NO_SPEAKERS_MESSAGE: &str = "No Sonos speakers found — press r to retry";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StartupState {
+  Searching,
+  NoSpeakers,
+}
+
 pub struct StartupView {
   store: Arc<Store>,
+  state: StartupState,
+  timeout: Duration,
+  started_at: Instant,
 }
 
 impl StartupView {
   pub fn new(store: Arc<Store>) -> Self {
-    Self { store }
+    Self::with_timeout(store, DEFAULT_DISCOVERY_TIMEOUT)
+  }
+
+  pub fn with_timeout(store: Arc<Store>, timeout: Duration) -> Self {
+    Self {
+      store,
+      state: StartupState::Searching,
+      timeout,
+      started_at: Instant::now(),
+    }
+  }
+
+  #[cfg(test)]
+  pub fn state(&self) -> StartupState {
+    self.state
+  }
+
+  /// Switch to the empty state once the timeout passes without any speakers
+  pub fn tick(&mut self, now: Instant) {
+    if self.state != StartupState::Searching || now.duration_since(self.started_at) < self.timeout {
+      return;
+    }
+
+    let found_speakers = self.store.with_state(|state| {
+      state.topology.as_ref().map_or(false, |topology| !topology.items.is_empty())
+    });
+    if !found_speakers {
+      log::debug!("No speakers found after {:?}", self.timeout);
+      self.state = StartupState::NoSpeakers;
+    }
   }
 
   fn get_status_message(&self) -> String {
-    self.store.with_state(|state| {
-      state.status_message.clone()
-    })
+    match self.state {
+      StartupState::Searching => self.store.with_state(|state| {
+        state.status_message.clone()
+      }),
+      StartupState::NoSpeakers => NO_SPEAKERS_MESSAGE.to_string(),
+    }
   }
 }
 
 impl View for StartupView {
   fn render(&mut self, frame: &mut Frame) {
+    self.tick(Instant::now());
+
     let logo = logo();
     let body = Text::from(self.get_status_message());
 
@@ -49,7 +99,60 @@ impl View for StartupView {
     frame.render_widget(body_paragraph, text_area);
   }
 
-  fn handle_input(&mut self, _key_event: KeyEvent, _store: &Store) -> io::Result<()> {
+  fn handle_input(&mut self, key_event: KeyEvent, store: &Store) -> io::Result<()> {
+    if self.state == StartupState::NoSpeakers && key_event.code == KeyCode::Char('r') {
+      self.state = StartupState::Searching;
+      self.started_at = Instant::now();
+      store.dispatch(AppAction::RequestDiscovery);
+    }
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use ratatui::{backend::TestBackend, Terminal};
+
+  use super::*;
+  use crate::topology::topology_list::TopologyList;
+
+  const TIMEOUT: Duration = Duration::from_millis(50);
+
+  fn rendered_text(view: &mut StartupView) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+    terminal.draw(|frame| view.render(frame)).unwrap();
+    terminal
+      .backend()
+      .buffer()
+      .content()
+      .iter()
+      .map(|cell| cell.symbol())
+      .collect()
+  }
+
+  #[test]
+  fn test_zero_speakers_enters_empty_state_after_timeout() {
+    let store = Arc::new(Store::new());
+    store.dispatch(AppAction::UpdateTopology(TopologyList::default()));
+    let mut view = StartupView::with_timeout(store, TIMEOUT);
+
+    view.tick(Instant::now());
+    assert_eq!(view.state(), StartupState::Searching);
+
+    view.tick(Instant::now() + TIMEOUT);
+    assert_eq!(view.state(), StartupState::NoSpeakers);
+    assert!(rendered_text(&mut view).contains("press r to retry"));
+  }
+
+  #[test]
+  fn test_retry_requests_discovery() {
+    let store = Arc::new(Store::new());
+    let mut view = StartupView::with_timeout(store.clone(), TIMEOUT);
+    view.tick(Instant::now() + TIMEOUT);
+
+    view.handle_input(KeyEvent::from(KeyCode::Char('r')), &store).unwrap();
+
+    assert_eq!(view.state(), StartupState::Searching);
+    assert!(store.with_state(|state| state.discovery_requested));
+  }
+}
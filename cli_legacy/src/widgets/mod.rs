@@ -1,4 +1,5 @@
 pub mod logo;
+pub mod now_playing;
 pub mod speaker_list;
 pub mod selectable_list;
 pub mod util;
\ No newline at end of file
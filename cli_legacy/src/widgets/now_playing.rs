@@ -0,0 +1,65 @@
+use ratatui::text::{Line, Text};
+
+use crate::state::store::Track;
+
+/**
+ * Title, artist and album of the selected group's track
+ */
+pub fn now_playing(track: Option<&Track>) -> Text<'static> {
+    match track {
+        Some(track) => {
+            let details: Vec<&str> = [track.artist.as_deref(), track.album.as_deref()]
+                .into_iter()
+                .flatten()
+                .collect();
+
+            Text::from(vec![
+                Line::raw(track.title.clone()),
+                Line::raw(details.join(" • ")),
+            ])
+        }
+        None => Text::from("Nothing playing."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{backend::TestBackend, widgets::Paragraph, Terminal};
+
+    use super::*;
+
+    fn render(track: Option<&Track>) -> String {
+        let backend = TestBackend::new(40, 2);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|frame| frame.render_widget(Paragraph::new(now_playing(track)), frame.area()))
+            .unwrap();
+
+        terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect()
+    }
+
+    #[test]
+    fn test_renders_track_details() {
+        let track = Track {
+            title: "Borderline".to_string(),
+            artist: Some("Tame Impala".to_string()),
+            album: Some("The Slow Rush".to_string()),
+        };
+
+        let rendered = render(Some(&track));
+
+        assert!(rendered.contains("Borderline"));
+        assert!(rendered.contains("Tame Impala • The Slow Rush"));
+    }
+
+    #[test]
+    fn test_renders_placeholder_without_track() {
+        assert!(render(None).contains("Nothing playing."));
+    }
+}
@@ -1,15 +1,23 @@
+use std::collections::BTreeSet;
+
 use ratatui::{
-    layout::Rect,
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Style, Stylize},
-    widgets::{Block, List, ListItem, ListState},
+    text::Line,
+    widgets::{Block, List, ListItem, ListState, Paragraph},
     Frame,
 };
 
+const CHECKBOX_WIDTH: u16 = 4;
+
 #[derive(Clone)]
 pub struct SelectableList {
     title: String,
     items: Vec<ListItem<'static>>,
     state: ListState,
+    /// When set, items can be marked for batch operations
+    multi_select: bool,
+    marked: BTreeSet<usize>,
 }
 
 impl SelectableList {
@@ -24,16 +32,76 @@ impl SelectableList {
             title: title.to_string(),
             items,
             state,
+            multi_select: false,
+            marked: BTreeSet::new(),
         }
     }
 
     pub fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let block = Block::default().title(self.title.clone());
+
+        if !self.multi_select {
+            let list = List::new(self.items.clone())
+                .block(block)
+                .highlight_style(Style::new().reversed())
+                .highlight_symbol("≡ ");
+
+            frame.render_stateful_widget(list, area, &mut self.state);
+            return;
+        }
+
+        // Checkboxes sit in their own column so items keep their own formatting
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(CHECKBOX_WIDTH), Constraint::Min(0)])
+            .split(inner);
+
         let list = List::new(self.items.clone())
-            .block(Block::default().title(self.title.clone()))
             .highlight_style(Style::new().reversed())
             .highlight_symbol("≡ ");
+        frame.render_stateful_widget(list, columns[1], &mut self.state);
+
+        let checkboxes: Vec<Line> = (self.state.offset()..self.items.len())
+            .take(columns[0].height as usize)
+            .map(|index| Line::raw(checkbox(self.marked.contains(&index))))
+            .collect();
+        frame.render_widget(Paragraph::new(checkboxes), columns[0]);
+    }
+
+    pub fn set_multi_select(&mut self, enabled: bool) {
+        self.multi_select = enabled;
+        if !enabled {
+            self.marked.clear();
+        }
+    }
+
+    pub fn is_multi_select(&self) -> bool {
+        self.multi_select
+    }
+
+    /// Mark or unmark the highlighted item; returns whether it is now marked
+    pub fn toggle_mark(&mut self) -> bool {
+        let Some(index) = self.state.selected().filter(|_| self.multi_select) else {
+            return false;
+        };
 
-        frame.render_stateful_widget(list, area, &mut self.state);
+        if self.marked.remove(&index) {
+            false
+        } else {
+            self.marked.insert(index);
+            true
+        }
+    }
+
+    /// Marked item indices in ascending order
+    pub fn selected_indices(&self) -> Vec<usize> {
+        self.marked.iter().copied().collect()
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
     }
 
     pub fn next(&mut self) -> Option<usize> {
@@ -66,6 +134,11 @@ impl SelectableList {
         Some(i)
     }
 
+    pub fn select_first(&mut self) {
+        let first = if self.items.is_empty() { None } else { Some(0) };
+        self.state.select(first);
+    }
+
     pub fn selected(&self) -> Option<usize> {
         self.state.selected()
     }
@@ -79,6 +152,9 @@ impl SelectableList {
         let will_have_items = !items.is_empty();
         
         self.items = items;
+
+        let len = self.items.len();
+        self.marked.retain(|&index| index < len);
         
         // If we didn't have items before but now we do, select the first item
         if !had_items && will_have_items && self.state.selected().is_none() {
@@ -99,6 +175,14 @@ impl SelectableList {
     }
 }
 
+fn checkbox(marked: bool) -> &'static str {
+    if marked {
+        "[x] "
+    } else {
+        "[ ] "
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use ratatui::{backend::TestBackend, Terminal};
@@ -277,4 +361,70 @@ mod tests {
         list.previous();
         assert_eq!(list.selected(), Some(0));
     }
+
+    #[test]
+    fn test_marking_requires_multi_select() {
+        let mut list = create_test_list();
+
+        assert!(!list.toggle_mark());
+        assert!(list.selected_indices().is_empty());
+    }
+
+    #[test]
+    fn test_mark_and_unmark() {
+        let mut list = create_test_list();
+        list.set_multi_select(true);
+
+        assert!(list.toggle_mark());
+        list.next();
+        list.next();
+        assert!(list.toggle_mark());
+        assert_eq!(list.selected_indices(), vec![0, 2]);
+
+        assert!(!list.toggle_mark());
+        assert_eq!(list.selected_indices(), vec![0]);
+    }
+
+    #[test]
+    fn test_navigation_keeps_marks() {
+        let mut list = create_test_list();
+        list.set_multi_select(true);
+        list.next();
+        list.toggle_mark();
+
+        // Wraps past the end and back
+        list.next();
+        list.next();
+        list.previous();
+
+        assert_eq!(list.selected_indices(), vec![1]);
+        assert_eq!(list.selected(), Some(2));
+    }
+
+    #[test]
+    fn test_leaving_multi_select_clears_marks() {
+        let mut list = create_test_list();
+        list.set_multi_select(true);
+        list.toggle_mark();
+
+        list.set_multi_select(false);
+
+        assert!(list.selected_indices().is_empty());
+    }
+
+    #[test]
+    fn test_marked_items_render_checkbox() {
+        let mut list = create_test_list();
+        list.set_multi_select(true);
+        list.next();
+        list.toggle_mark();
+
+        let mut terminal = Terminal::new(TestBackend::new(20, 4)).unwrap();
+        terminal.draw(|frame| list.draw(frame, frame.area())).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| -> String { (0..20).map(|x| buffer[(x, y)].symbol()).collect() };
+        assert!(row(1).starts_with("[ ] "));
+        assert!(row(2).starts_with("[x] "));
+    }
 }
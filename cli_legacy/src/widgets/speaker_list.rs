@@ -12,8 +12,7 @@ pub struct SpeakerList {
 impl SpeakerList {
     pub fn new(topology: &TopologyList) -> Self {
         let items: Vec<ListItem> = topology
-            .items
-            .iter()
+            .visible_items()
             .map(|item| item.to_list_item(false))
             .collect();
 
@@ -26,8 +25,7 @@ impl SpeakerList {
         let selected_index = self.widget.selected();
 
         let items: Vec<ListItem> = topology
-            .items
-            .iter()
+            .visible_items()
             .enumerate()
             .map(|(i, item)| {
                 let is_highlighted = selected_index == Some(i);
@@ -49,8 +47,40 @@ impl SpeakerList {
         self.widget.previous();
     }
 
+    /// Move highlight back to the first item, dropping any marks
+    pub fn reset_selection(&mut self) {
+        self.widget.select_first();
+        self.widget.clear_marks();
+    }
+
+    /// Switch between single selection and marking items for batch commands
+    pub fn toggle_multi_select(&mut self) -> bool {
+        let enabled = !self.widget.is_multi_select();
+        self.widget.set_multi_select(enabled);
+        enabled
+    }
+
+    pub fn is_multi_select(&self) -> bool {
+        self.widget.is_multi_select()
+    }
+
+    /// Mark or unmark the highlighted item
+    pub fn toggle_mark(&mut self) -> bool {
+        self.widget.toggle_mark()
+    }
+
+    /// Marked items in display order
+    pub fn marked<'a>(&self, topology: &'a TopologyList) -> Vec<&'a TopologyItem> {
+        let items: Vec<&TopologyItem> = topology.visible_items().collect();
+        self.widget
+            .selected_indices()
+            .into_iter()
+            .filter_map(|i| items.get(i).copied())
+            .collect()
+    }
+
     /// Get currently highlighted item
     pub fn selected<'a>(&self, topology: &'a TopologyList) -> Option<&'a TopologyItem> {
-        self.widget.selected().and_then(|i| topology.items.get(i))
+        self.widget.selected().and_then(|i| topology.visible_items().nth(i))
     }
 }
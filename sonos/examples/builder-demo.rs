@@ -11,6 +11,9 @@ fn create_test_speaker(id: &str, name: &str) -> Speaker {
         port: 1400,
         model_name: "Test Model".to_string(),
         satellites: vec![],
+        supported_services: vec![],
+        software_version: None,
+        hardware_version: None,
     }
 }
 
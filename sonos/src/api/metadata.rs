@@ -0,0 +1,167 @@
+//! URIs and DIDL-Lite metadata for playing music service items with
+//! [`SpeakerController::set_uri`](crate::SpeakerController::set_uri).
+//!
+//! Sonos only plays service content when the URI carries the service ID and
+//! the metadata names the service account, in the same shape the Sonos apps
+//! store in favorites. Each helper returns `(uri, didl)`.
+
+use quick_xml::escape::escape;
+
+use crate::model::Favorite;
+
+const DIDL_OPEN: &str = concat!(
+  r#"<DIDL-Lite xmlns:dc="http://purl.org/dc/elements/1.1/" "#,
+  r#"xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/" "#,
+  r#"xmlns:r="urn:schemas-rinconnetworks-com:metadata-1-0/" "#,
+  r#"xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/">"#,
+);
+
+/// Account descriptor for TuneIn, which needs no sign-in
+const TUNEIN_DESC: &str = "SA_RINCON65031_";
+
+/// Sonos service ID for Spotify, the `sid` of its URIs
+const SPOTIFY_SID: &str = "12";
+
+/// The Spotify account a household plays through
+///
+/// Both parts differ between households: `serial` is the `sn` in the
+/// account's URIs and `descriptor` the account token in their metadata, which
+/// also names the regional service (`SA_RINCON3079_...` in the US,
+/// `SA_RINCON2311_...` in Europe). Read them from a Spotify favorite with
+/// [`SpotifyAccount::from_favorite`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpotifyAccount {
+  pub serial: u32,
+  pub descriptor: String,
+}
+
+impl SpotifyAccount {
+  /// The account a Spotify favorite plays through; None for other services
+  pub fn from_favorite(favorite: &Favorite) -> Option<Self> {
+    let query = favorite.uri.split_once('?')?.1;
+    let param = |name: &str| query.split('&').find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='));
+    if param("sid")? != SPOTIFY_SID {
+      return None;
+    }
+
+    let descriptor = favorite.metadata.split_once("<desc")?.1.split_once('>')?.1.split('<').next()?;
+    Some(Self {
+      serial: param("sn")?.parse().ok()?,
+      descriptor: descriptor.to_string(),
+    })
+  }
+}
+
+/// A TuneIn radio station, by its ID with or without the leading `s` (`s44491` or `44491`)
+pub fn tunein_station(station_id: &str, title: &str) -> (String, String) {
+  let station_id = station_id.trim_start_matches('s');
+  let uri = format!("x-sonosapi-stream:s{}?sid=254&flags=8224&sn=0", station_id);
+  let didl = didl_item(
+    &format!("F00092020s{}", station_id),
+    "L",
+    title,
+    "object.item.audioItem.audioBroadcast",
+    TUNEIN_DESC,
+  );
+  (uri, didl)
+}
+
+/// A Spotify track played through `account`, by its bare ID or `spotify:track:` URI
+pub fn spotify_track(track_id: &str, title: &str, account: &SpotifyAccount) -> (String, String) {
+  let track_id = track_id.trim_start_matches("spotify:track:");
+  let encoded = format!("spotify%3atrack%3a{}", track_id);
+  let uri = format!("x-sonos-spotify:{}?sid={}&flags=8224&sn={}", encoded, SPOTIFY_SID, account.serial);
+  let didl = didl_item(
+    &format!("00032020{}", encoded),
+    "",
+    title,
+    "object.item.audioItem.musicTrack",
+    &account.descriptor,
+  );
+  (uri, didl)
+}
+
+fn didl_item(id: &str, parent_id: &str, title: &str, class: &str, desc: &str) -> String {
+  format!(
+    concat!(
+      r#"{}<item id="{}" parentID="{}" restricted="true"><dc:title>{}</dc:title>"#,
+      r#"<upnp:class>{}</upnp:class>"#,
+      r#"<desc id="cdudn" nameSpace="urn:schemas-rinconnetworks-com:metadata-1-0/">{}</desc></item></DIDL-Lite>"#,
+    ),
+    DIDL_OPEN,
+    id,
+    parent_id,
+    escape(title),
+    class,
+    desc
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::service::content_directory::parser::{BrowseResponse, FavoritesListing};
+
+  #[test]
+  fn test_tunein_station_matches_captured_favorite() {
+    // From the BBC Radio 6 Music favorite in favorites_browse_response.xml
+    let expected_didl = concat!(
+      r#"<DIDL-Lite xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/" "#,
+      r#"xmlns:r="urn:schemas-rinconnetworks-com:metadata-1-0/" xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/">"#,
+      r#"<item id="F00092020s44491" parentID="L" restricted="true"><dc:title>BBC Radio 6 Music</dc:title>"#,
+      r#"<upnp:class>object.item.audioItem.audioBroadcast</upnp:class>"#,
+      r#"<desc id="cdudn" nameSpace="urn:schemas-rinconnetworks-com:metadata-1-0/">SA_RINCON65031_</desc></item></DIDL-Lite>"#,
+    );
+
+    for station_id in ["s44491", "44491"] {
+      let (uri, didl) = tunein_station(station_id, "BBC Radio 6 Music");
+      assert_eq!(uri, "x-sonosapi-stream:s44491?sid=254&flags=8224&sn=0");
+      assert_eq!(didl, expected_didl);
+    }
+  }
+
+  fn captured_favorites() -> Vec<Favorite> {
+    let xml = include_str!("../../tests/fixtures/favorites_browse_response.xml");
+    BrowseResponse::<FavoritesListing>::parse(xml).unwrap().favorites()
+  }
+
+  #[test]
+  fn test_spotify_account_from_captured_favorite() {
+    let favorites = captured_favorites();
+
+    assert_eq!(
+      SpotifyAccount::from_favorite(&favorites[1]),
+      Some(SpotifyAccount { serial: 2, descriptor: "SA_RINCON3079_X_#Svc3079-0-Token".to_string() })
+    );
+    // The TuneIn station isn't a Spotify favorite
+    assert_eq!(SpotifyAccount::from_favorite(&favorites[0]), None);
+  }
+
+  #[test]
+  fn test_spotify_track_matches_captured_queue_item() {
+    // The account from the Peaceful Piano favorite, and the Borderline track
+    // that account queued in queue_browse_response.xml
+    let favorites = captured_favorites();
+    let account = SpotifyAccount::from_favorite(&favorites[1]).unwrap();
+    let expected_desc = concat!(
+      r#"<desc id="cdudn" nameSpace="urn:schemas-rinconnetworks-com:metadata-1-0/">"#,
+      r#"SA_RINCON3079_X_#Svc3079-0-Token</desc>"#,
+    );
+    assert!(favorites[1].metadata.contains(expected_desc));
+
+    for track_id in ["5hM5arv9KDbCHS0k9uqwjr", "spotify:track:5hM5arv9KDbCHS0k9uqwjr"] {
+      let (uri, didl) = spotify_track(track_id, "Borderline", &account);
+      assert_eq!(uri, "x-sonos-spotify:spotify%3atrack%3a5hM5arv9KDbCHS0k9uqwjr?sid=12&flags=8224&sn=2");
+      assert!(didl.contains(r#"<item id="00032020spotify%3atrack%3a5hM5arv9KDbCHS0k9uqwjr" parentID="" restricted="true">"#));
+      assert!(didl.contains("<upnp:class>object.item.audioItem.musicTrack</upnp:class>"));
+      assert!(didl.contains(expected_desc));
+    }
+  }
+
+  #[test]
+  fn test_titles_are_escaped() {
+    let (_, didl) = tunein_station("s24861", "Rock & Roll <Live>");
+
+    assert!(didl.contains("<dc:title>Rock &amp; Roll &lt;Live&gt;</dc:title>"));
+  }
+}
@@ -0,0 +1,6 @@
+pub mod metadata;
+mod speaker_controller;
+
+pub use speaker_controller::{
+  format_seek_time, parse_sleep_timer_duration, parse_track_time, scale_group_volume, SpeakerController,
+};
@@ -0,0 +1,975 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+
+use crate::error::{Result, SonosError};
+use crate::group::{Group, SpeakerRef};
+use crate::topology::Topology;
+use crate::model::{BatteryStatus, Favorite, GroupId, PlayMode, PlaybackState, Playlist, Speaker, SpeakerId, TrackInfo};
+use crate::service::content_directory::parser::{BrowseResponse, FavoritesListing, PlaylistsListing};
+use crate::service::zone_group_topology::parser::{BondRole, ZoneGroupState};
+use crate::streaming::ServiceType;
+use crate::transport::soap::{SoapAction, SoapClient, SoapResponse, UPNP_INVALID_ARGS};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_SLEEP_TIMER: Duration = Duration::from_secs(24 * 60 * 60);
+/// Number of entries requested per ContentDirectory `Browse` call
+const BROWSE_PAGE_SIZE: u32 = 100;
+/// Most SOAP requests `set_volume_all` has in flight at once
+const MAX_PARALLEL_REQUESTS: usize = 8;
+
+/// Issues UPnP control actions against a single speaker
+pub struct SpeakerController {
+  speaker: Speaker,
+  device_url: String,
+  soap_client: SoapClient,
+}
+
+impl SpeakerController {
+  /// Create a controller for the given speaker
+  pub fn new(speaker: &Speaker) -> Result<Self> {
+    Self::with_timeout(speaker, DEFAULT_TIMEOUT)
+  }
+
+  /// Create a controller with a custom request timeout
+  pub fn with_timeout(speaker: &Speaker, timeout: Duration) -> Result<Self> {
+    Self::with_retry(speaker, timeout, 0, Duration::ZERO)
+  }
+
+  /// Create a controller whose query actions retry after connection failures.
+  ///
+  /// See `SoapClient::with_retry`; actions that change state are never retried.
+  pub fn with_retry(speaker: &Speaker, timeout: Duration, retries: u8, backoff: Duration) -> Result<Self> {
+    Ok(Self {
+      speaker: speaker.clone(),
+      device_url: format!("http://{}:{}", speaker.ip_address, speaker.port),
+      soap_client: SoapClient::with_retry(timeout, retries, backoff)?,
+    })
+  }
+
+  /// Send `user_agent` and the `extra` headers on every request to the speaker
+  ///
+  /// See `SoapClient::with_headers`; the default User-Agent is `sonos-rs/<version>`.
+  pub fn with_http_headers(mut self, user_agent: &str, extra: &[(String, String)]) -> Result<Self> {
+    self.soap_client = self.soap_client.with_headers(user_agent, extra)?;
+    Ok(self)
+  }
+
+  pub fn device_url(&self) -> &str {
+    &self.device_url
+  }
+
+  /// Start playback
+  pub fn play(&self) -> Result<()> {
+    self.av_transport("Play", vec![("Speed", "1".to_string())])?;
+    Ok(())
+  }
+
+  /// Pause playback
+  pub fn pause(&self) -> Result<()> {
+    self.av_transport("Pause", vec![])?;
+    Ok(())
+  }
+
+  /// Stop playback
+  pub fn stop(&self) -> Result<()> {
+    self.av_transport("Stop", vec![])?;
+    Ok(())
+  }
+
+  /// Skip to the next track in the queue
+  pub fn next(&self) -> Result<()> {
+    self.av_transport("Next", vec![])?;
+    Ok(())
+  }
+
+  /// Return to the previous track in the queue
+  pub fn previous(&self) -> Result<()> {
+    self.av_transport("Previous", vec![])?;
+    Ok(())
+  }
+
+  /// Set the repeat/shuffle mode of the speaker's queue
+  pub fn set_play_mode(&self, mode: PlayMode) -> Result<()> {
+    self.av_transport("SetPlayMode", vec![("NewPlayMode", mode.as_str().to_string())])?;
+    Ok(())
+  }
+
+  /// Turn crossfading between queue tracks on or off
+  pub fn set_crossfade(&self, on: bool) -> Result<()> {
+    self.av_transport("SetCrossfadeMode", vec![("CrossfadeMode", bool_arg(on))])?;
+    Ok(())
+  }
+
+  /// Whether crossfade is on, for when no AVTransport event has arrived yet
+  pub fn get_crossfade(&self) -> Result<bool> {
+    let response = self.av_transport("GetCrossfadeMode", vec![])?;
+    match SoapClient::extract_xml_value(&response.body, "CrossfadeMode").as_deref() {
+      Some("1") => Ok(true),
+      Some("0") => Ok(false),
+      other => Err(SonosError::ParseError(format!("Invalid CrossfadeMode: {:?}", other))),
+    }
+  }
+
+  /// Seek to a position within the current track
+  pub fn seek_time(&self, position: Duration) -> Result<()> {
+    self.seek("REL_TIME", format_seek_time(position))
+  }
+
+  /// Jump to a track in the queue; positions start at 1
+  pub fn seek_track(&self, track_nr: u32) -> Result<()> {
+    if track_nr == 0 {
+      return Err(SonosError::InvalidArgument("Queue track numbers start at 1".to_string()));
+    }
+    self.seek("TRACK_NR", track_nr.to_string())
+  }
+
+  fn seek(&self, unit: &str, target: String) -> Result<()> {
+    self.av_transport("Seek", vec![("Unit", unit.to_string()), ("Target", target)])?;
+    Ok(())
+  }
+
+  /// Stop playback after `duration`, or cancel the sleep timer with `None`.
+  ///
+  /// Sonos accepts timers of up to 24 hours.
+  pub fn set_sleep_timer(&self, duration: Option<Duration>) -> Result<()> {
+    let value = match duration {
+      Some(duration) if duration > MAX_SLEEP_TIMER => {
+        return Err(SonosError::InvalidArgument(format!(
+          "Sleep timer of {}s exceeds the 24 hour maximum",
+          duration.as_secs()
+        )));
+      }
+      Some(duration) => format_seek_time(duration),
+      None => String::new(),
+    };
+    self.av_transport("ConfigureSleepTimer", vec![("NewSleepTimerDuration", value)])?;
+    Ok(())
+  }
+
+  /// Time left on the sleep timer, or `None` when no timer is set
+  pub fn get_sleep_timer(&self) -> Result<Option<Duration>> {
+    let response = self.av_transport("GetRemainingSleepTimerDuration", vec![])?;
+    let remaining = SoapClient::extract_xml_value(&response.body, "RemainingSleepTimerDuration").unwrap_or_default();
+    parse_sleep_timer_duration(&remaining)
+  }
+
+  /// Current transport state, for when no AVTransport event has arrived yet
+  pub fn get_transport_state(&self) -> Result<PlaybackState> {
+    let response = self.av_transport("GetTransportInfo", vec![])?;
+    let state = SoapClient::extract_xml_value(&response.body, "CurrentTransportState").unwrap_or_default();
+    PlaybackState::from_transport_state(&state)
+      .ok_or_else(|| SonosError::ParseError(format!("Invalid CurrentTransportState: {}", state)))
+  }
+
+  /// Current position and duration of the playing track.
+  ///
+  /// Streams without a fixed length report a zero duration.
+  pub fn position_info(&self) -> Result<(Duration, Duration)> {
+    let response = self.av_transport("GetPositionInfo", vec![])?;
+    let field = |name: &str| {
+      let value = SoapClient::extract_xml_value(&response.body, name).unwrap_or_default();
+      parse_track_time(&value).ok_or_else(|| SonosError::ParseError(format!("Invalid {}: {}", name, value)))
+    };
+    Ok((field("RelTime")?, field("TrackDuration")?))
+  }
+
+  /// Tracks in the play queue, in queue order.
+  ///
+  /// The queue is browsed in pages of `BROWSE_PAGE_SIZE`, so long queues take
+  /// several requests.
+  pub fn get_queue(&self) -> Result<Vec<TrackInfo>> {
+    let mut tracks = Vec::new();
+    loop {
+      let page: BrowseResponse = self.browse("Q:0", tracks.len())?;
+      tracks.extend(page.tracks());
+      if page.result().number_returned == 0 || tracks.len() >= page.result().total_matches as usize {
+        return Ok(tracks);
+      }
+    }
+  }
+
+  /// Sonos favorites ("My Sonos"), in the order the Sonos app shows them.
+  ///
+  /// Favorites without a playable URI, such as shortcuts into a service, are
+  /// left out.
+  pub fn get_favorites(&self) -> Result<Vec<Favorite>> {
+    let mut favorites = Vec::new();
+    let mut browsed = 0;
+    loop {
+      let page: BrowseResponse<FavoritesListing> = self.browse("FV:2", browsed)?;
+      browsed += page.result().number_returned as usize;
+      favorites.extend(page.favorites());
+      if page.result().number_returned == 0 || browsed >= page.result().total_matches as usize {
+        return Ok(favorites);
+      }
+    }
+  }
+
+  /// Start playing a favorite from [`get_favorites`](Self::get_favorites).
+  ///
+  /// Stations and single tracks become the current source; playlists and
+  /// albums replace the queue and play from its first track.
+  pub fn play_favorite(&self, favorite: &Favorite) -> Result<()> {
+    if favorite.is_container() {
+      self.clear_queue()?;
+      self.add_uri_to_queue(&favorite.uri, &favorite.metadata, None)?;
+      self.play_queue_from_start()
+    } else {
+      self.set_uri(&favorite.uri, Some(&favorite.metadata))?;
+      self.play()
+    }
+  }
+
+  /// Sonos playlists (saved queues), as listed under "Sonos Playlists" in the app
+  pub fn get_playlists(&self) -> Result<Vec<Playlist>> {
+    let mut playlists = Vec::new();
+    loop {
+      let page: BrowseResponse<PlaylistsListing> = self.browse("SQ:", playlists.len())?;
+      playlists.extend(page.playlists());
+      if page.result().number_returned == 0 || playlists.len() >= page.result().total_matches as usize {
+        return Ok(playlists);
+      }
+    }
+  }
+
+  /// Replace the queue with `playlist` and play it from the first track
+  pub fn play_playlist(&self, playlist: &Playlist) -> Result<()> {
+    self.clear_queue()?;
+    self.add_uri_to_queue(&playlist.uri, "", None)?;
+    self.play_queue_from_start()
+  }
+
+  /// Add `uri` to the queue and return the new queue length.
+  ///
+  /// `metadata` is the DIDL-Lite describing the item and may be empty. A
+  /// `position` of `None` or `Some(0)` appends; any other position inserts
+  /// the item there, counting from 1.
+  pub fn add_uri_to_queue(&self, uri: &str, metadata: &str, position: Option<u32>) -> Result<u32> {
+    let position = position.unwrap_or(0);
+    let response = self.av_transport(
+      "AddURIToQueue",
+      vec![
+        ("EnqueuedURI", uri.to_string()),
+        ("EnqueuedURIMetaData", metadata.to_string()),
+        ("DesiredFirstTrackNumberEnqueued", position.to_string()),
+        ("EnqueueAsNext", bool_arg(position != 0)),
+      ],
+    )?;
+
+    SoapClient::extract_xml_value(&response.body, "NewQueueLength")
+      .and_then(|length| length.trim().parse::<u32>().ok())
+      .ok_or_else(|| SonosError::ParseError("Missing or invalid NewQueueLength".to_string()))
+  }
+
+  /// Remove every track from the queue
+  pub fn clear_queue(&self) -> Result<()> {
+    self.av_transport("RemoveAllTracksFromQueue", vec![])?;
+    Ok(())
+  }
+
+  /// Battery level, power source and health of a portable speaker.
+  ///
+  /// Returns `SonosError::Unsupported` without a request for models that have no battery.
+  pub fn battery_status(&self) -> Result<BatteryStatus> {
+    if !self.speaker.capabilities().has_battery {
+      return Err(SonosError::Unsupported(format!("{} has no battery", self.speaker.model_name)));
+    }
+    let body = self.soap_client.get(&self.device_url, "/status/batterystatus")?;
+    BatteryStatus::from_xml(&body)
+  }
+
+  /// Current master volume, from 0 to 100
+  pub fn get_volume(&self) -> Result<u8> {
+    self.get_volume_at(&self.device_url)
+  }
+
+  /// Set the master volume; values above 100 are clamped to 100
+  pub fn set_volume(&self, volume: u8) -> Result<()> {
+    self.set_volume_at(&self.device_url, volume)
+  }
+
+  /// Change the master volume by `delta` and return the volume the speaker settled on.
+  ///
+  /// The speaker applies the change itself, so concurrent adjustments from other
+  /// controllers aren't lost the way a read-then-set would lose them. The result
+  /// stays within 0-100.
+  pub fn adjust_volume(&self, delta: i8) -> Result<u8> {
+    let response = self.rendering_control(
+      "SetRelativeVolume",
+      vec![("Channel", "Master".to_string()), ("Adjustment", delta.to_string())],
+    )?;
+
+    SoapClient::extract_xml_value(&response.body, "NewVolume")
+      .and_then(|volume| volume.trim().parse::<i32>().ok())
+      .map(|volume| volume.clamp(0, 100) as u8)
+      .ok_or_else(|| SonosError::ParseError("Missing or invalid NewVolume".to_string()))
+  }
+
+  /// Whether the master channel is muted, for when no RenderingControl event has arrived yet
+  pub fn get_mute(&self) -> Result<bool> {
+    let response = self.rendering_control("GetMute", vec![("Channel", "Master".to_string())])?;
+    match SoapClient::extract_xml_value(&response.body, "CurrentMute").as_deref() {
+      Some("1") => Ok(true),
+      Some("0") => Ok(false),
+      other => Err(SonosError::ParseError(format!("Invalid CurrentMute: {:?}", other))),
+    }
+  }
+
+  /// Mute or unmute the master channel
+  pub fn set_mute(&self, muted: bool) -> Result<()> {
+    self.rendering_control(
+      "SetMute",
+      vec![("Channel", "Master".to_string()), ("DesiredMute", bool_arg(muted))],
+    )?;
+    Ok(())
+  }
+
+  /// Shift a stereo pair's sound from -100 (full left) to 100 (full right).
+  ///
+  /// Turns down the opposite side's `LF` or `RF` channel volume; 0 plays both
+  /// sides at full level. Speakers that aren't half of a stereo pair return
+  /// `SonosError::Unsupported`.
+  pub fn set_balance(&self, balance: i8) -> Result<()> {
+    if !(-100..=100).contains(&balance) {
+      return Err(SonosError::InvalidArgument(format!("Balance must be between -100 and 100, got {}", balance)));
+    }
+
+    let state = self.get_zone_group_state()?;
+    let in_stereo_pair = state
+      .zone_groups
+      .zone_groups
+      .iter()
+      .flat_map(|group| group.zone_group_members.iter())
+      .any(|member| member.uuid == self.speaker.id && matches!(member.bond_role, Some(BondRole::Left | BondRole::Right)));
+    if !in_stereo_pair {
+      return Err(SonosError::Unsupported(format!(
+        "{} is not part of a stereo pair",
+        self.speaker.id.as_str()
+      )));
+    }
+
+    let (left, right) = balance_channel_volumes(balance);
+    for (channel, volume) in [("LF", left), ("RF", right)] {
+      self.rendering_control(
+        "SetVolume",
+        vec![("Channel", channel.to_string()), ("DesiredVolume", volume.to_string())],
+      )?;
+    }
+    Ok(())
+  }
+
+  /// Set the bass level, from -10 to 10
+  pub fn set_bass(&self, level: i8) -> Result<()> {
+    validate_eq_level("Bass", level)?;
+    self.rendering_control("SetBass", vec![("DesiredBass", level.to_string())])?;
+    Ok(())
+  }
+
+  /// Set the treble level, from -10 to 10
+  pub fn set_treble(&self, level: i8) -> Result<()> {
+    validate_eq_level("Treble", level)?;
+    self.rendering_control("SetTreble", vec![("DesiredTreble", level.to_string())])?;
+    Ok(())
+  }
+
+  /// Turn loudness compensation on or off
+  pub fn set_loudness(&self, on: bool) -> Result<()> {
+    self.rendering_control(
+      "SetLoudness",
+      vec![("Channel", "Master".to_string()), ("DesiredLoudness", bool_arg(on))],
+    )?;
+    Ok(())
+  }
+
+  /// Turn Night Sound on or off.
+  ///
+  /// Only home theater products support this; others return `SonosError::Unsupported`,
+  /// so callers can use it to probe capabilities.
+  pub fn set_night_mode(&self, on: bool) -> Result<()> {
+    self.set_eq("NightMode", bool_arg(on))
+  }
+
+  /// Turn Speech Enhancement on or off.
+  ///
+  /// Only home theater products support this; others return `SonosError::Unsupported`.
+  pub fn set_dialog_enhancement(&self, on: bool) -> Result<()> {
+    self.set_eq("DialogLevel", bool_arg(on))
+  }
+
+  /// Set the level of a bonded Sub, from -15 to 15.
+  ///
+  /// Speakers without a Sub return `SonosError::Unsupported`.
+  pub fn set_sub_gain(&self, gain: i8) -> Result<()> {
+    if !(-15..=15).contains(&gain) {
+      return Err(SonosError::InvalidArgument(format!("Sub gain must be between -15 and 15, got {}", gain)));
+    }
+    self.set_eq("SubGain", gain.to_string())
+  }
+
+  /// Switch a bonded Sub on or off.
+  ///
+  /// Speakers without a Sub return `SonosError::Unsupported`.
+  pub fn set_sub_enabled(&self, on: bool) -> Result<()> {
+    self.set_eq("SubEnable", bool_arg(on))
+  }
+
+  fn set_eq(&self, eq_type: &str, value: String) -> Result<()> {
+    match self.rendering_control(
+      "SetEQ",
+      vec![("EQType", eq_type.to_string()), ("DesiredValue", value)],
+    ) {
+      Ok(_) => Ok(()),
+      Err(SonosError::UpnpFault { code: UPNP_INVALID_ARGS, .. }) => {
+        Err(SonosError::Unsupported(format!("{} is not supported by this speaker", eq_type)))
+      }
+      Err(e) => Err(e),
+    }
+  }
+
+  /// Read the network's current grouping from this speaker's ZoneGroupTopology service
+  pub fn get_topology(&self) -> Result<Topology> {
+    Topology::from_zone_group_state(&self.get_zone_group_state()?)
+  }
+
+  fn get_zone_group_state(&self) -> Result<ZoneGroupState> {
+    let response = self.call(ServiceType::ZoneGroupTopology, "GetZoneGroupState", vec![])?;
+    let encoded = SoapClient::extract_xml_value(&response.body, "ZoneGroupState")
+      .ok_or_else(|| SonosError::ParseError("Missing ZoneGroupState".to_string()))?;
+    let decoded = quick_xml::escape::unescape(&encoded)
+      .map_err(|e| SonosError::ParseError(format!("Invalid ZoneGroupState: {}", e)))?;
+    crate::xml_decode::xml_decode::parse(&decoded)
+      .map_err(|e| SonosError::ParseError(format!("Invalid ZoneGroupState: {}", e)))
+  }
+
+  /// Join the group coordinated by `coordinator`.
+  ///
+  /// Once the speaker accepts, the ZoneGroupTopology subscription reports the
+  /// new grouping through a `GroupChange` event.
+  pub fn join_group(&self, coordinator: &SpeakerId) -> Result<()> {
+    self.set_uri(&format!("x-rincon:{}", rincon_uuid(coordinator)), None)
+  }
+
+  /// Play the analog line-in of `source`, which may be this speaker or another one.
+  ///
+  /// Returns `SonosError::Unsupported` when `source` is this speaker and its
+  /// model has no line-in.
+  pub fn play_line_in(&self, source: &SpeakerId) -> Result<()> {
+    if *source == self.speaker.id && !self.speaker.has_line_in() {
+      return Err(SonosError::Unsupported(format!("{} has no line-in", self.speaker.model_name)));
+    }
+    self.set_uri(&format!("x-rincon-stream:{}", rincon_uuid(source)), None)?;
+    self.play()
+  }
+
+  /// Switch a soundbar or amp to its TV input.
+  ///
+  /// Returns `SonosError::Unsupported` for models without a TV input.
+  pub fn play_tv(&self) -> Result<()> {
+    if !self.speaker.has_tv_input() {
+      return Err(SonosError::Unsupported(format!("{} has no TV input", self.speaker.model_name)));
+    }
+    self.set_uri(&format!("x-sonos-htastream:{}:spdif", rincon_uuid(&self.speaker.id)), None)?;
+    self.play()
+  }
+
+  /// Load `uri`, such as a radio stream or a direct file URL, as the current source.
+  ///
+  /// `metadata` is the DIDL-Lite describing the item; it is escaped into the
+  /// SOAP body, so pass it as plain XML. Playback doesn't start until
+  /// [`play`](Self::play) is called.
+  pub fn set_uri(&self, uri: &str, metadata: Option<&str>) -> Result<()> {
+    self.av_transport(
+      "SetAVTransportURI",
+      vec![
+        ("CurrentURI", uri.to_string()),
+        ("CurrentURIMetaData", metadata.unwrap_or_default().to_string()),
+      ],
+    )?;
+    Ok(())
+  }
+
+  /// Hand coordination of this speaker's group to `new_coordinator`, keeping playback going.
+  ///
+  /// `new_coordinator` must be a member of the same group, checked against the
+  /// current topology; otherwise `SonosError::InvalidArgument` is returned and
+  /// nothing is sent. The request goes to the group's current coordinator, which
+  /// stays in the group. Transferring to the current coordinator does nothing.
+  pub fn transfer_coordinator(&self, new_coordinator: &SpeakerId) -> Result<()> {
+    let topology = self.get_topology()?;
+    let group = topology.get_group_for_speaker(&self.speaker.id).ok_or_else(|| {
+      SonosError::InvalidState(format!("{} is not in any group", self.speaker.id.as_str()))
+    })?;
+    if !group.get_members().iter().any(|member| member.get_id() == new_coordinator) {
+      return Err(SonosError::InvalidArgument(format!(
+        "{} is not a member of group {}",
+        new_coordinator.as_str(),
+        group.get_id().as_str()
+      )));
+    }
+    if group.get_coordinator_id() == new_coordinator {
+      return Ok(());
+    }
+
+    let args = vec![
+      ("NewCoordinator", rincon_uuid(new_coordinator).to_string()),
+      ("RejoinGroup", bool_arg(true)),
+    ];
+    if *group.get_coordinator_id() == self.speaker.id {
+      self.av_transport("DelegateGroupCoordinationTo", args)?;
+    } else {
+      let device_url = topology
+        .coordinator_for(&self.speaker.id)
+        .and_then(|coordinator| coordinator.device_url())
+        .ok_or_else(|| {
+          SonosError::InvalidState(format!("No location known for coordinator of group {}", group.get_id().as_str()))
+        })?;
+      self.call_at(&device_url, ServiceType::AVTransport, "DelegateGroupCoordinationTo", args)?;
+    }
+    Ok(())
+  }
+
+  /// Leave the current group and become the coordinator of a standalone group
+  pub fn leave_group(&self) -> Result<()> {
+    self.av_transport("BecomeCoordinatorOfStandaloneGroup", vec![])?;
+    Ok(())
+  }
+
+  /// Set the volume of every group member so the group average matches `volume`.
+  ///
+  /// Members are scaled proportionally so their relative levels are preserved,
+  /// the same way the Sonos apps move the group volume slider. Returns the result
+  /// of setting each member's volume.
+  pub fn set_group_volume(&self, group: &Group, volume: u8) -> Result<Vec<(SpeakerId, Result<u8>)>> {
+    let members = group
+      .get_members()
+      .iter()
+      .map(|member| {
+        member
+          .device_url()
+          .map(|device_url| (member.get_id().clone(), device_url))
+          .ok_or_else(|| SonosError::InvalidState(format!("No location known for group member {}", member.get_id())))
+      })
+      .collect::<Result<Vec<_>>>()?;
+
+    let current = members
+      .iter()
+      .map(|(_, device_url)| self.get_volume_at(device_url))
+      .collect::<Result<Vec<_>>>()?;
+
+    let targets = scale_group_volume(&current, volume);
+
+    Ok(
+      members
+        .into_iter()
+        .zip(targets)
+        .map(|((speaker_id, device_url), target)| {
+          let result = self.set_volume_at(&device_url, target).map(|_| target);
+          (speaker_id, result)
+        })
+        .collect(),
+    )
+  }
+
+  /// Set several speakers' volumes at once, e.g. a whole-house volume change.
+  ///
+  /// Speakers are located from the grouping this speaker reports and the
+  /// requests run in parallel, at most `MAX_PARALLEL_REQUESTS` at a time, so
+  /// the speakers change together instead of one after another. Returns the
+  /// result for each target in order, or an error if the grouping couldn't be
+  /// read. Bonded satellites (surrounds, subs) follow their main speaker, so
+  /// targeting one reports [`SonosError::SatelliteSpeaker`].
+  pub fn set_volume_all(&self, targets: &[(SpeakerId, u8)]) -> Result<Vec<(SpeakerId, Result<()>)>> {
+    let topology = self.get_topology()?;
+    Ok(self.set_volume_all_in(&topology, targets))
+  }
+
+  fn set_volume_all_in(&self, topology: &Topology, targets: &[(SpeakerId, u8)]) -> Vec<(SpeakerId, Result<()>)> {
+    let next = AtomicUsize::new(0);
+    let results: Vec<Mutex<Option<Result<()>>>> = targets.iter().map(|_| Mutex::new(None)).collect();
+    let workers = targets.len().min(MAX_PARALLEL_REQUESTS);
+
+    std::thread::scope(|scope| {
+      for _ in 0..workers {
+        scope.spawn(|| loop {
+          let index = next.fetch_add(1, Ordering::SeqCst);
+          let Some((speaker_id, volume)) = targets.get(index) else { break };
+
+          let result = Self::volume_member(topology, speaker_id)
+            .and_then(|member| {
+              member
+                .device_url()
+                .ok_or_else(|| SonosError::InvalidState(format!("No location known for {}", speaker_id)))
+            })
+            .and_then(|device_url| self.set_volume_at(&device_url, *volume));
+          if let Ok(mut slot) = results[index].lock() {
+            *slot = Some(result);
+          }
+        });
+      }
+    });
+
+    targets
+      .iter()
+      .zip(results)
+      .map(|((speaker_id, _), result)| {
+        let result = result.into_inner().ok().flatten().unwrap_or_else(|| {
+          Err(SonosError::InvalidState(format!("No volume request made for {}", speaker_id)))
+        });
+        (speaker_id.clone(), result)
+      })
+      .collect()
+  }
+
+  /// The group member whose volume `speaker_id` sets
+  fn volume_member<'a>(topology: &'a Topology, speaker_id: &SpeakerId) -> Result<&'a SpeakerRef> {
+    let group = topology
+      .get_group_for_speaker(speaker_id)
+      .ok_or_else(|| SonosError::DeviceNotFound(format!("{} is not in the topology", speaker_id)))?;
+    let member = group
+      .get_members()
+      .iter()
+      .find(|member| member.get_id() == speaker_id || member.get_satellites().contains(speaker_id))
+      .ok_or_else(|| SonosError::DeviceNotFound(format!("{} is not in the topology", speaker_id)))?;
+    if member.get_id() != speaker_id {
+      return Err(SonosError::SatelliteSpeaker(format!(
+        "{} is bonded to {}, which sets its volume",
+        speaker_id,
+        member.get_id()
+      )));
+    }
+    Ok(member)
+  }
+
+  /// Pause every group this speaker's household knows about.
+  ///
+  /// The grouping is read from this speaker, and only coordinators are sent
+  /// the command since members follow them. Returns the result for each group
+  /// so partial failures are visible, or an error if the grouping couldn't be
+  /// read.
+  pub fn pause_all(&self) -> Result<Vec<(GroupId, Result<()>)>> {
+    let topology = self.get_topology()?;
+    Ok(self.for_each_coordinator(&topology, "Pause", vec![]))
+  }
+
+  /// Start playback in every group; see [`Self::pause_all`]
+  pub fn play_all(&self) -> Result<Vec<(GroupId, Result<()>)>> {
+    let topology = self.get_topology()?;
+    Ok(self.for_each_coordinator(&topology, "Play", vec![("Speed", "1".to_string())]))
+  }
+
+  fn for_each_coordinator(
+    &self,
+    topology: &Topology,
+    action: &str,
+    args: Vec<(&str, String)>,
+  ) -> Vec<(GroupId, Result<()>)> {
+    topology
+      .get_groups()
+      .iter()
+      .map(|group| {
+        let result = topology
+          .coordinator_for(group.get_coordinator_id())
+          .and_then(|coordinator| coordinator.device_url())
+          .ok_or_else(|| {
+            SonosError::InvalidState(format!("No location known for coordinator of group {}", group.get_id().as_str()))
+          })
+          .and_then(|device_url| self.call_at(&device_url, ServiceType::AVTransport, action, args.clone()))
+          .map(|_| ());
+        (group.get_id().clone(), result)
+      })
+      .collect()
+  }
+
+  /// Browse one page of a ContentDirectory container's direct children
+  fn browse<L: DeserializeOwned>(&self, object_id: &str, starting_index: usize) -> Result<BrowseResponse<L>> {
+    let response = self.call(
+      ServiceType::ContentDirectory,
+      "Browse",
+      vec![
+        ("ObjectID", object_id.to_string()),
+        ("BrowseFlag", "BrowseDirectChildren".to_string()),
+        ("Filter", "*".to_string()),
+        ("StartingIndex", starting_index.to_string()),
+        ("RequestedCount", BROWSE_PAGE_SIZE.to_string()),
+        ("SortCriteria", String::new()),
+      ],
+    )?;
+    BrowseResponse::parse(&response.body)
+      .map_err(|e| SonosError::ParseError(format!("Invalid Browse response: {}", e)))
+  }
+
+  /// Make the queue the current source and play it from the first track
+  fn play_queue_from_start(&self) -> Result<()> {
+    self.set_uri(&format!("x-rincon-queue:{}#0", rincon_uuid(&self.speaker.id)), None)?;
+    self.seek_track(1)?;
+    self.play()
+  }
+
+  fn get_volume_at(&self, device_url: &str) -> Result<u8> {
+    let response = self.call_at(
+      device_url,
+      ServiceType::RenderingControl,
+      "GetVolume",
+      vec![("Channel", "Master".to_string())],
+    )?;
+
+    SoapClient::extract_xml_value(&response.body, "CurrentVolume")
+      .and_then(|volume| volume.trim().parse::<u8>().ok())
+      .ok_or_else(|| SonosError::ParseError("Missing or invalid CurrentVolume".to_string()))
+  }
+
+  fn set_volume_at(&self, device_url: &str, volume: u8) -> Result<()> {
+    self.call_at(
+      device_url,
+      ServiceType::RenderingControl,
+      "SetVolume",
+      vec![("Channel", "Master".to_string()), ("DesiredVolume", volume.min(100).to_string())],
+    )?;
+    Ok(())
+  }
+
+  /// Call an AVTransport action; `InstanceID=0` is always sent first
+  fn av_transport(&self, action: &str, args: Vec<(&str, String)>) -> Result<SoapResponse> {
+    self.call(ServiceType::AVTransport, action, args)
+  }
+
+  /// Call a RenderingControl action; `InstanceID=0` is always sent first
+  fn rendering_control(&self, action: &str, args: Vec<(&str, String)>) -> Result<SoapResponse> {
+    self.call(ServiceType::RenderingControl, action, args)
+  }
+
+  fn call(&self, service: ServiceType, action: &str, args: Vec<(&str, String)>) -> Result<SoapResponse> {
+    self.call_at(&self.device_url, service, action, args)
+  }
+
+  /// AVTransport and RenderingControl actions get `InstanceID=0` prepended;
+  /// other services take no instance argument.
+  fn call_at(&self, device_url: &str, service: ServiceType, action: &str, args: Vec<(&str, String)>) -> Result<SoapResponse> {
+    let mut request = SoapAction::new(service.service_type_urn(), action);
+    if matches!(service, ServiceType::AVTransport | ServiceType::RenderingControl) {
+      request = request.arg("InstanceID", "0");
+    }
+    let request = args.into_iter().fold(request, |request, (name, value)| request.arg(name, value));
+
+    self.soap_client.send(device_url, service.control_url(), &request)
+  }
+}
+
+/// The `RINCON_...` device UUID, without any `::N` zone suffix
+fn rincon_uuid(id: &SpeakerId) -> &str {
+  id.as_str().split("::").next().unwrap_or_default()
+}
+
+fn validate_eq_level(name: &str, level: i8) -> Result<()> {
+  if !(-10..=10).contains(&level) {
+    return Err(SonosError::InvalidArgument(format!("{} must be between -10 and 10, got {}", name, level)));
+  }
+  Ok(())
+}
+
+/// `LF` and `RF` channel volumes for a balance from -100 (full left) to 100 (full right)
+fn balance_channel_volumes(balance: i8) -> (u8, u8) {
+  let balance = balance.clamp(-100, 100);
+  let left = 100 - balance.max(0) as u8;
+  let right = 100 - balance.min(0).unsigned_abs();
+  (left, right)
+}
+
+fn bool_arg(on: bool) -> String {
+  if on { "1" } else { "0" }.to_string()
+}
+
+/// Format a track position as the `H:MM:SS` target AVTransport `Seek` expects.
+///
+/// Hours are not padded or capped, so ten hours is `10:00:00`. Sub-second
+/// precision is dropped.
+pub fn format_seek_time(position: Duration) -> String {
+  let total_seconds = position.as_secs();
+  format!(
+    "{}:{:02}:{:02}",
+    total_seconds / 3600,
+    (total_seconds % 3600) / 60,
+    total_seconds % 60
+  )
+}
+
+/// Parse the `RemainingSleepTimerDuration` returned by `GetRemainingSleepTimerDuration`.
+///
+/// The speaker reports an empty string when no timer is set.
+pub fn parse_sleep_timer_duration(value: &str) -> Result<Option<Duration>> {
+  let value = value.trim();
+  if value.is_empty() {
+    return Ok(None);
+  }
+  parse_hms(value)
+    .map(Some)
+    .ok_or_else(|| SonosError::ParseError(format!("Invalid sleep timer duration: {}", value)))
+}
+
+/// Parse a `RelTime` or `TrackDuration` value from `GetPositionInfo`.
+///
+/// Radio streams and idle speakers report `NOT_IMPLEMENTED` or an empty value,
+/// which map to zero.
+pub fn parse_track_time(value: &str) -> Option<Duration> {
+  match value.trim() {
+    "" | "NOT_IMPLEMENTED" => Some(Duration::ZERO),
+    value => parse_hms(value),
+  }
+}
+
+/// Parse an `H:MM:SS` time as used throughout AVTransport
+fn parse_hms(value: &str) -> Option<Duration> {
+  let mut parts = value.split(':');
+  let hours: u64 = parts.next()?.parse().ok()?;
+  let minutes: u64 = parts.next()?.parse().ok()?;
+  let seconds: u64 = parts.next()?.parse().ok()?;
+  if parts.next().is_some() || minutes >= 60 || seconds >= 60 {
+    return None;
+  }
+  Some(Duration::from_secs(hours * 3600 + minutes * 60 + seconds))
+}
+
+/// Scale member volumes so their average becomes `target` while keeping their ratios.
+///
+/// Results are clamped to 0-100. When every member is at zero there are no ratios
+/// to preserve, so everyone is set to `target`.
+pub fn scale_group_volume(current: &[u8], target: u8) -> Vec<u8> {
+  let target = target.min(100);
+  if current.is_empty() {
+    return Vec::new();
+  }
+
+  let total: u32 = current.iter().map(|&v| v as u32).sum();
+  if total == 0 {
+    return vec![target; current.len()];
+  }
+
+  let average = total as f64 / current.len() as f64;
+  let factor = target as f64 / average;
+
+  current
+    .iter()
+    .map(|&v| (v as f64 * factor).round().clamp(0.0, 100.0) as u8)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::model::SpeakerId;
+
+  #[test]
+  fn test_format_seek_time() {
+    assert_eq!(format_seek_time(Duration::from_secs(0)), "0:00:00");
+    assert_eq!(format_seek_time(Duration::from_secs(65)), "0:01:05");
+    assert_eq!(format_seek_time(Duration::from_secs(3725)), "1:02:05");
+    assert_eq!(format_seek_time(Duration::from_secs(36_000)), "10:00:00");
+    assert_eq!(format_seek_time(Duration::from_millis(59_999)), "0:00:59");
+  }
+
+  #[test]
+  fn test_balance_channel_volumes() {
+    assert_eq!(balance_channel_volumes(-100), (100, 0));
+    assert_eq!(balance_channel_volumes(-40), (100, 60));
+    assert_eq!(balance_channel_volumes(0), (100, 100));
+    assert_eq!(balance_channel_volumes(25), (75, 100));
+    assert_eq!(balance_channel_volumes(100), (0, 100));
+  }
+
+  #[test]
+  fn test_parse_sleep_timer_duration() {
+    assert_eq!(parse_sleep_timer_duration("").unwrap(), None);
+    assert_eq!(parse_sleep_timer_duration("  ").unwrap(), None);
+    assert_eq!(parse_sleep_timer_duration("0:29:59").unwrap(), Some(Duration::from_secs(1799)));
+    assert_eq!(parse_sleep_timer_duration("02:00:00").unwrap(), Some(Duration::from_secs(7200)));
+    assert!(matches!(parse_sleep_timer_duration("soon"), Err(SonosError::ParseError(_))));
+    assert!(matches!(parse_sleep_timer_duration("0:75:00"), Err(SonosError::ParseError(_))));
+  }
+
+  #[test]
+  fn test_parse_track_time() {
+    assert_eq!(parse_track_time("0:00:00"), Some(Duration::ZERO));
+    assert_eq!(parse_track_time("0:03:25"), Some(Duration::from_secs(205)));
+    assert_eq!(parse_track_time("12:00:01"), Some(Duration::from_secs(43_201)));
+    assert_eq!(parse_track_time("NOT_IMPLEMENTED"), Some(Duration::ZERO));
+    assert_eq!(parse_track_time(""), Some(Duration::ZERO));
+    assert_eq!(parse_track_time("0:00:60"), None);
+    assert_eq!(parse_track_time("0:03"), None);
+    assert_eq!(parse_track_time("1:2:3:4"), None);
+    assert_eq!(parse_track_time("-1:00:00"), None);
+  }
+
+  #[test]
+  fn test_scale_group_volume_preserves_ratios() {
+    assert_eq!(scale_group_volume(&[20, 40], 60), vec![40, 80]);
+    assert_eq!(scale_group_volume(&[30, 30, 30], 15), vec![15, 15, 15]);
+    assert_eq!(scale_group_volume(&[10, 50], 15), vec![5, 25]);
+  }
+
+  #[test]
+  fn test_scale_group_volume_clamps_to_max() {
+    // Average 50 -> 90 scales the louder member past 100
+    assert_eq!(scale_group_volume(&[20, 80], 90), vec![36, 100]);
+    assert_eq!(scale_group_volume(&[50], 250), vec![100]);
+  }
+
+  #[test]
+  fn test_scale_group_volume_all_zero() {
+    assert_eq!(scale_group_volume(&[0, 0, 0], 25), vec![25, 25, 25]);
+  }
+
+  #[test]
+  fn test_scale_group_volume_to_zero_and_empty() {
+    assert_eq!(scale_group_volume(&[35, 70], 0), vec![0, 0]);
+    assert!(scale_group_volume(&[], 40).is_empty());
+  }
+
+  #[test]
+  fn test_set_group_volume_requires_member_locations() {
+    use crate::group::SpeakerRef;
+    use crate::model::GroupId;
+
+    let speaker = Speaker {
+      id: SpeakerId::new("uuid:RINCON_123456789::1"),
+      name: "Living Room".to_string(),
+      room_name: "Living Room".to_string(),
+      ip_address: "192.168.1.100".to_string(),
+      port: 1400,
+      model_name: "Sonos One".to_string(),
+      satellites: vec![],
+      supported_services: vec![],
+      software_version: None,
+      hardware_version: None,
+    };
+    let group = Group::new(
+      GroupId::new("RINCON_123456789:1"),
+      speaker.id.clone(),
+      vec![SpeakerRef::new(speaker.id.clone(), vec![])],
+    );
+
+    let controller = SpeakerController::new(&speaker).unwrap();
+    assert!(matches!(controller.set_group_volume(&group, 30), Err(SonosError::InvalidState(_))));
+  }
+
+  #[test]
+  fn test_device_url() {
+    let speaker = Speaker {
+      id: SpeakerId::new("uuid:RINCON_123456789::1"),
+      name: "Living Room".to_string(),
+      room_name: "Living Room".to_string(),
+      ip_address: "192.168.1.100".to_string(),
+      port: 1400,
+      model_name: "Sonos One".to_string(),
+      satellites: vec![],
+      supported_services: vec![],
+      software_version: None,
+      hardware_version: None,
+    };
+
+    let controller = SpeakerController::new(&speaker).unwrap();
+    assert_eq!(controller.device_url(), "http://192.168.1.100:1400");
+  }
+}
@@ -1,11 +1,23 @@
+use crate::streaming::StreamError;
+
 #[derive(Debug)]
 pub enum SonosError {
   CommunicationError(String),
   DeviceNotFound(String),
   DiscoveryFailed(String),
+  InvalidArgument(String),
   InvalidState(String),
   ParseError(String),
   SoapFault(String),
+  /// The event stream failed to start; keeps the stream's own error, e.g.
+  /// `AllSpeakersFailed` with the reason each speaker refused
+  StreamFailed(StreamError),
+  /// The device answered 503: it is a bonded satellite (surround, sub) that only
+  /// its main speaker controls. Expected, so UIs can skip it quietly.
+  SatelliteSpeaker(String),
+  /// SOAP fault carrying a UPnP error code, e.g. 701 "Transition not available"
+  UpnpFault { code: u16, description: String },
+  Unsupported(String),
 }
 
 impl std::fmt::Display for SonosError {
@@ -14,13 +26,25 @@ impl std::fmt::Display for SonosError {
             SonosError::CommunicationError(msg) => write!(f, "Communication error: {}", msg),
             SonosError::DeviceNotFound(msg) => write!(f, "Device not found: {}", msg),
             SonosError::DiscoveryFailed(msg) => write!(f, "Discovery failed: {}", msg),
+            SonosError::InvalidArgument(msg) => write!(f, "Invalid argument: {}", msg),
             SonosError::InvalidState(msg) => write!(f, "Invalid state: {}", msg),
             SonosError::ParseError(msg) => write!(f, "Parse error: {}", msg),
             SonosError::SoapFault(msg) => write!(f, "SOAP fault: {}", msg),
+            SonosError::StreamFailed(err) => write!(f, "Event stream failed: {}", err),
+            SonosError::SatelliteSpeaker(msg) => write!(f, "Satellite speaker: {}", msg),
+            SonosError::UpnpFault { code, description } => write!(f, "UPnP error {}: {}", code, description),
+            SonosError::Unsupported(msg) => write!(f, "Unsupported: {}", msg),
         }
     }
 }
 
-impl std::error::Error for SonosError {}
+impl std::error::Error for SonosError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SonosError::StreamFailed(err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 pub type Result<T> = std::result::Result<T, SonosError>;
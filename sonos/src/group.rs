@@ -1,32 +1,79 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{GroupId, SonosError, SpeakerId, service::zone_group_topology::parser::{ZoneGroup, ZoneGroupMember}};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpeakerRef {
   id: SpeakerId,
   satellite_ids: Vec<SpeakerId>,
+  #[serde(default)]
+  location: Option<String>,
+  #[serde(default)]
+  room_name: Option<String>,
 }
 
 impl SpeakerRef {
+  pub fn new(id: SpeakerId, satellite_ids: Vec<SpeakerId>) -> Self {
+    SpeakerRef { id, satellite_ids, location: None, room_name: None }
+  }
+
+  pub fn with_location(mut self, location: impl Into<String>) -> Self {
+    self.location = Some(location.into());
+    self
+  }
+
+  pub fn with_room_name(mut self, room_name: impl Into<String>) -> Self {
+    self.room_name = Some(room_name.into());
+    self
+  }
+
   pub fn from_zone_group_member(zone_group_member: &ZoneGroupMember) -> Result<Self, SonosError> {
     Ok(SpeakerRef {
       id: zone_group_member.uuid.clone(),
       satellite_ids: zone_group_member.satellites
         .iter()
         .map(|satellite| satellite.uuid.clone())
-        .collect()
+        .collect(),
+      location: Some(zone_group_member.location.clone()),
+      room_name: Some(zone_group_member.zone_name.clone()),
     })
   }
 
+  /// Device description URL reported by the topology, e.g. `http://192.168.1.10:1400/xml/device_description.xml`
+  pub fn get_location(&self) -> Option<&str> {
+    self.location.as_deref()
+  }
+
+  /// Base URL (`http://ip:port`) for control requests, derived from the location
+  pub fn device_url(&self) -> Option<String> {
+    let location = self.location.as_deref()?;
+    let (scheme, rest) = location.split_once("://")?;
+    let host = rest.split('/').next().filter(|host| !host.is_empty())?;
+    Some(format!("{}://{}", scheme, host))
+  }
+
   pub fn get_id(&self) -> &SpeakerId {
     &self.id
   }
 
+  /// Room (zone) name from the topology, shared by the speaker's satellites
+  pub fn get_room_name(&self) -> Option<&str> {
+    self.room_name.as_deref()
+  }
+
   pub fn get_satellites(&self) -> &[SpeakerId] {
     &self.satellite_ids
   }
+
+  /// Whether both refs describe the same speaker with the same satellites, in any order
+  pub fn same_member(&self, other: &SpeakerRef) -> bool {
+    self.id == other.id
+      && self.satellite_ids.len() == other.satellite_ids.len()
+      && self.satellite_ids.iter().all(|id| other.satellite_ids.contains(id))
+  }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Group {
   id: GroupId,
   coordinator_id: SpeakerId,
@@ -34,6 +81,10 @@ pub struct Group {
 }
 
 impl Group {
+  pub fn new(id: GroupId, coordinator_id: SpeakerId, members: Vec<SpeakerRef>) -> Self {
+    Group { id, coordinator_id, members }
+  }
+
   pub fn from_zone_group(zone_group: &ZoneGroup) -> Result<Self, SonosError> {
     let members = zone_group.zone_group_members
       .iter()
@@ -58,4 +109,140 @@ impl Group {
   pub fn get_members(&self) -> &[SpeakerRef] {
     &self.members
   }
+
+  /// Group identity that survives coordinator changes, see [`GroupId::from_members`]
+  pub fn stable_id(&self) -> GroupId {
+    GroupId::from_members(self.members.iter().map(|member| member.get_id()))
+  }
+
+  /// Pair each group in `new` with the group in `old` it continues
+  ///
+  /// Groups with the same members ([`stable_id`](Self::stable_id)) are paired
+  /// first, whatever their coordinators, so a coordinator swap keeps its group.
+  /// Remaining groups are paired by Sonos group ID. Groups left unpaired
+  /// appeared or dissolved. Used by both the ZoneGroupTopology subscription
+  /// and [`topology::diff`](crate::topology::diff).
+  pub fn match_groups<'a>(old: &'a [Group], new: &'a [Group]) -> Vec<(&'a Group, &'a Group)> {
+    let mut pairs: Vec<(&Group, &Group)> = Vec::new();
+    let mut unpaired: Vec<&Group> = Vec::new();
+
+    for group in new {
+      let stable_id = group.stable_id();
+      match old.iter().find(|previous| {
+        previous.stable_id() == stable_id && !pairs.iter().any(|(paired, _)| std::ptr::eq(*paired, *previous))
+      }) {
+        Some(previous) => pairs.push((previous, group)),
+        None => unpaired.push(group),
+      }
+    }
+
+    for group in unpaired {
+      if let Some(previous) = old.iter().find(|previous| {
+        previous.id == group.id && !pairs.iter().any(|(paired, _)| std::ptr::eq(*paired, *previous))
+      }) {
+        pairs.push((previous, group));
+      }
+    }
+
+    pairs
+  }
+
+  /// Whether both groups have the same coordinator and members, ignoring member order
+  pub fn same_topology(&self, other: &Group) -> bool {
+    self.coordinator_id == other.coordinator_id
+      && self.members.len() == other.members.len()
+      && self.members
+        .iter()
+        .all(|member| other.members.iter().any(|other_member| member.same_member(other_member)))
+  }
+
+  /// Whether two group sets are structurally identical, ignoring group order
+  pub fn same_topologies(groups: &[Group], other: &[Group]) -> bool {
+    groups.len() == other.len()
+      && groups
+        .iter()
+        .all(|group| other.iter().any(|other_group| group.same_topology(other_group)))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_speaker_ref_device_url() {
+    let speaker_ref = SpeakerRef::new(SpeakerId::new("RINCON_5CAAFDAE58BD01400"), vec![])
+      .with_location("http://192.168.4.40:1400/xml/device_description.xml");
+
+    assert_eq!(speaker_ref.device_url(), Some("http://192.168.4.40:1400".to_string()));
+    assert_eq!(SpeakerRef::new(SpeakerId::new("RINCON_1"), vec![]).device_url(), None);
+    assert_eq!(
+      SpeakerRef::new(SpeakerId::new("RINCON_1"), vec![]).with_location("not a url").device_url(),
+      None
+    );
+  }
+
+  #[test]
+  fn test_group_same_topology() {
+    let living_room = SpeakerRef::new(
+      SpeakerId::new("RINCON_1"),
+      vec![SpeakerId::new("RINCON_SAT1"), SpeakerId::new("RINCON_SAT2")],
+    );
+    let kitchen = SpeakerRef::new(SpeakerId::new("RINCON_2"), vec![]);
+    let group = Group::new(
+      GroupId::new("RINCON_1:1"),
+      SpeakerId::new("RINCON_1"),
+      vec![living_room.clone(), kitchen.clone()],
+    );
+
+    let reordered = Group::new(
+      GroupId::new("RINCON_1:1"),
+      SpeakerId::new("RINCON_1"),
+      vec![
+        kitchen.clone(),
+        SpeakerRef::new(
+          SpeakerId::new("RINCON_1"),
+          vec![SpeakerId::new("RINCON_SAT2"), SpeakerId::new("RINCON_SAT1")],
+        ),
+      ],
+    );
+    assert!(group.same_topology(&reordered));
+
+    let new_coordinator = Group::new(
+      GroupId::new("RINCON_2:1"),
+      SpeakerId::new("RINCON_2"),
+      vec![living_room.clone(), kitchen.clone()],
+    );
+    assert!(!group.same_topology(&new_coordinator));
+
+    let lost_satellite = Group::new(
+      GroupId::new("RINCON_1:1"),
+      SpeakerId::new("RINCON_1"),
+      vec![SpeakerRef::new(SpeakerId::new("RINCON_1"), vec![SpeakerId::new("RINCON_SAT1")]), kitchen],
+    );
+    assert!(!group.same_topology(&lost_satellite));
+
+    assert!(Group::same_topologies(&[group.clone(), new_coordinator.clone()], &[new_coordinator, group.clone()]));
+    assert!(!Group::same_topologies(&[group.clone()], &[group, lost_satellite]));
+  }
+
+  #[test]
+  fn test_stable_id_ignores_coordinator_and_order() {
+    let a = SpeakerId::new("RINCON_A");
+    let b = SpeakerId::new("RINCON_B");
+    let led_by_a = Group::new(
+      GroupId::new("RINCON_A:1"),
+      a.clone(),
+      vec![SpeakerRef::new(a.clone(), vec![]), SpeakerRef::new(b.clone(), vec![])],
+    );
+    let led_by_b = Group::new(
+      GroupId::new("RINCON_B:7"),
+      b.clone(),
+      vec![SpeakerRef::new(b.clone(), vec![]), SpeakerRef::new(a.clone(), vec![])],
+    );
+
+    assert_eq!(led_by_a.stable_id(), led_by_b.stable_id());
+    assert_eq!(led_by_a.stable_id(), GroupId::new("RINCON_A+RINCON_B"));
+    assert_ne!(led_by_a.stable_id(), GroupId::from_members([&a]));
+  }
 }
@@ -0,0 +1,245 @@
+//! HTTP/JSON control of speakers known to a [`StateCache`]
+//!
+//! Enabled with the `rest` feature. [`RestServer`] serves:
+//!
+//! - `GET /speakers`: every cached [`SpeakerState`], sorted by room
+//! - `GET /speakers/{id}/state`: one speaker's cached state
+//! - `POST /speakers/{id}/play` and `POST /speakers/{id}/pause`
+//! - `POST /speakers/{id}/volume` with a body like `{"volume": 30}`
+//!
+//! `{id}` is the speaker ID, e.g. `RINCON_000E58A0123401400`. Control requests
+//! go to the speaker through a [`SpeakerController`] and answer with the
+//! speaker's state after the change. Errors answer with `{"error": "..."}` and
+//! a status matching the [`SonosError`]: 400 for bad input, 404 for an unknown
+//! speaker, 409 when the speaker can't do it and 502 when the speaker failed.
+//!
+//! The server only reads and nudges the cache; keep it current by feeding it
+//! from an event stream.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use sonos::integrations::http::RestServer;
+//! use sonos::StateCache;
+//!
+//! let cache = StateCache::new();
+//! cache.initialize(sonos::discover_speakers()?, vec![]);
+//!
+//! let mut server = RestServer::new(cache);
+//! let address = server.start("0.0.0.0:8080".parse()?)?;
+//! println!("Listening on http://{}", address);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::net::SocketAddr;
+use std::thread::JoinHandle;
+
+use serde::Deserialize;
+use tokio::sync::oneshot;
+use warp::http::StatusCode;
+use warp::reply::{self, Response};
+use warp::{Filter, Reply};
+
+use crate::error::{Result, SonosError};
+use crate::model::{PlaybackState, SpeakerId, SpeakerState};
+use crate::{SpeakerController, StateCache};
+
+/// Serves the REST API on a background thread until shut down or dropped
+pub struct RestServer {
+    state: StateCache,
+    server_handle: Option<JoinHandle<()>>,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+}
+
+impl RestServer {
+    /// Server backed by `state`
+    pub fn new(state: StateCache) -> Self {
+        Self {
+            state,
+            server_handle: None,
+            shutdown_tx: None,
+        }
+    }
+
+    /// Start listening on `address`, returning the address actually bound
+    ///
+    /// Port 0 lets the OS pick a free port.
+    pub fn start(&mut self, address: SocketAddr) -> Result<SocketAddr> {
+        if self.server_handle.is_some() {
+            return Err(SonosError::InvalidState("REST server is already running".to_string()));
+        }
+
+        let routes = routes(self.state.clone());
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+
+        let server_handle = std::thread::spawn(move || {
+            let rt = match tokio::runtime::Runtime::new() {
+                Ok(rt) => rt,
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e.to_string()));
+                    return;
+                }
+            };
+            rt.block_on(async move {
+                let shutdown = async move {
+                    let _ = shutdown_rx.await;
+                };
+                match warp::serve(routes).try_bind_with_graceful_shutdown(address, shutdown) {
+                    Ok((bound, server)) => {
+                        let _ = ready_tx.send(Ok(bound));
+                        server.await;
+                    }
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e.to_string()));
+                    }
+                }
+            });
+        });
+
+        let bound = ready_rx
+            .recv()
+            .unwrap_or_else(|_| Err("server thread exited".to_string()))
+            .map_err(|e| {
+                SonosError::CommunicationError(format!("Failed to start REST server on {}: {}", address, e))
+            })?;
+
+        log::info!("REST server listening on http://{}", bound);
+        self.server_handle = Some(server_handle);
+        self.shutdown_tx = Some(shutdown_tx);
+        Ok(bound)
+    }
+
+    /// Stop the server and wait for its thread to finish
+    pub fn shutdown(&mut self) -> Result<()> {
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            let _ = shutdown_tx.send(());
+        }
+
+        if let Some(handle) = self.server_handle.take() {
+            handle
+                .join()
+                .map_err(|_| SonosError::InvalidState("Failed to join REST server thread".to_string()))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for RestServer {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+/// Body of `POST /speakers/{id}/volume`
+#[derive(Debug, Deserialize)]
+struct VolumeRequest {
+    volume: u8,
+}
+
+fn routes(state: StateCache) -> impl Filter<Extract = (Response,), Error = warp::Rejection> + Clone + Send + Sync + 'static {
+    let with_state = warp::any().map(move || state.clone());
+
+    let list = warp::path!("speakers")
+        .and(warp::get())
+        .and(with_state.clone())
+        .map(list_speakers);
+
+    let speaker_state = warp::path!("speakers" / String / "state")
+        .and(warp::get())
+        .and(with_state.clone())
+        .map(|id: String, state: StateCache| match cached_speaker(&state, &id) {
+            Ok(speaker) => reply::json(&speaker).into_response(),
+            Err(e) => error_reply(&e),
+        });
+
+    let control = warp::path!("speakers" / String / String)
+        .and(warp::post())
+        .and(warp::body::bytes())
+        .and(with_state)
+        .and_then(control_speaker);
+
+    list.or(speaker_state).unify().or(control).unify()
+}
+
+fn list_speakers(state: StateCache) -> Response {
+    let mut speakers = state.get_all_speakers();
+    speakers.sort_by(|a, b| {
+        (&a.speaker.room_name, a.speaker.id.as_str()).cmp(&(&b.speaker.room_name, b.speaker.id.as_str()))
+    });
+    reply::json(&speakers).into_response()
+}
+
+async fn control_speaker(
+    id: String,
+    action: String,
+    body: bytes::Bytes,
+    state: StateCache,
+) -> std::result::Result<Response, warp::Rejection> {
+    let volume = match action.as_str() {
+        "play" | "pause" => None,
+        "volume" => match serde_json::from_slice::<VolumeRequest>(&body) {
+            Ok(request) => Some(request.volume),
+            Err(e) => {
+                return Ok(error_reply(&SonosError::InvalidArgument(format!(
+                    "Expected a body like {{\"volume\": 30}}: {}",
+                    e
+                ))))
+            }
+        },
+        _ => return Err(warp::reject::not_found()),
+    };
+
+    // The controller blocks on the speaker, so keep it off the async workers
+    let result = tokio::task::spawn_blocking(move || {
+        let speaker = cached_speaker(&state, &id)?;
+        let controller = SpeakerController::new(&speaker.speaker)?;
+        let speaker_id = speaker.speaker.id.clone();
+        match volume {
+            Some(volume) => {
+                controller.set_volume(volume)?;
+                state.update_volume(&speaker_id, volume.min(100));
+            }
+            None if action == "play" => {
+                controller.play()?;
+                state.update_playback_state(&speaker_id, PlaybackState::Playing);
+            }
+            None => {
+                controller.pause()?;
+                state.update_playback_state(&speaker_id, PlaybackState::Paused);
+            }
+        }
+        cached_speaker(&state, speaker_id.as_str())
+    })
+    .await
+    .unwrap_or_else(|e| Err(SonosError::InvalidState(format!("Control request failed: {}", e))));
+
+    Ok(match result {
+        Ok(speaker) => reply::json(&speaker).into_response(),
+        Err(e) => error_reply(&e),
+    })
+}
+
+fn cached_speaker(state: &StateCache, id: &str) -> Result<SpeakerState> {
+    state
+        .get_speaker(&SpeakerId::new(id))
+        .ok_or_else(|| SonosError::DeviceNotFound(format!("No speaker with ID {}", id)))
+}
+
+fn error_reply(error: &SonosError) -> Response {
+    let status = match error {
+        SonosError::InvalidArgument(_) => StatusCode::BAD_REQUEST,
+        SonosError::DeviceNotFound(_) => StatusCode::NOT_FOUND,
+        SonosError::InvalidState(_) | SonosError::SatelliteSpeaker(_) | SonosError::Unsupported(_) => {
+            StatusCode::CONFLICT
+        }
+        SonosError::CommunicationError(_)
+        | SonosError::DiscoveryFailed(_)
+        | SonosError::ParseError(_)
+        | SonosError::SoapFault(_)
+        | SonosError::StreamFailed(_)
+        | SonosError::UpnpFault { .. } => StatusCode::BAD_GATEWAY,
+    };
+    reply::with_status(reply::json(&serde_json::json!({ "error": error.to_string() })), status).into_response()
+}
@@ -0,0 +1,7 @@
+//! Bridges from the event stream to other systems, each behind its own feature
+
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+
+#[cfg(feature = "rest")]
+pub mod http;
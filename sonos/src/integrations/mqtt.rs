@@ -0,0 +1,590 @@
+//! Publish state changes to an MQTT broker
+//!
+//! Enabled with the `mqtt` feature. [`MqttBridge`] sends each [`StateChange`]
+//! as JSON to `<prefix>/<speaker_id>/<event_type>`, e.g.
+//! `home/sonos/RINCON_000E58A0123401400/VolumeChanged`. Changes that aren't
+//! about a single speaker (`GroupChange`, `CoordinatorChanged`) go to
+//! `<prefix>/<event_type>`.
+//!
+//! Publishing happens on a background thread, so a slow or unreachable broker
+//! never holds up event processing. Changes wait in a bounded queue and are
+//! dropped when it is full; after a failed publish the bridge backs off,
+//! dropping changes, before it tries the broker again.
+//!
+//! The built-in [`TcpMqttClient`] speaks just enough MQTT 3.1.1 to publish at
+//! QoS 0 over plain TCP, so the feature pulls in no extra dependencies. To use
+//! TLS or authentication, implement [`MqttClient`] over another MQTT library and
+//! pass it to [`MqttBridge::with_client`].
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use sonos::integrations::mqtt::MqttBridge;
+//! use sonos::streaming::EventStreamBuilder;
+//!
+//! let speakers = sonos::discover_speakers()?;
+//! let bridge = MqttBridge::new("mqtt://192.168.1.5:1883", "home/sonos")?;
+//!
+//! let stream = EventStreamBuilder::new(speakers)?
+//!     .with_event_handler(bridge.into_handler())
+//!     .start()?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::io::{self, Read, Write};
+use std::net::{Ipv6Addr, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::{Result, SonosError};
+use crate::model::{SpeakerId, StateChange};
+
+/// Port brokers listen on for plain MQTT
+const DEFAULT_PORT: u16 = 1883;
+
+/// Limit on connecting and waiting for the broker's CONNACK
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Changes that can wait for the publisher thread before new ones are dropped
+const QUEUE_CAPACITY: usize = 256;
+
+/// Wait after the first failed publish, doubled on each failure after that
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Longest wait between attempts to reach the broker
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Sends messages to an MQTT broker
+pub trait MqttClient: Send {
+    /// Publish `payload` to `topic` at QoS 0
+    fn publish(&mut self, topic: &str, payload: &[u8]) -> io::Result<()>;
+}
+
+/// Minimal MQTT 3.1.1 client that publishes at QoS 0 over plain TCP
+///
+/// Connects on the first publish. If the broker has dropped the connection,
+/// for example because it restarted, the next publish reconnects first, and a
+/// publish that fails to send is retried once on a fresh connection.
+pub struct TcpMqttClient {
+    address: String,
+    client_id: String,
+    stream: Option<TcpStream>,
+}
+
+impl TcpMqttClient {
+    /// Client for the broker at `address` (`host:port`)
+    pub fn new(address: impl Into<String>) -> Self {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        Self {
+            address: address.into(),
+            client_id: format!("sonos-rs-{}", &id[..8]),
+            stream: None,
+        }
+    }
+
+    fn connect(&self) -> io::Result<TcpStream> {
+        let address = self.address.to_socket_addrs()?.next().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, format!("No address found for {}", self.address))
+        })?;
+        let mut stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?;
+        stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+        stream.write_all(&connect_packet(&self.client_id))?;
+
+        let mut connack = [0u8; 4];
+        stream.read_exact(&mut connack)?;
+        if connack[..2] != [0x20, 0x02] {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Expected CONNACK from broker"));
+        }
+        if connack[3] != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                format!("Broker refused connection (return code {})", connack[3]),
+            ));
+        }
+        log::debug!("Connected to MQTT broker at {} as {}", self.address, self.client_id);
+        Ok(stream)
+    }
+
+    fn send(&mut self, packet: &[u8]) -> io::Result<()> {
+        if self.stream.as_ref().is_some_and(is_closed) {
+            log::debug!("MQTT broker at {} closed the connection", self.address);
+            self.stream = None;
+        }
+        let stream = match &mut self.stream {
+            Some(stream) => stream,
+            None => self.stream.insert(self.connect()?),
+        };
+
+        let result = stream.write_all(packet).and_then(|_| stream.flush());
+        if result.is_err() {
+            self.stream = None;
+        }
+        result
+    }
+}
+
+impl MqttClient for TcpMqttClient {
+    fn publish(&mut self, topic: &str, payload: &[u8]) -> io::Result<()> {
+        let packet = publish_packet(topic, payload);
+        self.send(&packet).or_else(|e| {
+            log::debug!("MQTT publish to {} failed ({}), reconnecting", topic, e);
+            self.send(&packet)
+        })
+    }
+}
+
+/// Publishes every [`StateChange`] it is given to an MQTT broker
+///
+/// Changes are queued and sent by a background thread that owns the client.
+/// The thread exits once the bridge, or its handler, is dropped and the queue
+/// has drained.
+pub struct MqttBridge {
+    queue: SyncSender<(String, Vec<u8>)>,
+    topic_prefix: String,
+}
+
+impl MqttBridge {
+    /// Bridge to the broker at `broker_url`, publishing under `topic_prefix`
+    ///
+    /// `broker_url` is `mqtt://host:port`, `tcp://host:port` or `host:port`;
+    /// the port defaults to 1883. Nothing is sent until the first change.
+    pub fn new(broker_url: &str, topic_prefix: impl Into<String>) -> Result<Self> {
+        let address = broker_address(broker_url)?;
+        Ok(Self::with_client(TcpMqttClient::new(address), topic_prefix))
+    }
+
+    /// Bridge that publishes through `client`
+    pub fn with_client(client: impl MqttClient + 'static, topic_prefix: impl Into<String>) -> Self {
+        let (queue, messages) = mpsc::sync_channel(QUEUE_CAPACITY);
+        thread::Builder::new()
+            .name("mqtt-publisher".to_string())
+            .spawn(move || run_publisher(client, messages))
+            .expect("Failed to spawn MQTT publisher thread");
+
+        Self {
+            queue,
+            topic_prefix: topic_prefix.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Queue `change` to be published as JSON to its topic
+    ///
+    /// Fails if the queue is full; broker errors are logged by the publisher
+    /// thread rather than returned here.
+    pub fn publish(&self, change: &StateChange) -> Result<()> {
+        let payload = serde_json::to_vec(change)
+            .map_err(|e| SonosError::ParseError(format!("Failed to serialize state change: {}", e)))?;
+        let topic = self.topic(change);
+
+        self.queue.try_send((topic, payload)).map_err(|e| match e {
+            TrySendError::Full((topic, _)) => {
+                SonosError::CommunicationError(format!("MQTT queue full, dropped change for {}", topic))
+            }
+            TrySendError::Disconnected((topic, _)) => {
+                SonosError::CommunicationError(format!("MQTT publisher stopped, dropped change for {}", topic))
+            }
+        })
+    }
+
+    /// Topic `change` is published to
+    pub fn topic(&self, change: &StateChange) -> String {
+        let event_type = event_type(change);
+        match speaker_id(change) {
+            Some(speaker_id) => format!("{}/{}/{}", self.topic_prefix, speaker_id.as_str(), event_type),
+            None => format!("{}/{}", self.topic_prefix, event_type),
+        }
+    }
+
+    /// Event handler for [`EventStreamBuilder::with_event_handler`](crate::streaming::EventStreamBuilder::with_event_handler)
+    ///
+    /// Changes that can't be queued are logged and dropped.
+    pub fn into_handler(self) -> impl Fn(StateChange) + Send + Sync + 'static {
+        move |change| {
+            if let Err(e) = self.publish(&change) {
+                log::warn!("{}", e);
+            }
+        }
+    }
+}
+
+/// Publish queued messages until every sender is gone, backing off after failures
+fn run_publisher(mut client: impl MqttClient, messages: Receiver<(String, Vec<u8>)>) {
+    let mut backoff = INITIAL_BACKOFF;
+    let mut retry_at: Option<Instant> = None;
+
+    for (topic, payload) in messages {
+        if retry_at.is_some_and(|retry_at| Instant::now() < retry_at) {
+            log::debug!("MQTT broker unavailable, dropped change for {}", topic);
+            continue;
+        }
+
+        match client.publish(&topic, &payload) {
+            Ok(()) => {
+                retry_at = None;
+                backoff = INITIAL_BACKOFF;
+            }
+            Err(e) => {
+                log::warn!("MQTT publish to {} failed ({}), retrying in {:?}", topic, e, backoff);
+                retry_at = Some(Instant::now() + backoff);
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+    }
+}
+
+/// `host:port` of the broker at `broker_url`
+///
+/// IPv6 hosts take brackets when they carry a port, e.g. `mqtt://[::1]:1884`.
+fn broker_address(broker_url: &str) -> Result<String> {
+    let invalid = || SonosError::InvalidArgument(format!("Invalid MQTT broker URL: {}", broker_url));
+    let address = ["mqtt://", "tcp://"]
+        .iter()
+        .find_map(|scheme| broker_url.strip_prefix(scheme))
+        .unwrap_or(broker_url)
+        .trim_end_matches('/');
+    if address.is_empty() || address.contains("://") || address.contains('/') {
+        return Err(invalid());
+    }
+    if address.parse::<Ipv6Addr>().is_ok() {
+        return Ok(format!("[{}]:{}", address, DEFAULT_PORT));
+    }
+
+    // A bracketed host has colons of its own, so only look for the port after it
+    let (host, port) = match address.strip_prefix('[') {
+        Some(bracketed) => {
+            let (ip, rest) = bracketed.split_once(']').ok_or_else(invalid)?;
+            if ip.parse::<Ipv6Addr>().is_err() {
+                return Err(invalid());
+            }
+            let port = match rest {
+                "" => None,
+                rest => Some(rest.strip_prefix(':').ok_or_else(invalid)?),
+            };
+            (&address[..ip.len() + 2], port)
+        }
+        None => match address.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (address, None),
+        },
+    };
+
+    match port {
+        Some(port) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok(address.to_string()),
+        Some(_) => Err(invalid()),
+        None => Ok(format!("{}:{}", host, DEFAULT_PORT)),
+    }
+}
+
+/// Name of the `StateChange` variant, as in the JSON `type` field
+fn event_type(change: &StateChange) -> &'static str {
+    match change {
+        StateChange::VolumeChanged { .. } => "VolumeChanged",
+        StateChange::MuteChanged { .. } => "MuteChanged",
+        StateChange::BassChanged { .. } => "BassChanged",
+        StateChange::TrebleChanged { .. } => "TrebleChanged",
+        StateChange::LoudnessChanged { .. } => "LoudnessChanged",
+        StateChange::SubGainChanged { .. } => "SubGainChanged",
+        StateChange::SubEnabledChanged { .. } => "SubEnabledChanged",
+        StateChange::PlaybackStateChanged { .. } => "PlaybackStateChanged",
+        StateChange::PlayModeChanged { .. } => "PlayModeChanged",
+        StateChange::CrossfadeChanged { .. } => "CrossfadeChanged",
+        StateChange::PositionChanged { .. } => "PositionChanged",
+        StateChange::GroupChange { .. } => "GroupChange",
+        StateChange::GroupMembershipChanged { .. } => "GroupMembershipChanged",
+        StateChange::CoordinatorChanged { .. } => "CoordinatorChanged",
+        StateChange::SpeakerVanished { .. } => "SpeakerVanished",
+        StateChange::TrackChanged { .. } => "TrackChanged",
+        StateChange::TransportInfoChanged { .. } => "TransportInfoChanged",
+        StateChange::QueueChanged { .. } => "QueueChanged",
+        StateChange::LibraryUpdated { .. } => "LibraryUpdated",
+        StateChange::SubscriptionError { .. } => "SubscriptionError",
+        StateChange::EventsMissed { .. } => "EventsMissed",
+    }
+}
+
+/// The speaker a change is about, if it is about one speaker
+fn speaker_id(change: &StateChange) -> Option<&SpeakerId> {
+    match change {
+        StateChange::VolumeChanged { speaker_id, .. }
+        | StateChange::MuteChanged { speaker_id, .. }
+        | StateChange::BassChanged { speaker_id, .. }
+        | StateChange::TrebleChanged { speaker_id, .. }
+        | StateChange::LoudnessChanged { speaker_id, .. }
+        | StateChange::SubGainChanged { speaker_id, .. }
+        | StateChange::SubEnabledChanged { speaker_id, .. }
+        | StateChange::PlaybackStateChanged { speaker_id, .. }
+        | StateChange::PlayModeChanged { speaker_id, .. }
+        | StateChange::CrossfadeChanged { speaker_id, .. }
+        | StateChange::PositionChanged { speaker_id, .. }
+        | StateChange::GroupMembershipChanged { speaker_id, .. }
+        | StateChange::SpeakerVanished { speaker_id, .. }
+        | StateChange::TrackChanged { speaker_id, .. }
+        | StateChange::TransportInfoChanged { speaker_id, .. }
+        | StateChange::QueueChanged { speaker_id }
+        | StateChange::LibraryUpdated { speaker_id }
+        | StateChange::SubscriptionError { speaker_id, .. }
+        | StateChange::EventsMissed { speaker_id, .. } => Some(speaker_id),
+        StateChange::GroupChange { .. } | StateChange::CoordinatorChanged { .. } => None,
+    }
+}
+
+/// Whether the broker has closed `stream`; it sends nothing else at QoS 0
+fn is_closed(stream: &TcpStream) -> bool {
+    if stream.set_nonblocking(true).is_err() {
+        return true;
+    }
+    let closed = match stream.peek(&mut [0u8; 1]) {
+        Ok(0) => true,
+        Ok(_) => false,
+        Err(e) => e.kind() != io::ErrorKind::WouldBlock,
+    };
+    stream.set_nonblocking(false).is_err() || closed
+}
+
+/// CONNECT with a clean session and keep-alive off, since QoS 0 needs no pings
+fn connect_packet(client_id: &str) -> Vec<u8> {
+    let mut body = Vec::new();
+    push_string(&mut body, "MQTT");
+    body.push(0x04); // protocol level 3.1.1
+    body.push(0x02); // clean session
+    body.extend_from_slice(&0u16.to_be_bytes());
+    push_string(&mut body, client_id);
+    packet(0x10, body)
+}
+
+/// PUBLISH at QoS 0, not retained
+fn publish_packet(topic: &str, payload: &[u8]) -> Vec<u8> {
+    let mut body = Vec::new();
+    push_string(&mut body, topic);
+    body.extend_from_slice(payload);
+    packet(0x30, body)
+}
+
+/// Prefix `body` with the fixed header: packet type and variable-length size
+fn packet(packet_type: u8, body: Vec<u8>) -> Vec<u8> {
+    let mut packet = vec![packet_type];
+    let mut remaining = body.len();
+    loop {
+        let mut byte = (remaining % 128) as u8;
+        remaining /= 128;
+        if remaining > 0 {
+            byte |= 0x80;
+        }
+        packet.push(byte);
+        if remaining == 0 {
+            break;
+        }
+    }
+    packet.extend(body);
+    packet
+}
+
+fn push_string(buffer: &mut Vec<u8>, value: &str) {
+    buffer.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    buffer.extend_from_slice(value.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GroupId;
+    use std::net::TcpListener;
+    use std::sync::mpsc::Sender;
+
+    struct RecordingClient(Sender<(String, Vec<u8>)>);
+
+    impl MqttClient for RecordingClient {
+        fn publish(&mut self, topic: &str, payload: &[u8]) -> io::Result<()> {
+            self.0.send((topic.to_string(), payload.to_vec())).unwrap();
+            Ok(())
+        }
+    }
+
+    /// Reports each attempt, then fails it as an unreachable broker would
+    struct FailingClient(Sender<String>);
+
+    impl MqttClient for FailingClient {
+        fn publish(&mut self, topic: &str, _payload: &[u8]) -> io::Result<()> {
+            self.0.send(topic.to_string()).unwrap();
+            Err(io::Error::new(io::ErrorKind::ConnectionRefused, "broker unreachable"))
+        }
+    }
+
+    /// Reports each attempt, then waits until released
+    struct BlockingClient(Sender<String>, Receiver<()>);
+
+    impl MqttClient for BlockingClient {
+        fn publish(&mut self, topic: &str, _payload: &[u8]) -> io::Result<()> {
+            self.0.send(topic.to_string()).unwrap();
+            let _ = self.1.recv();
+            Ok(())
+        }
+    }
+
+    fn volume_changed(volume: u8) -> StateChange {
+        StateChange::VolumeChanged { speaker_id: SpeakerId::new("RINCON_000E58A0123401400"), volume }
+    }
+
+    /// Read one packet, returning its type byte and body
+    fn read_packet(stream: &mut TcpStream) -> (u8, Vec<u8>) {
+        let mut header = [0u8; 1];
+        stream.read_exact(&mut header).unwrap();
+        let (mut length, mut multiplier) = (0usize, 1usize);
+        loop {
+            let mut byte = [0u8; 1];
+            stream.read_exact(&mut byte).unwrap();
+            length += (byte[0] & 0x7f) as usize * multiplier;
+            multiplier *= 128;
+            if byte[0] & 0x80 == 0 {
+                break;
+            }
+        }
+        let mut body = vec![0u8; length];
+        stream.read_exact(&mut body).unwrap();
+        (header[0], body)
+    }
+
+    /// Accept a client, answer its CONNECT and return the topic and payload of its first PUBLISH
+    fn accept_publish(listener: &TcpListener) -> (TcpStream, String, Vec<u8>) {
+        let (mut stream, _) = listener.accept().unwrap();
+        let (packet_type, connect) = read_packet(&mut stream);
+        assert_eq!(packet_type, 0x10);
+        assert_eq!(&connect[..7], b"\x00\x04MQTT\x04");
+        stream.write_all(&[0x20, 0x02, 0x00, 0x00]).unwrap();
+
+        let (packet_type, publish) = read_packet(&mut stream);
+        assert_eq!(packet_type, 0x30);
+        let topic_length = u16::from_be_bytes([publish[0], publish[1]]) as usize;
+        let topic = String::from_utf8(publish[2..2 + topic_length].to_vec()).unwrap();
+        (stream, topic, publish[2 + topic_length..].to_vec())
+    }
+
+    #[test]
+    fn test_volume_changed_published_to_speaker_topic() {
+        let (sender, published) = mpsc::channel();
+        let bridge = MqttBridge::with_client(RecordingClient(sender), "home/sonos/");
+
+        bridge.into_handler()(volume_changed(42));
+
+        let (topic, payload) = published.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(topic, "home/sonos/RINCON_000E58A0123401400/VolumeChanged");
+        let payload: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(
+            payload,
+            serde_json::json!({"type": "VolumeChanged", "speaker_id": "RINCON_000E58A0123401400", "volume": 42})
+        );
+    }
+
+    #[test]
+    fn test_changes_without_a_speaker_use_prefix_topic() {
+        let bridge = MqttBridge::with_client(RecordingClient(mpsc::channel().0), "home/sonos");
+        let change = StateChange::CoordinatorChanged {
+            group_id: GroupId::new("RINCON_000E58A0123401400:1"),
+            old_coordinator: SpeakerId::new("RINCON_000E58A0123401400"),
+            new_coordinator: SpeakerId::new("RINCON_347E5C0F2D3A01400"),
+        };
+
+        assert_eq!(bridge.topic(&change), "home/sonos/CoordinatorChanged");
+    }
+
+    #[test]
+    fn test_publishes_to_broker_and_reconnects_after_disconnect() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (disconnected_sender, disconnected) = mpsc::channel();
+        let broker = std::thread::spawn(move || {
+            let (first, topic, payload) = accept_publish(&listener);
+            drop(first);
+            disconnected_sender.send(()).unwrap();
+
+            let (_second, second_topic, second_payload) = accept_publish(&listener);
+            vec![(topic, payload), (second_topic, second_payload)]
+        });
+
+        let bridge = MqttBridge::new(&format!("mqtt://127.0.0.1:{}", port), "home/sonos").unwrap();
+        bridge.publish(&volume_changed(30)).unwrap();
+        disconnected.recv_timeout(Duration::from_secs(5)).unwrap();
+        // Give the close time to reach the client
+        std::thread::sleep(Duration::from_millis(100));
+        bridge.publish(&volume_changed(31)).unwrap();
+
+        let messages = broker.join().unwrap();
+        for ((topic, payload), volume) in messages.iter().zip([30, 31]) {
+            assert_eq!(topic, "home/sonos/RINCON_000E58A0123401400/VolumeChanged");
+            let payload: serde_json::Value = serde_json::from_slice(payload).unwrap();
+            assert_eq!(payload["volume"], volume);
+        }
+    }
+
+    #[test]
+    fn test_backs_off_after_failed_publish() {
+        let (sender, attempts) = mpsc::channel();
+        let bridge = MqttBridge::with_client(FailingClient(sender), "home/sonos");
+
+        for volume in 0..5 {
+            bridge.publish(&volume_changed(volume)).unwrap();
+        }
+
+        attempts.recv_timeout(Duration::from_secs(5)).unwrap();
+        // The rest arrive while backing off and are dropped without contacting the broker
+        assert!(attempts.recv_timeout(Duration::from_millis(200)).is_err());
+    }
+
+    #[test]
+    fn test_publish_fails_when_queue_is_full() {
+        let (sender, attempts) = mpsc::channel();
+        let (release, released) = mpsc::channel();
+        let bridge = MqttBridge::with_client(BlockingClient(sender, released), "home/sonos");
+
+        // Hold the publisher thread on the first change so the rest stay queued
+        bridge.publish(&volume_changed(0)).unwrap();
+        attempts.recv_timeout(Duration::from_secs(5)).unwrap();
+        let results: Vec<Result<()>> = (0..=QUEUE_CAPACITY).map(|_| bridge.publish(&volume_changed(1))).collect();
+
+        assert!(results[..QUEUE_CAPACITY].iter().all(|result| result.is_ok()));
+        assert!(matches!(results[QUEUE_CAPACITY], Err(SonosError::CommunicationError(_))));
+        drop(release);
+    }
+
+    #[test]
+    fn test_broker_address() {
+        assert_eq!(broker_address("mqtt://broker.local:1884").unwrap(), "broker.local:1884");
+        assert_eq!(broker_address("tcp://10.0.0.2").unwrap(), "10.0.0.2:1883");
+        assert_eq!(broker_address("broker.local").unwrap(), "broker.local:1883");
+        assert!(matches!(broker_address("mqtts://broker.local"), Err(SonosError::InvalidArgument(_))));
+        assert!(matches!(broker_address("mqtt://broker.local:port"), Err(SonosError::InvalidArgument(_))));
+        assert!(matches!(broker_address(""), Err(SonosError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_broker_address_with_ipv6_host() {
+        assert_eq!(broker_address("mqtt://[::1]:1884").unwrap(), "[::1]:1884");
+        assert_eq!(broker_address("tcp://[fe80::1]").unwrap(), "[fe80::1]:1883");
+        assert_eq!(broker_address("::1").unwrap(), "[::1]:1883");
+        assert!(matches!(broker_address("mqtt://[::1]:port"), Err(SonosError::InvalidArgument(_))));
+        assert!(matches!(broker_address("mqtt://[::1"), Err(SonosError::InvalidArgument(_))));
+        assert!(matches!(broker_address("mqtt://[broker.local]:1883"), Err(SonosError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn test_event_type_matches_json_type() {
+        let changes = [
+            volume_changed(42),
+            StateChange::GroupChange { groups: vec![] },
+            StateChange::QueueChanged { speaker_id: SpeakerId::new("RINCON_000E58A0123401400") },
+        ];
+
+        for change in &changes {
+            assert_eq!(serde_json::to_value(change).unwrap()["type"], event_type(change));
+        }
+    }
+
+    #[test]
+    fn test_remaining_length_uses_continuation_bytes() {
+        let packet = publish_packet("t", &[0u8; 200]);
+        // 2-byte topic length + 1-byte topic + 200-byte payload = 203 = 0xCB 0x01
+        assert_eq!(&packet[..3], &[0x30, 0xCB, 0x01]);
+        assert_eq!(packet.len(), 3 + 203);
+    }
+}
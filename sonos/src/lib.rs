@@ -7,10 +7,18 @@ pub mod streaming;
 pub mod service;
 pub mod xml_decode;
 pub mod group;
+pub mod topology;
+pub mod watch;
+pub mod integrations;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
 
 // Re-export key types for easier access
-pub use model::{Speaker, SpeakerId, GroupId, SpeakerState, PlaybackState, StateChange};
+pub use model::{Speaker, SpeakerId, GroupId, SpeakerState, PlaybackState, PlayMode, StateChange, ModelCapabilities};
 pub use error::{SonosError, Result};
-pub use state::StateCache;
-pub use transport::discovery::{discover_speakers, discover_speakers_with_timeout};
+pub use api::SpeakerController;
+pub use state::{StateCache, CacheSnapshot};
+pub use topology::Topology;
+pub use watch::watch_system;
+pub use transport::discovery::{discover_speakers, discover_speakers_async, discover_speakers_until, discover_speakers_with_config, discover_speakers_with_interface, discover_speakers_with_timeout, DiscoveryConfig};
 pub use streaming::{EventStreamBuilder, ActiveEventStream, ServiceType, StreamError, LifecycleHandlers, StreamStats};
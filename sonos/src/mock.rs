@@ -0,0 +1,289 @@
+//! Test doubles for exercising the streaming layer without real speakers
+//!
+//! Enabled with the `mock` feature. [`MockSpeaker`] builds [`Speaker`] values
+//! with sensible defaults, and [`MockSubscription`] stands in for a UPnP
+//! subscription whose subscribe/renew outcomes and parsed events are scripted.
+//! Pass [`MockSubscription::factory`] to
+//! [`EventStreamBuilder::with_subscription_factory`](crate::streaming::EventStreamBuilder::with_subscription_factory)
+//! to run a whole event stream offline.
+
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crate::model::{Speaker, SpeakerId, StateChange};
+use crate::streaming::subscription::{ServiceSubscription, SubscriptionError, SubscriptionResult};
+use crate::streaming::{ServiceType, SubscriptionConfig, SubscriptionFactory, SubscriptionId};
+
+/// Scripted stand-in for a UPnP service subscription
+///
+/// Subscribing and renewing succeed unless told otherwise, and every event
+/// notification parses to the same canned state changes regardless of its XML.
+#[derive(Debug, Clone)]
+pub struct MockSubscription {
+    service_type: ServiceType,
+    speaker_id: SpeakerId,
+    callback_url: String,
+    config: SubscriptionConfig,
+    events: Vec<StateChange>,
+    fail_subscribe: bool,
+    fail_renew: bool,
+    subscription_id: Option<SubscriptionId>,
+    last_renewal: Option<SystemTime>,
+}
+
+impl MockSubscription {
+    pub fn new(service_type: ServiceType, speaker_id: SpeakerId) -> Self {
+        Self {
+            service_type,
+            speaker_id,
+            callback_url: String::new(),
+            config: SubscriptionConfig::default(),
+            events: Vec::new(),
+            fail_subscribe: false,
+            fail_renew: false,
+            subscription_id: None,
+            last_renewal: None,
+        }
+    }
+
+    /// State changes returned from every `parse_event` call
+    pub fn with_events(mut self, events: Vec<StateChange>) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Make `subscribe` fail as if the speaker refused the request
+    pub fn failing_subscribe(mut self) -> Self {
+        self.fail_subscribe = true;
+        self
+    }
+
+    /// Make `renew` fail as if the subscription had expired on the speaker
+    pub fn failing_renew(mut self) -> Self {
+        self.fail_renew = true;
+        self
+    }
+
+    pub fn with_callback_url(mut self, callback_url: String) -> Self {
+        self.callback_url = callback_url;
+        self
+    }
+
+    pub fn with_config(mut self, config: SubscriptionConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Factory that gives every speaker/service a copy of `template`
+    ///
+    /// The service type, speaker, callback URL and config of the template are
+    /// replaced with the ones the subscription manager asks for.
+    pub fn factory(template: MockSubscription) -> SubscriptionFactory {
+        Arc::new(move |speaker: &Speaker, service_type, callback_url, config| {
+            let subscription = MockSubscription {
+                service_type,
+                speaker_id: speaker.id.clone(),
+                ..template.clone()
+            }
+            .with_callback_url(callback_url)
+            .with_config(config);
+            Ok(Box::new(subscription) as Box<dyn ServiceSubscription>)
+        })
+    }
+}
+
+impl ServiceSubscription for MockSubscription {
+    fn service_type(&self) -> ServiceType {
+        self.service_type
+    }
+
+    fn subscription_scope(&self) -> crate::streaming::SubscriptionScope {
+        self.service_type.subscription_scope()
+    }
+
+    fn speaker_id(&self) -> &SpeakerId {
+        &self.speaker_id
+    }
+
+    fn subscribe(&mut self) -> SubscriptionResult<SubscriptionId> {
+        if self.fail_subscribe {
+            return Err(SubscriptionError::SubscriptionFailed(format!(
+                "mock {:?} subscription refused",
+                self.service_type
+            )));
+        }
+
+        let id = SubscriptionId::new();
+        self.subscription_id = Some(id);
+        self.last_renewal = Some(SystemTime::now());
+        Ok(id)
+    }
+
+    fn unsubscribe(&mut self) -> SubscriptionResult<()> {
+        self.subscription_id = None;
+        self.last_renewal = None;
+        Ok(())
+    }
+
+    fn renew(&mut self) -> SubscriptionResult<()> {
+        if self.fail_renew || self.subscription_id.is_none() {
+            return Err(SubscriptionError::SubscriptionExpired);
+        }
+        self.last_renewal = Some(SystemTime::now());
+        Ok(())
+    }
+
+    fn parse_event(&self, _event_xml: &str) -> SubscriptionResult<Vec<StateChange>> {
+        Ok(self.events.clone())
+    }
+
+    fn is_active(&self) -> bool {
+        self.subscription_id.is_some()
+    }
+
+    fn last_renewal(&self) -> Option<SystemTime> {
+        self.last_renewal
+    }
+
+    fn subscription_id(&self) -> Option<SubscriptionId> {
+        self.subscription_id
+    }
+
+    fn get_config(&self) -> &SubscriptionConfig {
+        &self.config
+    }
+
+    fn callback_url(&self) -> &str {
+        &self.callback_url
+    }
+}
+
+/// Entry point for building fake speakers
+pub struct MockSpeaker;
+
+impl MockSpeaker {
+    pub fn builder() -> MockSpeakerBuilder {
+        MockSpeakerBuilder::default()
+    }
+}
+
+/// Builder for a [`Speaker`] that only has to look real to the streaming layer
+#[derive(Debug, Clone)]
+pub struct MockSpeakerBuilder {
+    speaker: Speaker,
+}
+
+impl Default for MockSpeakerBuilder {
+    fn default() -> Self {
+        Self {
+            speaker: Speaker {
+                id: SpeakerId::new("uuid:RINCON_MOCK00000001400::1"),
+                name: "Mock Speaker".to_string(),
+                room_name: "Mock Room".to_string(),
+                ip_address: "127.0.0.1".to_string(),
+                port: 1400,
+                model_name: "Sonos One".to_string(),
+                satellites: vec![],
+                supported_services: vec![],
+                software_version: None,
+                hardware_version: None,
+            },
+        }
+    }
+}
+
+impl MockSpeakerBuilder {
+    pub fn id(mut self, id: &str) -> Self {
+        self.speaker.id = SpeakerId::new(id);
+        self
+    }
+
+    /// Set both the speaker and room name
+    pub fn name(mut self, name: &str) -> Self {
+        self.speaker.name = name.to_string();
+        self.speaker.room_name = name.to_string();
+        self
+    }
+
+    pub fn ip(mut self, ip_address: &str) -> Self {
+        self.speaker.ip_address = ip_address.to_string();
+        self
+    }
+
+    pub fn port(mut self, port: u16) -> Self {
+        self.speaker.port = port;
+        self
+    }
+
+    pub fn model(mut self, model_name: &str) -> Self {
+        self.speaker.model_name = model_name.to_string();
+        self
+    }
+
+    pub fn satellites(mut self, satellites: Vec<SpeakerId>) -> Self {
+        self.speaker.satellites = satellites;
+        self
+    }
+
+    pub fn services(mut self, services: &[ServiceType]) -> Self {
+        self.speaker.supported_services = services.to_vec();
+        self
+    }
+
+    pub fn build(self) -> Speaker {
+        self.speaker
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_speaker_builder() {
+        let speaker = MockSpeaker::builder()
+            .id("uuid:RINCON_KITCHEN::1")
+            .name("Kitchen")
+            .ip("10.0.0.5")
+            .services(&[ServiceType::AVTransport])
+            .build();
+
+        assert_eq!(speaker.id, SpeakerId::new("uuid:RINCON_KITCHEN::1"));
+        assert_eq!(speaker.room_name, "Kitchen");
+        assert_eq!(speaker.ip_address, "10.0.0.5");
+        assert!(speaker.supports_service(ServiceType::AVTransport));
+        assert!(!speaker.supports_service(ServiceType::RenderingControl));
+    }
+
+    #[test]
+    fn test_mock_subscription_emits_canned_events() {
+        let speaker_id = SpeakerId::new("uuid:RINCON_KITCHEN::1");
+        let change = StateChange::VolumeChanged { speaker_id: speaker_id.clone(), volume: 25 };
+        let mut subscription = MockSubscription::new(ServiceType::RenderingControl, speaker_id)
+            .with_events(vec![change.clone()]);
+
+        subscription.subscribe().unwrap();
+        assert!(subscription.is_active());
+        assert!(subscription.renew().is_ok());
+
+        let changes = subscription.parse_event("<anything/>").unwrap();
+        assert_eq!(changes.len(), 1);
+        assert!(matches!(changes[0], StateChange::VolumeChanged { volume: 25, .. }));
+
+        subscription.unsubscribe().unwrap();
+        assert!(!subscription.is_active());
+    }
+
+    #[test]
+    fn test_mock_subscription_failures() {
+        let speaker_id = SpeakerId::new("uuid:RINCON_KITCHEN::1");
+
+        let mut refused = MockSubscription::new(ServiceType::AVTransport, speaker_id.clone()).failing_subscribe();
+        assert!(matches!(refused.subscribe(), Err(SubscriptionError::SubscriptionFailed(_))));
+        assert!(!refused.is_active());
+
+        let mut expiring = MockSubscription::new(ServiceType::AVTransport, speaker_id).failing_renew();
+        expiring.subscribe().unwrap();
+        assert!(matches!(expiring.renew(), Err(SubscriptionError::SubscriptionExpired)));
+    }
+}
@@ -0,0 +1,101 @@
+use serde::Deserialize;
+
+use crate::error::SonosError;
+
+/// What a portable speaker is currently running from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PowerSource {
+    Battery,
+    /// Docked on the wireless charging base
+    ChargingRing,
+    UsbPower,
+    Other(String),
+}
+
+impl PowerSource {
+    fn from_sonos(value: &str) -> Self {
+        match value {
+            "BATTERY" => PowerSource::Battery,
+            "SONOS_CHARGING_RING" => PowerSource::ChargingRing,
+            "USB_POWER" => PowerSource::UsbPower,
+            other => PowerSource::Other(other.to_string()),
+        }
+    }
+}
+
+/// Battery state reported by a portable speaker's `/status/batterystatus` page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatteryStatus {
+    /// Charge in percent
+    pub level: u8,
+    pub power_source: PowerSource,
+    /// Battery health as reported, e.g. `GREEN`
+    pub health: String,
+}
+
+impl BatteryStatus {
+    pub fn from_xml(xml: &str) -> Result<Self, SonosError> {
+        let info: SupportInfo = quick_xml::de::from_str(xml)
+            .map_err(|e| SonosError::ParseError(format!("Invalid battery status: {}", e)))?;
+        let field = |name: &str| {
+            info.battery
+                .data
+                .iter()
+                .find(|data| data.name == name)
+                .map(|data| data.value.trim().to_string())
+                .ok_or_else(|| SonosError::ParseError(format!("Battery status is missing {}", name)))
+        };
+
+        let level = field("Level")?;
+        Ok(Self {
+            level: level
+                .parse::<u8>()
+                .map_err(|_| SonosError::ParseError(format!("Invalid battery level: {}", level)))?,
+            power_source: PowerSource::from_sonos(&field("PowerSource")?),
+            health: field("Health")?,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename = "ZPSupportInfo")]
+struct SupportInfo {
+    #[serde(rename = "LocalBatteryStatus")]
+    battery: LocalBatteryStatus,
+}
+
+#[derive(Debug, Deserialize)]
+struct LocalBatteryStatus {
+    #[serde(rename = "Data", default)]
+    data: Vec<Data>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Data {
+    #[serde(rename = "@name")]
+    name: String,
+    #[serde(rename = "$text", default)]
+    value: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_battery_status() {
+        let xml = include_str!("../../tests/fixtures/battery_status.xml");
+        let status = BatteryStatus::from_xml(xml).unwrap();
+
+        assert_eq!(status.level, 87);
+        assert_eq!(status.power_source, PowerSource::ChargingRing);
+        assert_eq!(status.health, "GREEN");
+    }
+
+    #[test]
+    fn test_parse_battery_status_missing_level() {
+        let xml = r#"<ZPSupportInfo><LocalBatteryStatus><Data name="Health">GREEN</Data><Data name="PowerSource">BATTERY</Data></LocalBatteryStatus></ZPSupportInfo>"#;
+
+        assert!(matches!(BatteryStatus::from_xml(xml), Err(SonosError::ParseError(_))));
+    }
+}
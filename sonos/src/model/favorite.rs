@@ -0,0 +1,25 @@
+/// A Sonos favorite from the `FV:2` container, as listed under "My Sonos"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Favorite {
+    /// Object ID within the favorites container, e.g. `FV:2/13`
+    pub id: String,
+    pub title: String,
+    /// What the favorite plays, e.g. a radio stream or a service playlist
+    pub uri: String,
+    /// DIDL-Lite describing `uri`, which services need to play it
+    pub metadata: String,
+    /// UPnP class of the item described by `metadata`
+    pub class: Option<String>,
+    pub album_art_uri: Option<String>,
+    /// Short label such as `TuneIn Station` or `Spotify Playlist`
+    pub description: Option<String>,
+}
+
+impl Favorite {
+    /// Playlists and albums have to be queued; single items can be set as the transport URI
+    pub fn is_container(&self) -> bool {
+        self.class
+            .as_deref()
+            .is_some_and(|class| class.starts_with("object.container"))
+    }
+}
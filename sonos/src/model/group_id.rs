@@ -1,3 +1,5 @@
+use crate::SpeakerId;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct GroupId(String);
 
@@ -6,6 +8,18 @@ impl GroupId {
     GroupId(id.into())
   }
 
+  /// Identity derived from the sorted member IDs instead of the Sonos group ID
+  ///
+  /// Sonos names a group after its coordinator (`RINCON_x:N`), so moving the
+  /// coordinator produces a new ID. This one only changes when a speaker joins
+  /// or leaves, e.g. `RINCON_A+RINCON_B`.
+  pub fn from_members<'a>(members: impl IntoIterator<Item = &'a SpeakerId>) -> Self {
+    let mut ids: Vec<&str> = members.into_iter().map(|id| id.as_str()).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    GroupId(ids.join("+"))
+  }
+
   pub fn as_str(&self) -> &str {
     &self.0
   }
@@ -1,15 +1,28 @@
+mod battery_status;
+mod favorite;
 mod group_id;
+mod model_capabilities;
+mod play_mode;
+mod playlist;
 mod playback_state;
+pub(crate) mod serde_millis;
 mod speaker;
 mod speaker_id;
 mod speaker_state;
 mod state_change;
 mod track_info;
+mod track_source_kind;
 
+pub use battery_status::{BatteryStatus, PowerSource};
+pub use favorite::Favorite;
 pub use group_id::GroupId;
+pub use model_capabilities::ModelCapabilities;
+pub use play_mode::PlayMode;
+pub use playlist::Playlist;
 pub use playback_state::PlaybackState;
 pub use speaker::Speaker;
 pub use speaker_id::SpeakerId;
 pub use speaker_state::SpeakerState;
 pub use state_change::{StateChange, TransportStatus};
 pub use track_info::TrackInfo;
+pub use track_source_kind::TrackSourceKind;
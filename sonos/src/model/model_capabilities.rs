@@ -0,0 +1,128 @@
+/// Hardware features of a Sonos model, used to hide controls a speaker can't honour
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ModelCapabilities {
+    /// Analog line-in that can be played as a source
+    pub has_line_in: bool,
+    /// Home theater soundbar (Night Sound, Speech Enhancement, surrounds)
+    pub is_soundbar: bool,
+    /// TV input over HDMI or optical; soundbars and the Amp
+    pub has_tv_input: bool,
+    /// Can be bonded with a Sub
+    pub supports_subwoofer: bool,
+    /// Bluetooth-capable portable speaker
+    pub is_portable: bool,
+    pub has_battery: bool,
+}
+
+impl ModelCapabilities {
+    /// Look up a model by its `modelName`, e.g. `Sonos Beam` or `Play:1`.
+    ///
+    /// Unknown models report no optional features.
+    pub fn for_model(model_name: &str) -> Self {
+        let model = model_name.trim();
+        let model = model.strip_prefix("Sonos ").unwrap_or(model).to_lowercase();
+
+        MODEL_TABLE
+            .iter()
+            .find(|(name, _)| *name == model)
+            .map(|(_, capabilities)| *capabilities)
+            .unwrap_or_default()
+    }
+}
+
+const fn caps(
+    has_line_in: bool,
+    is_soundbar: bool,
+    has_tv_input: bool,
+    supports_subwoofer: bool,
+    is_portable: bool,
+) -> ModelCapabilities {
+    ModelCapabilities {
+        has_line_in,
+        is_soundbar,
+        has_tv_input,
+        supports_subwoofer,
+        is_portable,
+        has_battery: is_portable,
+    }
+}
+
+const SPEAKER: ModelCapabilities = caps(false, false, false, true, false);
+const SPEAKER_WITH_LINE_IN: ModelCapabilities = caps(true, false, false, true, false);
+const SOUNDBAR: ModelCapabilities = caps(false, true, true, true, false);
+const PORTABLE: ModelCapabilities = caps(false, false, false, false, true);
+
+/// Lowercased model names without the "Sonos " prefix
+const MODEL_TABLE: &[(&str, ModelCapabilities)] = &[
+    ("play:1", SPEAKER),
+    ("play:3", SPEAKER),
+    ("one", SPEAKER),
+    ("one sl", SPEAKER),
+    ("play:5", SPEAKER_WITH_LINE_IN),
+    ("five", SPEAKER_WITH_LINE_IN),
+    ("era 100", SPEAKER_WITH_LINE_IN),
+    ("era 300", SPEAKER_WITH_LINE_IN),
+    ("port", SPEAKER_WITH_LINE_IN),
+    ("connect:amp", SPEAKER_WITH_LINE_IN),
+    ("connect", caps(true, false, false, false, false)),
+    ("amp", caps(true, false, true, true, false)),
+    ("playbar", SOUNDBAR),
+    ("playbase", SOUNDBAR),
+    ("beam", SOUNDBAR),
+    ("ray", SOUNDBAR),
+    ("arc", SOUNDBAR),
+    ("arc ultra", SOUNDBAR),
+    ("move", PORTABLE),
+    ("move 2", PORTABLE),
+    ("roam", PORTABLE),
+    ("roam sl", PORTABLE),
+    ("roam 2", PORTABLE),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beam_is_a_soundbar() {
+        let beam = ModelCapabilities::for_model("Sonos Beam");
+        assert!(beam.is_soundbar);
+        assert!(beam.has_tv_input);
+        assert!(beam.supports_subwoofer);
+        assert!(!beam.has_line_in);
+        assert!(!beam.is_portable);
+    }
+
+    #[test]
+    fn test_move_is_portable_with_battery() {
+        let portable = ModelCapabilities::for_model("Sonos Move");
+        assert!(portable.is_portable);
+        assert!(portable.has_battery);
+        assert!(!portable.is_soundbar);
+        assert!(!portable.supports_subwoofer);
+    }
+
+    #[test]
+    fn test_play1_supports_sub_only() {
+        assert_eq!(
+            ModelCapabilities::for_model("Sonos Play:1"),
+            ModelCapabilities {
+                supports_subwoofer: true,
+                ..ModelCapabilities::default()
+            }
+        );
+        // The prefix is optional and matching ignores case
+        assert_eq!(
+            ModelCapabilities::for_model("PLAY:1"),
+            ModelCapabilities::for_model("Sonos Play:1")
+        );
+    }
+
+    #[test]
+    fn test_unknown_model_has_no_features() {
+        assert_eq!(
+            ModelCapabilities::for_model("Sonos Prototype X"),
+            ModelCapabilities::default()
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::SonosError;
+
+/// Repeat and shuffle setting of a speaker's queue, as reported in AVTransport `CurrentPlayMode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum PlayMode {
+    Normal,
+    RepeatAll,
+    RepeatOne,
+    /// Shuffle and repeat the whole queue
+    Shuffle,
+    ShuffleNoRepeat,
+    ShuffleRepeatOne,
+}
+
+impl PlayMode {
+    /// The play-mode string Sonos uses in events and `SetPlayMode`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlayMode::Normal => "NORMAL",
+            PlayMode::RepeatAll => "REPEAT_ALL",
+            PlayMode::RepeatOne => "REPEAT_ONE",
+            PlayMode::Shuffle => "SHUFFLE",
+            PlayMode::ShuffleNoRepeat => "SHUFFLE_NOREPEAT",
+            PlayMode::ShuffleRepeatOne => "SHUFFLE_REPEAT_ONE",
+        }
+    }
+}
+
+impl fmt::Display for PlayMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PlayMode {
+    type Err = SonosError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "NORMAL" => Ok(PlayMode::Normal),
+            "REPEAT_ALL" => Ok(PlayMode::RepeatAll),
+            "REPEAT_ONE" => Ok(PlayMode::RepeatOne),
+            "SHUFFLE" => Ok(PlayMode::Shuffle),
+            "SHUFFLE_NOREPEAT" => Ok(PlayMode::ShuffleNoRepeat),
+            "SHUFFLE_REPEAT_ONE" => Ok(PlayMode::ShuffleRepeatOne),
+            other => Err(SonosError::ParseError(format!("Unknown play mode: {}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_MODES: [PlayMode; 6] = [
+        PlayMode::Normal,
+        PlayMode::RepeatAll,
+        PlayMode::RepeatOne,
+        PlayMode::Shuffle,
+        PlayMode::ShuffleNoRepeat,
+        PlayMode::ShuffleRepeatOne,
+    ];
+
+    #[test]
+    fn test_play_mode_round_trip() {
+        for mode in ALL_MODES {
+            assert_eq!(mode.as_str().parse::<PlayMode>().unwrap(), mode);
+            assert_eq!(mode.to_string(), mode.as_str());
+        }
+    }
+
+    #[test]
+    fn test_play_mode_from_sonos_strings() {
+        assert_eq!("SHUFFLE_NOREPEAT".parse::<PlayMode>().unwrap(), PlayMode::ShuffleNoRepeat);
+        assert_eq!("REPEAT_ONE".parse::<PlayMode>().unwrap(), PlayMode::RepeatOne);
+        assert!(matches!("shuffle".parse::<PlayMode>(), Err(SonosError::ParseError(_))));
+        assert!("".parse::<PlayMode>().is_err());
+    }
+}
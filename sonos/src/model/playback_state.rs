@@ -1,7 +1,40 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum PlaybackState {
     Playing,
     Paused,
     Stopped,
     Transitioning,
-}
\ No newline at end of file
+}
+impl PlaybackState {
+    /// Map a UPnP AVTransport `TransportState` value, e.g. `PAUSED_PLAYBACK`
+    ///
+    /// `NO_MEDIA_PRESENT` counts as stopped. Recording states never occur on
+    /// Sonos and map to `None`, like any unknown value.
+    pub fn from_transport_state(value: &str) -> Option<Self> {
+        match value.trim() {
+            "PLAYING" => Some(PlaybackState::Playing),
+            "PAUSED_PLAYBACK" => Some(PlaybackState::Paused),
+            "STOPPED" | "NO_MEDIA_PRESENT" => Some(PlaybackState::Stopped),
+            "TRANSITIONING" => Some(PlaybackState::Transitioning),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_transport_state() {
+        assert_eq!(PlaybackState::from_transport_state("PLAYING"), Some(PlaybackState::Playing));
+        assert_eq!(PlaybackState::from_transport_state("PAUSED_PLAYBACK"), Some(PlaybackState::Paused));
+        assert_eq!(PlaybackState::from_transport_state("STOPPED"), Some(PlaybackState::Stopped));
+        assert_eq!(PlaybackState::from_transport_state("NO_MEDIA_PRESENT"), Some(PlaybackState::Stopped));
+        assert_eq!(
+            PlaybackState::from_transport_state("TRANSITIONING"),
+            Some(PlaybackState::Transitioning)
+        );
+        assert_eq!(PlaybackState::from_transport_state("RECORDING"), None);
+    }
+}
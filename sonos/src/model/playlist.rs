@@ -0,0 +1,10 @@
+/// A Sonos playlist (saved queue) from the `SQ:` container
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Playlist {
+    /// Container ID, e.g. `SQ:3`
+    pub id: String,
+    pub title: String,
+    /// URI that enqueues the whole playlist, e.g. `file:///jffs/settings/savedqueues.rsq#3`
+    pub uri: String,
+    pub album_art_uri: Option<String>,
+}
@@ -1,6 +1,13 @@
-use crate::SpeakerId;
+use crate::{ServiceType, SpeakerId};
 
-#[derive(Debug, Clone)]
+use super::ModelCapabilities;
+
+/// A Sonos speaker as found by discovery.
+///
+/// Identity is the device UDN in `id`: equality and hashing ignore every other
+/// field, so a speaker whose IP changed after a DHCP renewal is still the same
+/// speaker.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Speaker {
     pub id: SpeakerId,
     pub name: String,
@@ -9,10 +16,97 @@ pub struct Speaker {
     pub port: u16,
     pub model_name: String,
     pub satellites: Vec<SpeakerId>,
+    /// Services advertised in the device description; empty when unknown
+    #[serde(default)]
+    pub supported_services: Vec<ServiceType>,
+    /// Firmware version from the device description, e.g. `78.1-52020`
+    #[serde(default)]
+    pub software_version: Option<String>,
+    /// Hardware revision from the device description, e.g. `1.20.1.6-2.0`
+    #[serde(default)]
+    pub hardware_version: Option<String>,
+}
+
+impl PartialEq for Speaker {
+  fn eq(&self, other: &Self) -> bool {
+    self.id == other.id
+  }
+}
+
+impl Eq for Speaker {}
+
+impl std::hash::Hash for Speaker {
+  fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    self.id.hash(state);
+  }
 }
 
 impl Speaker {
   pub fn get_id(&self) -> &SpeakerId {
     &self.id
   }
-}
\ No newline at end of file
+
+  /// Whether the speaker advertises `service_type`
+  ///
+  /// Speakers whose services are unknown are assumed to support everything.
+  pub fn supports_service(&self, service_type: ServiceType) -> bool {
+    self.supported_services.is_empty() || self.supported_services.contains(&service_type)
+  }
+
+  /// Hardware features of this speaker's model
+  pub fn capabilities(&self) -> ModelCapabilities {
+    ModelCapabilities::for_model(&self.model_name)
+  }
+
+  /// Whether the model has an analog line-in that can be played as a source
+  pub fn has_line_in(&self) -> bool {
+    self.capabilities().has_line_in
+  }
+
+  /// Whether the model is a soundbar or amp with a TV (HDMI or optical) input
+  pub fn has_tv_input(&self) -> bool {
+    self.capabilities().has_tv_input
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::collections::HashSet;
+
+  fn speaker(id: &str, ip: &str) -> Speaker {
+    Speaker {
+      id: SpeakerId::new(id),
+      name: "Kitchen".to_string(),
+      room_name: "Kitchen".to_string(),
+      ip_address: ip.to_string(),
+      port: 1400,
+      model_name: "Sonos One".to_string(),
+      satellites: vec![],
+      supported_services: vec![],
+      software_version: None,
+      hardware_version: None,
+    }
+  }
+
+  #[test]
+  fn test_identity_survives_ip_change() {
+    let before = speaker("uuid:RINCON_000E58A0123401400", "192.168.1.20");
+    let after = speaker("RINCON_000E58A0123401400", "192.168.1.57");
+
+    assert_eq!(before, after);
+
+    let mut seen = HashSet::new();
+    seen.insert(before);
+    assert!(!seen.insert(after));
+    assert_eq!(seen.len(), 1);
+  }
+
+  #[test]
+  fn test_different_udns_are_different_speakers() {
+    assert_ne!(
+      speaker("RINCON_000E58A0123401400", "192.168.1.20"),
+      speaker("RINCON_5CAAFDAE58BD01400", "192.168.1.20")
+    );
+  }
+}
@@ -1,5 +1,11 @@
 use serde::{Deserialize, Serialize};
 
+/// Identity of a physical Sonos device
+///
+/// The canonical form is the bare device ID, e.g. `RINCON_000E58A0123401400`:
+/// no `uuid:` prefix and no `::N` instance or `::urn:...` USN suffix. Every
+/// constructor normalizes to this form, so a UDN from discovery and a UUID from
+/// an event for the same device compare equal.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct SpeakerId(String);
 
@@ -14,11 +20,25 @@ impl<'de> Deserialize<'de> for SpeakerId {
 }
 
 impl SpeakerId {
-  /// Creates a new SpeakerId, stripping the "uuid:" prefix if present
+  /// Creates a new SpeakerId in canonical form, see [`SpeakerId::from_udn`]
   pub fn new(id: impl Into<String>) -> Self {
-    let id = id.into();
-    let normalized = id.strip_prefix("uuid:").unwrap_or(&id);
-    Self(normalized.to_string())
+    Self::from_udn(&id.into())
+  }
+
+  /// Parses a UDN, USN or bare device ID into the canonical form
+  ///
+  /// `RINCON_x`, `uuid:RINCON_x` and `uuid:RINCON_x::1` all yield `RINCON_x`.
+  /// Normalizing an already canonical ID returns it unchanged.
+  pub fn from_udn(udn: &str) -> Self {
+    let udn = udn.trim();
+    let id = udn.strip_prefix("uuid:").unwrap_or(udn);
+    let id = id.split_once("::").map_or(id, |(device, _)| device);
+    Self(id.to_string())
+  }
+
+  /// Returns the ID as a UDN, e.g. `uuid:RINCON_000E58A0123401400`
+  pub fn to_udn(&self) -> String {
+    format!("uuid:{}", self.0)
   }
 
   /// Returns the ID as a string slice
@@ -38,3 +58,37 @@ impl AsRef<str> for SpeakerId {
     &self.0
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_from_udn_normalizes_prefix_and_suffix() {
+    let canonical = SpeakerId::from_udn("RINCON_000E58A0123401400");
+
+    assert_eq!(canonical.as_str(), "RINCON_000E58A0123401400");
+    assert_eq!(SpeakerId::from_udn("uuid:RINCON_000E58A0123401400"), canonical);
+    assert_eq!(SpeakerId::from_udn("uuid:RINCON_000E58A0123401400::1"), canonical);
+    assert_eq!(
+      SpeakerId::from_udn("uuid:RINCON_000E58A0123401400::urn:schemas-upnp-org:device:ZonePlayer:1"),
+      canonical
+    );
+    assert_eq!(SpeakerId::new("uuid:RINCON_000E58A0123401400::1"), canonical);
+  }
+
+  #[test]
+  fn test_from_udn_is_idempotent() {
+    let id = SpeakerId::from_udn("uuid:RINCON_000E58A0123401400::1");
+
+    assert_eq!(SpeakerId::from_udn(id.as_str()), id);
+    assert_eq!(SpeakerId::from_udn(&id.to_udn()), id);
+    assert_eq!(id.to_udn(), "uuid:RINCON_000E58A0123401400");
+  }
+
+  #[test]
+  fn test_deserialize_normalizes() {
+    let id: SpeakerId = serde_json::from_str("\"uuid:RINCON_000E58A0123401400::1\"").unwrap();
+    assert_eq!(id, SpeakerId::from_udn("RINCON_000E58A0123401400"));
+  }
+}
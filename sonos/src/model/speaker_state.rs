@@ -1,6 +1,6 @@
 use crate::{GroupId, PlaybackState, Speaker};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SpeakerState {
   pub speaker: Speaker,
   pub playback_state: PlaybackState,
@@ -1,6 +1,14 @@
-use crate::{PlaybackState, ServiceType, SpeakerId, group::Group, model::TrackInfo};
+use std::time::Duration;
 
-#[derive(Debug, Clone)]
+use crate::{GroupId, PlaybackState, ServiceType, SpeakerId, group::Group, model::{PlayMode, TrackInfo}};
+
+/// A change reported by a speaker
+///
+/// Serializes with the variant name in a `type` field, e.g.
+/// `{"type":"VolumeChanged","speaker_id":"RINCON_000E58A0123401400","volume":42}`,
+/// and durations as milliseconds.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type")]
 pub enum StateChange {
   VolumeChanged {
     speaker_id: SpeakerId,
@@ -10,17 +18,70 @@ pub enum StateChange {
     speaker_id: SpeakerId,
     muted: bool,
   },
+  BassChanged {
+    speaker_id: SpeakerId,
+    bass: i8,
+  },
+  TrebleChanged {
+    speaker_id: SpeakerId,
+    treble: i8,
+  },
+  LoudnessChanged {
+    speaker_id: SpeakerId,
+    loudness: bool,
+  },
+  /// Level of a bonded Sub, from -15 to 15
+  SubGainChanged {
+    speaker_id: SpeakerId,
+    gain: i8,
+  },
+  /// A bonded Sub was switched on or off
+  SubEnabledChanged {
+    speaker_id: SpeakerId,
+    enabled: bool,
+  },
   PlaybackStateChanged {
     speaker_id: SpeakerId,
     state: PlaybackState,
   },
+  PlayModeChanged {
+    speaker_id: SpeakerId,
+    mode: PlayMode,
+  },
+  CrossfadeChanged {
+    speaker_id: SpeakerId,
+    crossfade: bool,
+  },
+  /// Emitted by position polling; AVTransport doesn't event the track position
   PositionChanged {
     speaker_id: SpeakerId,
-    position_ms: u64,
+    #[serde(with = "super::serde_millis")]
+    position: Duration,
+    #[serde(with = "super::serde_millis")]
+    duration: Duration,
   },
   GroupChange {
     groups: Vec<Group>
   },
+  GroupMembershipChanged {
+    speaker_id: SpeakerId,
+    group_id: Option<GroupId>,
+    is_coordinator: bool,
+  },
+  /// Coordination moved to another speaker while the group kept the same members
+  ///
+  /// `group_id` is the member-based [`GroupId::from_members`] identity, which
+  /// stays the same across the change.
+  CoordinatorChanged {
+    group_id: GroupId,
+    old_coordinator: SpeakerId,
+    new_coordinator: SpeakerId,
+  },
+  /// A speaker dropped off the network (e.g. powered off), as opposed to leaving its group
+  SpeakerVanished {
+    speaker_id: SpeakerId,
+    reason: String,
+  },
   // New streaming-specific variants
   TrackChanged {
     speaker_id: SpeakerId,
@@ -31,15 +92,162 @@ pub enum StateChange {
     transport_state: PlaybackState,
     transport_status: TransportStatus,
   },
+  QueueChanged {
+    speaker_id: SpeakerId,
+  },
+  LibraryUpdated {
+    speaker_id: SpeakerId,
+  },
   SubscriptionError {
     speaker_id: SpeakerId,
     service: ServiceType,
     error: String,
   },
+  /// The `SEQ` of a subscription's events skipped ahead, so `gap` events never arrived
+  ///
+  /// State from that service may be stale until its next full event.
+  EventsMissed {
+    speaker_id: SpeakerId,
+    service: ServiceType,
+    gap: u32,
+  },
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum TransportStatus {
     Ok,
     ErrorOccurred,
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::group::SpeakerRef;
+  use crate::model::TrackSourceKind;
+  use serde_json::json;
+
+  /// Serialize, deserialize and serialize again, returning the JSON if both passes agree
+  fn round_trip(change: StateChange) -> serde_json::Value {
+    let value = serde_json::to_value(&change).unwrap();
+    let parsed: StateChange = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&parsed).unwrap(), value);
+    value
+  }
+
+  fn kitchen() -> SpeakerId {
+    SpeakerId::new("RINCON_000E58A0123401400")
+  }
+
+  #[test]
+  fn test_volume_changed_json_is_tagged() {
+    let value = round_trip(StateChange::VolumeChanged { speaker_id: kitchen(), volume: 42 });
+
+    assert_eq!(value, json!({"type": "VolumeChanged", "speaker_id": "RINCON_000E58A0123401400", "volume": 42}));
+  }
+
+  #[test]
+  fn test_durations_serialize_as_milliseconds() {
+    let value = round_trip(StateChange::PositionChanged {
+      speaker_id: kitchen(),
+      position: Duration::from_millis(61_500),
+      duration: Duration::from_secs(240),
+    });
+    assert_eq!(value["position"], 61_500);
+    assert_eq!(value["duration"], 240_000);
+
+    let track_info = TrackInfo {
+      title: Some("Heroes".to_string()),
+      artist: Some("David Bowie".to_string()),
+      album: None,
+      duration_ms: Some(371_000),
+      uri: Some("x-sonos-spotify:spotify%3atrack%3a7Jh1bpe76CNTCgdgAdBw4Z".to_string()),
+      album_art_uri: None,
+      source_kind: TrackSourceKind::StreamingService,
+      duration: Some(Duration::from_millis(371_000)),
+    };
+    let value = round_trip(StateChange::TrackChanged { speaker_id: kitchen(), track_info: Some(track_info) });
+    assert_eq!(value["track_info"]["duration"], 371_000);
+    assert_eq!(value["track_info"]["source_kind"], "StreamingService");
+  }
+
+  #[test]
+  fn test_every_variant_round_trips() {
+    let group_id = GroupId::new("RINCON_000E58A0123401400:3127441935");
+    let office = SpeakerId::new("RINCON_347E5C0F2D3A01400");
+    let group = Group::new(
+      group_id.clone(),
+      kitchen(),
+      vec![SpeakerRef::new(kitchen(), vec![]), SpeakerRef::new(office.clone(), vec![])],
+    );
+
+    let changes = vec![
+      StateChange::VolumeChanged { speaker_id: kitchen(), volume: 15 },
+      StateChange::MuteChanged { speaker_id: kitchen(), muted: true },
+      StateChange::BassChanged { speaker_id: kitchen(), bass: -3 },
+      StateChange::TrebleChanged { speaker_id: kitchen(), treble: 2 },
+      StateChange::LoudnessChanged { speaker_id: kitchen(), loudness: false },
+      StateChange::SubGainChanged { speaker_id: kitchen(), gain: -8 },
+      StateChange::SubEnabledChanged { speaker_id: kitchen(), enabled: true },
+      StateChange::PlaybackStateChanged { speaker_id: kitchen(), state: PlaybackState::Playing },
+      StateChange::PlayModeChanged { speaker_id: kitchen(), mode: PlayMode::ShuffleNoRepeat },
+      StateChange::CrossfadeChanged { speaker_id: kitchen(), crossfade: true },
+      StateChange::PositionChanged {
+        speaker_id: kitchen(),
+        position: Duration::from_secs(12),
+        duration: Duration::from_secs(180),
+      },
+      StateChange::GroupChange { groups: vec![group] },
+      StateChange::GroupMembershipChanged {
+        speaker_id: office.clone(),
+        group_id: Some(group_id.clone()),
+        is_coordinator: false,
+      },
+      StateChange::CoordinatorChanged {
+        group_id: group_id.clone(),
+        old_coordinator: kitchen(),
+        new_coordinator: office.clone(),
+      },
+      StateChange::SpeakerVanished { speaker_id: office.clone(), reason: "powered off".to_string() },
+      StateChange::TrackChanged { speaker_id: kitchen(), track_info: None },
+      StateChange::TransportInfoChanged {
+        speaker_id: kitchen(),
+        transport_state: PlaybackState::Paused,
+        transport_status: TransportStatus::ErrorOccurred,
+      },
+      StateChange::QueueChanged { speaker_id: kitchen() },
+      StateChange::LibraryUpdated { speaker_id: kitchen() },
+      StateChange::SubscriptionError {
+        speaker_id: kitchen(),
+        service: ServiceType::RenderingControl,
+        error: "subscription expired".to_string(),
+      },
+      StateChange::EventsMissed { speaker_id: kitchen(), service: ServiceType::AVTransport, gap: 3 },
+    ];
+
+    for change in changes {
+      let expected_type = format!("{:?}", change).split([' ', '{']).next().unwrap().to_string();
+      let value = round_trip(change);
+      assert_eq!(value["type"], expected_type.as_str());
+    }
+  }
+
+  #[test]
+  fn test_ids_deserialize_to_canonical_form() {
+    let change: StateChange = serde_json::from_value(json!({
+      "type": "GroupMembershipChanged",
+      "speaker_id": "uuid:RINCON_347E5C0F2D3A01400::1",
+      "group_id": null,
+      "is_coordinator": true,
+    }))
+    .unwrap();
+
+    match change {
+      StateChange::GroupMembershipChanged { speaker_id, group_id, is_coordinator } => {
+        assert_eq!(speaker_id.as_str(), "RINCON_347E5C0F2D3A01400");
+        assert_eq!(group_id, None);
+        assert!(is_coordinator);
+      }
+      other => panic!("Expected GroupMembershipChanged, got {:?}", other),
+    }
+  }
+}
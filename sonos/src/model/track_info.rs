@@ -1,8 +1,29 @@
-#[derive(Debug, Clone)]
+use std::time::Duration;
+
+use super::TrackSourceKind;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TrackInfo {
     pub title: Option<String>,
     pub artist: Option<String>,
     pub album: Option<String>,
+    /// Length the speaker reported in milliseconds, even for live sources; see `duration`
     pub duration_ms: Option<u64>,
     pub uri: Option<String>,
+    pub album_art_uri: Option<String>,
+    /// Kind of source the track plays from, classified by its URI scheme
+    pub source_kind: TrackSourceKind,
+    /// Length of the track; `None` for live sources and when the speaker doesn't report it
+    #[serde(with = "super::serde_millis::option", default)]
+    pub duration: Option<Duration>,
+}
+
+impl TrackInfo {
+    /// Duration of a track from `source_kind` whose speaker reported `duration_ms`
+    pub(crate) fn duration_for(source_kind: TrackSourceKind, duration_ms: Option<u64>) -> Option<Duration> {
+        if source_kind.is_live() {
+            return None;
+        }
+        duration_ms.map(Duration::from_millis)
+    }
 }
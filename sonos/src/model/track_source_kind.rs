@@ -0,0 +1,133 @@
+/// Where a speaker's current track comes from, derived from the track URI scheme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum TrackSourceKind {
+    /// Internet radio such as TuneIn or a direct MP3/AAC stream
+    Radio,
+    /// A file from the local music library on a network share
+    Library,
+    /// A track from a streaming service like Spotify or Apple Music
+    StreamingService,
+    /// The analog or digital line-in of a speaker
+    LineIn,
+    /// The TV input of a home theater speaker
+    Tv,
+    /// Following another speaker's playback as a group member
+    GroupMember,
+    /// A scheme we don't recognise
+    Unknown,
+}
+
+impl TrackSourceKind {
+    /// Classify a track URI such as `x-rincon-mp3radio://…` or `x-file-cifs://…`
+    pub fn from_uri(uri: &str) -> Self {
+        let scheme = uri
+            .split_once(':')
+            .map(|(scheme, _)| scheme.to_ascii_lowercase())
+            .unwrap_or_default();
+        match scheme.as_str() {
+            "x-rincon-mp3radio" | "x-sonosapi-stream" | "x-sonosapi-radio" | "aac" | "hls-radio" => {
+                TrackSourceKind::Radio
+            }
+            "x-file-cifs" | "x-smb" | "file" => TrackSourceKind::Library,
+            "x-sonos-spotify" | "x-sonos-http" | "x-sonosapi-hls-static" | "x-sonosprog-http" => {
+                TrackSourceKind::StreamingService
+            }
+            "x-rincon-stream" => TrackSourceKind::LineIn,
+            "x-sonos-htastream" => TrackSourceKind::Tv,
+            "x-rincon" => TrackSourceKind::GroupMember,
+            _ => TrackSourceKind::Unknown,
+        }
+    }
+
+    /// Live sources have no fixed length and can't be scrubbed
+    pub fn is_live(&self) -> bool {
+        matches!(
+            self,
+            TrackSourceKind::Radio | TrackSourceKind::LineIn | TrackSourceKind::Tv
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_radio_uris() {
+        for uri in [
+            "x-rincon-mp3radio://stream.example.com/live.mp3",
+            "x-sonosapi-stream:s24861?sid=254&flags=8224&sn=0",
+            "x-sonosapi-radio:station%3a123?sid=236",
+            "aac://http://radio.example.com/stream.aac",
+        ] {
+            assert_eq!(TrackSourceKind::from_uri(uri), TrackSourceKind::Radio, "{}", uri);
+        }
+    }
+
+    #[test]
+    fn test_library_uris() {
+        assert_eq!(
+            TrackSourceKind::from_uri("x-file-cifs://nas/music/15%20Step.flac"),
+            TrackSourceKind::Library
+        );
+        assert_eq!(
+            TrackSourceKind::from_uri("x-smb://nas/music/track.mp3"),
+            TrackSourceKind::Library
+        );
+    }
+
+    #[test]
+    fn test_streaming_service_uris() {
+        assert_eq!(
+            TrackSourceKind::from_uri("x-sonos-spotify:spotify%3atrack%3a123?sid=12&flags=8224&sn=2"),
+            TrackSourceKind::StreamingService
+        );
+        assert_eq!(
+            TrackSourceKind::from_uri("x-sonos-http:librarytrack%3aa.123.mp4?sid=204"),
+            TrackSourceKind::StreamingService
+        );
+    }
+
+    #[test]
+    fn test_line_in_tv_and_group_uris() {
+        assert_eq!(
+            TrackSourceKind::from_uri("x-rincon-stream:RINCON_000E58A0123401400"),
+            TrackSourceKind::LineIn
+        );
+        assert_eq!(
+            TrackSourceKind::from_uri("x-sonos-htastream:RINCON_000E58A0123401400:spdif"),
+            TrackSourceKind::Tv
+        );
+        assert_eq!(
+            TrackSourceKind::from_uri("x-rincon:RINCON_000E58A0123401400"),
+            TrackSourceKind::GroupMember
+        );
+    }
+
+    #[test]
+    fn test_unknown_uris() {
+        assert_eq!(TrackSourceKind::from_uri(""), TrackSourceKind::Unknown);
+        assert_eq!(TrackSourceKind::from_uri("no-scheme"), TrackSourceKind::Unknown);
+        assert_eq!(
+            TrackSourceKind::from_uri("x-new-scheme:whatever"),
+            TrackSourceKind::Unknown
+        );
+    }
+
+    #[test]
+    fn test_scheme_is_case_insensitive() {
+        assert_eq!(
+            TrackSourceKind::from_uri("X-Rincon-MP3Radio://stream.example.com"),
+            TrackSourceKind::Radio
+        );
+    }
+
+    #[test]
+    fn test_live_sources() {
+        assert!(TrackSourceKind::Radio.is_live());
+        assert!(TrackSourceKind::LineIn.is_live());
+        assert!(TrackSourceKind::Tv.is_live());
+        assert!(!TrackSourceKind::Library.is_live());
+        assert!(!TrackSourceKind::StreamingService.is_live());
+    }
+}
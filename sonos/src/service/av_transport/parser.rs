@@ -1,21 +1,50 @@
-use crate::{PlaybackState, model::TrackInfo, xml_decode::{NestedAttribute, ValueAttribute}};
+use crate::{PlaybackState, model::{PlayMode, TrackInfo, TrackSourceKind}, xml_decode::{NestedAttribute, ValueAttribute}};
 
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
-#[serde(rename = "propertyset")]
+#[serde(rename = "propertyset", try_from = "PropertySet")]
 pub struct AVTransportParser {
-    #[serde(rename = "property")]
     pub property: Property,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug)]
 pub struct Property {
+    pub last_change: LastChangeEvent,
+}
+
+/// The `<propertyset>` as sent, which may carry properties besides `LastChange`
+#[derive(Debug, Deserialize)]
+struct PropertySet {
+    #[serde(rename = "property", default)]
+    properties: Vec<RawProperty>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawProperty {
     #[serde(
         rename = "LastChange",
-        deserialize_with = "crate::xml_decode::xml_decode::deserialize_nested"
+        deserialize_with = "crate::xml_decode::xml_decode::deserialize_nested",
+        default
     )]
-    pub last_change: LastChangeEvent,
+    last_change: Option<LastChangeEvent>,
+}
+
+impl TryFrom<PropertySet> for AVTransportParser {
+    type Error = String;
+
+    /// Each `LastChange` is a full snapshot of the transport, so the last one wins
+    fn try_from(property_set: PropertySet) -> Result<Self, Self::Error> {
+        property_set
+            .properties
+            .into_iter()
+            .rev()
+            .find_map(|property| property.last_change)
+            .map(|last_change| AVTransportParser {
+                property: Property { last_change },
+            })
+            .ok_or_else(|| "AVTransport event has no LastChange property".to_string())
+    }
 }
 
 // The root element for decoded LastChange content
@@ -138,7 +167,7 @@ pub struct DidlItem {
     #[serde(rename = "res")]
     pub res: DidlResource,
 
-    #[serde(rename = "albumArtURI")]
+    #[serde(rename = "albumArtURI", default)]
     pub album_art_uri: Option<String>,
 
     #[serde(rename = "class")]
@@ -200,32 +229,27 @@ impl AVTransportParser {
     }
 
     pub fn get_playback_state(&self) -> Option<PlaybackState> {
-      println!("");
-      println!("");
-      println!("TRISTAN");
-      println!("");
-      println!("");
-      println!("get_playback_state() --> {}", self
-            .property
+        PlaybackState::from_transport_state(&self.property.last_change.instance.transport_state.val)
+    }
+
+    pub fn get_play_mode(&self) -> Option<PlayMode> {
+        self.property
             .last_change
             .instance
-            .transport_state
+            .current_play_mode
             .val
-            .as_str());
-        match self
+            .parse()
+            .ok()
+    }
+
+    pub fn get_crossfade(&self) -> Option<bool> {
+        let crossfade = self
             .property
             .last_change
             .instance
-            .transport_state
-            .val
-            .as_str()
-        {
-            "PLAYING" => Some(PlaybackState::Playing),
-            "PAUSED_PLAYBACK" => Some(PlaybackState::Paused),
-            "STOPPED" => Some(PlaybackState::Stopped),
-            "TRANSITIONING" => Some(PlaybackState::Transitioning),
-            _ => None,
-        }
+            .current_crossfade_mode
+            .as_ref()?;
+        Some(crossfade.val == "1")
     }
 
     pub fn get_track_info(&self) -> Option<TrackInfo> {
@@ -236,7 +260,7 @@ impl AVTransportParser {
             .current_track_metadata
             .val
             .as_ref()?;
-        let duration_ms = self.parse_duration(
+        let duration_ms = parse_duration(
             &self
                 .property
                 .last_change
@@ -245,30 +269,46 @@ impl AVTransportParser {
                 .val,
         );
         let uri = &self.property.last_change.instance.current_track_uri.val;
+        let source_kind = TrackSourceKind::from_uri(uri);
         Some(TrackInfo {
             title: Some(didl.item.title.clone()),
             artist: didl.item.creator.clone(),
             album: didl.item.album.clone(),
             duration_ms,
             uri: Some(uri.clone()),
+            album_art_uri: didl.item.album_art_uri().map(str::to_string),
+            source_kind,
+            duration: TrackInfo::duration_for(source_kind, duration_ms),
         })
     }
 
-    fn parse_duration(&self, duration_str: &str) -> Option<u64> {
-        let parts: Vec<&str> = duration_str.split(':').collect();
-        if parts.len() >= 3 {
-            let hours: u64 = parts[0].parse().ok()?;
-            let minutes: u64 = parts[1].parse().ok()?;
+    /// Track info with a relative album art URI resolved against the speaker's
+    /// base URL, e.g. `http://192.168.1.100:1400`
+    pub fn get_track_info_for_speaker(&self, base_url: &str) -> Option<TrackInfo> {
+        let mut track_info = self.get_track_info()?;
+        track_info.album_art_uri = track_info
+            .album_art_uri
+            .map(|uri| resolve_album_art_uri(&uri, base_url));
+        Some(track_info)
+    }
 
-            // Handle seconds with optional milliseconds
-            let seconds_part = parts[2];
-            let seconds: f64 = seconds_part.parse().ok()?;
+}
 
-            let total_ms = (hours * 3600 + minutes * 60) * 1000 + (seconds * 1000.0) as u64;
-            Some(total_ms)
-        } else {
-            None
-        }
+/// Parse an `H:MM:SS[.mmm]` duration into milliseconds
+fn parse_duration(duration_str: &str) -> Option<u64> {
+    let parts: Vec<&str> = duration_str.split(':').collect();
+    if parts.len() >= 3 {
+        let hours: u64 = parts[0].parse().ok()?;
+        let minutes: u64 = parts[1].parse().ok()?;
+
+        // Handle seconds with optional milliseconds
+        let seconds_part = parts[2];
+        let seconds: f64 = seconds_part.parse().ok()?;
+
+        let total_ms = (hours * 3600 + minutes * 60) * 1000 + (seconds * 1000.0) as u64;
+        Some(total_ms)
+    } else {
+        None
     }
 }
 
@@ -284,6 +324,46 @@ impl DidlLite {
     }
 }
 
+impl DidlItem {
+    /// Track details for this item; the duration comes from the `res` element
+    pub fn to_track_info(&self) -> TrackInfo {
+        let duration_ms = self.res.duration.as_deref().and_then(parse_duration);
+        let source_kind = TrackSourceKind::from_uri(&self.res.uri);
+        TrackInfo {
+            title: Some(self.title.clone()),
+            artist: self.creator.clone(),
+            album: self.album.clone(),
+            duration_ms,
+            uri: Some(self.res.uri.clone()),
+            album_art_uri: self.album_art_uri().map(str::to_string),
+            source_kind,
+            duration: TrackInfo::duration_for(source_kind, duration_ms),
+        }
+    }
+
+    /// The album art URI, or `None` when the element is missing or empty
+    pub fn album_art_uri(&self) -> Option<&str> {
+        self.album_art_uri
+            .as_deref()
+            .map(str::trim)
+            .filter(|uri| !uri.is_empty())
+    }
+}
+
+/// Resolve an album art URI against a speaker base URL
+///
+/// Streaming services return absolute URLs, while tracks from the local music
+/// library point at the speaker's `/getaa` endpoint with a relative path.
+pub fn resolve_album_art_uri(uri: &str, base_url: &str) -> String {
+    if uri.starts_with("http://") || uri.starts_with("https://") {
+        uri.to_string()
+    } else if uri.starts_with('/') {
+        format!("{}{}", base_url.trim_end_matches('/'), uri)
+    } else {
+        format!("{}/{}", base_url.trim_end_matches('/'), uri)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -312,6 +392,7 @@ mod tests {
         assert_eq!(last_change.instance.id, "0");
         assert_eq!(last_change.instance.transport_state.val, "PAUSED_PLAYBACK");
         assert_eq!(last_change.instance.current_play_mode.val, "REPEAT_ALL");
+        assert_eq!(parsed.get_play_mode(), Some(PlayMode::RepeatAll));
         assert_eq!(last_change.instance.current_track_duration.val, "0:03:57");
         assert_eq!(last_change.instance.number_of_tracks.val, "1");
         assert_eq!(last_change.instance.current_track.val, "1");
@@ -406,4 +487,138 @@ mod tests {
 
         println!("✅ Successfully parsed complete raw event with all fields");
     }
+
+    #[test]
+    fn test_album_art_uri_spotify() {
+        let didl_xml = r#"<DIDL-Lite xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/" xmlns:r="urn:schemas-rinconnetworks-com:metadata-1-0/" xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/"><item id="-1" parentID="-1"><res duration="0:03:58">x-sonos-spotify:spotify:track:5hM5arv9KDbCHS0k9uqwjr?sid=12&amp;flags=0&amp;sn=2</res><upnp:albumArtURI>https://i.scdn.co/image/ab67616d0000b27358267bd34420a00d5cf83a49</upnp:albumArtURI><upnp:class>object.item.audioItem.musicTrack</upnp:class><dc:title>Borderline</dc:title><dc:creator>Tame Impala</dc:creator><upnp:album>The Slow Rush</upnp:album></item></DIDL-Lite>"#;
+
+        let didl = DidlLite::from_xml(didl_xml).unwrap();
+        let album_art_uri = didl.item.album_art_uri().unwrap();
+
+        assert_eq!(
+            album_art_uri,
+            "https://i.scdn.co/image/ab67616d0000b27358267bd34420a00d5cf83a49"
+        );
+        // Absolute URIs are left untouched
+        assert_eq!(
+            resolve_album_art_uri(album_art_uri, "http://192.168.1.100:1400"),
+            album_art_uri
+        );
+    }
+
+    #[test]
+    fn test_album_art_uri_local_library() {
+        let didl_xml = r#"<DIDL-Lite xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/" xmlns:r="urn:schemas-rinconnetworks-com:metadata-1-0/" xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/"><item id="-1" parentID="-1" restricted="true"><res duration="0:04:21">x-file-cifs://nas/music/Radiohead/In%20Rainbows/01%20-%2015%20Step.flac</res><upnp:albumArtURI>/getaa?s=1&amp;u=x-file-cifs%3a%2f%2fnas%2fmusic%2fRadiohead%2fIn%2520Rainbows%2f01%2520-%252015%2520Step.flac</upnp:albumArtURI><upnp:class>object.item.audioItem.musicTrack</upnp:class><dc:title>15 Step</dc:title><dc:creator>Radiohead</dc:creator><upnp:album>In Rainbows</upnp:album></item></DIDL-Lite>"#;
+
+        let didl = DidlLite::from_xml(didl_xml).unwrap();
+        let album_art_uri = didl.item.album_art_uri().unwrap();
+
+        assert!(album_art_uri.starts_with("/getaa?s=1&u=x-file-cifs"));
+        assert_eq!(
+            resolve_album_art_uri(album_art_uri, "http://192.168.1.100:1400"),
+            format!("http://192.168.1.100:1400{}", album_art_uri)
+        );
+    }
+
+    #[test]
+    fn test_album_art_uri_empty() {
+        let didl_xml = r#"<DIDL-Lite xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/" xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/"><item id="-1" parentID="-1"><res>x-sonos-spotify:spotify:track:0</res><upnp:albumArtURI></upnp:albumArtURI><upnp:class>object.item.audioItem.musicTrack</upnp:class><dc:title>Pink + White</dc:title></item></DIDL-Lite>"#;
+
+        let didl = DidlLite::from_xml(didl_xml).unwrap();
+        assert_eq!(didl.item.album_art_uri(), None);
+    }
+
+    #[test]
+    fn test_track_info_resolves_album_art_for_speaker() {
+        let raw_event = r#"<e:propertyset xmlns:e="urn:schemas-upnp-org:event-1-0"><e:property><LastChange>&lt;Event xmlns=&quot;urn:schemas-upnp-org:metadata-1-0/AVT/&quot;&gt;&lt;InstanceID val=&quot;0&quot;&gt;&lt;TransportState val=&quot;PLAYING&quot;/&gt;&lt;CurrentPlayMode val=&quot;NORMAL&quot;/&gt;&lt;NumberOfTracks val=&quot;1&quot;/&gt;&lt;CurrentTrack val=&quot;1&quot;/&gt;&lt;CurrentTrackURI val=&quot;x-file-cifs://nas/music/15%20Step.flac&quot;/&gt;&lt;CurrentTrackDuration val=&quot;0:04:21&quot;/&gt;&lt;CurrentTrackMetaData val=&quot;&amp;lt;DIDL-Lite xmlns:dc=&amp;quot;http://purl.org/dc/elements/1.1/&amp;quot; xmlns:upnp=&amp;quot;urn:schemas-upnp-org:metadata-1-0/upnp/&amp;quot; xmlns=&amp;quot;urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/&amp;quot;&amp;gt;&amp;lt;item id=&amp;quot;-1&amp;quot; parentID=&amp;quot;-1&amp;quot;&amp;gt;&amp;lt;res duration=&amp;quot;0:04:21&amp;quot;&amp;gt;x-file-cifs://nas/music/15%20Step.flac&amp;lt;/res&amp;gt;&amp;lt;upnp:albumArtURI&amp;gt;/getaa?s=1&amp;amp;amp;u=x-file-cifs%3a%2f%2fnas%2fmusic%2f15%2520Step.flac&amp;lt;/upnp:albumArtURI&amp;gt;&amp;lt;upnp:class&amp;gt;object.item.audioItem.musicTrack&amp;lt;/upnp:class&amp;gt;&amp;lt;dc:title&amp;gt;15 Step&amp;lt;/dc:title&amp;gt;&amp;lt;dc:creator&amp;gt;Radiohead&amp;lt;/dc:creator&amp;gt;&amp;lt;upnp:album&amp;gt;In Rainbows&amp;lt;/upnp:album&amp;gt;&amp;lt;/item&amp;gt;&amp;lt;/DIDL-Lite&amp;gt;&quot;/&gt;&lt;/InstanceID&gt;&lt;/Event&gt;</LastChange></e:property></e:propertyset>"#;
+
+        let parsed = AVTransportParser::from_xml(raw_event).unwrap();
+
+        let track_info = parsed
+            .get_track_info_for_speaker("http://192.168.1.100:1400")
+            .unwrap();
+        assert_eq!(track_info.title, Some("15 Step".to_string()));
+        assert_eq!(
+            track_info.album_art_uri,
+            Some("http://192.168.1.100:1400/getaa?s=1&u=x-file-cifs%3a%2f%2fnas%2fmusic%2f15%2520Step.flac".to_string())
+        );
+        assert_eq!(track_info.source_kind, TrackSourceKind::Library);
+        assert_eq!(track_info.duration, Some(std::time::Duration::from_secs(261)));
+
+        // The same item playing from a radio stream is live and has no duration
+        let radio_event = raw_event.replace(
+            "CurrentTrackURI val=&quot;x-file-cifs://nas/music/15%20Step.flac&quot;",
+            "CurrentTrackURI val=&quot;x-rincon-mp3radio://stream.example.com/live.mp3&quot;",
+        );
+        let track_info = AVTransportParser::from_xml(&radio_event)
+            .unwrap()
+            .get_track_info()
+            .unwrap();
+        assert_eq!(track_info.source_kind, TrackSourceKind::Radio);
+        assert_eq!(track_info.duration_ms, Some(261_000));
+        assert_eq!(track_info.duration, None);
+    }
+
+    const TRANSITIONING_XML: &str = r#"<e:propertyset xmlns:e="urn:schemas-upnp-org:event-1-0"><e:property><LastChange>&lt;Event xmlns=&quot;urn:schemas-upnp-org:metadata-1-0/AVT/&quot; xmlns:r=&quot;urn:schemas-rinconnetworks-com:metadata-1-0/&quot;&gt;&lt;InstanceID val=&quot;0&quot;&gt;&lt;TransportState val=&quot;TRANSITIONING&quot;/&gt;&lt;CurrentPlayMode val=&quot;NORMAL&quot;/&gt;&lt;NumberOfTracks val=&quot;12&quot;/&gt;&lt;CurrentTrack val=&quot;4&quot;/&gt;&lt;CurrentTrackURI val=&quot;x-sonos-spotify:spotify:track:5hM5arv9KDbCHS0k9uqwjr&quot;/&gt;&lt;CurrentTrackDuration val=&quot;0:03:58&quot;/&gt;&lt;CurrentTrackMetaData val=&quot;&quot;/&gt;&lt;/InstanceID&gt;&lt;/Event&gt;</LastChange></e:property></e:propertyset>"#;
+
+    #[test]
+    fn test_transitioning_transport_state() {
+        let parsed = AVTransportParser::from_xml(TRANSITIONING_XML).unwrap();
+
+        assert_eq!(
+            parsed.property.last_change.instance.transport_state.val,
+            "TRANSITIONING"
+        );
+        assert_eq!(
+            parsed.get_playback_state(),
+            Some(PlaybackState::Transitioning)
+        );
+        assert_eq!(parsed.get_play_mode(), Some(PlayMode::Normal));
+        // Empty metadata while the next track loads
+        assert!(parsed.get_track_info().is_none());
+    }
+
+    #[test]
+    fn test_extra_properties_do_not_hide_last_change() {
+        let with_extra = SAMPLE_XML.replace(
+            "<e:property><LastChange>",
+            "<e:property><SomeOtherVariable>1</SomeOtherVariable></e:property><e:property><LastChange>",
+        );
+        let parsed = AVTransportParser::from_xml(&with_extra).unwrap();
+        assert_eq!(parsed.get_playback_state(), Some(PlaybackState::Paused));
+
+        // The newest LastChange wins when several arrive together
+        let transitioning = TRANSITIONING_XML
+            .split_once("<e:property>")
+            .and_then(|(_, rest)| rest.rsplit_once("</e:propertyset>"))
+            .map(|(property, _)| property)
+            .unwrap();
+        let both = SAMPLE_XML.replace(
+            "</e:propertyset>",
+            &format!("<e:property>{}</e:propertyset>", transitioning),
+        );
+        let parsed = AVTransportParser::from_xml(&both).unwrap();
+        assert_eq!(parsed.get_playback_state(), Some(PlaybackState::Transitioning));
+
+        let without_last_change = r#"<e:propertyset xmlns:e="urn:schemas-upnp-org:event-1-0"><e:property><SomeOtherVariable>1</SomeOtherVariable></e:property></e:propertyset>"#;
+        assert!(AVTransportParser::from_xml(without_last_change).is_err());
+    }
+
+    #[test]
+    fn test_crossfade_mode() {
+        let parsed = AVTransportParser::from_xml(SAMPLE_XML).unwrap();
+        assert_eq!(parsed.get_crossfade(), Some(false));
+
+        let enabled_xml = SAMPLE_XML.replace(
+            "CurrentCrossfadeMode val=&quot;0&quot;",
+            "CurrentCrossfadeMode val=&quot;1&quot;",
+        );
+        let parsed = AVTransportParser::from_xml(&enabled_xml).unwrap();
+        assert_eq!(parsed.get_crossfade(), Some(true));
+
+        // Events that don't mention crossfade leave it unknown
+        let parsed = AVTransportParser::from_xml(TRANSITIONING_XML).unwrap();
+        assert_eq!(parsed.get_crossfade(), None);
+    }
+
 }
@@ -0,0 +1,433 @@
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::model::{Favorite, Playlist, TrackInfo};
+use crate::service::av_transport::parser::DidlItem;
+
+/// ContentDirectory events are plain property sets (no LastChange wrapper),
+/// with each update id delivered in its own `<property>` element.
+#[derive(Debug, Deserialize)]
+#[serde(rename = "propertyset")]
+pub struct ContentDirectoryParser {
+    #[serde(rename = "property", default)]
+    pub properties: Vec<Property>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Property {
+    #[serde(rename = "SystemUpdateID", default)]
+    pub system_update_id: Option<String>,
+    #[serde(rename = "ContainerUpdateIDs", default)]
+    pub container_update_ids: Option<String>,
+    #[serde(rename = "FavoritesUpdateID", default)]
+    pub favorites_update_id: Option<String>,
+    #[serde(rename = "SavedQueuesUpdateID", default)]
+    pub saved_queues_update_id: Option<String>,
+    #[serde(rename = "ShareListUpdateID", default)]
+    pub share_list_update_id: Option<String>,
+}
+
+/// A single `container,update_id` pair from a ContainerUpdateIDs value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerUpdate {
+    pub container_id: String,
+    pub update_id: String,
+}
+
+impl ContentDirectoryParser {
+    pub fn from_xml(xml: &str) -> Result<Self, quick_xml::DeError> {
+        crate::xml_decode::xml_decode::parse(xml)
+    }
+
+    pub fn get_system_update_id(&self) -> Option<&str> {
+        self.properties
+            .iter()
+            .find_map(|p| p.system_update_id.as_deref())
+    }
+
+    /// Parse every ContainerUpdateIDs property, e.g. `Q:0,12,SQ:,4` -> [(Q:0, 12), (SQ:, 4)]
+    pub fn get_container_updates(&self) -> Vec<ContainerUpdate> {
+        self.properties
+            .iter()
+            .filter_map(|p| p.container_update_ids.as_deref())
+            .flat_map(|ids| {
+                let parts: Vec<&str> = ids.split(',').map(str::trim).collect();
+                parts
+                    .chunks_exact(2)
+                    .map(|pair| ContainerUpdate {
+                        container_id: pair[0].to_string(),
+                        update_id: pair[1].to_string(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Whether any updated container is a play queue (`Q:` containers)
+    pub fn is_queue_changed(&self) -> bool {
+        self.get_container_updates()
+            .iter()
+            .any(|update| update.container_id.starts_with("Q:"))
+    }
+
+    /// Whether the library (shares, favorites, saved queues, or non-queue containers) changed
+    pub fn is_library_updated(&self) -> bool {
+        self.get_system_update_id().is_some()
+            || self.properties.iter().any(|p| {
+                p.favorites_update_id.is_some()
+                    || p.saved_queues_update_id.is_some()
+                    || p.share_list_update_id.is_some()
+            })
+            || self
+                .get_container_updates()
+                .iter()
+                .any(|update| !update.container_id.starts_with("Q:"))
+    }
+}
+
+/// SOAP response to a ContentDirectory `Browse` request
+///
+/// `L` is the shape of the DIDL-Lite listing, which depends on the container
+/// browsed; it defaults to the track listing of the queue.
+#[derive(Debug, Deserialize)]
+#[serde(rename = "Envelope", bound(deserialize = "L: DeserializeOwned"))]
+pub struct BrowseResponse<L = DidlListing> {
+    #[serde(rename = "Body")]
+    body: BrowseBody<L>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(bound(deserialize = "L: DeserializeOwned"))]
+struct BrowseBody<L> {
+    #[serde(rename = "BrowseResponse")]
+    response: BrowseResult<L>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(bound(deserialize = "L: DeserializeOwned"))]
+pub struct BrowseResult<L = DidlListing> {
+    /// DIDL-Lite listing, delivered entity-encoded inside the SOAP body
+    #[serde(
+        rename = "Result",
+        deserialize_with = "crate::xml_decode::xml_decode::deserialize_nested"
+    )]
+    pub didl: L,
+    #[serde(rename = "NumberReturned")]
+    pub number_returned: u32,
+    #[serde(rename = "TotalMatches")]
+    pub total_matches: u32,
+}
+
+/// A DIDL-Lite document with any number of items, as returned by `Browse`
+#[derive(Debug, Deserialize)]
+#[serde(rename = "DIDL-Lite")]
+pub struct DidlListing {
+    #[serde(rename = "item", default)]
+    pub items: Vec<DidlItem>,
+}
+
+/// The DIDL-Lite listing of the favorites container `FV:2`
+#[derive(Debug, Deserialize)]
+#[serde(rename = "DIDL-Lite")]
+pub struct FavoritesListing {
+    #[serde(rename = "item", default)]
+    pub items: Vec<FavoriteItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FavoriteItem {
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(rename = "title")]
+    pub title: String,
+    /// Missing for shortcuts that only open a service's browser
+    #[serde(rename = "res", default)]
+    pub res: Option<ResourceUri>,
+    #[serde(rename = "albumArtURI", default)]
+    pub album_art_uri: Option<String>,
+    #[serde(rename = "description", default)]
+    pub description: Option<String>,
+    /// DIDL-Lite for the resource, still as XML text
+    #[serde(rename = "resMD", default)]
+    pub res_md: Option<String>,
+}
+
+/// The URI text of a `<res>` element
+#[derive(Debug, Deserialize)]
+pub struct ResourceUri {
+    #[serde(rename = "$value")]
+    pub uri: String,
+}
+
+/// The DIDL-Lite listing of the Sonos playlists container `SQ:`
+#[derive(Debug, Deserialize)]
+#[serde(rename = "DIDL-Lite")]
+pub struct PlaylistsListing {
+    #[serde(rename = "container", default)]
+    pub containers: Vec<PlaylistContainer>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PlaylistContainer {
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(rename = "title")]
+    pub title: String,
+    #[serde(rename = "res", default)]
+    pub res: Option<ResourceUri>,
+    #[serde(rename = "albumArtURI", default)]
+    pub album_art_uri: Option<String>,
+}
+
+impl PlaylistContainer {
+    /// Falls back to the saved queue file when the container has no `<res>`
+    pub fn to_playlist(&self) -> Playlist {
+        let uri = match &self.res {
+            Some(res) => res.uri.clone(),
+            None => {
+                let number = self.id.strip_prefix("SQ:").unwrap_or(&self.id);
+                format!("file:///jffs/settings/savedqueues.rsq#{}", number)
+            }
+        };
+        Playlist {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            uri,
+            album_art_uri: self.album_art_uri.clone(),
+        }
+    }
+}
+
+/// The single object described by a favorite's `r:resMD`
+#[derive(Debug, Deserialize)]
+#[serde(rename = "DIDL-Lite")]
+struct ResourceMetadata {
+    #[serde(rename = "$value")]
+    object: ResourceObject,
+}
+
+/// Services describe containers as either `<item>` or `<container>`
+#[derive(Debug, Deserialize)]
+struct ResourceObject {
+    #[serde(rename = "class", default)]
+    class: Option<String>,
+}
+
+impl FavoriteItem {
+    /// `None` for favorites without a playable resource
+    pub fn to_favorite(&self) -> Option<Favorite> {
+        let res = self.res.as_ref()?;
+        let metadata = self.res_md.clone().unwrap_or_default();
+        let class = crate::xml_decode::xml_decode::parse::<ResourceMetadata>(&metadata)
+            .ok()
+            .and_then(|metadata| metadata.object.class);
+        Some(Favorite {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            uri: res.uri.clone(),
+            metadata,
+            class,
+            album_art_uri: self.album_art_uri.clone(),
+            description: self.description.clone(),
+        })
+    }
+}
+
+impl<L: DeserializeOwned> BrowseResponse<L> {
+    /// Parse a response whose listing has the shape `L`
+    pub fn parse(xml: &str) -> Result<Self, quick_xml::DeError> {
+        crate::xml_decode::xml_decode::parse(xml)
+    }
+
+    pub fn result(&self) -> &BrowseResult<L> {
+        &self.body.response
+    }
+}
+
+impl BrowseResponse {
+    pub fn from_xml(xml: &str) -> Result<Self, quick_xml::DeError> {
+        Self::parse(xml)
+    }
+
+    pub fn tracks(&self) -> Vec<TrackInfo> {
+        self.result()
+            .didl
+            .items
+            .iter()
+            .map(DidlItem::to_track_info)
+            .collect()
+    }
+}
+
+impl BrowseResponse<FavoritesListing> {
+    /// Playable favorites in their "My Sonos" order; shortcuts without a resource are skipped
+    pub fn favorites(&self) -> Vec<Favorite> {
+        self.result()
+            .didl
+            .items
+            .iter()
+            .filter_map(FavoriteItem::to_favorite)
+            .collect()
+    }
+}
+
+impl BrowseResponse<PlaylistsListing> {
+    pub fn playlists(&self) -> Vec<Playlist> {
+        self.result()
+            .didl
+            .containers
+            .iter()
+            .map(PlaylistContainer::to_playlist)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_XML: &str = r#"<e:propertyset xmlns:e="urn:schemas-upnp-org:event-1-0"><e:property><ContainerUpdateIDs>Q:0,37</ContainerUpdateIDs></e:property><e:property><SystemUpdateID>412</SystemUpdateID></e:property></e:propertyset>"#;
+
+    #[test]
+    fn test_parse_content_directory_sample_xml() {
+        let result = ContentDirectoryParser::from_xml(SAMPLE_XML);
+        assert!(result.is_ok(), "Failed to parse sample XML: {:?}", result.err());
+
+        let parser = result.unwrap();
+        assert_eq!(parser.properties.len(), 2);
+        assert_eq!(parser.get_system_update_id(), Some("412"));
+        assert_eq!(
+            parser.get_container_updates(),
+            vec![ContainerUpdate {
+                container_id: "Q:0".to_string(),
+                update_id: "37".to_string(),
+            }]
+        );
+        assert!(parser.is_queue_changed());
+        assert!(parser.is_library_updated());
+    }
+
+    #[test]
+    fn test_parse_multiple_container_updates() {
+        let xml = r#"<e:propertyset xmlns:e="urn:schemas-upnp-org:event-1-0"><e:property><ContainerUpdateIDs>SQ:,4,FV:2,9</ContainerUpdateIDs></e:property></e:propertyset>"#;
+        let parser = ContentDirectoryParser::from_xml(xml).unwrap();
+
+        let updates = parser.get_container_updates();
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].container_id, "SQ:");
+        assert_eq!(updates[1].container_id, "FV:2");
+        assert!(!parser.is_queue_changed());
+        assert!(parser.is_library_updated());
+    }
+
+    #[test]
+    fn test_parse_queue_browse_response() {
+        let xml = include_str!("../../../tests/fixtures/queue_browse_response.xml");
+        let response = BrowseResponse::from_xml(xml).unwrap();
+
+        assert_eq!(response.result().number_returned, 3);
+        assert_eq!(response.result().total_matches, 3);
+
+        let tracks = response.tracks();
+        assert_eq!(tracks.len(), 3);
+
+        assert_eq!(tracks[0].title.as_deref(), Some("Borderline"));
+        assert_eq!(tracks[0].artist.as_deref(), Some("Tame Impala"));
+        assert_eq!(tracks[0].album.as_deref(), Some("The Slow Rush"));
+        assert_eq!(tracks[0].duration_ms, Some(237_000));
+        assert_eq!(
+            tracks[0].uri.as_deref(),
+            Some("x-sonos-spotify:spotify%3atrack%3a5hM5arv9KDbCHS0k9uqwjr?sid=12&flags=8224&sn=2")
+        );
+
+        // Double-encoded ampersands decode to a single `&`
+        assert_eq!(tracks[1].artist.as_deref(), Some("Simon & Garfunkel"));
+
+        // A track without an album
+        assert_eq!(tracks[2].title.as_deref(), Some("Voice Memo"));
+        assert_eq!(tracks[2].album, None);
+        assert_eq!(tracks[2].album_art_uri, None);
+    }
+
+    #[test]
+    fn test_parse_favorites_browse_response() {
+        let xml = include_str!("../../../tests/fixtures/favorites_browse_response.xml");
+        let response = BrowseResponse::<FavoritesListing>::parse(xml).unwrap();
+
+        let favorites = response.favorites();
+        assert_eq!(favorites.len(), 2);
+
+        let radio = &favorites[0];
+        assert_eq!(radio.id, "FV:2/13");
+        assert_eq!(radio.title, "BBC Radio 6 Music");
+        assert_eq!(radio.uri, "x-sonosapi-stream:s44491?sid=254&flags=8224&sn=0");
+        assert_eq!(radio.description.as_deref(), Some("TuneIn Station"));
+        assert_eq!(radio.class.as_deref(), Some("object.item.audioItem.audioBroadcast"));
+        assert!(radio.metadata.starts_with("<DIDL-Lite"));
+        assert!(radio.metadata.contains("<desc id=\"cdudn\""));
+        assert!(!radio.is_container());
+
+        let playlist = &favorites[1];
+        assert_eq!(playlist.title, "Peaceful Piano");
+        assert_eq!(
+            playlist.uri,
+            "x-rincon-cpcontainer:1006206cspotify%3aplaylist%3a37i9dQZF1DX4sWSpwq3LiO?sid=12&flags=8300&sn=2"
+        );
+        assert_eq!(playlist.class.as_deref(), Some("object.container.playlistContainer"));
+        assert!(playlist.is_container());
+    }
+
+    #[test]
+    fn test_favorite_without_resource_is_skipped() {
+        let xml = r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body><u:BrowseResponse xmlns:u="urn:schemas-upnp-org:service:ContentDirectory:1"><Result>&lt;DIDL-Lite xmlns:dc=&quot;http://purl.org/dc/elements/1.1/&quot; xmlns=&quot;urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/&quot;&gt;&lt;item id=&quot;FV:2/4&quot; parentID=&quot;FV:2&quot;&gt;&lt;dc:title&gt;Browse Sonos Radio&lt;/dc:title&gt;&lt;/item&gt;&lt;/DIDL-Lite&gt;</Result><NumberReturned>1</NumberReturned><TotalMatches>1</TotalMatches><UpdateID>9</UpdateID></u:BrowseResponse></s:Body></s:Envelope>"#;
+        let response = BrowseResponse::<FavoritesListing>::parse(xml).unwrap();
+
+        assert_eq!(response.result().total_matches, 1);
+        assert!(response.favorites().is_empty());
+    }
+
+    #[test]
+    fn test_parse_playlists_browse_response() {
+        let xml = include_str!("../../../tests/fixtures/playlists_browse_response.xml");
+        let response = BrowseResponse::<PlaylistsListing>::parse(xml).unwrap();
+
+        let playlists = response.playlists();
+        assert_eq!(playlists.len(), 2);
+        assert_eq!(playlists[0].id, "SQ:3");
+        assert_eq!(playlists[0].title, "Dinner Party");
+        assert_eq!(playlists[0].uri, "file:///jffs/settings/savedqueues.rsq#3");
+        assert_eq!(
+            playlists[0].album_art_uri.as_deref(),
+            Some("/getaa?u=x-file-cifs%3a%2f%2fnas%2fmusic%2fcover3.jpg&v=12")
+        );
+        assert_eq!(playlists[1].id, "SQ:7");
+        assert_eq!(playlists[1].title, "Rock & Roll");
+    }
+
+    #[test]
+    fn test_playlist_without_resource_uses_saved_queue_uri() {
+        let xml = r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body><u:BrowseResponse xmlns:u="urn:schemas-upnp-org:service:ContentDirectory:1"><Result>&lt;DIDL-Lite xmlns:dc=&quot;http://purl.org/dc/elements/1.1/&quot; xmlns=&quot;urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/&quot;&gt;&lt;container id=&quot;SQ:12&quot; parentID=&quot;SQ:&quot; restricted=&quot;true&quot;&gt;&lt;dc:title&gt;Empty&lt;/dc:title&gt;&lt;/container&gt;&lt;/DIDL-Lite&gt;</Result><NumberReturned>1</NumberReturned><TotalMatches>1</TotalMatches><UpdateID>4</UpdateID></u:BrowseResponse></s:Body></s:Envelope>"#;
+        let response = BrowseResponse::<PlaylistsListing>::parse(xml).unwrap();
+
+        let playlists = response.playlists();
+        assert_eq!(playlists[0].uri, "file:///jffs/settings/savedqueues.rsq#12");
+    }
+
+    #[test]
+    fn test_parse_empty_queue_browse_response() {
+        let xml = r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body><u:BrowseResponse xmlns:u="urn:schemas-upnp-org:service:ContentDirectory:1"><Result>&lt;DIDL-Lite xmlns=&quot;urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/&quot;&gt;&lt;/DIDL-Lite&gt;</Result><NumberReturned>0</NumberReturned><TotalMatches>0</TotalMatches><UpdateID>1</UpdateID></u:BrowseResponse></s:Body></s:Envelope>"#;
+        let response = BrowseResponse::from_xml(xml).unwrap();
+
+        assert_eq!(response.result().total_matches, 0);
+        assert!(response.tracks().is_empty());
+    }
+
+    #[test]
+    fn test_parse_unrelated_properties() {
+        let xml = r#"<e:propertyset xmlns:e="urn:schemas-upnp-org:event-1-0"><e:property><RadioLocationUpdateID>RINCON_123,1</RadioLocationUpdateID></e:property></e:propertyset>"#;
+        let parser = ContentDirectoryParser::from_xml(xml).unwrap();
+
+        assert!(!parser.is_queue_changed());
+        assert!(!parser.is_library_updated());
+    }
+}
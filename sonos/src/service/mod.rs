@@ -1,3 +1,4 @@
 pub mod av_transport;
+pub mod content_directory;
 pub mod rendering_control;
 pub mod zone_group_topology;
\ No newline at end of file
@@ -4,16 +4,17 @@ use serde::Deserialize;
 #[serde(rename = "propertyset")]
 pub struct RenderingControlParser {
     #[serde(rename = "property")]
-    pub last_change: Property,
+    pub properties: Vec<Property>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Property {
     #[serde(
         rename = "LastChange",
-        deserialize_with = "crate::xml_decode::xml_decode::deserialize_nested"
+        deserialize_with = "crate::xml_decode::xml_decode::deserialize_nested",
+        default
     )]
-    pub last_change: LastChangeEvent,
+    pub last_change: Option<LastChangeEvent>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -114,14 +115,22 @@ impl RenderingControlParser {
         crate::xml_decode::xml_decode::parse(xml)
     }
 
+    /// The instance of every `LastChange` property, newest first
+    ///
+    /// A speaker may split one notification across several properties, so
+    /// each getter takes the newest instance that reports its value.
+    pub fn instances(&self) -> impl Iterator<Item = &InstanceID> {
+        self.properties
+            .iter()
+            .rev()
+            .filter_map(|property| property.last_change.as_ref())
+            .map(|last_change| &last_change.instance)
+    }
+
     pub fn get_volume(&self) -> Option<u8> {
         let volume = self
-            .last_change
-            .last_change
-            .instance
-            .volumes
-            .iter()
-            .find(|v| v.channel == "Master")?;
+            .instances()
+            .find_map(|instance| instance.volumes.iter().find(|v| v.channel == "Master"))?;
         match volume.value.parse::<i32>() {
             Ok(volume_int) => {
                 if volume_int < 0 {
@@ -139,14 +148,49 @@ impl RenderingControlParser {
 
     pub fn get_mute(&self) -> Option<bool> {
         let mute = self
-            .last_change
-            .last_change
-            .instance
-            .mutes
-            .iter()
-            .find(|m| m.channel == "Master")?;
+            .instances()
+            .find_map(|instance| instance.mutes.iter().find(|m| m.channel == "Master"))?;
         Some(mute.value == "1")
     }
+
+    pub fn get_bass(&self) -> Option<i8> {
+        Self::parse_eq_level(self.instances().find_map(|instance| instance.bass.as_ref())?)
+    }
+
+    pub fn get_treble(&self) -> Option<i8> {
+        Self::parse_eq_level(self.instances().find_map(|instance| instance.treble.as_ref())?)
+    }
+
+    pub fn get_loudness(&self) -> Option<bool> {
+        let loudness = self
+            .instances()
+            .find_map(|instance| instance.loudness.iter().find(|l| l.channel == "Master"))?;
+        Some(loudness.value == "1")
+    }
+
+    /// Subwoofer level, reported in the range -15..=15
+    pub fn get_sub_gain(&self) -> Option<i8> {
+        self.instances()
+            .find_map(|instance| instance.sub_gain.as_ref())?
+            .value
+            .parse::<i8>()
+            .ok()
+            .filter(|gain| (-15..=15).contains(gain))
+    }
+
+    pub fn get_sub_enabled(&self) -> Option<bool> {
+        let enabled = self.instances().find_map(|instance| instance.sub_enabled.as_ref())?;
+        Some(enabled.value == "1")
+    }
+
+    /// Bass and treble are reported in the range -10..=10
+    fn parse_eq_level(value: &SimpleValue) -> Option<i8> {
+        value
+            .value
+            .parse::<i8>()
+            .ok()
+            .filter(|level| (-10..=10).contains(level))
+    }
 }
 
 #[cfg(test)]
@@ -166,7 +210,7 @@ mod tests {
         );
 
         let parsed = result.unwrap();
-        let instance = &parsed.last_change.last_change.instance;
+        let instance = parsed.instances().next().unwrap();
 
         // Test basic instance ID
         assert_eq!(instance.id, "0");
@@ -210,6 +254,78 @@ mod tests {
         );
     }
 
+    fn event_with(instance_body: &str) -> String {
+        format!(
+            r#"<e:propertyset xmlns:e="urn:schemas-upnp-org:event-1-0"><e:property><LastChange>&lt;Event xmlns=&quot;urn:schemas-upnp-org:metadata-1-0/RCS/&quot;&gt;&lt;InstanceID val=&quot;0&quot;&gt;{}&lt;/InstanceID&gt;&lt;/Event&gt;</LastChange></e:property></e:propertyset>"#,
+            instance_body
+        )
+    }
+
+    #[test]
+    fn test_get_bass() {
+        let parsed = RenderingControlParser::from_xml(&event_with(
+            "&lt;Bass val=&quot;-7&quot;/&gt;",
+        ))
+        .unwrap();
+        assert_eq!(parsed.get_bass(), Some(-7));
+        assert_eq!(parsed.get_treble(), None);
+
+        let out_of_range = RenderingControlParser::from_xml(&event_with(
+            "&lt;Bass val=&quot;12&quot;/&gt;",
+        ))
+        .unwrap();
+        assert_eq!(out_of_range.get_bass(), None);
+    }
+
+    #[test]
+    fn test_get_treble() {
+        let parsed = RenderingControlParser::from_xml(&event_with(
+            "&lt;Treble val=&quot;3&quot;/&gt;",
+        ))
+        .unwrap();
+        assert_eq!(parsed.get_treble(), Some(3));
+        assert_eq!(parsed.get_bass(), None);
+    }
+
+    #[test]
+    fn test_get_loudness() {
+        let on = RenderingControlParser::from_xml(SAMPLE_XML).unwrap();
+        assert_eq!(on.get_loudness(), Some(true));
+
+        let off = RenderingControlParser::from_xml(&event_with(
+            "&lt;Loudness channel=&quot;Master&quot; val=&quot;0&quot;/&gt;",
+        ))
+        .unwrap();
+        assert_eq!(off.get_loudness(), Some(false));
+
+        let missing = RenderingControlParser::from_xml(&event_with(
+            "&lt;Volume channel=&quot;Master&quot; val=&quot;20&quot;/&gt;",
+        ))
+        .unwrap();
+        assert_eq!(missing.get_loudness(), None);
+    }
+
+    #[test]
+    fn test_get_sub_gain_and_enabled() {
+        let sample = RenderingControlParser::from_xml(SAMPLE_XML).unwrap();
+        assert_eq!(sample.get_sub_gain(), Some(0));
+        assert_eq!(sample.get_sub_enabled(), Some(true));
+
+        let parsed = RenderingControlParser::from_xml(&event_with(
+            "&lt;SubGain val=&quot;-12&quot;/&gt;&lt;SubEnabled val=&quot;0&quot;/&gt;",
+        ))
+        .unwrap();
+        assert_eq!(parsed.get_sub_gain(), Some(-12));
+        assert_eq!(parsed.get_sub_enabled(), Some(false));
+
+        let out_of_range = RenderingControlParser::from_xml(&event_with(
+            "&lt;SubGain val=&quot;16&quot;/&gt;",
+        ))
+        .unwrap();
+        assert_eq!(out_of_range.get_sub_gain(), None);
+        assert_eq!(out_of_range.get_sub_enabled(), None);
+    }
+
     #[test]
     fn test_parse_rendering_control_invalid_xml() {
         let invalid_xml = "<invalid>xml</invalid>";
@@ -30,16 +30,79 @@ pub struct Property {
 pub struct ZoneGroupState {
     #[serde(rename = "ZoneGroups")]
     pub zone_groups: ZoneGroups,
-    #[serde(rename = "VanishedDevices")]
+    #[serde(rename = "VanishedDevices", default)]
     pub vanished_devices: VanishedDevices,
 }
 
 #[derive(Debug, Deserialize, Clone)]
+#[serde(from = "RawZoneGroups")]
 pub struct ZoneGroups {
-    #[serde(rename = "ZoneGroup", default)]
     pub zone_groups: Vec<ZoneGroup>,
 }
 
+/// `ZoneGroups` as it appears in the XML, before bond roles are resolved
+#[derive(Deserialize)]
+struct RawZoneGroups {
+    #[serde(rename = "ZoneGroup", default)]
+    zone_groups: Vec<ZoneGroup>,
+}
+
+impl From<RawZoneGroups> for ZoneGroups {
+    fn from(raw: RawZoneGroups) -> Self {
+        let mut zone_groups = raw.zone_groups;
+        for member in zone_groups.iter_mut().flat_map(|group| group.zone_group_members.iter_mut()) {
+            member.bond_role = [&member.channel_map_set, &member.ht_sat_chan_map_set]
+                .into_iter()
+                .flatten()
+                .find_map(|map| BondRole::from_channel_map(map, &member.uuid));
+            for satellite in member.satellites.iter_mut() {
+                satellite.bond_role = [Some(&satellite.ht_sat_chan_map_set), member.ht_sat_chan_map_set.as_ref()]
+                    .into_iter()
+                    .flatten()
+                    .find_map(|map| BondRole::from_channel_map(map, &satellite.uuid));
+            }
+        }
+        Self { zone_groups }
+    }
+}
+
+/// The part a bonded speaker plays in a stereo pair or home theater
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BondRole {
+    Left,
+    Right,
+    Sub,
+    SurroundLeft,
+    SurroundRight,
+}
+
+impl BondRole {
+    /// Find `speaker`'s role in a `ChannelMapSet` or `HTSatChanMapSet` value
+    ///
+    /// Both look like `RINCON_A:LF,LF;RINCON_B:RF,RF;RINCON_C:SW,SW`. A speaker
+    /// playing both front channels (a soundbar, or an unpaired speaker) has no role.
+    pub fn from_channel_map(map: &str, speaker: &SpeakerId) -> Option<Self> {
+        let channels = map.split(';').find_map(|entry| {
+            let (id, channels) = entry.split_once(':')?;
+            (SpeakerId::new(id) == *speaker).then_some(channels)
+        })?;
+
+        let mut channels = channels.split(',').map(str::trim);
+        let first = channels.next()?;
+        if channels.any(|channel| channel != first) {
+            return None;
+        }
+        match first {
+            "LF" => Some(BondRole::Left),
+            "RF" => Some(BondRole::Right),
+            "SW" => Some(BondRole::Sub),
+            "LR" => Some(BondRole::SurroundLeft),
+            "RR" => Some(BondRole::SurroundRight),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct ZoneGroup {
     #[serde(rename = "@Coordinator")]
@@ -72,6 +135,9 @@ pub struct ZoneGroupMember {
     pub legacy_compatible_version: String,
     #[serde(rename = "@HTSatChanMapSet", default)]
     pub ht_sat_chan_map_set: Option<String>,
+    /// Channel assignment of a stereo pair, present on each bonded member
+    #[serde(rename = "@ChannelMapSet", default)]
+    pub channel_map_set: Option<String>,
     #[serde(rename = "@ActiveZoneID", default)]
     pub active_zone_id: Option<String>,
     #[serde(rename = "@BootSeq")]
@@ -118,6 +184,9 @@ pub struct ZoneGroupMember {
     pub hhssl_port: String,
     #[serde(rename = "Satellite", default)]
     pub satellites: Vec<Satellite>,
+    /// Role in a stereo pair, or as the sub of one; `None` for standalone speakers
+    #[serde(skip)]
+    pub bond_role: Option<BondRole>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -186,11 +255,27 @@ pub struct Satellite {
     pub ssl_port: String,
     #[serde(rename = "@HHSSLPort")]
     pub hhssl_port: String,
+    /// Role in the home theater: sub or a surround
+    #[serde(skip)]
+    pub bond_role: Option<BondRole>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 pub struct VanishedDevices {
-    // Empty for now, can be extended if needed
+    #[serde(rename = "Device", default)]
+    pub devices: Vec<VanishedDevice>,
+}
+
+/// A speaker the household still knows about but that has left the network
+#[derive(Debug, Deserialize, Clone)]
+pub struct VanishedDevice {
+    #[serde(rename = "@UUID")]
+    pub uuid: SpeakerId,
+    #[serde(rename = "@ZoneName", default)]
+    pub zone_name: String,
+    /// Why the speaker vanished, e.g. "powered off"
+    #[serde(rename = "@Reason", default)]
+    pub reason: String,
 }
 
 impl ZoneGroupTopologyParser {
@@ -271,4 +356,69 @@ mod tests {
         assert_eq!(zone_groups[2].zone_group_members[0].zone_name, "Bedroom");
         assert_eq!(zone_groups[3].zone_group_members[0].zone_name, "Bathroom");
     }
+
+    #[test]
+    fn test_parse_stereo_pair_and_home_theater_bond_roles() {
+        let xml = include_str!("../../../tests/fixtures/zone_group_topology_stereo_pair.xml");
+        let parsed = ZoneGroupTopologyParser::from_xml(xml).unwrap();
+        let zone_groups = &parsed
+            .zone_group_state()
+            .and_then(|property| property.zone_group_state.as_ref())
+            .expect("Should have ZoneGroupState property")
+            .zone_groups
+            .zone_groups;
+
+        let pair: Vec<_> = zone_groups[0]
+            .zone_group_members
+            .iter()
+            .map(|member| (member.zone_name.as_str(), member.bond_role))
+            .collect();
+        assert_eq!(
+            pair,
+            vec![
+                ("Living Room", Some(BondRole::Left)),
+                ("Living Room", Some(BondRole::Right)),
+                ("Living Room", Some(BondRole::Sub)),
+            ]
+        );
+
+        let soundbar = &zone_groups[1].zone_group_members[0];
+        assert_eq!(soundbar.bond_role, None);
+        let satellites: Vec<_> = soundbar.satellites.iter().map(|satellite| satellite.bond_role).collect();
+        assert_eq!(
+            satellites,
+            vec![Some(BondRole::Sub), Some(BondRole::SurroundLeft), Some(BondRole::SurroundRight)]
+        );
+    }
+
+    #[test]
+    fn test_bond_role_from_channel_map() {
+        let map = "RINCON_A:LF,RF;RINCON_B:SW;RINCON_C:LR";
+        assert_eq!(BondRole::from_channel_map(map, &SpeakerId::new("RINCON_A")), None);
+        assert_eq!(BondRole::from_channel_map(map, &SpeakerId::new("RINCON_B")), Some(BondRole::Sub));
+        assert_eq!(
+            BondRole::from_channel_map(map, &SpeakerId::new("uuid:RINCON_C")),
+            Some(BondRole::SurroundLeft)
+        );
+        assert_eq!(BondRole::from_channel_map(map, &SpeakerId::new("RINCON_D")), None);
+        assert_eq!(BondRole::from_channel_map("", &SpeakerId::new("RINCON_A")), None);
+    }
+
+    #[test]
+    fn test_parse_vanished_devices() {
+        let xml = include_str!("../../../tests/fixtures/zone_group_topology_vanished_event.xml");
+        let parsed = ZoneGroupTopologyParser::from_xml(xml).unwrap();
+        let zone_group_state = parsed
+            .zone_group_state()
+            .and_then(|property| property.zone_group_state.as_ref())
+            .expect("Should have ZoneGroupState property");
+
+        assert_eq!(zone_group_state.zone_groups.zone_groups.len(), 1);
+        let devices = &zone_group_state.vanished_devices.devices;
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].uuid, SpeakerId::new("RINCON_000E583FEE3401400"));
+        assert_eq!(devices[0].zone_name, "Kitchen");
+        assert_eq!(devices[0].reason, "powered off");
+    }
+
 }
\ No newline at end of file
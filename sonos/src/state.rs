@@ -1,7 +1,9 @@
-use crate::model::{GroupId, PlaybackState, Speaker, SpeakerId, SpeakerState};
+use crate::model::{GroupId, PlaybackState, Speaker, SpeakerId, SpeakerState, StateChange};
 use crate::group::Group;
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use crate::topology::Topology;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{mpsc, Arc, Mutex, RwLock};
 
 /// A snapshot of the current state that provides efficient read-only access
 /// to speakers and groups without cloning the entire collections.
@@ -56,9 +58,20 @@ impl<'a> StateSnapshot<'a> {
     }
 }
 
+/// An owned, serializable copy of every speaker state and group in the cache.
+///
+/// Restoring a snapshot only rewrites the cache; it does not touch the devices.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheSnapshot {
+    pub speakers: Vec<SpeakerState>,
+    pub groups: Vec<Group>,
+}
+
 pub struct StateCache {
     speakers: Arc<RwLock<HashMap<SpeakerId, SpeakerState>>>,
     groups: Arc<RwLock<HashMap<GroupId, Group>>>,
+    /// Channels from `subscribe`, told which speaker changed after each update
+    subscribers: Arc<Mutex<Vec<mpsc::Sender<SpeakerId>>>>,
 }
 
 impl StateCache {
@@ -66,12 +79,31 @@ impl StateCache {
         Self {
             speakers: Arc::new(RwLock::new(HashMap::new())),
             groups: Arc::new(RwLock::new(HashMap::new())),
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Receive the ID of every speaker whose cached state changes
+    ///
+    /// Each call returns an independent receiver; a UI can use it to redraw
+    /// only the speakers that changed. Dropping the receiver unsubscribes.
+    pub fn subscribe(&self) -> mpsc::Receiver<SpeakerId> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.lock().unwrap().push(sender);
+        receiver
+    }
+
+    /// Tell subscribers `id` changed, forgetting any whose receiver is gone
+    fn notify(&self, id: &SpeakerId) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.retain(|subscriber| subscriber.send(id.clone()).is_ok());
         }
     }
 
     pub fn initialize(&self, speakers: Vec<Speaker>, groups: Vec<Group>) {
         // Initialize speakers
         let mut speaker_cache = self.speakers.write().unwrap();
+        let ids: Vec<SpeakerId> = speakers.iter().map(|speaker| speaker.get_id().clone()).collect();
         for speaker in speakers {
             let id = speaker.get_id().clone();
             speaker_cache.insert(
@@ -95,12 +127,71 @@ impl StateCache {
         for group in groups {
             group_cache.insert(group.get_id().clone(), group);
         }
+        drop(group_cache);
+
+        for id in &ids {
+            self.notify(id);
+        }
+    }
+
+    /// Add a speaker, or refresh its details if the cache already knows its UDN.
+    ///
+    /// A known speaker keeps its playback state, volume and grouping; only the
+    /// discovery details such as the IP address are replaced.
+    pub fn store_speaker(&self, speaker: Speaker) {
+        let id = speaker.get_id().clone();
+        let mut speakers = self.speakers.write().unwrap();
+        match speakers.get_mut(speaker.get_id()) {
+            Some(state) => state.speaker = speaker,
+            None => {
+                speakers.insert(
+                    speaker.get_id().clone(),
+                    SpeakerState {
+                        speaker,
+                        playback_state: PlaybackState::Stopped,
+                        volume: 0,
+                        muted: false,
+                        position_ms: 0,
+                        duration_ms: 0,
+                        is_coordinator: false,
+                        group_id: None,
+                    },
+                );
+            }
+        }
+        drop(speakers);
+
+        self.notify(&id);
     }
 
     pub fn get_speaker(&self, id: &SpeakerId) -> Option<SpeakerState> {
         self.speakers.read().unwrap().get(id).cloned()
     }
 
+    /// Current state for a single speaker
+    pub fn speaker_state(&self, id: &SpeakerId) -> Option<SpeakerState> {
+        self.get_speaker(id)
+    }
+
+    /// Playback state of a group, which is the playback state of its coordinator
+    pub fn group_playback_state(&self, group_id: &GroupId) -> Option<PlaybackState> {
+        let coordinator_id = self
+            .groups
+            .read()
+            .unwrap()
+            .get(group_id)
+            .map(|group| group.get_coordinator_id().clone());
+
+        let speakers = self.speakers.read().unwrap();
+        match coordinator_id {
+            Some(coordinator_id) => speakers.get(&coordinator_id).map(|s| s.playback_state),
+            None => speakers
+                .values()
+                .find(|s| s.group_id.as_ref() == Some(group_id) && s.is_coordinator)
+                .map(|s| s.playback_state),
+        }
+    }
+
     pub fn get_all_speakers(&self) -> Vec<SpeakerState> {
         self.speakers.read().unwrap().values().cloned().collect()
     }
@@ -125,35 +216,64 @@ impl StateCache {
     }
 
     pub fn update_volume(&self, id: &SpeakerId, volume: u8) {
-        if let Ok(mut speakers) = self.speakers.write() {
-            if let Some(state) = speakers.get_mut(id) {
-                state.volume = volume;
-            }
-        }
+        self.update_speaker(id, |state| state.volume = volume);
     }
 
     pub fn update_mute(&self, id: &SpeakerId, muted: bool) {
-        if let Ok(mut speakers) = self.speakers.write() {
-            if let Some(state) = speakers.get_mut(id) {
-                state.muted = muted;
-            }
-        }
+        self.update_speaker(id, |state| state.muted = muted);
     }
 
     pub fn update_playback_state(&self, id: &SpeakerId, state: PlaybackState) {
-        if let Ok(mut speakers) = self.speakers.write() {
-            if let Some(speaker_state) = speakers.get_mut(id) {
-                speaker_state.playback_state = state;
-            }
-        }
+        self.update_speaker(id, |speaker_state| speaker_state.playback_state = state);
     }
 
     pub fn update_position(&self, id: &SpeakerId, position_ms: u64) {
-        if let Ok(mut speakers) = self.speakers.write() {
-            if let Some(state) = speakers.get_mut(id) {
-                state.position_ms = position_ms;
+        self.update_speaker(id, |state| state.position_ms = position_ms);
+    }
+
+    pub fn update_group_membership(&self, id: &SpeakerId, group_id: Option<GroupId>, is_coordinator: bool) {
+        self.update_speaker(id, |state| {
+            state.group_id = group_id;
+            state.is_coordinator = is_coordinator;
+        });
+    }
+
+    /// Apply `update` to a cached speaker and notify subscribers; unknown speakers are ignored
+    fn update_speaker(&self, id: &SpeakerId, update: impl FnOnce(&mut SpeakerState)) {
+        let updated = match self.speakers.write() {
+            Ok(mut speakers) => speakers.get_mut(id).map(update).is_some(),
+            Err(_) => false,
+        };
+        if updated {
+            self.notify(id);
+        }
+    }
+
+    /// Align cached group memberships and coordinators with an authoritative topology.
+    ///
+    /// Returns a `GroupMembershipChanged` for every speaker whose cached grouping
+    /// disagreed with the topology, so subscribers can be told about the correction.
+    pub fn reconcile(&self, topology: &Topology) -> Vec<StateChange> {
+        let mut changes = Vec::new();
+
+        if let Ok(speakers) = self.speakers.read() {
+            for (speaker_id, state) in speakers.iter() {
+                let group = topology.get_group_for_speaker(speaker_id);
+                let group_id = group.map(|g| g.get_id().clone());
+                let is_coordinator = group.is_some_and(|g| g.get_coordinator_id() == speaker_id);
+
+                if state.group_id != group_id || state.is_coordinator != is_coordinator {
+                    changes.push(StateChange::GroupMembershipChanged {
+                        speaker_id: speaker_id.clone(),
+                        group_id,
+                        is_coordinator,
+                    });
+                }
             }
         }
+
+        self.set_groups(topology.get_groups().to_vec());
+        changes
     }
 
     pub fn set_groups(&self, groups: Vec<Group>) {
@@ -164,7 +284,13 @@ impl StateCache {
         group_cache.insert(group.get_id().clone(), group);
       }
 
+        let mut changed = Vec::new();
         if let Ok(mut speakers) = self.speakers.write() {
+            let previous: HashMap<SpeakerId, (Option<GroupId>, bool)> = speakers
+                .iter()
+                .map(|(id, state)| (id.clone(), (state.group_id.clone(), state.is_coordinator)))
+                .collect();
+
             for speaker_state in speakers.values_mut() {
                 speaker_state.group_id = None;
                 speaker_state.is_coordinator = false;
@@ -186,6 +312,61 @@ impl StateCache {
                     }
                 }
             }
+
+            changed.extend(
+                speakers
+                    .iter()
+                    .filter(|(id, state)| {
+                        previous.get(*id) != Some(&(state.group_id.clone(), state.is_coordinator))
+                    })
+                    .map(|(id, _)| id.clone()),
+            );
+        }
+        drop(group_cache);
+
+        for id in &changed {
+            self.notify(id);
+        }
+    }
+
+    /// Capture all speaker states and group memberships
+    pub fn snapshot(&self) -> CacheSnapshot {
+        CacheSnapshot {
+            speakers: self.speakers.read().unwrap().values().cloned().collect(),
+            groups: self.groups.read().unwrap().values().cloned().collect(),
+        }
+    }
+
+    /// Replace the cache contents with a previously captured snapshot.
+    ///
+    /// Subscribers hear about every speaker in the snapshot and every speaker
+    /// the restore removed.
+    pub fn restore_from(&self, snapshot: &CacheSnapshot) {
+        let mut group_cache = self.groups.write().unwrap();
+        let mut speaker_cache = self.speakers.write().unwrap();
+
+        let mut affected: Vec<SpeakerId> = speaker_cache
+            .keys()
+            .filter(|id| !snapshot.speakers.iter().any(|state| state.speaker.get_id() == *id))
+            .cloned()
+            .collect();
+        affected.extend(snapshot.speakers.iter().map(|state| state.speaker.get_id().clone()));
+
+        *speaker_cache = snapshot
+            .speakers
+            .iter()
+            .map(|state| (state.speaker.get_id().clone(), state.clone()))
+            .collect();
+        *group_cache = snapshot
+            .groups
+            .iter()
+            .map(|group| (group.get_id().clone(), group.clone()))
+            .collect();
+        drop(speaker_cache);
+        drop(group_cache);
+
+        for id in &affected {
+            self.notify(id);
         }
     }
 
@@ -198,6 +379,48 @@ impl StateCache {
         self.groups.read().unwrap().get(id).cloned()
     }
 
+    /// Human-readable name for a speaker, for labelling events
+    ///
+    /// Prefers the room name from the last topology the cache received, where
+    /// satellites and bonded speakers take their room's name, then the room name
+    /// from discovery. Unknown speakers fall back to the bare device ID, e.g.
+    /// `RINCON_000E58A0123401400`; only an empty ID gives `None`.
+    pub fn display_name(&self, id: &SpeakerId) -> Option<String> {
+        let from_topology = self.groups.read().unwrap().values().find_map(|group| {
+            group
+                .get_members()
+                .iter()
+                .find(|member| member.get_id() == id || member.get_satellites().contains(id))
+                .and_then(|member| member.get_room_name().map(str::to_string))
+        });
+        from_topology
+            .or_else(|| self.get_speaker(id).map(|state| state.speaker.room_name))
+            .filter(|name| !name.is_empty())
+            .or_else(|| Some(id.as_str().to_string()).filter(|id| !id.is_empty()))
+    }
+
+    /// Known speakers grouped by their display name, so a stereo pair or a
+    /// home theater lists as one room
+    pub fn rooms(&self) -> BTreeMap<String, Vec<SpeakerId>> {
+        let mut ids: Vec<SpeakerId> = self.speakers.read().unwrap().keys().cloned().collect();
+        for group in self.groups.read().unwrap().values() {
+            for member in group.get_members() {
+                ids.push(member.get_id().clone());
+                ids.extend(member.get_satellites().iter().cloned());
+            }
+        }
+        ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        ids.dedup();
+
+        let mut rooms: BTreeMap<String, Vec<SpeakerId>> = BTreeMap::new();
+        for id in ids {
+            if let Some(name) = self.display_name(&id) {
+                rooms.entry(name).or_default().push(id);
+            }
+        }
+        rooms
+    }
+
     pub fn get_speaker_states_by_group_id(&self, group_id: &GroupId) -> Vec<SpeakerState> {
     let groups = self.groups.read().unwrap();
     let Some(group) = groups.get(group_id) else {
@@ -219,6 +442,7 @@ impl Clone for StateCache {
         Self {
             speakers: self.speakers.clone(),
             groups: self.groups.clone(),
+            subscribers: self.subscribers.clone(),
         }
     }
 }
@@ -247,6 +471,9 @@ mod tests {
             port: 1400,
             model_name: "Sonos One".to_string(),
             satellites: vec![],
+            supported_services: vec![],
+            software_version: None,
+            hardware_version: None,
         };
 
         let speaker2 = Speaker {
@@ -257,12 +484,57 @@ mod tests {
             port: 1400,
             model_name: "Sonos Play:1".to_string(),
             satellites: vec![],
+            supported_services: vec![],
+            software_version: None,
+            hardware_version: None,
         };
 
         cache.initialize(vec![speaker1.clone(), speaker2.clone()], vec![]);
         (cache, speaker1, speaker2)
     }
 
+    #[test]
+    fn test_display_name_falls_back_to_discovery_then_id() {
+        let (cache, speaker1, _) = create_test_cache();
+
+        assert_eq!(cache.display_name(&speaker1.id).as_deref(), Some("Living Room"));
+        assert_eq!(
+            cache.display_name(&SpeakerId::new("uuid:RINCON_000E58A0123401400::1")).as_deref(),
+            Some("RINCON_000E58A0123401400")
+        );
+        assert_eq!(cache.display_name(&SpeakerId::new("")), None);
+    }
+
+    #[test]
+    fn test_subscribers_receive_updated_speaker_id() {
+        let (cache, speaker1, _) = create_test_cache();
+        let first = cache.subscribe();
+        let second = cache.subscribe();
+
+        cache.update_volume(speaker1.get_id(), 35);
+        cache.update_volume(&SpeakerId::new("uuid:RINCON_999999999::1"), 10);
+
+        assert_eq!(first.try_recv().unwrap(), speaker1.id);
+        assert_eq!(second.try_recv().unwrap(), speaker1.id);
+        assert!(first.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_initialize_and_store_speaker_notify_subscribers() {
+        let cache = StateCache::new();
+        let subscriber = cache.subscribe();
+        let (_, speaker1, speaker2) = create_test_cache();
+
+        cache.initialize(vec![speaker1.clone(), speaker2.clone()], vec![]);
+        cache.store_speaker(speaker1.clone());
+
+        let notified: Vec<SpeakerId> = subscriber.try_iter().collect();
+        assert_eq!(notified.len(), 3);
+        assert!(notified[..2].contains(&speaker1.id));
+        assert!(notified[..2].contains(&speaker2.id));
+        assert_eq!(notified[2], speaker1.id);
+    }
+
     #[test]
     fn test_new() {
         let cache = StateCache::new();
@@ -283,6 +555,9 @@ mod tests {
             port: 1400,
             model_name: "Sonos One".to_string(),
             satellites: vec![],
+            supported_services: vec![],
+            software_version: None,
+            hardware_version: None,
         };
 
         let speaker2 = Speaker {
@@ -293,6 +568,9 @@ mod tests {
             port: 1400,
             model_name: "Sonos Play:1".to_string(),
             satellites: vec![],
+            supported_services: vec![],
+            software_version: None,
+            hardware_version: None,
         };
 
         let speakers = vec![speaker1.clone(), speaker2.clone()];
@@ -397,6 +675,147 @@ mod tests {
         assert_eq!(state.position_ms, 30000);
     }
 
+    #[test]
+    fn test_reconcile_corrects_stale_grouping() {
+        use crate::group::SpeakerRef;
+
+        let (cache, speaker1, speaker2) = create_test_cache();
+        let stale_group_id = GroupId::new("RINCON_123456789:1");
+        let fresh_group_id = GroupId::new("RINCON_987654321:7");
+
+        // Cache believes both speakers are grouped under speaker1
+        cache.set_groups(vec![Group::new(
+            stale_group_id.clone(),
+            speaker1.get_id().clone(),
+            vec![
+                SpeakerRef::new(speaker1.get_id().clone(), vec![]),
+                SpeakerRef::new(speaker2.get_id().clone(), vec![]),
+            ],
+        )]);
+
+        // Reality: speaker2 left and now coordinates its own group
+        let topology = Topology::new(vec![
+            Group::new(
+                stale_group_id.clone(),
+                speaker1.get_id().clone(),
+                vec![SpeakerRef::new(speaker1.get_id().clone(), vec![])],
+            ),
+            Group::new(
+                fresh_group_id.clone(),
+                speaker2.get_id().clone(),
+                vec![SpeakerRef::new(speaker2.get_id().clone(), vec![])],
+            ),
+        ]);
+
+        let changes = cache.reconcile(&topology);
+        assert_eq!(changes.len(), 1);
+        match &changes[0] {
+            StateChange::GroupMembershipChanged { speaker_id, group_id, is_coordinator } => {
+                assert_eq!(speaker_id, speaker2.get_id());
+                assert_eq!(group_id.as_ref(), Some(&fresh_group_id));
+                assert!(*is_coordinator);
+            }
+            other => panic!("Unexpected change: {:?}", other),
+        }
+
+        let state1 = cache.get_speaker(speaker1.get_id()).unwrap();
+        assert_eq!(state1.group_id, Some(stale_group_id.clone()));
+        assert!(state1.is_coordinator);
+        let state2 = cache.get_speaker(speaker2.get_id()).unwrap();
+        assert_eq!(state2.group_id, Some(fresh_group_id.clone()));
+        assert!(state2.is_coordinator);
+        assert_eq!(cache.get_groups().len(), 2);
+
+        // A second pass is a no-op once the cache is consistent
+        assert!(cache.reconcile(&topology).is_empty());
+    }
+
+    #[test]
+    fn test_store_speaker_keeps_state_across_ip_change() {
+        let (cache, speaker1, _) = create_test_cache();
+        cache.update_volume(speaker1.get_id(), 35);
+
+        let renewed = Speaker {
+            ip_address: "192.168.1.150".to_string(),
+            ..speaker1.clone()
+        };
+        cache.store_speaker(renewed);
+
+        let state = cache.get_speaker(speaker1.get_id()).unwrap();
+        assert_eq!(state.speaker.ip_address, "192.168.1.150");
+        assert_eq!(state.volume, 35);
+        assert_eq!(cache.get_all_speakers().len(), 2);
+    }
+
+    #[test]
+    fn test_store_speaker_adds_unknown_speaker() {
+        let (cache, _, _) = create_test_cache();
+        let speaker3 = Speaker {
+            id: SpeakerId::new("uuid:RINCON_555555555::1"),
+            ip_address: "192.168.1.102".to_string(),
+            ..create_test_cache().1
+        };
+
+        cache.store_speaker(speaker3.clone());
+
+        assert_eq!(cache.get_speaker(speaker3.get_id()).unwrap().volume, 0);
+        assert_eq!(cache.get_all_speakers().len(), 3);
+    }
+
+    #[test]
+    fn test_snapshot_restore_round_trip() {
+        use crate::group::SpeakerRef;
+
+        let (cache, speaker1, speaker2) = create_test_cache();
+        let group_id = GroupId::new("RINCON_123456789:3");
+        cache.set_groups(vec![Group::new(
+            group_id.clone(),
+            speaker1.get_id().clone(),
+            vec![
+                SpeakerRef::new(speaker1.get_id().clone(), vec![]),
+                SpeakerRef::new(speaker2.get_id().clone(), vec![]),
+            ],
+        )]);
+        cache.update_volume(speaker1.get_id(), 35);
+        cache.update_playback_state(speaker1.get_id(), PlaybackState::Playing);
+        cache.update_mute(speaker2.get_id(), true);
+
+        let snapshot = cache.snapshot();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let restored_snapshot: CacheSnapshot = serde_json::from_str(&json).unwrap();
+
+        // Simulate an announcement changing everything
+        cache.update_volume(speaker1.get_id(), 80);
+        cache.update_playback_state(speaker1.get_id(), PlaybackState::Stopped);
+        cache.set_groups(vec![]);
+
+        let restored = StateCache::new();
+        let subscriber = restored.subscribe();
+        restored.restore_from(&restored_snapshot);
+        cache.restore_from(&restored_snapshot);
+
+        let mut notified: Vec<SpeakerId> = subscriber.try_iter().collect();
+        notified.sort_by_key(|id| id.to_string());
+        let mut expected = vec![speaker1.id.clone(), speaker2.id.clone()];
+        expected.sort_by_key(|id| id.to_string());
+        assert_eq!(notified, expected);
+
+        for target in [&cache, &restored] {
+            let state1 = target.get_speaker(speaker1.get_id()).unwrap();
+            assert_eq!(state1.volume, 35);
+            assert_eq!(state1.playback_state, PlaybackState::Playing);
+            assert_eq!(state1.group_id, Some(group_id.clone()));
+            assert!(state1.is_coordinator);
+
+            let state2 = target.get_speaker(speaker2.get_id()).unwrap();
+            assert!(state2.muted);
+            assert!(!state2.is_coordinator);
+
+            assert_eq!(target.get_all_speakers().len(), 2);
+            assert_eq!(target.get_group(&group_id).unwrap().get_members().len(), 2);
+        }
+    }
+
     #[test]
     fn test_clone() {
         let (cache, speaker, _) = create_test_cache();
@@ -36,7 +36,9 @@ impl AVTransportSubscription {
         callback_url: String,
         config: SubscriptionConfig,
     ) -> SubscriptionResult<Self> {
+        config.validate().map_err(SubscriptionError::InvalidConfiguration)?;
         let soap_client = SoapClient::new(std::time::Duration::from_secs(30))
+            .and_then(|client| client.with_headers(&config.user_agent, &config.extra_headers))
             .map_err(|e| SubscriptionError::NetworkError(e.to_string()))?;
 
         Ok(Self {
@@ -62,16 +64,15 @@ impl AVTransportSubscription {
         let event_sub_url = ServiceType::AVTransport.event_sub_url();
         let full_url = format!("{}{}", device_url, event_sub_url);
 
-        println!("📡 Sending SUBSCRIBE request to: {}", full_url);
-        println!("   Callback URL: {}", self.callback_url);
+        log::debug!(
+            "Sending AVTransport SUBSCRIBE request to {} (callback {})",
+            full_url,
+            self.callback_url
+        );
 
         // Create HTTP client for subscription requests with timeout
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .build()
-            .map_err(|e| SubscriptionError::NetworkError(e.to_string()))?;
+        let client = self.config.http_client()?;
 
-        println!("🔄 Making HTTP SUBSCRIBE request...");
         let response = client
             .request(
                 reqwest::Method::from_bytes(b"SUBSCRIBE").unwrap(),
@@ -86,7 +87,7 @@ impl AVTransportSubscription {
             .header("TIMEOUT", format!("Second-{}", self.config.timeout_seconds))
             .send()
             .map_err(|e| {
-                println!("❌ HTTP request failed: {}", e);
+                log::debug!("AVTransport SUBSCRIBE request failed: {}", e);
                 SubscriptionError::NetworkError(e.to_string())
             })?;
 
@@ -125,10 +126,7 @@ impl AVTransportSubscription {
         let event_sub_url = ServiceType::AVTransport.event_sub_url();
         let full_url = format!("{}{}", device_url, event_sub_url);
 
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .build()
-            .map_err(|e| SubscriptionError::NetworkError(e.to_string()))?;
+        let client = self.config.http_client()?;
 
         let response = client
             .request(
@@ -159,10 +157,7 @@ impl AVTransportSubscription {
         let event_sub_url = ServiceType::AVTransport.event_sub_url();
         let full_url = format!("{}{}", device_url, event_sub_url);
 
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .build()
-            .map_err(|e| SubscriptionError::NetworkError(e.to_string()))?;
+        let client = self.config.http_client()?;
 
         let response = client
             .request(
@@ -255,16 +250,30 @@ impl ServiceSubscription for AVTransportSubscription {
                     None => {}
                 }
 
-                match parser.get_track_info() {
-                    Some(track_info) => changes.push(StateChange::TrackChanged {
+                if let Some(mode) = parser.get_play_mode() {
+                    changes.push(StateChange::PlayModeChanged {
+                        speaker_id: self.speaker_id().clone(),
+                        mode,
+                    });
+                }
+
+                if let Some(crossfade) = parser.get_crossfade() {
+                    changes.push(StateChange::CrossfadeChanged {
+                        speaker_id: self.speaker_id().clone(),
+                        crossfade,
+                    });
+                }
+
+                let base_url = format!("http://{}:{}", self.speaker.ip_address, self.speaker.port);
+                if let Some(track_info) = parser.get_track_info_for_speaker(&base_url) {
+                    changes.push(StateChange::TrackChanged {
                         speaker_id: self.speaker_id().clone(),
                         track_info: Some(track_info),
-                    }),
-                    None => {}
+                    });
                 }
             }
             Err(e) => {
-              println!("no parser, {:?}", e);
+              log::warn!("Failed to parse AVTransport event: {:?}", e);
             }
         }
 
@@ -316,6 +325,9 @@ mod tests {
             port: 1400,
             model_name: "Test Model".to_string(),
             satellites: vec![],
+            supported_services: vec![],
+            software_version: None,
+            hardware_version: None,
         }
     }
 
@@ -337,6 +349,51 @@ mod tests {
         assert!(sub.subscription_id().is_none());
     }
 
+    #[test]
+    fn test_subscribe_sends_configured_headers() {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let speaker = Speaker {
+            ip_address: "127.0.0.1".to_string(),
+            port: listener.local_addr().unwrap().port(),
+            ..create_test_speaker()
+        };
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut headers = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                headers.push(line.trim().to_lowercase());
+            }
+            let mut stream = stream;
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nSID: uuid:sub-1\r\nTIMEOUT: Second-1800\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            headers
+        });
+
+        let config = SubscriptionConfig {
+            user_agent: "home-dashboard/2.1".to_string(),
+            extra_headers: vec![("X-Request-Source".to_string(), "kitchen-panel".to_string())],
+            ..SubscriptionConfig::default()
+        };
+        let mut subscription =
+            AVTransportSubscription::new(speaker, "http://127.0.0.1:8080/callback/test".to_string(), config)
+                .unwrap();
+        subscription.subscribe().unwrap();
+
+        let headers = server.join().unwrap();
+        assert!(headers[0].starts_with("subscribe "));
+        assert!(headers.contains(&"user-agent: home-dashboard/2.1".to_string()));
+        assert!(headers.contains(&"x-request-source: kitchen-panel".to_string()));
+    }
+
     #[test]
     fn test_parse_event_with_state_change() {
         let speaker = create_test_speaker();
@@ -532,4 +589,34 @@ mod tests {
         assert!(subscription.unsubscribe().is_ok());
         assert!(!subscription.is_active());
     }
+
+    #[test]
+    fn test_parse_event_transitioning() {
+        let speaker = create_test_speaker();
+        let subscription = AVTransportSubscription::new(
+            speaker.clone(),
+            "http://localhost:8080/callback".to_string(),
+            SubscriptionConfig::default(),
+        )
+        .unwrap();
+
+        let event_xml = r#"<e:propertyset xmlns:e="urn:schemas-upnp-org:event-1-0"><e:property><LastChange>&lt;Event xmlns=&quot;urn:schemas-upnp-org:metadata-1-0/AVT/&quot; xmlns:r=&quot;urn:schemas-rinconnetworks-com:metadata-1-0/&quot;&gt;&lt;InstanceID val=&quot;0&quot;&gt;&lt;TransportState val=&quot;TRANSITIONING&quot;/&gt;&lt;CurrentPlayMode val=&quot;NORMAL&quot;/&gt;&lt;NumberOfTracks val=&quot;12&quot;/&gt;&lt;CurrentTrack val=&quot;4&quot;/&gt;&lt;CurrentTrackURI val=&quot;x-sonos-spotify:spotify:track:5hM5arv9KDbCHS0k9uqwjr&quot;/&gt;&lt;CurrentTrackDuration val=&quot;0:03:58&quot;/&gt;&lt;CurrentTrackMetaData val=&quot;&quot;/&gt;&lt;/InstanceID&gt;&lt;/Event&gt;</LastChange></e:property></e:propertyset>"#;
+
+        let changes = subscription.parse_event(event_xml).unwrap();
+        assert_eq!(changes.len(), 2);
+        match &changes[0] {
+            StateChange::PlaybackStateChanged { speaker_id, state } => {
+                assert_eq!(*speaker_id, speaker.id);
+                assert_eq!(*state, PlaybackState::Transitioning);
+            }
+            other => panic!("Expected PlaybackStateChanged, got {:?}", other),
+        }
+        assert!(matches!(
+            changes[1],
+            StateChange::PlayModeChanged {
+                mode: crate::PlayMode::Normal,
+                ..
+            }
+        ));
+    }
 }
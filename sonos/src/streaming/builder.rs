@@ -1,11 +1,17 @@
+use super::event_channel::{event_channel, EventReceiver, EventSender};
 use super::interface::{ConfigOverrides, LifecycleHandlers, StreamError, StreamStats};
-use super::manager::SubscriptionManager;
-use super::types::{ServiceType, StreamConfig};
+use super::manager::{
+    HealthCheckReport, RenewalFailureHandler, SubscriptionFactory, SubscriptionInfo, SubscriptionManager,
+};
+use super::types::{BackoffJitter, OverflowPolicy, ServiceType, StreamConfig, StreamCounters, SubscriptionId};
+use crate::api::SpeakerController;
 use crate::model::{Speaker, SpeakerId, StateChange};
 use crate::state::StateCache;
-use std::sync::{mpsc, Arc};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, Weak};
 use std::thread::JoinHandle;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Builder for creating EventStream instances with a fluent interface
 ///
@@ -36,6 +42,12 @@ pub struct EventStreamBuilder {
     event_handlers: Vec<Box<dyn Fn(StateChange) + Send + Sync>>,
     lifecycle_handlers: LifecycleHandlers,
     config_overrides: ConfigOverrides,
+    volume_debounce: Option<Duration>,
+    renewal_failure_handler: Option<RenewalFailureHandler>,
+    position_polling: Option<Duration>,
+    initial_state_prime: bool,
+    auto_reconnect: Option<Duration>,
+    subscription_factory: Option<SubscriptionFactory>,
 }
 
 impl std::fmt::Debug for EventStreamBuilder {
@@ -94,9 +106,52 @@ impl EventStreamBuilder {
             event_handlers: Vec::new(),
             lifecycle_handlers: LifecycleHandlers::default(),
             config_overrides: ConfigOverrides::default(),
+            volume_debounce: None,
+            renewal_failure_handler: None,
+            position_polling: None,
+            initial_state_prime: false,
+            auto_reconnect: None,
+            subscription_factory: None,
         })
     }
 
+    /// Create a builder that subscribes to AVTransport only
+    ///
+    /// The lowest-overhead preset: one subscription per speaker, covering
+    /// playback state and track changes but not volume or grouping.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use sonos::streaming::EventStreamBuilder;
+    ///
+    /// let speakers = vec![/* discovered speakers */];
+    /// let builder = EventStreamBuilder::minimal(speakers)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn minimal(speakers: Vec<Speaker>) -> Result<Self, StreamError> {
+        Ok(Self::new(speakers)?.with_services(&[ServiceType::AVTransport]))
+    }
+
+    /// Create a builder that subscribes to every supported service
+    ///
+    /// Adds ContentDirectory (queue and music library changes) to the
+    /// default AVTransport, RenderingControl and ZoneGroupTopology set, at the
+    /// cost of an extra subscription per speaker.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use sonos::streaming::EventStreamBuilder;
+    ///
+    /// let speakers = vec![/* discovered speakers */];
+    /// let builder = EventStreamBuilder::full(speakers)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn full(speakers: Vec<Speaker>) -> Result<Self, StreamError> {
+        Ok(Self::new(speakers)?.with_services(&ServiceType::ALL))
+    }
+
     /// Enable automatic StateCache integration
     ///
     /// When a StateCache is provided, the event stream will automatically update
@@ -290,16 +345,40 @@ impl EventStreamBuilder {
         self
     }
 
+    /// Randomize retry backoff so speakers don't retry in lockstep
+    ///
+    /// After a router reboot every subscription fails at once, and with plain
+    /// exponential backoff they all retry in synchronized waves. Jitter spreads
+    /// the retries out. Waits stay capped at 30 seconds; the default is
+    /// [`BackoffJitter::None`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use sonos::streaming::{BackoffJitter, EventStreamBuilder};
+    ///
+    /// let speakers = vec![/* discovered speakers */];
+    ///
+    /// let builder = EventStreamBuilder::new(speakers)?
+    ///     .with_retry_jitter(BackoffJitter::Full);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_retry_jitter(mut self, jitter: BackoffJitter) -> Self {
+        self.config_overrides.retry_jitter = Some(jitter);
+        self
+    }
+
     /// Configure callback server port range
     ///
     /// The streaming system runs an HTTP callback server to receive events from
     /// Sonos speakers. This method allows you to specify which port range the
     /// server should use. This is useful in environments with firewall restrictions
-    /// or when running multiple instances.
+    /// or when running multiple instances. Pass `(0, 0)` to let the OS assign a
+    /// free port instead; [`ActiveEventStream::callback_port`] reports which one.
     ///
     /// # Arguments
     ///
-    /// * `start` - Starting port number (must be >= 1024)
+    /// * `start` - Starting port number (must be >= 1024, or 0 with `end` 0)
     /// * `end` - Ending port number (must be > start)
     ///
     /// # Returns
@@ -322,6 +401,300 @@ impl EventStreamBuilder {
         self
     }
 
+    /// Listen for speaker events on a specific local address
+    ///
+    /// By default the callback server listens on every IPv4 interface and
+    /// advertises whichever local address it detects. On multi-homed or
+    /// IPv6-only networks, pass the address your speakers can reach.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use sonos::streaming::EventStreamBuilder;
+    ///
+    /// let speakers = vec![/* discovered speakers */];
+    ///
+    /// let builder = EventStreamBuilder::new(speakers)?
+    ///     .with_callback_bind_address("192.168.1.20".parse()?);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_callback_bind_address(mut self, address: std::net::IpAddr) -> Self {
+        self.config_overrides.callback_bind_address = Some(address);
+        self
+    }
+
+    /// Record every raw UPnP event to a file for debugging
+    ///
+    /// Each event is appended to `path` as one JSON object per line, with the
+    /// subscription ID, service, timestamp and raw XML, before it is parsed. This
+    /// captures events the parsers reject, ready to attach to a bug report.
+    /// Nothing is written unless this is set.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use sonos::streaming::EventStreamBuilder;
+    ///
+    /// let speakers = vec![/* discovered speakers */];
+    ///
+    /// let builder = EventStreamBuilder::new(speakers)?
+    ///     .with_raw_event_log("sonos-events.jsonl".into());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_raw_event_log(mut self, path: PathBuf) -> Self {
+        self.config_overrides.raw_event_log = Some(path);
+        self
+    }
+
+    /// Identify requests to speakers with a custom `User-Agent`
+    ///
+    /// Applies to SUBSCRIBE, renewal and UNSUBSCRIBE requests and to the SOAP
+    /// calls made while subscribing. Defaults to `sonos-rs/<version>`.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.config_overrides.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Send an additional header on every request to speakers
+    ///
+    /// Invalid header names or values are reported by [`start`](Self::start).
+    pub fn with_http_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config_overrides.extra_headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Limit how many raw events are parsed at the same time
+    ///
+    /// Events are parsed on a fixed pool of `workers` threads. During an event
+    /// storm, extra events wait in a queue instead of spawning more work. Events
+    /// from one subscription are always parsed in the order they arrived.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use sonos::streaming::EventStreamBuilder;
+    ///
+    /// let speakers = vec![/* discovered speakers */];
+    ///
+    /// let builder = EventStreamBuilder::new(speakers)?
+    ///     .with_parse_concurrency(2);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_parse_concurrency(mut self, workers: usize) -> Self {
+        self.config_overrides.parse_concurrency = Some(workers);
+        self
+    }
+
+    /// Set how long a speaker reachability check may take
+    ///
+    /// Reconnection and health checks give up on a speaker that has not
+    /// accepted a TCP connection within `timeout`. Shorter values keep health
+    /// displays responsive at the cost of flagging slow speakers as offline.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use sonos::streaming::EventStreamBuilder;
+    /// use std::time::Duration;
+    ///
+    /// let speakers = vec![/* discovered speakers */];
+    ///
+    /// let builder = EventStreamBuilder::new(speakers)?
+    ///     .with_connect_timeout(Duration::from_millis(500));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.config_overrides.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Bound the event channel between subscriptions and handlers
+    ///
+    /// By default the channel is unbounded, so a stalled event handler lets
+    /// events pile up in memory. With a capacity, at most `capacity` events are
+    /// queued and `policy` decides what happens next: `DropOldest` discards
+    /// the oldest queued event and counts it in [`StreamStats::events_dropped`],
+    /// while `Block` holds the producing thread until the handlers catch up.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use sonos::streaming::{EventStreamBuilder, OverflowPolicy};
+    ///
+    /// let speakers = vec![/* discovered speakers */];
+    ///
+    /// let builder = EventStreamBuilder::new(speakers)?
+    ///     .with_event_channel_capacity(256, OverflowPolicy::DropOldest);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_event_channel_capacity(mut self, capacity: usize, policy: OverflowPolicy) -> Self {
+        self.config_overrides.event_channel_capacity = Some(capacity);
+        self.config_overrides.overflow_policy = Some(policy);
+        self
+    }
+
+    /// Coalesce bursts of volume changes
+    ///
+    /// Dragging a volume slider makes RenderingControl fire many `VolumeChanged`
+    /// events in quick succession. With a debounce window set, only the latest
+    /// volume per speaker is delivered, once no newer value has arrived for
+    /// `window`. All other events are delivered immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - How long a speaker's volume must be stable before it is delivered
+    ///
+    /// # Returns
+    ///
+    /// Returns the builder instance for method chaining.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use sonos::streaming::EventStreamBuilder;
+    /// use std::time::Duration;
+    ///
+    /// let speakers = vec![/* discovered speakers */];
+    /// let builder = EventStreamBuilder::new(speakers)?
+    ///     .with_volume_debounce(Duration::from_millis(250));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_volume_debounce(mut self, window: Duration) -> Self {
+        self.volume_debounce = Some(window);
+        self
+    }
+
+    /// Poll each speaker's track position and emit `PositionChanged` events
+    ///
+    /// AVTransport doesn't send events as a track progresses, so progress bars
+    /// need the position polled with `GetPositionInfo`. Polling is off by default
+    /// because it costs one request per speaker per interval.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - Time between polls
+    ///
+    /// # Returns
+    ///
+    /// Returns the builder instance for method chaining.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use sonos::streaming::EventStreamBuilder;
+    /// use std::time::Duration;
+    ///
+    /// let speakers = vec![/* discovered speakers */];
+    /// let builder = EventStreamBuilder::new(speakers)?
+    ///     .with_position_polling(Duration::from_secs(1));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_position_polling(mut self, interval: Duration) -> Self {
+        self.position_polling = Some(interval);
+        self
+    }
+
+    /// Query each speaker's transport state when the stream starts
+    ///
+    /// Without this, playback state stays unknown until a speaker sends its first
+    /// AVTransport event. When enabled, `start()` asks every speaker for its
+    /// current state with `GetTransportInfo` and emits a `PlaybackStateChanged`
+    /// for each one that answers. Group members report their coordinator's state.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use sonos::streaming::EventStreamBuilder;
+    ///
+    /// let speakers = vec![/* discovered speakers */];
+    ///
+    /// let builder = EventStreamBuilder::new(speakers)?
+    ///     .with_initial_state_prime(true);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_initial_state_prime(mut self, enabled: bool) -> Self {
+        self.initial_state_prime = enabled;
+        self
+    }
+
+    /// Re-subscribe speakers that come back online
+    ///
+    /// A speaker that is unplugged or drops off the network loses its
+    /// subscriptions, and a speaker that was offline at `start()` never gets
+    /// any. When enabled, every `interval` the stream checks whether such
+    /// speakers accept connections again and, if so, replaces their old
+    /// subscriptions with fresh ones. Off by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - Time between reachability checks
+    ///
+    /// # Returns
+    ///
+    /// Returns the builder instance for method chaining.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use sonos::streaming::EventStreamBuilder;
+    /// use std::time::Duration;
+    ///
+    /// let speakers = vec![/* discovered speakers */];
+    /// let builder = EventStreamBuilder::new(speakers)?
+    ///     .with_auto_reconnect(Duration::from_secs(30));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_auto_reconnect(mut self, interval: Duration) -> Self {
+        self.auto_reconnect = Some(interval);
+        self
+    }
+
+    /// Register a callback for subscriptions that could not be renewed
+    ///
+    /// By default a subscription whose renewal keeps failing is only logged and
+    /// marked inactive. The handler is called once per subscription after all
+    /// renewal retries are exhausted, which lets headless applications react,
+    /// for example by re-running discovery.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - Called with the subscription ID, its service, and its speaker
+    ///
+    /// # Returns
+    ///
+    /// Returns the builder instance for method chaining.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use sonos::streaming::EventStreamBuilder;
+    ///
+    /// let speakers = vec![/* discovered speakers */];
+    /// let builder = EventStreamBuilder::new(speakers)?
+    ///     .with_renewal_failure_handler(|subscription_id, service, speaker_id| {
+    ///         eprintln!("Lost {:?} on {:?} ({})", service, speaker_id, subscription_id);
+    ///     });
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_renewal_failure_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(SubscriptionId, ServiceType, SpeakerId) + Send + Sync + 'static,
+    {
+        self.renewal_failure_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Build subscriptions with `factory` instead of talking UPnP to the speakers
+    ///
+    /// Only available with the `mock` feature; pair it with
+    /// [`MockSubscription::factory`](crate::mock::MockSubscription::factory) to
+    /// run a stream without any devices on the network.
+    #[cfg(any(test, feature = "mock"))]
+    pub fn with_subscription_factory(mut self, factory: SubscriptionFactory) -> Self {
+        self.subscription_factory = Some(factory);
+        self
+    }
+
     /// Build and start the EventStream
     ///
     /// This method creates the internal components (SubscriptionManager, event processing
@@ -351,65 +724,87 @@ impl EventStreamBuilder {
         let config = self.build_stream_config()?;
 
         // Create channel for events
-        let (sender, receiver) = mpsc::channel();
+        let (sender, receiver) = event_channel(config.event_channel_capacity, config.overflow_policy);
 
         // Create SubscriptionManager using existing implementation
-        println!("🚀 Creating subscription manager...");
-        println!("   Enabled services: {:?}", config.enabled_services);
-        let subscription_manager =
-            Arc::new(SubscriptionManager::new(config, sender).map_err(StreamError::from)?);
-        println!("✅ Subscription manager created successfully");
+        log::debug!(
+            "Creating subscription manager for services {:?}",
+            config.enabled_services
+        );
+        let subscription_manager = match self.subscription_factory {
+            Some(factory) => SubscriptionManager::with_subscription_factory(config, sender.clone(), factory),
+            None => SubscriptionManager::new(config, sender.clone()),
+        };
+        let subscription_manager = Arc::new(subscription_manager.map_err(StreamError::from)?);
+        if let Some(handler) = self.renewal_failure_handler {
+            subscription_manager.on_renewal_failure(handler);
+        }
+
+        // Prime before subscribing so the subscriptions' initial events win
+        if self.initial_state_prime {
+            prime_playback_state(&self.speakers, &sender);
+        }
 
         // Add all speakers to subscription manager using existing add_speaker() method
         let total_speakers = self.speakers.len();
         let mut successful_speakers = 0;
+        let mut failures = Vec::new();
         for speaker in self.speakers {
-            println!(
-                "🔗 Setting up subscriptions for speaker: {} ({}:{})",
+            log::debug!(
+                "Setting up subscriptions for speaker: {} ({}:{})",
                 speaker.name, speaker.ip_address, speaker.port
             );
 
             match subscription_manager.add_speaker(&speaker) {
                 Ok(()) => {
-                    println!("✅ Successfully set up subscriptions for {}", speaker.name);
+                    log::debug!("Successfully set up subscriptions for {}", speaker.name);
                     successful_speakers += 1;
                 }
                 Err(super::subscription::SubscriptionError::SatelliteSpeaker) => {
-                    println!("🔗 Skipping {} (satellite/bonded speaker)", speaker.name);
+                    log::debug!("Skipping {} (satellite/bonded speaker)", speaker.name);
                     // Don't count as failure - satellite speakers are expected to be skipped
                 }
                 Err(e) => {
-                    println!(
-                        "⚠️  Failed to create subscriptions for {}: {:?}",
+                    log::warn!(
+                        "Failed to create subscriptions for {}: {:?}",
                         speaker.name, e
                     );
-                    println!("   Continuing with other speakers...");
                     // Continue with other speakers instead of failing completely
+                    failures.push((speaker.id.clone(), StreamError::from(e)));
                 }
             }
         }
 
         if successful_speakers == 0 {
+            if !failures.is_empty() {
+                return Err(StreamError::AllSpeakersFailed(failures));
+            }
             return Err(StreamError::InitializationFailed(
                 "No speakers could be subscribed to".to_string(),
             ));
         }
 
-        println!(
-            "🎯 Successfully set up subscriptions for {}/{} speakers",
+        log::info!(
+            "Set up subscriptions for {}/{} speakers",
             successful_speakers, total_speakers
         );
 
         // Return ActiveEventStream instance with running event processing
-        let active_stream = ActiveEventStream::new(
+        let mut active_stream = ActiveEventStream::new(
             subscription_manager,
             receiver,
             self.state_cache,
             self.event_handlers,
             self.lifecycle_handlers,
+            self.volume_debounce,
         )?;
-
-        println!("🎯 EventStream ready to receive events");
+        active_stream.initial_failures = failures;
+        active_stream.position_poller = self.position_polling.map(|interval| {
+            PositionPoller::start(interval, Arc::clone(&active_stream.subscription_manager), sender)
+        });
+        active_stream.reconnector = self.auto_reconnect.map(|interval| {
+            Reconnector::start(interval, Arc::clone(&active_stream.subscription_manager))
+        });
 
         Ok(active_stream)
     }
@@ -432,28 +827,67 @@ impl EventStreamBuilder {
             config = config.with_retry_backoff(backoff);
         }
 
+        if let Some(jitter) = self.config_overrides.retry_jitter {
+            config = config.with_retry_jitter(jitter);
+        }
+
         if let Some((start, end)) = self.config_overrides.callback_port_range {
             config = config
                 .with_callback_port_range(start, end)
                 .map_err(StreamError::ConfigurationError)?;
         }
 
+        if let Some(address) = self.config_overrides.callback_bind_address {
+            config = config.with_callback_bind_address(address);
+        }
+
+        if let Some(path) = &self.config_overrides.raw_event_log {
+            config = config.with_raw_event_log(path.clone());
+        }
+
+        if let Some(user_agent) = &self.config_overrides.user_agent {
+            config = config.with_user_agent(user_agent.clone());
+        }
+
+        for (name, value) in &self.config_overrides.extra_headers {
+            config = config.with_extra_header(name.clone(), value.clone());
+        }
+
         if let Some(size) = self.config_overrides.buffer_size {
             config = config
                 .with_buffer_size(size)
                 .map_err(StreamError::ConfigurationError)?;
         }
 
-        if let Some(attempts) = self.config_overrides.max_retry_attempts {
+        if let Some(workers) = self.config_overrides.parse_concurrency {
             config = config
-                .with_retry_attempts(attempts)
+                .with_parse_concurrency(workers)
                 .map_err(StreamError::ConfigurationError)?;
         }
 
-        // Validate the final configuration
-        config.validate().map_err(StreamError::ConfigurationError)?;
-
-        Ok(config)
+        if let Some(timeout) = self.config_overrides.connect_timeout {
+            config = config
+                .with_connect_timeout(timeout)
+                .map_err(StreamError::ConfigurationError)?;
+        }
+
+        if let Some(capacity) = self.config_overrides.event_channel_capacity {
+            let policy = self.config_overrides.overflow_policy.unwrap_or_default();
+            config = config
+                .with_event_channel_capacity(Some(capacity), policy)
+                .map_err(StreamError::ConfigurationError)?;
+        }
+
+        if let Some(attempts) = self.config_overrides.max_retry_attempts {
+            config = config
+                .with_retry_attempts(attempts)
+                .map_err(StreamError::ConfigurationError)?;
+        }
+
+        // Validate the final configuration
+        config.validate().map_err(StreamError::ConfigurationError)?;
+
+        Ok(config)
     }
 }
 
@@ -465,7 +899,19 @@ impl EventStreamBuilder {
 pub struct ActiveEventStream {
     subscription_manager: Arc<SubscriptionManager>,
     _event_processor: Option<JoinHandle<()>>,
-    shutdown_sender: mpsc::Sender<()>,
+    shutdown_sender: mpsc::Sender<ShutdownSignal>,
+    counters: Arc<StreamCounters>,
+    initial_failures: Vec<(SpeakerId, StreamError)>,
+    position_poller: Option<PositionPoller>,
+    reconnector: Option<Reconnector>,
+}
+
+/// How the event processing loop should stop
+enum ShutdownSignal {
+    /// Stop the next time the event channel is idle
+    Immediate,
+    /// Keep delivering queued events until the channel is empty or `deadline` passes
+    Drain { deadline: Instant },
 }
 
 impl ActiveEventStream {
@@ -475,21 +921,36 @@ impl ActiveEventStream {
     /// StateCache updates, user event handlers, and lifecycle callbacks.
     fn new(
         subscription_manager: Arc<SubscriptionManager>,
-        receiver: mpsc::Receiver<StateChange>,
+        receiver: impl Into<EventReceiver>,
         state_cache: Option<Arc<StateCache>>,
         event_handlers: Vec<Box<dyn Fn(StateChange) + Send + Sync>>,
         lifecycle_handlers: LifecycleHandlers,
+        volume_debounce: Option<Duration>,
     ) -> Result<Self, StreamError> {
+        let receiver = receiver.into();
         let (shutdown_sender, shutdown_receiver) = mpsc::channel();
+        let counters = subscription_manager.counters();
+        let loop_counters = Arc::clone(&counters);
+        let manager = Arc::downgrade(&subscription_manager);
+        // Stream bookkeeping for each received event, kept apart from the user's handlers
+        let on_received = move |state_change: &StateChange| {
+            StreamCounters::increment(&loop_counters.events_received);
+            if matches!(state_change, StateChange::SubscriptionError { .. }) {
+                StreamCounters::increment(&loop_counters.subscription_errors);
+            }
+            Self::migrate_on_coordinator_change(&manager, state_change);
+        };
 
         // Start event processing thread
         let event_processor = std::thread::spawn(move || {
             Self::event_processing_loop(
                 receiver,
                 shutdown_receiver,
+                on_received,
                 state_cache,
                 event_handlers,
                 lifecycle_handlers,
+                volume_debounce,
             );
         });
 
@@ -497,13 +958,38 @@ impl ActiveEventStream {
             subscription_manager,
             _event_processor: Some(event_processor),
             shutdown_sender,
+            counters,
+            initial_failures: Vec::new(),
+            position_poller: None,
+            reconnector: None,
         })
     }
 
+    /// Let the manager move network-wide subscriptions off a coordinator that
+    /// has left the network
+    ///
+    /// Migration may subscribe over the network, so it runs on its own thread to
+    /// keep the event processing loop responsive.
+    fn migrate_on_coordinator_change(subscription_manager: &Weak<SubscriptionManager>, state_change: &StateChange) {
+        let StateChange::CoordinatorChanged { old_coordinator, new_coordinator, .. } = state_change else {
+            return;
+        };
+        let Some(manager) = subscription_manager.upgrade() else {
+            return;
+        };
+        let (old_coordinator, new_coordinator) = (old_coordinator.clone(), new_coordinator.clone());
+        std::thread::spawn(move || {
+            if let Err(e) = manager.handle_coordinator_change(&old_coordinator, &new_coordinator) {
+                log::warn!("Failed to migrate subscriptions after coordinator change: {}", e);
+            }
+        });
+    }
+
     /// Main event processing loop that runs in a background thread
     ///
     /// This loop continuously processes events from the receiver, updates the StateCache
-    /// if provided, calls user event handlers, and handles lifecycle events.
+    /// if provided, calls user event handlers, and handles lifecycle events. Each event
+    /// goes to `on_received` first, for the stream's own bookkeeping.
     ///
     /// The loop uses existing EventStream::process_state_change logic for StateCache updates
     /// and handles shutdown signals gracefully to terminate event processing.
@@ -511,11 +997,13 @@ impl ActiveEventStream {
     /// This implementation is non-blocking and uses flag-based updates to avoid I/O operations
     /// in the event processing thread.
     fn event_processing_loop(
-        receiver: mpsc::Receiver<StateChange>,
-        shutdown_receiver: mpsc::Receiver<()>,
+        receiver: EventReceiver,
+        shutdown_receiver: mpsc::Receiver<ShutdownSignal>,
+        on_received: impl Fn(&StateChange),
         state_cache: Option<Arc<StateCache>>,
         event_handlers: Vec<Box<dyn Fn(StateChange) + Send + Sync>>,
         lifecycle_handlers: LifecycleHandlers,
+        volume_debounce: Option<Duration>,
     ) {
         log::debug!("Event processing loop started");
 
@@ -532,50 +1020,71 @@ impl ActiveEventStream {
         let mut events_processed = 0u64;
         let mut display_update_needed = false;
         let mut last_stats_update = std::time::Instant::now();
+        let mut debouncer = volume_debounce.map(VolumeDebouncer::new);
+        let poll_interval = Duration::from_millis(50);
+
+        let dispatch = |state_change: StateChange, events_processed: &mut u64| {
+            *events_processed += 1;
+            Self::dispatch_event(
+                state_change,
+                &state_cache,
+                &event_handlers,
+                &lifecycle_handlers,
+                *events_processed,
+            );
+        };
+
+        let mut shutdown_requested = false;
+        let mut drain_deadline: Option<Instant> = None;
 
         loop {
+            // Check for shutdown signal (non-blocking)
+            match shutdown_receiver.try_recv() {
+                Ok(ShutdownSignal::Immediate) => shutdown_requested = true,
+                Ok(ShutdownSignal::Drain { deadline }) => {
+                    shutdown_requested = true;
+                    drain_deadline = Some(deadline);
+                }
+                Err(_) => {}
+            }
+
+            if let Some(deadline) = drain_deadline {
+                if Instant::now() >= deadline {
+                    log::debug!("Drain deadline reached, terminating event processing loop");
+                    break;
+                }
+            }
+
+            // Wake up early if a debounced volume or the drain deadline is due before the next poll
+            let timeout = debouncer
+                .as_ref()
+                .and_then(VolumeDebouncer::next_deadline)
+                .into_iter()
+                .chain(drain_deadline)
+                .min()
+                .map(|deadline| {
+                    deadline
+                        .saturating_duration_since(Instant::now())
+                        .min(poll_interval)
+                })
+                .unwrap_or(poll_interval);
+
             // Use select-like behavior to handle both events and shutdown signals
             // We use a short timeout to allow periodic shutdown signal checking and flag processing
-            match receiver.recv_timeout(Duration::from_millis(50)) {
+            match receiver.recv_timeout(timeout) {
                 Ok(state_change) => {
                     log::debug!("Processing event: {:?}", state_change);
-                    events_processed += 1;
+                    on_received(&state_change);
 
-                    // Update StateCache if provided using existing EventStream logic (non-blocking)
-                    if let Some(ref cache) = state_cache {
-                        use super::event_stream::EventStream;
-                        EventStream::process_state_change(cache, state_change.clone());
-                        log::debug!("StateCache updated for event #{}", events_processed);
-                    }
-
-                    // Call user event handlers in registration order (non-blocking)
-                    // Support multiple event handlers called in registration order as per requirements
-                    for (index, handler) in event_handlers.iter().enumerate() {
-                        log::debug!(
-                            "Calling event handler #{} for event #{}",
-                            index + 1,
-                            events_processed
-                        );
-
-                        // Call the handler - we use std::panic::catch_unwind to prevent
-                        // a panicking handler from crashing the entire event processing loop
-                        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                            handler(state_change.clone());
-                        }));
-
-                        if let Err(_) = result {
-                            log::error!(
-                                "Event handler #{} panicked while processing event #{}",
-                                index + 1,
-                                events_processed
-                            );
-                            // Continue with other handlers even if one panics
+                    match debouncer.as_mut() {
+                        Some(debouncer) => {
+                            if let Some(state_change) = debouncer.hold(state_change) {
+                                dispatch(state_change, &mut events_processed);
+                            }
                         }
+                        None => dispatch(state_change, &mut events_processed),
                     }
 
-                    // Handle lifecycle events (connection, disconnection, errors) - non-blocking
-                    Self::handle_lifecycle_event(&state_change, &lifecycle_handlers);
-
                     // Set flag for display updates instead of direct I/O
                     display_update_needed = true;
                 }
@@ -594,8 +1103,8 @@ impl ActiveEventStream {
                         }
                     }
 
-                    // Check for shutdown signal (non-blocking)
-                    if shutdown_receiver.try_recv().is_ok() {
+                    // The channel is idle, so a drain has nothing left to deliver
+                    if shutdown_requested {
                         log::debug!("Shutdown signal received, terminating event processing loop");
                         break;
                     }
@@ -607,6 +1116,19 @@ impl ActiveEventStream {
                     break;
                 }
             }
+
+            if let Some(debouncer) = debouncer.as_mut() {
+                for state_change in debouncer.take_due(Instant::now()) {
+                    dispatch(state_change, &mut events_processed);
+                }
+            }
+        }
+
+        // Deliver any volume changes still waiting out their debounce window
+        if let Some(debouncer) = debouncer.as_mut() {
+            for state_change in debouncer.drain() {
+                dispatch(state_change, &mut events_processed);
+            }
         }
 
         log::debug!(
@@ -625,6 +1147,50 @@ impl ActiveEventStream {
         }
     }
 
+    /// Deliver a single event to the StateCache, user event handlers, and lifecycle handlers
+    fn dispatch_event(
+        state_change: StateChange,
+        state_cache: &Option<Arc<StateCache>>,
+        event_handlers: &[Box<dyn Fn(StateChange) + Send + Sync>],
+        lifecycle_handlers: &LifecycleHandlers,
+        events_processed: u64,
+    ) {
+        // Update StateCache if provided using existing EventStream logic (non-blocking)
+        if let Some(ref cache) = state_cache {
+            use super::event_stream::EventStream;
+            EventStream::process_state_change(cache, state_change.clone());
+            log::debug!("StateCache updated for event #{}", events_processed);
+        }
+
+        // Call user event handlers in registration order (non-blocking)
+        // Support multiple event handlers called in registration order as per requirements
+        for (index, handler) in event_handlers.iter().enumerate() {
+            log::debug!(
+                "Calling event handler #{} for event #{}",
+                index + 1,
+                events_processed
+            );
+
+            // Call the handler - we use std::panic::catch_unwind to prevent
+            // a panicking handler from crashing the entire event processing loop
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                handler(state_change.clone());
+            }));
+
+            if result.is_err() {
+                log::error!(
+                    "Event handler #{} panicked while processing event #{}",
+                    index + 1,
+                    events_processed
+                );
+                // Continue with other handlers even if one panics
+            }
+        }
+
+        // Handle lifecycle events (connection, disconnection, errors) - non-blocking
+        Self::handle_lifecycle_event(&state_change, lifecycle_handlers);
+    }
+
     /// Handle lifecycle events by calling appropriate callbacks
     ///
     /// This method detects connection, disconnection, and error events from subscription
@@ -718,8 +1284,17 @@ impl ActiveEventStream {
             StateChange::PlaybackStateChanged { speaker_id, .. }
             | StateChange::VolumeChanged { speaker_id, .. }
             | StateChange::MuteChanged { speaker_id, .. }
+            | StateChange::BassChanged { speaker_id, .. }
+            | StateChange::TrebleChanged { speaker_id, .. }
+            | StateChange::LoudnessChanged { speaker_id, .. }
+            | StateChange::SubGainChanged { speaker_id, .. }
+            | StateChange::SubEnabledChanged { speaker_id, .. }
+            | StateChange::PlayModeChanged { speaker_id, .. }
+            | StateChange::CrossfadeChanged { speaker_id, .. }
             | StateChange::PositionChanged { speaker_id, .. }
-            | StateChange::TrackChanged { speaker_id, .. } => {
+            | StateChange::TrackChanged { speaker_id, .. }
+            | StateChange::QueueChanged { speaker_id }
+            | StateChange::LibraryUpdated { speaker_id } => {
                 // These events indicate the speaker is connected and responding (non-blocking log only)
                 log::debug!(
                     "Received successful event from speaker {:?}, indicating connectivity",
@@ -731,10 +1306,30 @@ impl ActiveEventStream {
                 // when a speaker transitions from disconnected to connected state.
             }
 
-            StateChange::GroupChange { .. } => {
+            StateChange::GroupChange { .. }
+            | StateChange::GroupMembershipChanged { .. }
+            | StateChange::CoordinatorChanged { .. } => {
                 // Group structure changes indicate network-wide connectivity (non-blocking log only)
                 log::debug!("Group structure changed, indicating network connectivity");
             }
+
+            StateChange::SpeakerVanished { speaker_id, reason } => {
+                log::info!("Speaker {:?} vanished from the network: {}", speaker_id, reason);
+            }
+
+            StateChange::EventsMissed {
+                speaker_id,
+                service,
+                gap,
+            } => {
+                // The subscription is still alive; the next full event resyncs state
+                log::warn!(
+                    "Speaker {:?} missed {} {:?} event(s)",
+                    speaker_id,
+                    gap,
+                    service
+                );
+            }
         }
     }
 
@@ -857,6 +1452,109 @@ impl ActiveEventStream {
             .map_err(StreamError::from)
     }
 
+    /// Speakers currently managed by the stream
+    ///
+    /// Includes speakers added with [`add_speaker`](Self::add_speaker) and
+    /// excludes removed ones, so the returned address and model details can
+    /// be used for follow-up control calls.
+    pub fn speakers(&self) -> Vec<Speaker> {
+        self.subscription_manager.speakers()
+    }
+
+    /// Start subscribing to a service on every managed speaker
+    ///
+    /// Lets a service that was left out at build time be turned on without
+    /// restarting the stream. Speakers added later are subscribed to it too.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use sonos::streaming::{ActiveEventStream, ServiceType};
+    /// # let stream: ActiveEventStream = todo!();
+    /// stream.enable_service(ServiceType::RenderingControl)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn enable_service(&self, service_type: ServiceType) -> Result<(), StreamError> {
+        self.subscription_manager
+            .enable_service(service_type)
+            .map_err(StreamError::from)
+    }
+
+    /// Unsubscribe from a service on every managed speaker
+    ///
+    /// Useful for silencing a noisy service at runtime. Disabling
+    /// ZoneGroupTopology removes its single network-wide subscription.
+    pub fn disable_service(&self, service_type: ServiceType) -> Result<(), StreamError> {
+        self.subscription_manager
+            .disable_service(service_type)
+            .map_err(StreamError::from)
+    }
+
+    /// List every subscription in the stream
+    ///
+    /// Unlike [`stats`](Self::stats), this reports each speaker/service pair
+    /// separately, with whether it is active, when it was last renewed and
+    /// whether a renewal is due. Useful for a diagnostics view.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use sonos::streaming::ActiveEventStream;
+    /// # let stream: ActiveEventStream = todo!();
+    /// for info in stream.subscriptions() {
+    ///     println!("{} {:?}: active={} needs_renewal={}",
+    ///         info.speaker_name, info.service_type, info.is_active, info.needs_renewal);
+    /// }
+    /// ```
+    pub fn subscriptions(&self) -> Vec<SubscriptionInfo> {
+        self.subscription_manager.get_subscription_info()
+    }
+
+    /// Whether the speaker accepts connections within the configured connect timeout
+    ///
+    /// Blocks for up to the timeout set with
+    /// [`EventStreamBuilder::with_connect_timeout`].
+    pub fn is_speaker_reachable(&self, speaker: &Speaker) -> bool {
+        self.subscription_manager.is_speaker_reachable(speaker)
+    }
+
+    /// Check many speakers concurrently, e.g. for a health view
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use sonos::streaming::ActiveEventStream;
+    /// # async fn example(stream: ActiveEventStream, speakers: Vec<sonos::Speaker>) {
+    /// for (speaker_id, reachable) in stream.check_speakers_reachable(&speakers).await {
+    ///     println!("{}: {}", speaker_id, if reachable { "online" } else { "offline" });
+    /// }
+    /// # }
+    /// ```
+    pub async fn check_speakers_reachable(&self, speakers: &[Speaker]) -> Vec<(SpeakerId, bool)> {
+        self.subscription_manager.check_speakers_reachable(speakers).await
+    }
+
+    /// Check speaker reachability and per-service subscription health
+    ///
+    /// Speakers left with no active subscription are re-subscribed if they
+    /// answer again. Blocks for up to the connect timeout per speaker.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use sonos::streaming::ActiveEventStream;
+    /// # let stream: ActiveEventStream = todo!();
+    /// let report = stream.health_check();
+    /// for (service, health) in &report.services {
+    ///     if health.missing_representative || health.failed > 0 {
+    ///         println!("{:?} degraded: {:?}", service, health);
+    ///     }
+    /// }
+    /// ```
+    pub fn health_check(&self) -> HealthCheckReport {
+        self.subscription_manager.health_check_and_recover()
+    }
+
     /// Get streaming statistics
     ///
     /// Returns current statistics about the streaming session, including
@@ -879,12 +1577,46 @@ impl ActiveEventStream {
         StreamStats {
             active_subscriptions: self.subscription_manager.subscription_count(),
             active_speakers: self.subscription_manager.speaker_count(),
-            total_events_received: 0, // TODO: Add event counter in future enhancement
-            subscription_errors: 0,   // TODO: Add error counter in future enhancement
-            successful_renewals: 0,   // TODO: Add renewal counter in future enhancement
+            total_events_received: StreamCounters::get(&self.counters.events_received),
+            subscription_errors: StreamCounters::get(&self.counters.subscription_errors),
+            successful_renewals: StreamCounters::get(&self.counters.successful_renewals),
+            events_dropped: self.subscription_manager.dropped_events(),
         }
     }
 
+    /// Port the callback server bound to
+    ///
+    /// Useful for firewall rules when the configured port range is wide.
+    /// Returns `None` once the callback server has been shut down.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use sonos::streaming::ActiveEventStream;
+    /// # let stream: ActiveEventStream = todo!();
+    /// if let Some(port) = stream.callback_port() {
+    ///     println!("Allow inbound TCP on port {}", port);
+    /// }
+    /// ```
+    pub fn callback_port(&self) -> Option<u16> {
+        self.subscription_manager.callback_server_port()
+    }
+
+    /// Base URL speakers deliver events to, e.g. `http://192.168.1.10:8080`
+    ///
+    /// Returns `None` once the callback server has been shut down.
+    pub fn base_callback_url(&self) -> Option<String> {
+        self.subscription_manager.callback_base_url()
+    }
+
+    /// Speakers that could not be subscribed when the stream started
+    ///
+    /// `start()` only fails when every speaker fails; otherwise the stream runs
+    /// with the reachable speakers and the rest are reported here.
+    pub fn initial_failures(&self) -> &[(SpeakerId, StreamError)] {
+        &self.initial_failures
+    }
+
     /// Gracefully shutdown the stream
     ///
     /// This method signals the background event processing thread to shut down
@@ -906,8 +1638,15 @@ impl ActiveEventStream {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn shutdown(mut self) -> Result<(), StreamError> {
+        if let Some(poller) = self.position_poller.take() {
+            poller.stop();
+        }
+        if let Some(reconnector) = self.reconnector.take() {
+            reconnector.stop();
+        }
+
         // Send shutdown signal to event processing thread
-        let _ = self.shutdown_sender.send(());
+        let _ = self.shutdown_sender.send(ShutdownSignal::Immediate);
 
         // Wait for the event processor thread to complete gracefully
         if let Some(handle) = self._event_processor.take() {
@@ -921,13 +1660,214 @@ impl ActiveEventStream {
 
         Ok(())
     }
+
+    /// Shutdown after delivering the events that are already queued
+    ///
+    /// New speakers are refused as soon as this is called. Queued events keep
+    /// flowing to the StateCache and event handlers until the channel is empty
+    /// or `max_wait` elapses, whichever comes first; then the processing thread
+    /// is joined as in [`shutdown`](Self::shutdown).
+    ///
+    /// # Arguments
+    ///
+    /// * `max_wait` - Upper bound on how long to keep delivering queued events
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use sonos::streaming::ActiveEventStream;
+    /// # use std::time::Duration;
+    /// # let stream: ActiveEventStream = todo!();
+    /// // Make sure every event reaches the log before exiting
+    /// stream.shutdown_drain(Duration::from_secs(2))?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn shutdown_drain(mut self, max_wait: Duration) -> Result<(), StreamError> {
+        self.subscription_manager.stop_accepting_speakers();
+        if let Some(poller) = self.position_poller.take() {
+            poller.stop();
+        }
+        if let Some(reconnector) = self.reconnector.take() {
+            reconnector.stop();
+        }
+
+        let _ = self.shutdown_sender.send(ShutdownSignal::Drain {
+            deadline: Instant::now() + max_wait,
+        });
+
+        if let Some(handle) = self._event_processor.take() {
+            handle.join().map_err(|_| StreamError::ShutdownFailed)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Background thread that polls `GetPositionInfo` on every managed speaker
+struct PositionPoller {
+    stop_sender: mpsc::Sender<()>,
+    handle: JoinHandle<()>,
+}
+
+impl PositionPoller {
+    fn start(
+        interval: Duration,
+        subscription_manager: Arc<SubscriptionManager>,
+        event_sender: EventSender,
+    ) -> Self {
+        let (stop_sender, stop_receiver) = mpsc::channel();
+        let handle = std::thread::spawn(move || {
+            // Waiting on the stop channel doubles as the poll interval
+            while let Err(mpsc::RecvTimeoutError::Timeout) = stop_receiver.recv_timeout(interval) {
+                for speaker in subscription_manager.speakers() {
+                    let position_info = SpeakerController::with_timeout(&speaker, interval)
+                        .and_then(|controller| controller.position_info());
+                    match position_info {
+                        Ok((position, duration)) => {
+                            let change = StateChange::PositionChanged {
+                                speaker_id: speaker.id.clone(),
+                                position,
+                                duration,
+                            };
+                            if event_sender.send(change).is_err() {
+                                return;
+                            }
+                        }
+                        Err(e) => {
+                            log::debug!("Failed to poll position for {}: {}", speaker.name, e);
+                        }
+                    }
+                }
+            }
+        });
+
+        Self {
+            stop_sender,
+            handle,
+        }
+    }
+
+    fn stop(self) {
+        let _ = self.stop_sender.send(());
+        let _ = self.handle.join();
+    }
+}
+
+/// Background thread that re-subscribes speakers once they are reachable again
+struct Reconnector {
+    stop_sender: mpsc::Sender<()>,
+    handle: JoinHandle<()>,
+}
+
+impl Reconnector {
+    fn start(interval: Duration, subscription_manager: Arc<SubscriptionManager>) -> Self {
+        let (stop_sender, stop_receiver) = mpsc::channel();
+        let handle = std::thread::spawn(move || {
+            while let Err(mpsc::RecvTimeoutError::Timeout) = stop_receiver.recv_timeout(interval) {
+                let connect_timeout = subscription_manager.connect_timeout();
+                subscription_manager.reconnect_inactive_speakers(interval.min(connect_timeout));
+            }
+        });
+
+        Self {
+            stop_sender,
+            handle,
+        }
+    }
+
+    fn stop(self) {
+        let _ = self.stop_sender.send(());
+        let _ = self.handle.join();
+    }
+}
+
+/// Emit each speaker's current transport state, skipping speakers that don't answer
+fn prime_playback_state(speakers: &[Speaker], event_sender: &EventSender) {
+    for speaker in speakers {
+        match SpeakerController::new(speaker).and_then(|controller| controller.get_transport_state()) {
+            Ok(state) => {
+                let _ = event_sender.send(StateChange::PlaybackStateChanged {
+                    speaker_id: speaker.id.clone(),
+                    state,
+                });
+            }
+            Err(e) => {
+                log::debug!("Failed to read transport state for {}: {}", speaker.name, e);
+            }
+        }
+    }
+}
+
+/// Holds the latest `VolumeChanged` per speaker until it has been stable for the window
+struct VolumeDebouncer {
+    window: Duration,
+    pending: HashMap<SpeakerId, (StateChange, Instant)>,
+}
+
+impl VolumeDebouncer {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Hold volume changes back; any other event is returned for immediate delivery
+    fn hold(&mut self, state_change: StateChange) -> Option<StateChange> {
+        match &state_change {
+            StateChange::VolumeChanged { speaker_id, .. } => {
+                self.pending
+                    .insert(speaker_id.clone(), (state_change.clone(), Instant::now()));
+                None
+            }
+            _ => Some(state_change),
+        }
+    }
+
+    /// When the oldest pending volume change becomes due
+    fn next_deadline(&self) -> Option<Instant> {
+        self.pending
+            .values()
+            .map(|(_, received)| *received + self.window)
+            .min()
+    }
+
+    /// Remove and return the volume changes whose window has elapsed
+    fn take_due(&mut self, now: Instant) -> Vec<StateChange> {
+        let due: Vec<SpeakerId> = self
+            .pending
+            .iter()
+            .filter(|(_, (_, received))| now.duration_since(*received) >= self.window)
+            .map(|(speaker_id, _)| speaker_id.clone())
+            .collect();
+
+        due.into_iter()
+            .filter_map(|speaker_id| self.pending.remove(&speaker_id))
+            .map(|(state_change, _)| state_change)
+            .collect()
+    }
+
+    /// Remove and return every pending volume change
+    fn drain(&mut self) -> Vec<StateChange> {
+        self.pending
+            .drain()
+            .map(|(_, (state_change, _))| state_change)
+            .collect()
+    }
 }
 
 impl Drop for ActiveEventStream {
     /// Ensure graceful cleanup even if shutdown() wasn't called explicitly
     fn drop(&mut self) {
+        if let Some(poller) = self.position_poller.take() {
+            poller.stop();
+        }
+        if let Some(reconnector) = self.reconnector.take() {
+            reconnector.stop();
+        }
+
         // Send shutdown signal (ignore errors since we're dropping)
-        let _ = self.shutdown_sender.send(());
+        let _ = self.shutdown_sender.send(ShutdownSignal::Immediate);
 
         // Try to join the thread if it's still available
         if let Some(handle) = self._event_processor.take() {
@@ -945,7 +1885,7 @@ impl Drop for ActiveEventStream {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::{Speaker, SpeakerId};
+    use crate::model::{PlaybackState, Speaker, SpeakerId};
 
     fn create_test_speaker(id: &str, name: &str) -> Speaker {
         Speaker {
@@ -956,6 +1896,9 @@ mod tests {
             port: 1400,
             model_name: "Test Model".to_string(),
             satellites: vec![],
+            supported_services: vec![],
+            software_version: None,
+            hardware_version: None,
         }
     }
 
@@ -1018,6 +1961,20 @@ mod tests {
         assert_eq!(builder.services, services);
     }
 
+    #[test]
+    fn test_builder_presets_enable_expected_services() {
+        let speakers = vec![create_test_speaker("uuid:RINCON_123456789::1", "Test Speaker")];
+
+        let minimal = EventStreamBuilder::minimal(speakers.clone()).unwrap();
+        assert_eq!(minimal.services, vec![ServiceType::AVTransport]);
+
+        let full = EventStreamBuilder::full(speakers.clone()).unwrap();
+        assert_eq!(full.services, ServiceType::ALL.to_vec());
+
+        assert!(EventStreamBuilder::minimal(vec![]).is_err());
+        assert!(EventStreamBuilder::full(vec![]).is_err());
+    }
+
     #[test]
     fn test_builder_with_services_empty() {
         let speakers = vec![create_test_speaker(
@@ -1079,13 +2036,17 @@ mod tests {
         builder.config_overrides = ConfigOverrides::new()
             .with_subscription_timeout(Duration::from_secs(3600))
             .with_buffer_size(2000)
-            .with_callback_port_range(9000, 9010);
+            .with_callback_port_range(9000, 9010)
+            .with_callback_bind_address("192.168.1.20".parse().unwrap())
+            .with_parse_concurrency(2);
 
         let config = builder.build_stream_config().unwrap();
 
         assert_eq!(config.subscription_timeout, Duration::from_secs(3600));
         assert_eq!(config.buffer_size, 2000);
         assert_eq!(config.callback_port_range, (9000, 9010));
+        assert_eq!(config.callback_bind_address, Some("192.168.1.20".parse().unwrap()));
+        assert_eq!(config.parse_concurrency, 2);
     }
 
     #[test]
@@ -1217,4 +2178,626 @@ mod tests {
         assert!(stats.is_active());
         assert_eq!(stats.avg_subscriptions_per_speaker(), 3.0);
     }
+
+    #[test]
+    fn test_active_event_stream_counts_events() {
+        let (sender, receiver) = mpsc::channel();
+        let manager =
+            Arc::new(SubscriptionManager::new(StreamConfig::default(), sender.clone()).unwrap());
+        let stream = ActiveEventStream::new(
+            manager,
+            receiver,
+            None,
+            Vec::new(),
+            LifecycleHandlers::default(),
+            None,
+        )
+        .unwrap();
+
+        let speaker_id = SpeakerId::new("uuid:RINCON_123456789::1");
+        let event_count = 25u64;
+        for volume in 0..event_count {
+            sender
+                .send(StateChange::VolumeChanged {
+                    speaker_id: speaker_id.clone(),
+                    volume: volume as u8,
+                })
+                .unwrap();
+        }
+        sender
+            .send(StateChange::SubscriptionError {
+                speaker_id: speaker_id.clone(),
+                service: ServiceType::AVTransport,
+                error: "parse error".to_string(),
+            })
+            .unwrap();
+
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while stream.stats().total_events_received < event_count + 1
+            && std::time::Instant::now() < deadline
+        {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let stats = stream.stats();
+        assert_eq!(stats.total_events_received, event_count + 1);
+        assert_eq!(stats.subscription_errors, 1);
+        assert_eq!(stats.successful_renewals, 0);
+
+        // Counters remain readable once shutdown has been initiated
+        let counters = Arc::clone(&stream.counters);
+        stream.shutdown().unwrap();
+        assert_eq!(
+            StreamCounters::get(&counters.events_received),
+            event_count + 1
+        );
+    }
+
+    #[test]
+    fn test_active_event_stream_reports_callback_port() {
+        let config = StreamConfig::default()
+            .with_callback_port_range(38400, 38410)
+            .unwrap();
+        let (sender, receiver) = mpsc::channel();
+        let manager = Arc::new(SubscriptionManager::new(config, sender).unwrap());
+        let stream = ActiveEventStream::new(
+            manager,
+            receiver,
+            None,
+            Vec::new(),
+            LifecycleHandlers::default(),
+            None,
+        )
+        .unwrap();
+
+        let port = stream.callback_port().unwrap();
+        assert!((38400..=38410).contains(&port));
+
+        let base_url = stream.base_callback_url().unwrap();
+        assert!(base_url.starts_with("http://"));
+        assert!(base_url.ends_with(&format!(":{}", port)));
+
+        stream.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_shutdown_drain_delivers_queued_events() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let (sender, receiver) = mpsc::channel();
+        let manager =
+            Arc::new(SubscriptionManager::new(StreamConfig::default(), sender.clone()).unwrap());
+        let handled = Arc::new(AtomicUsize::new(0));
+        let handled_clone = Arc::clone(&handled);
+        // A slow handler so events are still queued when the drain starts
+        let handler: Box<dyn Fn(StateChange) + Send + Sync> = Box::new(move |_| {
+            std::thread::sleep(Duration::from_millis(2));
+            handled_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let stream = ActiveEventStream::new(
+            Arc::clone(&manager),
+            receiver,
+            None,
+            vec![handler],
+            LifecycleHandlers::default(),
+            None,
+        )
+        .unwrap();
+
+        let speaker_id = SpeakerId::new("uuid:RINCON_123456789::1");
+        for volume in 0..100u8 {
+            sender
+                .send(StateChange::VolumeChanged {
+                    speaker_id: speaker_id.clone(),
+                    volume,
+                })
+                .unwrap();
+        }
+
+        stream.shutdown_drain(Duration::from_secs(10)).unwrap();
+        assert_eq!(handled.load(Ordering::SeqCst), 100);
+
+        // The manager no longer accepts new speakers once draining has begun
+        let speaker = create_test_speaker("uuid:RINCON_987654321::1", "Kitchen");
+        assert!(manager.add_speaker(&speaker).is_err());
+    }
+
+    #[test]
+    fn test_shutdown_drain_respects_max_wait() {
+        let (sender, receiver) = mpsc::channel();
+        let manager =
+            Arc::new(SubscriptionManager::new(StreamConfig::default(), sender.clone()).unwrap());
+        let handler: Box<dyn Fn(StateChange) + Send + Sync> =
+            Box::new(|_| std::thread::sleep(Duration::from_millis(50)));
+
+        let stream = ActiveEventStream::new(
+            manager,
+            receiver,
+            None,
+            vec![handler],
+            LifecycleHandlers::default(),
+            None,
+        )
+        .unwrap();
+
+        let speaker_id = SpeakerId::new("uuid:RINCON_123456789::1");
+        for volume in 0..100u8 {
+            sender
+                .send(StateChange::VolumeChanged {
+                    speaker_id: speaker_id.clone(),
+                    volume,
+                })
+                .unwrap();
+        }
+
+        let started = Instant::now();
+        stream.shutdown_drain(Duration::from_millis(200)).unwrap();
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_builder_with_volume_debounce() {
+        let speakers = vec![create_test_speaker(
+            "uuid:RINCON_123456789::1",
+            "Test Speaker",
+        )];
+
+        let builder = EventStreamBuilder::new(speakers).unwrap();
+        assert_eq!(builder.volume_debounce, None);
+
+        let builder = builder.with_volume_debounce(Duration::from_millis(200));
+        assert_eq!(builder.volume_debounce, Some(Duration::from_millis(200)));
+    }
+
+    #[test]
+    fn test_volume_debounce_delivers_only_latest_volume() {
+        use std::sync::Mutex;
+
+        let (sender, receiver) = mpsc::channel();
+        let manager =
+            Arc::new(SubscriptionManager::new(StreamConfig::default(), sender.clone()).unwrap());
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = Arc::clone(&received);
+        let handler: Box<dyn Fn(StateChange) + Send + Sync> = Box::new(move |event| {
+            received_clone.lock().unwrap().push(event);
+        });
+
+        let stream = ActiveEventStream::new(
+            manager,
+            receiver,
+            None,
+            vec![handler],
+            LifecycleHandlers::default(),
+            Some(Duration::from_millis(100)),
+        )
+        .unwrap();
+
+        let speaker_id = SpeakerId::new("uuid:RINCON_123456789::1");
+        for volume in 1..=10 {
+            sender
+                .send(StateChange::VolumeChanged {
+                    speaker_id: speaker_id.clone(),
+                    volume,
+                })
+                .unwrap();
+        }
+        sender
+            .send(StateChange::MuteChanged {
+                speaker_id: speaker_id.clone(),
+                muted: true,
+            })
+            .unwrap();
+
+        std::thread::sleep(Duration::from_millis(400));
+        stream.shutdown().unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        // The mute change is not held back behind the pending volume
+        assert!(matches!(
+            received[0],
+            StateChange::MuteChanged { muted: true, .. }
+        ));
+        assert!(matches!(
+            received[1],
+            StateChange::VolumeChanged { volume: 10, .. }
+        ));
+    }
+
+    #[test]
+    fn test_volume_debounce_flushes_on_shutdown() {
+        use std::sync::Mutex;
+
+        let (sender, receiver) = mpsc::channel();
+        let manager =
+            Arc::new(SubscriptionManager::new(StreamConfig::default(), sender.clone()).unwrap());
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = Arc::clone(&received);
+        let handler: Box<dyn Fn(StateChange) + Send + Sync> = Box::new(move |event| {
+            received_clone.lock().unwrap().push(event);
+        });
+
+        let stream = ActiveEventStream::new(
+            manager,
+            receiver,
+            None,
+            vec![handler],
+            LifecycleHandlers::default(),
+            Some(Duration::from_secs(60)),
+        )
+        .unwrap();
+
+        sender
+            .send(StateChange::VolumeChanged {
+                speaker_id: SpeakerId::new("uuid:RINCON_123456789::1"),
+                volume: 42,
+            })
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(received.lock().unwrap().is_empty());
+
+        stream.shutdown().unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert!(matches!(
+            received[0],
+            StateChange::VolumeChanged { volume: 42, .. }
+        ));
+    }
+
+    /// Minimal speaker stand-in that accepts every SUBSCRIBE/UNSUBSCRIBE
+    fn spawn_subscribe_server() -> u16 {
+        spawn_speaker_server("")
+    }
+
+    /// Speaker stand-in that accepts subscriptions and answers every SOAP call with `body`
+    fn spawn_speaker_server(body: &'static str) -> u16 {
+        serve_speaker(std::net::TcpListener::bind("127.0.0.1:0").unwrap(), body)
+    }
+
+    fn serve_speaker(listener: std::net::TcpListener, body: &'static str) -> u16 {
+        use std::io::{BufRead, BufReader, Read, Write};
+
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                // Consume the whole request so the client never sees a reset
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 || line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap_or(0);
+                        }
+                    }
+                }
+                let mut request_body = vec![0; content_length];
+                let _ = reader.read_exact(&mut request_body);
+
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nSID: uuid:test-sid\r\nTIMEOUT: Second-1800\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        port
+    }
+
+    fn closed_local_port() -> u16 {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().port()
+    }
+
+    fn local_speaker(id: &str, port: u16) -> Speaker {
+        Speaker {
+            ip_address: "127.0.0.1".to_string(),
+            port,
+            ..create_test_speaker(id, id)
+        }
+    }
+
+    #[test]
+    fn test_start_reports_partial_failures() {
+        let reachable = local_speaker("uuid:RINCON_REACHABLE::1", spawn_subscribe_server());
+        let unreachable_a = local_speaker("uuid:RINCON_UNREACHABLE_A::1", closed_local_port());
+        let unreachable_b = local_speaker("uuid:RINCON_UNREACHABLE_B::1", closed_local_port());
+
+        let stream = EventStreamBuilder::new(vec![
+            unreachable_a.clone(),
+            reachable,
+            unreachable_b.clone(),
+        ])
+        .unwrap()
+        .with_services(&[ServiceType::AVTransport])
+        .with_timeouts(Duration::from_secs(1800), Duration::from_millis(10))
+        .with_callback_ports(38420, 38430)
+        .start()
+        .expect("stream should start when at least one speaker subscribes");
+
+        let failed: Vec<&SpeakerId> = stream.initial_failures().iter().map(|(id, _)| id).collect();
+        assert_eq!(failed, vec![&unreachable_a.id, &unreachable_b.id]);
+        assert!(stream
+            .initial_failures()
+            .iter()
+            .all(|(_, error)| matches!(error, StreamError::NetworkError(_))));
+
+        stream.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_subscriptions_lists_each_subscription() {
+        let first = local_speaker("uuid:RINCON_FIRST::1", spawn_subscribe_server());
+        let second = local_speaker("uuid:RINCON_SECOND::1", spawn_subscribe_server());
+
+        let stream = EventStreamBuilder::new(vec![first.clone()])
+            .unwrap()
+            .with_services(&[ServiceType::AVTransport])
+            .with_callback_ports(38450, 38459)
+            .start()
+            .unwrap();
+        assert_eq!(stream.subscriptions().len(), 1);
+
+        stream.add_speaker(&second).unwrap();
+
+        let subscriptions = stream.subscriptions();
+        assert_eq!(subscriptions.len(), stream.stats().active_subscriptions);
+        assert_eq!(subscriptions.len(), 2);
+        assert!(subscriptions.iter().any(|info| info.speaker_id == second.id));
+        assert!(subscriptions
+            .iter()
+            .all(|info| info.is_active && info.service_type == ServiceType::AVTransport));
+
+        stream.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_speakers_lists_managed_speakers() {
+        let first = local_speaker("uuid:RINCON_FIRST::1", spawn_subscribe_server());
+        let second = local_speaker("uuid:RINCON_SECOND::1", spawn_subscribe_server());
+
+        let stream = EventStreamBuilder::new(vec![first.clone(), second.clone()])
+            .unwrap()
+            .with_services(&[ServiceType::AVTransport])
+            .with_callback_ports(38460, 38469)
+            .start()
+            .unwrap();
+
+        let speakers = stream.speakers();
+        assert_eq!(speakers.len(), 2);
+        for expected in [&first, &second] {
+            let speaker = speakers.iter().find(|speaker| speaker.id == expected.id).unwrap();
+            assert_eq!(speaker.port, expected.port);
+            assert_eq!(speaker.ip_address, expected.ip_address);
+        }
+
+        stream.remove_speaker(&first.id).unwrap();
+        assert_eq!(stream.speakers().iter().map(|speaker| &speaker.id).collect::<Vec<_>>(), vec![&second.id]);
+
+        stream.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_enable_and_disable_service_at_runtime() {
+        use crate::mock::{MockSpeaker, MockSubscription};
+
+        let speakers = vec![
+            MockSpeaker::builder().id("uuid:RINCON_KITCHEN::1").name("Kitchen").build(),
+            MockSpeaker::builder().id("uuid:RINCON_OFFICE::1").name("Office").build(),
+        ];
+        let template = MockSubscription::new(ServiceType::AVTransport, speakers[0].id.clone());
+
+        let stream = EventStreamBuilder::new(speakers)
+            .unwrap()
+            .with_services(&[ServiceType::AVTransport, ServiceType::ZoneGroupTopology])
+            .with_callback_bind_address(std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST))
+            .with_subscription_factory(MockSubscription::factory(template))
+            .start()
+            .unwrap();
+        let count = |service_type| {
+            stream
+                .subscriptions()
+                .iter()
+                .filter(|info| info.service_type == service_type)
+                .count()
+        };
+        assert_eq!(stream.stats().active_subscriptions, 3);
+
+        stream.enable_service(ServiceType::RenderingControl).unwrap();
+        assert_eq!(count(ServiceType::RenderingControl), 2);
+        assert_eq!(stream.stats().active_subscriptions, 5);
+
+        // Enabling twice must not duplicate subscriptions
+        stream.enable_service(ServiceType::RenderingControl).unwrap();
+        assert_eq!(stream.stats().active_subscriptions, 5);
+
+        stream.disable_service(ServiceType::RenderingControl).unwrap();
+        assert_eq!(count(ServiceType::RenderingControl), 0);
+        assert_eq!(stream.stats().active_subscriptions, 3);
+
+        stream.disable_service(ServiceType::ZoneGroupTopology).unwrap();
+        assert_eq!(count(ServiceType::ZoneGroupTopology), 0);
+        assert_eq!(stream.stats().active_subscriptions, 2);
+
+        stream.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_enable_service_that_fails_everywhere_stays_disabled() {
+        use crate::mock::{MockSpeaker, MockSubscription};
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let speaker = MockSpeaker::builder().id("uuid:RINCON_KITCHEN::1").name("Kitchen").build();
+        let working = MockSubscription::factory(MockSubscription::new(ServiceType::AVTransport, speaker.id.clone()));
+        let failing = MockSubscription::factory(
+            MockSubscription::new(ServiceType::AVTransport, speaker.id.clone()).failing_subscribe(),
+        );
+        let refuse_rendering_control = Arc::new(AtomicBool::new(true));
+        let refuse = Arc::clone(&refuse_rendering_control);
+        let factory: SubscriptionFactory = Arc::new(move |speaker, service_type, callback_url, config| {
+            if service_type == ServiceType::RenderingControl && refuse.load(Ordering::SeqCst) {
+                failing(speaker, service_type, callback_url, config)
+            } else {
+                working(speaker, service_type, callback_url, config)
+            }
+        });
+
+        let stream = EventStreamBuilder::new(vec![speaker])
+            .unwrap()
+            .with_services(&[ServiceType::AVTransport])
+            .with_callback_bind_address(std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST))
+            .with_subscription_factory(factory)
+            .start()
+            .unwrap();
+
+        assert!(stream.enable_service(ServiceType::RenderingControl).is_err());
+        assert_eq!(stream.stats().active_subscriptions, 1);
+
+        // The failed attempt must not leave the service marked as enabled
+        refuse_rendering_control.store(false, Ordering::SeqCst);
+        stream.enable_service(ServiceType::RenderingControl).unwrap();
+        assert_eq!(stream.stats().active_subscriptions, 2);
+
+        stream.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_start_fails_when_all_speakers_fail() {
+        let speakers = vec![
+            local_speaker("uuid:RINCON_UNREACHABLE_A::1", closed_local_port()),
+            local_speaker("uuid:RINCON_UNREACHABLE_B::1", closed_local_port()),
+        ];
+
+        let result = EventStreamBuilder::new(speakers)
+            .unwrap()
+            .with_services(&[ServiceType::AVTransport])
+            .with_timeouts(Duration::from_secs(1800), Duration::from_millis(10))
+            .with_callback_ports(38431, 38440)
+            .start();
+
+        match result {
+            Err(StreamError::AllSpeakersFailed(failures)) => assert_eq!(failures.len(), 2),
+            Err(other) => panic!("expected AllSpeakersFailed, got {:?}", other),
+            Ok(_) => panic!("expected start to fail"),
+        }
+    }
+
+    #[test]
+    fn test_position_polling_emits_position_changed() {
+        let port = spawn_speaker_server(
+            "<s:Envelope><s:Body><u:GetPositionInfoResponse><Track>1</Track><TrackDuration>0:03:30</TrackDuration><RelTime>0:01:05</RelTime></u:GetPositionInfoResponse></s:Body></s:Envelope>",
+        );
+        let speaker = local_speaker("uuid:RINCON_POLLED::1", port);
+        let (position_sender, position_receiver) = mpsc::channel();
+        let position_sender = std::sync::Mutex::new(position_sender);
+
+        let stream = EventStreamBuilder::new(vec![speaker.clone()])
+            .unwrap()
+            .with_services(&[ServiceType::AVTransport])
+            .with_callback_ports(38441, 38449)
+            .with_position_polling(Duration::from_millis(50))
+            .with_event_handler(move |event| {
+                if let StateChange::PositionChanged { .. } = event {
+                    let _ = position_sender.lock().unwrap().send(event);
+                }
+            })
+            .start()
+            .unwrap();
+
+        let event = position_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .expect("no PositionChanged event");
+        match event {
+            StateChange::PositionChanged {
+                speaker_id,
+                position,
+                duration,
+            } => {
+                assert_eq!(speaker_id, speaker.id);
+                assert_eq!(position, Duration::from_secs(65));
+                assert_eq!(duration, Duration::from_secs(210));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        stream.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_auto_reconnect_resubscribes_speaker_once_reachable() {
+        let online = local_speaker("uuid:RINCON_ONLINE::1", spawn_subscribe_server());
+        let returning_port = closed_local_port();
+        let returning = local_speaker("uuid:RINCON_RETURNING::1", returning_port);
+
+        let stream = EventStreamBuilder::new(vec![online, returning])
+            .unwrap()
+            .with_services(&[ServiceType::AVTransport])
+            .with_timeouts(Duration::from_secs(1800), Duration::from_millis(10))
+            .with_callback_ports(38490, 38499)
+            .with_auto_reconnect(Duration::from_millis(50))
+            .start()
+            .expect("stream should start with one reachable speaker");
+
+        assert_eq!(stream.initial_failures().len(), 1);
+        assert_eq!(stream.subscription_manager.subscription_count(), 1);
+
+        // While the speaker is still offline nothing is recreated
+        std::thread::sleep(Duration::from_millis(200));
+        assert_eq!(stream.subscription_manager.subscription_count(), 1);
+
+        let listener = std::net::TcpListener::bind(("127.0.0.1", returning_port)).unwrap();
+        serve_speaker(listener, "");
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while stream.subscription_manager.subscription_count() < 2 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(stream.subscription_manager.subscription_count(), 2);
+        assert!(stream.subscription_manager.inactive_speakers().is_empty());
+
+        stream.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_initial_state_prime_emits_playback_state() {
+        let port = spawn_speaker_server(
+            "<s:Envelope><s:Body><u:GetTransportInfoResponse><CurrentTransportState>PLAYING</CurrentTransportState></u:GetTransportInfoResponse></s:Body></s:Envelope>",
+        );
+        let speaker = local_speaker("uuid:RINCON_PRIMED::1", port);
+        let (state_sender, state_receiver) = mpsc::channel();
+        let state_sender = std::sync::Mutex::new(state_sender);
+
+        let stream = EventStreamBuilder::new(vec![speaker.clone()])
+            .unwrap()
+            .with_services(&[ServiceType::AVTransport])
+            .with_callback_ports(38481, 38489)
+            .with_initial_state_prime(true)
+            .with_event_handler(move |event| {
+                if let StateChange::PlaybackStateChanged { .. } = event {
+                    let _ = state_sender.lock().unwrap().send(event);
+                }
+            })
+            .start()
+            .unwrap();
+
+        match state_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .expect("no PlaybackStateChanged event")
+        {
+            StateChange::PlaybackStateChanged { speaker_id, state } => {
+                assert_eq!(speaker_id, speaker.id);
+                assert_eq!(state, PlaybackState::Playing);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        stream.shutdown().unwrap();
+    }
 }
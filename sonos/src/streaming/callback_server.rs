@@ -1,48 +1,55 @@
 use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::Arc;
 use std::thread::JoinHandle;
 use tokio::sync::{mpsc, RwLock};
 use warp::Filter;
 
-use crate::streaming::subscription::SubscriptionError;
+use crate::streaming::subscription::{PortBindFailure, SubscriptionError};
 use crate::streaming::types::{RawEvent, SubscriptionId};
 
 /// HTTP server for receiving UPnP event notifications from Sonos devices
 pub struct CallbackServer {
     server_handle: Option<JoinHandle<()>>,
     port: u16,
+    bind_address: Option<IpAddr>,
     event_router: Arc<EventRouter>,
     shutdown_tx: Option<mpsc::UnboundedSender<()>>,
 }
 
 impl CallbackServer {
     /// Create a new CallbackServer that will bind to a port in the given range
+    ///
+    /// Listens on `bind_address`, or on all IPv4 interfaces when `None`. A
+    /// range of `(0, 0)` lets the OS assign an ephemeral port. When no port in
+    /// the range binds, the error lists every port tried and why it failed.
     pub fn new(
         port_range: (u16, u16),
+        bind_address: Option<IpAddr>,
         event_sender: mpsc::UnboundedSender<RawEvent>,
     ) -> Result<Self, SubscriptionError> {
         let event_router = Arc::new(EventRouter::new(event_sender));
+        let listen_ip = bind_address.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
 
         // Try to bind to a port in the range
         let mut port = None;
+        let mut failures = Vec::new();
         for p in port_range.0..=port_range.1 {
-            if Self::is_port_available(p) {
-                port = Some(p);
-                break;
+            match Self::probe_port(listen_ip, p) {
+                Ok(bound) => {
+                    port = Some(bound);
+                    break;
+                }
+                Err(e) => failures.push(PortBindFailure { port: p, reason: (&e).into() }),
             }
         }
 
-        let port = port.ok_or_else(|| {
-            SubscriptionError::CallbackServerError(format!(
-                "No available ports in range {}..{}",
-                port_range.0, port_range.1
-            ))
-        })?;
+        let port = port.ok_or(SubscriptionError::NoCallbackPort { range: port_range, failures })?;
 
         Ok(Self {
             server_handle: None,
             port,
+            bind_address,
             event_router,
             shutdown_tx: None,
         })
@@ -58,7 +65,10 @@ impl CallbackServer {
 
         let (shutdown_tx, mut shutdown_rx) = mpsc::unbounded_channel();
         let event_router = Arc::clone(&self.event_router);
-        let port = self.port;
+        let listen_addr = SocketAddr::new(
+            self.bind_address.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+            self.port,
+        );
 
         let server_handle = std::thread::spawn(move || {
             let rt = tokio::runtime::Runtime::new().unwrap();
@@ -69,6 +79,8 @@ impl CallbackServer {
                 // Create the NOTIFY route for UPnP event notifications
                 let notify_route = warp::path::full()
                     .and(warp::header::headers_cloned())
+                    // Buffers the whole body, decoding chunked transfer encoding,
+                    // so events without a Content-Length arrive complete
                     .and(warp::body::bytes())
                     .and(with_router.clone())
                     .and_then(handle_notify_request);
@@ -84,9 +96,9 @@ impl CallbackServer {
                 // Combine routes - try notify route first, then catch-all
                 let routes = notify_route.or(catch_all);
 
-                // Create the server - bind to all interfaces so Sonos devices can reach it
+                // Bind to the configured address, or all interfaces so Sonos devices can reach it
                 let (_addr, server) = warp::serve(routes).bind_with_graceful_shutdown(
-                    SocketAddr::from(([0, 0, 0, 0], port)),
+                    listen_addr,
                     async move {
                         shutdown_rx.recv().await;
                     },
@@ -142,6 +154,11 @@ impl CallbackServer {
 
     /// Get the base callback URL for this server
     pub fn base_url(&self) -> String {
+        // A specific bind address is the only one the server answers on
+        if let Some(ip) = self.bind_address.filter(|ip| !ip.is_unspecified()) {
+            return format!("http://{}", SocketAddr::new(ip, self.port));
+        }
+
         // Try to get the local IP address instead of using localhost
         match Self::get_local_ip() {
             Some(ip) => format!("http://{}:{}", ip, self.port),
@@ -152,6 +169,26 @@ impl CallbackServer {
         }
     }
 
+    /// Callback URL base a speaker at `speaker_ip` can reach.
+    ///
+    /// With several interfaces (or a VPN) active, the detected address in
+    /// `base_url` may be on a different subnet than the speaker, so this asks the
+    /// routing table which local address traffic to the speaker leaves from.
+    pub fn base_url_for(&self, speaker_ip: &str) -> String {
+        if self.bind_address.is_some_and(|ip| !ip.is_unspecified()) {
+            return self.base_url();
+        }
+
+        match speaker_ip
+            .parse::<IpAddr>()
+            .ok()
+            .and_then(|destination| pick_source_ip(destination, route_source_ip))
+        {
+            Some(ip) => format!("http://{}", SocketAddr::new(ip, self.port)),
+            None => self.base_url(),
+        }
+    }
+
     /// Get the local IP address of this machine
     fn get_local_ip() -> Option<String> {
         use std::net::{TcpStream, UdpSocket};
@@ -187,12 +224,42 @@ impl CallbackServer {
         None
     }
 
-    /// Check if a port is available for binding
-    fn is_port_available(port: u16) -> bool {
-        std::net::TcpListener::bind(("0.0.0.0", port)).is_ok()
+    /// Check that a port can be bound, returning the port actually bound
+    ///
+    /// Port 0 resolves to the ephemeral port the OS picked.
+    fn probe_port(ip: IpAddr, port: u16) -> std::io::Result<u16> {
+        Ok(std::net::TcpListener::bind((ip, port))?.local_addr()?.port())
     }
 }
 
+/// Ask the OS which local address it would use to reach `destination`.
+///
+/// Connecting a UDP socket only selects a route; no packet is sent.
+fn route_source_ip(destination: SocketAddr) -> std::io::Result<IpAddr> {
+    let unspecified = match destination {
+        SocketAddr::V4(_) => SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+        SocketAddr::V6(_) => SocketAddr::from((std::net::Ipv6Addr::UNSPECIFIED, 0)),
+    };
+    let socket = std::net::UdpSocket::bind(unspecified)?;
+    socket.connect(destination)?;
+    Ok(socket.local_addr()?.ip())
+}
+
+/// Pick the local source address for `destination` using `route`.
+///
+/// Loopback answers are rejected unless the destination itself is loopback,
+/// since a speaker can never reach them.
+fn pick_source_ip(
+    destination: IpAddr,
+    route: impl Fn(SocketAddr) -> std::io::Result<IpAddr>,
+) -> Option<IpAddr> {
+    let source = route(SocketAddr::new(destination, 1400)).ok()?;
+    if source.is_unspecified() || (source.is_loopback() && !destination.is_loopback()) {
+        return None;
+    }
+    Some(source)
+}
+
 impl Drop for CallbackServer {
     fn drop(&mut self) {
         let _ = self.shutdown();
@@ -219,33 +286,17 @@ async fn handle_any_request(
     body: bytes::Bytes,
     _event_router: Arc<EventRouter>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    println!("\n🌐 === ANY HTTP REQUEST ===");
-    println!("📡 Method: {}", method);
-    println!("📡 Path: {}", path.as_str());
-    println!("📏 Body size: {} bytes", body.len());
-
-    println!("📋 Headers:");
-    for (name, value) in headers.iter() {
-        println!("   {}: {:?}", name, value);
-    }
-
-    if body.len() > 0 {
-        match String::from_utf8(body.to_vec()) {
-            Ok(body_str) => {
-                println!("📄 Body content:");
-                println!("   {}", body_str.chars().take(300).collect::<String>());
-                if body_str.len() > 300 {
-                    println!("   ... (truncated, total {} chars)", body_str.len());
-                }
-            }
-            Err(_) => {
-                println!("📄 Body: [Invalid UTF-8, {} bytes]", body.len());
-            }
-        }
-    } else {
-        println!("📄 Body: [Empty]");
-    }
-    println!("🌐 === END ANY REQUEST ===\n");
+    log::debug!(
+        "Unhandled {} request to {} ({} bytes)",
+        method,
+        path.as_str(),
+        body.len()
+    );
+    log::trace!("Headers: {:?}", headers);
+    log::trace!(
+        "Body: {}",
+        String::from_utf8_lossy(&body).chars().take(300).collect::<String>()
+    );
 
     // Return a simple response
     Ok(warp::reply::with_status(
@@ -277,8 +328,8 @@ impl EventRouter {
         subscription_id: SubscriptionId,
         callback_path: String,
     ) -> Result<(), SubscriptionError> {
-        println!(
-            "📝 Registering subscription: {} -> {}",
+        log::debug!(
+            "Registering subscription: {} -> {}",
             callback_path, subscription_id
         );
 
@@ -312,7 +363,7 @@ impl EventRouter {
             }
         }
 
-        println!("✅ Successfully registered subscription: {}", path_clone);
+        log::debug!("Registered subscription path {}", path_clone);
         Ok(())
     }
 
@@ -356,33 +407,16 @@ impl EventRouter {
         headers: warp::http::HeaderMap,
         body: bytes::Bytes,
     ) -> Result<impl warp::Reply, warp::Rejection> {
-        // LOG: Incoming HTTP request details
-        println!("\n🌐 === INCOMING HTTP REQUEST ===");
-        println!("📡 Path: {}", path.as_str());
-        println!("📏 Body size: {} bytes", body.len());
-
-        println!("📋 Headers:");
-        for (name, value) in headers.iter() {
-            println!("   {}: {:?}", name, value);
-        }
-
-        if body.len() > 0 {
-            match String::from_utf8(body.to_vec()) {
-                Ok(body_str) => {
-                    println!("📄 Body content:");
-                    println!("   {}", body_str.chars().take(500).collect::<String>());
-                    if body_str.len() > 500 {
-                        println!("   ... (truncated, total {} chars)", body_str.len());
-                    }
-                }
-                Err(_) => {
-                    println!("📄 Body: [Invalid UTF-8, {} bytes]", body.len());
-                }
-            }
-        } else {
-            println!("📄 Body: [Empty]");
-        }
-        println!("🌐 === END HTTP REQUEST ===\n");
+        log::debug!(
+            "Incoming NOTIFY request to {} ({} bytes)",
+            path.as_str(),
+            body.len()
+        );
+        log::trace!("Headers: {:?}", headers);
+        log::trace!(
+            "Body: {}",
+            String::from_utf8_lossy(&body).chars().take(500).collect::<String>()
+        );
 
         // Extract the callback path
         let callback_path = path.as_str().to_string();
@@ -395,24 +429,16 @@ impl EventRouter {
 
         let subscription_id = match subscription_id {
             Some(id) => {
-                println!(
-                    "✅ Found subscription ID: {} for path: {}",
+                log::debug!(
+                    "Found subscription ID: {} for path: {}",
                     id, callback_path
                 );
                 id
             }
             None => {
-                println!("❌ No subscription found for path: {}", callback_path);
-
                 // Show all registered paths for debugging
                 let subscriptions = self.subscriptions.read().await;
-                println!("📋 Currently registered paths:");
-                for (path, id) in subscriptions.iter() {
-                    println!("   {} -> {}", path, id);
-                }
-                if subscriptions.is_empty() {
-                    println!("   (No subscriptions registered)");
-                }
+                log::debug!("Currently registered paths: {:?}", *subscriptions);
 
                 log::warn!(
                     "Received event for unknown callback path: {}",
@@ -452,15 +478,23 @@ impl EventRouter {
             }
         };
 
-        // Create and send the raw event
-        let raw_event = RawEvent::new(subscription_id, event_xml);
+        // Create and send the raw event, keeping SEQ so the manager can spot missed events
+        let mut raw_event = RawEvent::new(subscription_id, event_xml);
+        if let Some(seq) = headers
+            .get("seq")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u32>().ok())
+        {
+            raw_event = raw_event.with_seq(seq);
+        }
 
-        println!("📤 Sending raw event to subscription manager...");
-        println!("   Subscription ID: {}", subscription_id);
-        println!("   Event XML length: {} bytes", raw_event.event_xml.len());
+        log::debug!(
+            "Sending {} byte event for subscription {} to subscription manager",
+            raw_event.event_xml.len(),
+            subscription_id
+        );
 
         if let Err(_) = self.event_sender.send(raw_event) {
-            println!("❌ Failed to send raw event to subscription manager!");
             log::error!("Failed to send event for subscription {}", subscription_id);
             return Ok(warp::reply::with_status(
                 "Internal server error",
@@ -468,7 +502,6 @@ impl EventRouter {
             ));
         }
 
-        println!("✅ Raw event sent to subscription manager successfully");
         log::debug!(
             "Successfully processed event for subscription {}",
             subscription_id
@@ -512,13 +545,199 @@ mod tests {
         assert!(!EventRouter::validate_notify_headers(&headers));
     }
 
+    #[test]
+    fn test_occupied_port_range_lists_each_port() {
+        use crate::streaming::subscription::PortBindFailureReason;
+
+        let _held: Vec<_> = (38470..=38472)
+            .map(|port| std::net::TcpListener::bind((Ipv4Addr::UNSPECIFIED, port)).unwrap())
+            .collect();
+        let (tx, _rx) = mpsc::unbounded_channel();
+
+        let err = CallbackServer::new((38470, 38472), None, tx).err().unwrap();
+
+        match &err {
+            SubscriptionError::NoCallbackPort { range, failures } => {
+                assert_eq!(*range, (38470, 38472));
+                assert_eq!(
+                    failures.iter().map(|failure| failure.port).collect::<Vec<_>>(),
+                    vec![38470, 38471, 38472]
+                );
+                assert!(failures
+                    .iter()
+                    .all(|failure| failure.reason == PortBindFailureReason::AddressInUse));
+            }
+            other => panic!("expected NoCallbackPort, got {:?}", other),
+        }
+        assert!(err.to_string().contains("38471 (address in use)"));
+    }
+
+    #[test]
+    fn test_zero_port_range_uses_ephemeral_port() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let server = CallbackServer::new((0, 0), None, tx).unwrap();
+
+        assert_ne!(server.port(), 0);
+    }
+
     #[test]
     fn test_callback_server_base_url() {
         let (tx, _rx) = mpsc::unbounded_channel();
-        let server = CallbackServer::new((8080, 8090), tx).unwrap();
+        let server = CallbackServer::new((8080, 8090), None, tx).unwrap();
 
         let base_url = server.base_url();
         assert!(base_url.starts_with("http://"));
         assert!(base_url.contains(&server.port().to_string()));
     }
+
+    fn server_bound_to(bind_address: &str) -> CallbackServer {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        CallbackServer {
+            server_handle: None,
+            port: 8085,
+            bind_address: Some(bind_address.parse().unwrap()),
+            event_router: Arc::new(EventRouter::new(tx)),
+            shutdown_tx: None,
+        }
+    }
+
+    #[test]
+    fn test_callback_base_url_uses_bind_address() {
+        assert_eq!(server_bound_to("192.168.1.50").base_url(), "http://192.168.1.50:8085");
+        assert_eq!(
+            server_bound_to("2001:db8::50").base_url(),
+            "http://[2001:db8::50]:8085"
+        );
+
+        // An unspecified bind address still advertises a detected address
+        assert!(!server_bound_to("0.0.0.0").base_url().contains("0.0.0.0"));
+    }
+
+    #[test]
+    fn test_pick_source_ip_follows_route_per_destination() {
+        // Wi-Fi on 192.168.1.0/24 and a VPN on 10.8.0.0/24
+        let route = |destination: SocketAddr| -> std::io::Result<IpAddr> {
+            match destination.ip() {
+                IpAddr::V4(ip) if ip.octets()[..3] == [192, 168, 1] => Ok("192.168.1.20".parse().unwrap()),
+                IpAddr::V4(ip) if ip.octets()[0] == 10 => Ok("10.8.0.5".parse().unwrap()),
+                IpAddr::V6(_) => Ok("fd00::20".parse().unwrap()),
+                _ => Err(std::io::Error::new(std::io::ErrorKind::Other, "no route")),
+            }
+        };
+
+        assert_eq!(
+            pick_source_ip("192.168.1.40".parse().unwrap(), route),
+            Some("192.168.1.20".parse().unwrap())
+        );
+        assert_eq!(
+            pick_source_ip("10.8.0.1".parse().unwrap(), route),
+            Some("10.8.0.5".parse().unwrap())
+        );
+        assert_eq!(
+            pick_source_ip("fd00::40".parse().unwrap(), route),
+            Some("fd00::20".parse().unwrap())
+        );
+        assert_eq!(pick_source_ip("172.16.0.1".parse().unwrap(), route), None);
+    }
+
+    #[test]
+    fn test_pick_source_ip_rejects_unreachable_answers() {
+        let loopback = |_: SocketAddr| -> std::io::Result<IpAddr> { Ok("127.0.0.1".parse().unwrap()) };
+        assert_eq!(pick_source_ip("192.168.1.40".parse().unwrap(), loopback), None);
+        assert_eq!(
+            pick_source_ip("127.0.0.1".parse().unwrap(), loopback),
+            Some("127.0.0.1".parse().unwrap())
+        );
+
+        let unspecified = |_: SocketAddr| -> std::io::Result<IpAddr> { Ok("0.0.0.0".parse().unwrap()) };
+        assert_eq!(pick_source_ip("192.168.1.40".parse().unwrap(), unspecified), None);
+    }
+
+    #[test]
+    fn test_base_url_for_prefers_bind_address() {
+        let server = server_bound_to("192.168.1.50");
+        assert_eq!(server.base_url_for("10.0.0.8"), "http://192.168.1.50:8085");
+        assert_eq!(
+            server_bound_to("0.0.0.0").base_url_for("127.0.0.1"),
+            "http://127.0.0.1:8085"
+        );
+    }
+
+    #[test]
+    fn test_callback_server_binds_ipv6_loopback() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let Ok(mut server) = CallbackServer::new(
+            (38470, 38480),
+            Some(IpAddr::V6(std::net::Ipv6Addr::LOCALHOST)),
+            tx,
+        ) else {
+            // No IPv6 loopback in this environment
+            return;
+        };
+
+        server.start().unwrap();
+        assert_eq!(server.base_url(), format!("http://[::1]:{}", server.port()));
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        assert!(std::net::TcpStream::connect(("::1", server.port())).is_ok());
+        server.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_chunked_notify_with_two_properties_is_read_completely() {
+        use crate::service::rendering_control::parser::RenderingControlParser;
+        use std::io::{Read, Write};
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut server = CallbackServer::new(
+            (38490, 38500),
+            Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+            tx,
+        )
+        .unwrap();
+        server.start().unwrap();
+        let subscription_id = SubscriptionId::new();
+        server
+            .register_subscription(subscription_id, "/chunked".to_string())
+            .unwrap();
+
+        let body = concat!(
+            r#"<e:propertyset xmlns:e="urn:schemas-upnp-org:event-1-0">"#,
+            r#"<e:property><LastChange>&lt;Event xmlns=&quot;urn:schemas-upnp-org:metadata-1-0/RCS/&quot;&gt;&lt;InstanceID val=&quot;0&quot;&gt;&lt;Volume channel=&quot;Master&quot; val=&quot;30&quot;/&gt;&lt;/InstanceID&gt;&lt;/Event&gt;</LastChange></e:property>"#,
+            r#"<e:property><LastChange>&lt;Event xmlns=&quot;urn:schemas-upnp-org:metadata-1-0/RCS/&quot;&gt;&lt;InstanceID val=&quot;0&quot;&gt;&lt;Mute channel=&quot;Master&quot; val=&quot;1&quot;/&gt;&lt;/InstanceID&gt;&lt;/Event&gt;</LastChange></e:property>"#,
+            r#"</e:propertyset>"#,
+        );
+        // Split the body into small chunks so no single chunk holds a whole property
+        let mut chunked = String::new();
+        for chunk in body.as_bytes().chunks(97) {
+            chunked.push_str(&format!("{:x}\r\n", chunk.len()));
+            chunked.push_str(std::str::from_utf8(chunk).unwrap());
+            chunked.push_str("\r\n");
+        }
+        chunked.push_str("0\r\n\r\n");
+
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        let mut stream = std::net::TcpStream::connect(("127.0.0.1", server.port())).unwrap();
+        write!(
+            stream,
+            "NOTIFY /chunked HTTP/1.1\r\nHost: 127.0.0.1\r\nContent-Type: text/xml\r\n\
+             NT: upnp:event\r\nNTS: upnp:propchange\r\nSID: uuid:test\r\nSEQ: 0\r\n\
+             Transfer-Encoding: chunked\r\nConnection: close\r\n\r\n{}",
+            chunked
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+
+        let raw_event = rx.try_recv().expect("event should be forwarded before the reply");
+        assert_eq!(raw_event.subscription_id, subscription_id);
+        assert_eq!(raw_event.event_xml, body);
+
+        let parsed = RenderingControlParser::from_xml(&raw_event.event_xml).unwrap();
+        assert_eq!(parsed.properties.len(), 2);
+        assert_eq!(parsed.get_volume(), Some(30));
+        assert_eq!(parsed.get_mute(), Some(true));
+
+        server.shutdown().unwrap();
+    }
 }
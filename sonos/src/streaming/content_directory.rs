@@ -0,0 +1,430 @@
+use super::subscription::{ServiceSubscription, SubscriptionError, SubscriptionResult};
+use super::types::{ServiceType, SubscriptionConfig, SubscriptionId, SubscriptionScope};
+use crate::model::{Speaker, SpeakerId, StateChange};
+use crate::service::content_directory;
+use crate::transport::soap::SoapClient;
+use std::time::SystemTime;
+
+/// ContentDirectory service subscription implementation
+///
+/// This struct handles UPnP subscriptions to the ContentDirectory service on Sonos devices,
+/// which provides events when the play queue, favorites, saved queues, or music library change.
+pub struct ContentDirectorySubscription {
+    /// The speaker this subscription is associated with
+    speaker: Speaker,
+    /// Current subscription ID (None if not subscribed)
+    subscription_id: Option<SubscriptionId>,
+    /// UPnP SID (Subscription ID) returned by the device
+    upnp_sid: Option<String>,
+    /// URL where the device should send event notifications
+    callback_url: String,
+    /// SOAP client for making UPnP requests (kept for future use)
+    #[allow(dead_code)]
+    soap_client: SoapClient,
+    /// Timestamp of the last successful renewal
+    last_renewal: Option<SystemTime>,
+    /// Configuration for this subscription
+    config: SubscriptionConfig,
+    /// Whether the subscription is currently active
+    active: bool,
+}
+
+impl ContentDirectorySubscription {
+    /// Create a new ContentDirectory subscription
+    pub fn new(
+        speaker: Speaker,
+        callback_url: String,
+        config: SubscriptionConfig,
+    ) -> SubscriptionResult<Self> {
+        config.validate().map_err(SubscriptionError::InvalidConfiguration)?;
+        let soap_client = SoapClient::new(std::time::Duration::from_secs(30))
+            .and_then(|client| client.with_headers(&config.user_agent, &config.extra_headers))
+            .map_err(|e| SubscriptionError::NetworkError(e.to_string()))?;
+
+        Ok(Self {
+            speaker,
+            subscription_id: None,
+            upnp_sid: None,
+            callback_url,
+            soap_client,
+            last_renewal: None,
+            config,
+            active: false,
+        })
+    }
+
+    /// Get the device URL for this speaker
+    fn device_url(&self) -> String {
+        format!("http://{}:{}", self.speaker.ip_address, self.speaker.port)
+    }
+
+    /// Send a UPnP SUBSCRIBE request to establish the subscription
+    fn send_subscribe_request(&self) -> SubscriptionResult<String> {
+        let device_url = self.device_url();
+        let event_sub_url = ServiceType::ContentDirectory.event_sub_url();
+        let full_url = format!("{}{}", device_url, event_sub_url);
+
+        log::debug!("Sending ContentDirectory SUBSCRIBE request to: {}", full_url);
+
+        // Create HTTP client for subscription requests with timeout
+        let client = self.config.http_client()?;
+
+        let response = client
+            .request(
+                reqwest::Method::from_bytes(b"SUBSCRIBE").unwrap(),
+                &full_url,
+            )
+            .header(
+                "HOST",
+                format!("{}:{}", self.speaker.ip_address, self.speaker.port),
+            )
+            .header("CALLBACK", format!("<{}>", self.callback_url))
+            .header("NT", "upnp:event")
+            .header("TIMEOUT", format!("Second-{}", self.config.timeout_seconds))
+            .send()
+            .map_err(|e| SubscriptionError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return match response.status().as_u16() {
+                503 => {
+                    // Don't print error message here - let the caller handle satellite speaker detection
+                    Err(SubscriptionError::SatelliteSpeaker)
+                }
+                _ => {
+                    let error_msg = format!(
+                        "HTTP {} - {}",
+                        response.status(),
+                        response.status().canonical_reason().unwrap_or("Unknown")
+                    );
+                    Err(SubscriptionError::SubscriptionFailed(error_msg))
+                }
+            };
+        }
+
+        // Extract SID from response headers
+        let sid = response
+            .headers()
+            .get("SID")
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| {
+                SubscriptionError::SubscriptionFailed("No SID in response".to_string())
+            })?;
+
+        Ok(sid.to_string())
+    }
+
+    /// Send a UPnP UNSUBSCRIBE request to terminate the subscription
+    fn send_unsubscribe_request(&self, sid: &str) -> SubscriptionResult<()> {
+        let device_url = self.device_url();
+        let event_sub_url = ServiceType::ContentDirectory.event_sub_url();
+        let full_url = format!("{}{}", device_url, event_sub_url);
+
+        let client = self.config.http_client()?;
+
+        let response = client
+            .request(
+                reqwest::Method::from_bytes(b"UNSUBSCRIBE").unwrap(),
+                &full_url,
+            )
+            .header(
+                "HOST",
+                format!("{}:{}", self.speaker.ip_address, self.speaker.port),
+            )
+            .header("SID", sid)
+            .send()
+            .map_err(|e| SubscriptionError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(SubscriptionError::SubscriptionFailed(format!(
+                "UNSUBSCRIBE failed: HTTP {}",
+                response.status()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Send a subscription renewal request
+    fn send_renewal_request(&self, sid: &str) -> SubscriptionResult<()> {
+        let device_url = self.device_url();
+        let event_sub_url = ServiceType::ContentDirectory.event_sub_url();
+        let full_url = format!("{}{}", device_url, event_sub_url);
+
+        let client = self.config.http_client()?;
+
+        let response = client
+            .request(
+                reqwest::Method::from_bytes(b"SUBSCRIBE").unwrap(),
+                &full_url,
+            )
+            .header(
+                "HOST",
+                format!("{}:{}", self.speaker.ip_address, self.speaker.port),
+            )
+            .header("SID", sid)
+            .header("TIMEOUT", format!("Second-{}", self.config.timeout_seconds))
+            .send()
+            .map_err(|e| SubscriptionError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(SubscriptionError::SubscriptionFailed(format!(
+                "Renewal failed: HTTP {}",
+                response.status()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl ServiceSubscription for ContentDirectorySubscription {
+    fn service_type(&self) -> ServiceType {
+        ServiceType::ContentDirectory
+    }
+
+    fn subscription_scope(&self) -> SubscriptionScope {
+        SubscriptionScope::PerSpeaker
+    }
+
+    fn speaker_id(&self) -> &SpeakerId {
+        &self.speaker.id
+    }
+
+    fn subscribe(&mut self) -> SubscriptionResult<SubscriptionId> {
+        // Send SUBSCRIBE request
+        let upnp_sid = self.send_subscribe_request()?;
+
+        // Create subscription ID and update state
+        let subscription_id = SubscriptionId::new();
+        self.subscription_id = Some(subscription_id);
+        self.upnp_sid = Some(upnp_sid);
+        self.active = true;
+        self.last_renewal = Some(SystemTime::now());
+
+        Ok(subscription_id)
+    }
+
+    fn unsubscribe(&mut self) -> SubscriptionResult<()> {
+        if let Some(upnp_sid) = &self.upnp_sid {
+            self.send_unsubscribe_request(upnp_sid)?;
+        }
+
+        self.subscription_id = None;
+        self.upnp_sid = None;
+        self.active = false;
+        self.last_renewal = None;
+        Ok(())
+    }
+
+    fn renew(&mut self) -> SubscriptionResult<()> {
+        if !self.active {
+            return Err(SubscriptionError::SubscriptionExpired);
+        }
+
+        if let Some(upnp_sid) = &self.upnp_sid {
+            self.send_renewal_request(upnp_sid)?;
+            self.last_renewal = Some(SystemTime::now());
+            Ok(())
+        } else {
+            Err(SubscriptionError::SubscriptionExpired)
+        }
+    }
+
+    fn parse_event(&self, event_xml: &str) -> SubscriptionResult<Vec<StateChange>> {
+        let mut changes = Vec::new();
+
+        if event_xml.is_empty() {
+            return Ok(changes);
+        }
+
+        match content_directory::parser::ContentDirectoryParser::from_xml(event_xml) {
+            Ok(parser) => {
+                if parser.is_queue_changed() {
+                    changes.push(StateChange::QueueChanged {
+                        speaker_id: self.speaker_id().clone(),
+                    });
+                }
+
+                if parser.is_library_updated() {
+                    changes.push(StateChange::LibraryUpdated {
+                        speaker_id: self.speaker_id().clone(),
+                    });
+                }
+            }
+            Err(e) => {
+                log::debug!("Ignoring unparseable ContentDirectory event: {:?}", e);
+            }
+        }
+
+        Ok(changes)
+    }
+
+    fn is_active(&self) -> bool {
+        self.active
+    }
+
+    fn last_renewal(&self) -> Option<SystemTime> {
+        self.last_renewal
+    }
+
+    fn subscription_id(&self) -> Option<SubscriptionId> {
+        self.subscription_id
+    }
+
+    fn get_config(&self) -> &SubscriptionConfig {
+        &self.config
+    }
+
+    fn callback_url(&self) -> &str {
+        &self.callback_url
+    }
+
+    fn on_subscription_state_changed(&mut self, active: bool) -> SubscriptionResult<()> {
+        self.active = active;
+        if !active {
+            self.subscription_id = None;
+            self.upnp_sid = None;
+            self.last_renewal = None;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Speaker;
+
+    fn create_test_speaker() -> Speaker {
+        Speaker {
+            id: SpeakerId::new("uuid:RINCON_123456789::1"),
+            name: "Test Speaker".to_string(),
+            room_name: "Test Room".to_string(),
+            ip_address: "192.168.1.100".to_string(),
+            port: 1400,
+            model_name: "Test Model".to_string(),
+            satellites: vec![],
+            supported_services: vec![],
+            software_version: None,
+            hardware_version: None,
+        }
+    }
+
+    fn create_test_subscription(speaker: Speaker) -> ContentDirectorySubscription {
+        ContentDirectorySubscription::new(
+            speaker,
+            "http://localhost:8080/callback".to_string(),
+            SubscriptionConfig::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_content_directory_subscription_creation() {
+        let speaker = create_test_speaker();
+        let callback_url = "http://localhost:8080/callback/test".to_string();
+
+        let subscription = ContentDirectorySubscription::new(
+            speaker.clone(),
+            callback_url.clone(),
+            SubscriptionConfig::default(),
+        );
+        assert!(subscription.is_ok());
+
+        let sub = subscription.unwrap();
+        assert_eq!(sub.service_type(), ServiceType::ContentDirectory);
+        assert_eq!(sub.subscription_scope(), SubscriptionScope::PerSpeaker);
+        assert_eq!(sub.speaker_id(), speaker.get_id());
+        assert_eq!(sub.callback_url(), &callback_url);
+        assert!(!sub.is_active());
+        assert!(sub.subscription_id().is_none());
+    }
+
+    #[test]
+    fn test_parse_event_with_queue_change() {
+        let speaker = create_test_speaker();
+        let subscription = create_test_subscription(speaker.clone());
+
+        let event_xml = r#"
+            <e:propertyset xmlns:e="urn:schemas-upnp-org:event-1-0">
+                <e:property><ContainerUpdateIDs>Q:0,12</ContainerUpdateIDs></e:property>
+            </e:propertyset>
+        "#;
+
+        let changes = subscription.parse_event(event_xml).unwrap();
+        assert_eq!(changes.len(), 1);
+
+        if let StateChange::QueueChanged { speaker_id } = &changes[0] {
+            assert_eq!(*speaker_id, speaker.id);
+        } else {
+            panic!("Expected QueueChanged");
+        }
+    }
+
+    #[test]
+    fn test_parse_event_with_library_update() {
+        let speaker = create_test_speaker();
+        let subscription = create_test_subscription(speaker.clone());
+
+        let event_xml = r#"
+            <e:propertyset xmlns:e="urn:schemas-upnp-org:event-1-0">
+                <e:property><SystemUpdateID>305</SystemUpdateID></e:property>
+                <e:property><ContainerUpdateIDs>Q:0,4</ContainerUpdateIDs></e:property>
+            </e:propertyset>
+        "#;
+
+        let changes = subscription.parse_event(event_xml).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert!(matches!(changes[0], StateChange::QueueChanged { .. }));
+
+        if let StateChange::LibraryUpdated { speaker_id } = &changes[1] {
+            assert_eq!(*speaker_id, speaker.id);
+        } else {
+            panic!("Expected LibraryUpdated");
+        }
+    }
+
+    #[test]
+    fn test_parse_event_unrelated_properties() {
+        let subscription = create_test_subscription(create_test_speaker());
+
+        let event_xml = r#"
+            <e:propertyset xmlns:e="urn:schemas-upnp-org:event-1-0">
+                <e:property><RadioFavoritesUpdateID>RINCON_123,5</RadioFavoritesUpdateID></e:property>
+            </e:propertyset>
+        "#;
+
+        let changes = subscription.parse_event(event_xml).unwrap();
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_parsing_error_handling() {
+        let subscription = create_test_subscription(create_test_speaker());
+
+        for malformed_xml in ["", "<", "Not XML at all", "<property><SystemUpdateID>1</property>"] {
+            let result = subscription.parse_event(malformed_xml);
+            assert!(result.is_ok(), "Should handle malformed XML gracefully: {}", malformed_xml);
+            assert!(result.unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_subscription_error_handling() {
+        let mut subscription = create_test_subscription(create_test_speaker());
+
+        // Renew when not active
+        assert!(matches!(
+            subscription.renew(),
+            Err(SubscriptionError::SubscriptionExpired)
+        ));
+
+        // Renew when active but no SID
+        subscription.active = true;
+        subscription.upnp_sid = None;
+        assert!(matches!(
+            subscription.renew(),
+            Err(SubscriptionError::SubscriptionExpired)
+        ));
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use super::types::OverflowPolicy;
+use crate::model::StateChange;
+
+/// How long a blocked sender sleeps before checking whether the receiver is gone
+const BLOCKED_SEND_RECHECK: Duration = Duration::from_millis(100);
+
+/// Create the channel carrying state changes to the event processing loop
+///
+/// Without a capacity this is a plain unbounded `mpsc` channel. With one, at
+/// most `capacity` events are queued and `policy` decides what a full queue does.
+pub(crate) fn event_channel(
+    capacity: Option<usize>,
+    policy: OverflowPolicy,
+) -> (EventSender, EventReceiver) {
+    match capacity {
+        None => {
+            let (sender, receiver) = mpsc::channel();
+            (sender.into(), receiver.into())
+        }
+        Some(capacity) => {
+            let shared = Arc::new(BoundedChannel {
+                queue: Mutex::new(VecDeque::with_capacity(capacity)),
+                not_empty: Condvar::new(),
+                not_full: Condvar::new(),
+                capacity: capacity.max(1),
+                policy,
+                senders: AtomicUsize::new(1),
+                receiver_alive: AtomicBool::new(true),
+                dropped: AtomicU64::new(0),
+            });
+            (
+                EventSender::Bounded(Arc::clone(&shared)),
+                EventReceiver::Bounded(shared),
+            )
+        }
+    }
+}
+
+/// Queue shared by the bounded sender and receiver
+pub struct BoundedChannel {
+    queue: Mutex<VecDeque<StateChange>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize,
+    policy: OverflowPolicy,
+    /// Live senders; the receiver disconnects once this reaches zero
+    senders: AtomicUsize,
+    receiver_alive: AtomicBool,
+    /// Events discarded by `OverflowPolicy::DropOldest`
+    dropped: AtomicU64,
+}
+
+/// Sending half of the state change channel
+pub enum EventSender {
+    Unbounded(mpsc::Sender<StateChange>),
+    Bounded(Arc<BoundedChannel>),
+}
+
+impl EventSender {
+    /// Queue a state change, applying the overflow policy when the channel is full
+    ///
+    /// Fails only when the receiver has been dropped.
+    pub fn send(&self, change: StateChange) -> Result<(), Box<mpsc::SendError<StateChange>>> {
+        let channel = match self {
+            EventSender::Unbounded(sender) => return sender.send(change).map_err(Box::new),
+            EventSender::Bounded(channel) => channel,
+        };
+
+        if !channel.receiver_alive.load(Ordering::SeqCst) {
+            return Err(Box::new(mpsc::SendError(change)));
+        }
+
+        let Ok(mut queue) = channel.queue.lock() else {
+            return Err(Box::new(mpsc::SendError(change)));
+        };
+        if queue.len() >= channel.capacity {
+            match channel.policy {
+                OverflowPolicy::DropOldest => {
+                    queue.pop_front();
+                    channel.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                OverflowPolicy::Block => {
+                    while queue.len() >= channel.capacity {
+                        if !channel.receiver_alive.load(Ordering::SeqCst) {
+                            return Err(Box::new(mpsc::SendError(change)));
+                        }
+                        queue = match channel.not_full.wait_timeout(queue, BLOCKED_SEND_RECHECK) {
+                            Ok((queue, _)) => queue,
+                            Err(_) => return Err(Box::new(mpsc::SendError(change))),
+                        };
+                    }
+                }
+            }
+        }
+        queue.push_back(change);
+        channel.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Events discarded because the channel was full
+    pub fn dropped(&self) -> u64 {
+        match self {
+            EventSender::Unbounded(_) => 0,
+            EventSender::Bounded(channel) => channel.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Clone for EventSender {
+    fn clone(&self) -> Self {
+        match self {
+            EventSender::Unbounded(sender) => EventSender::Unbounded(sender.clone()),
+            EventSender::Bounded(channel) => {
+                channel.senders.fetch_add(1, Ordering::SeqCst);
+                EventSender::Bounded(Arc::clone(channel))
+            }
+        }
+    }
+}
+
+impl Drop for EventSender {
+    fn drop(&mut self) {
+        if let EventSender::Bounded(channel) = self {
+            if channel.senders.fetch_sub(1, Ordering::SeqCst) == 1 {
+                // Take the lock so a receiver between its check and its wait still wakes up
+                let _queue = channel.queue.lock();
+                channel.not_empty.notify_all();
+            }
+        }
+    }
+}
+
+impl From<mpsc::Sender<StateChange>> for EventSender {
+    fn from(sender: mpsc::Sender<StateChange>) -> Self {
+        EventSender::Unbounded(sender)
+    }
+}
+
+/// Receiving half of the state change channel
+pub enum EventReceiver {
+    Unbounded(mpsc::Receiver<StateChange>),
+    Bounded(Arc<BoundedChannel>),
+}
+
+impl EventReceiver {
+    /// Wait up to `timeout` for the next state change
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<StateChange, mpsc::RecvTimeoutError> {
+        let channel = match self {
+            EventReceiver::Unbounded(receiver) => return receiver.recv_timeout(timeout),
+            EventReceiver::Bounded(channel) => channel,
+        };
+
+        let deadline = Instant::now() + timeout;
+        let mut queue = channel
+            .queue
+            .lock()
+            .map_err(|_| mpsc::RecvTimeoutError::Disconnected)?;
+        loop {
+            if let Some(change) = queue.pop_front() {
+                channel.not_full.notify_one();
+                return Ok(change);
+            }
+            if channel.senders.load(Ordering::SeqCst) == 0 {
+                return Err(mpsc::RecvTimeoutError::Disconnected);
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(mpsc::RecvTimeoutError::Timeout);
+            }
+            queue = channel
+                .not_empty
+                .wait_timeout(queue, remaining)
+                .map_err(|_| mpsc::RecvTimeoutError::Disconnected)?
+                .0;
+        }
+    }
+}
+
+impl Drop for EventReceiver {
+    fn drop(&mut self) {
+        if let EventReceiver::Bounded(channel) = self {
+            channel.receiver_alive.store(false, Ordering::SeqCst);
+            let _queue = channel.queue.lock();
+            channel.not_full.notify_all();
+        }
+    }
+}
+
+impl From<mpsc::Receiver<StateChange>> for EventReceiver {
+    fn from(receiver: mpsc::Receiver<StateChange>) -> Self {
+        EventReceiver::Unbounded(receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::SpeakerId;
+
+    fn volume(volume: u8) -> StateChange {
+        StateChange::VolumeChanged {
+            speaker_id: SpeakerId::new("RINCON_KITCHEN"),
+            volume,
+        }
+    }
+
+    fn received_volume(receiver: &EventReceiver) -> u8 {
+        match receiver.recv_timeout(Duration::from_millis(100)).unwrap() {
+            StateChange::VolumeChanged { volume, .. } => volume,
+            other => panic!("Expected VolumeChanged, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_drop_oldest_counts_dropped_events() {
+        let (sender, receiver) = event_channel(Some(3), OverflowPolicy::DropOldest);
+
+        for value in 0..5 {
+            sender.send(volume(value)).unwrap();
+        }
+
+        assert_eq!(sender.dropped(), 2);
+        assert_eq!(received_volume(&receiver), 2);
+        assert_eq!(received_volume(&receiver), 3);
+        assert_eq!(received_volume(&receiver), 4);
+        assert!(matches!(
+            receiver.recv_timeout(Duration::from_millis(10)),
+            Err(mpsc::RecvTimeoutError::Timeout)
+        ));
+    }
+
+    #[test]
+    fn test_block_waits_for_room() {
+        let (sender, receiver) = event_channel(Some(1), OverflowPolicy::Block);
+        sender.send(volume(1)).unwrap();
+
+        let blocked = std::thread::spawn(move || {
+            sender.send(volume(2)).unwrap();
+            sender.dropped()
+        });
+
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(!blocked.is_finished());
+        assert_eq!(received_volume(&receiver), 1);
+        assert_eq!(blocked.join().unwrap(), 0);
+        assert_eq!(received_volume(&receiver), 2);
+    }
+
+    #[test]
+    fn test_bounded_receiver_disconnects_when_senders_drop() {
+        let (sender, receiver) = event_channel(Some(2), OverflowPolicy::DropOldest);
+        sender.clone().send(volume(7)).unwrap();
+        drop(sender);
+
+        assert_eq!(received_volume(&receiver), 7);
+        assert!(matches!(
+            receiver.recv_timeout(Duration::from_millis(10)),
+            Err(mpsc::RecvTimeoutError::Disconnected)
+        ));
+    }
+
+    #[test]
+    fn test_send_fails_once_receiver_is_dropped() {
+        let (sender, receiver) = event_channel(Some(1), OverflowPolicy::Block);
+        sender.send(volume(1)).unwrap();
+        drop(receiver);
+
+        assert!(sender.send(volume(2)).is_err());
+    }
+}
@@ -102,15 +102,32 @@ impl EventStream {
                 log::debug!("🔇 Processing mute change: Speaker {:?} -> {}", speaker_id, if muted { "MUTED" } else { "UNMUTED" });
                 state_cache.update_mute(&speaker_id, muted);
             }
+            StateChange::BassChanged { speaker_id, bass } => {
+                // The cache doesn't track EQ settings; consumers read them from the event
+                log::debug!("Bass changed for speaker {:?}: {}", speaker_id, bass);
+            }
+            StateChange::TrebleChanged { speaker_id, treble } => {
+                log::debug!("Treble changed for speaker {:?}: {}", speaker_id, treble);
+            }
+            StateChange::LoudnessChanged { speaker_id, loudness } => {
+                log::debug!("Loudness changed for speaker {:?}: {}", speaker_id, loudness);
+            }
+            StateChange::SubGainChanged { speaker_id, gain } => {
+                log::debug!("Sub gain changed for speaker {:?}: {}", speaker_id, gain);
+            }
+            StateChange::SubEnabledChanged { speaker_id, enabled } => {
+                log::debug!("Sub enabled changed for speaker {:?}: {}", speaker_id, enabled);
+            }
             StateChange::PlaybackStateChanged { speaker_id, state } => {
                 log::debug!("▶️ Processing playback state change: Speaker {:?} -> {:?}", speaker_id, state);
                 state_cache.update_playback_state(&speaker_id, state);
             }
             StateChange::PositionChanged {
                 speaker_id,
-                position_ms,
+                position,
+                duration: _,
             } => {
-                state_cache.update_position(&speaker_id, position_ms);
+                state_cache.update_position(&speaker_id, position.as_millis() as u64);
             }
 
             StateChange::TrackChanged {
@@ -134,6 +151,20 @@ impl EventStream {
                 // Update playback state from transport info
                 state_cache.update_playback_state(&speaker_id, transport_state);
             }
+            StateChange::PlayModeChanged { speaker_id, mode } => {
+                // The cache doesn't track play mode; consumers read it from the event
+                log::debug!("Play mode changed for speaker {:?}: {}", speaker_id, mode);
+            }
+            StateChange::CrossfadeChanged { speaker_id, crossfade } => {
+                log::debug!("Crossfade changed for speaker {:?}: {}", speaker_id, crossfade);
+            }
+            StateChange::QueueChanged { speaker_id } => {
+                // The cache doesn't store queue contents; consumers re-browse on this event
+                log::debug!("Queue changed for speaker {:?}", speaker_id);
+            }
+            StateChange::LibraryUpdated { speaker_id } => {
+                log::debug!("Music library updated for speaker {:?}", speaker_id);
+            }
             StateChange::SubscriptionError {
                 speaker_id,
                 service,
@@ -152,6 +183,43 @@ impl EventStream {
             } => {
                 state_cache.set_groups(groups);
             }
+            StateChange::GroupMembershipChanged {
+                speaker_id,
+                group_id,
+                is_coordinator,
+            } => {
+                state_cache.update_group_membership(&speaker_id, group_id, is_coordinator);
+            }
+            StateChange::CoordinatorChanged {
+                group_id,
+                old_coordinator,
+                new_coordinator,
+            } => {
+                // The accompanying GroupChange carries the new groups for the cache
+                log::debug!(
+                    "Group {:?} coordinator moved from {:?} to {:?}",
+                    group_id,
+                    old_coordinator,
+                    new_coordinator
+                );
+            }
+            StateChange::SpeakerVanished { speaker_id, reason } => {
+                // Kept in the cache; the speaker usually returns with the same UDN
+                log::debug!("Speaker {:?} vanished: {}", speaker_id, reason);
+            }
+            StateChange::EventsMissed {
+                speaker_id,
+                service,
+                gap,
+            } => {
+                // Cached values stay until the service's next event overwrites them
+                log::debug!(
+                    "Speaker {:?} missed {} {:?} event(s)",
+                    speaker_id,
+                    gap,
+                    service
+                );
+            }
         }
     }
 }
@@ -170,6 +238,9 @@ mod tests {
             port: 1400,
             model_name: "Test Model".to_string(),
             satellites: vec![],
+            supported_services: vec![],
+            software_version: None,
+            hardware_version: None,
         }
     }
 
@@ -195,4 +266,109 @@ mod tests {
         };
         EventStream::process_state_change(&state_cache, playback_event);
     }
+
+    #[test]
+    fn test_subscriber_notified_of_processed_volume_change() {
+        let state_cache = StateCache::new();
+        let speaker = create_test_speaker("uuid:RINCON_123456789::1");
+        state_cache.initialize(vec![speaker.clone()], vec![]);
+        let changes = state_cache.subscribe();
+
+        EventStream::process_state_change(
+            &state_cache,
+            StateChange::VolumeChanged { speaker_id: speaker.id.clone(), volume: 42 },
+        );
+
+        assert_eq!(changes.try_recv().unwrap(), speaker.id);
+        assert_eq!(state_cache.get_speaker(&speaker.id).unwrap().volume, 42);
+    }
+
+    #[test]
+    fn test_group_playback_state_follows_coordinator() {
+        use crate::group::{Group, SpeakerRef};
+        use crate::model::GroupId;
+
+        let state_cache = StateCache::new();
+        let coordinator = create_test_speaker("uuid:RINCON_111111111::1");
+        let member = create_test_speaker("uuid:RINCON_222222222::1");
+        let group_id = GroupId::new("RINCON_111111111:42");
+
+        state_cache.initialize(vec![coordinator.clone(), member.clone()], vec![]);
+        EventStream::process_state_change(
+            &state_cache,
+            StateChange::GroupChange {
+                groups: vec![Group::new(
+                    group_id.clone(),
+                    coordinator.id.clone(),
+                    vec![
+                        SpeakerRef::new(coordinator.id.clone(), vec![]),
+                        SpeakerRef::new(member.id.clone(), vec![]),
+                    ],
+                )],
+            },
+        );
+
+        for (speaker_id, state) in [
+            (&member.id, PlaybackState::Paused),
+            (&coordinator.id, PlaybackState::Transitioning),
+            (&coordinator.id, PlaybackState::Playing),
+        ] {
+            EventStream::process_state_change(
+                &state_cache,
+                StateChange::PlaybackStateChanged {
+                    speaker_id: speaker_id.clone(),
+                    state,
+                },
+            );
+        }
+
+        assert_eq!(
+            state_cache.group_playback_state(&group_id),
+            Some(PlaybackState::Playing)
+        );
+        assert_eq!(
+            state_cache.speaker_state(&member.id).unwrap().playback_state,
+            PlaybackState::Paused
+        );
+        assert_eq!(
+            state_cache.group_playback_state(&GroupId::new("RINCON_999:1")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_display_name_resolves_from_topology_event() {
+        use crate::group::Group;
+        use crate::service::zone_group_topology::parser::ZoneGroupTopologyParser;
+
+        let xml = include_str!("../../tests/fixtures/zone_group_topology_stereo_pair.xml");
+        let parsed = ZoneGroupTopologyParser::from_xml(xml).unwrap();
+        let groups = parsed
+            .zone_group_state()
+            .and_then(|property| property.zone_group_state.as_ref())
+            .unwrap()
+            .zone_groups
+            .zone_groups
+            .iter()
+            .map(|zone_group| Group::from_zone_group(zone_group).unwrap())
+            .collect();
+
+        let state_cache = StateCache::new();
+        EventStream::process_state_change(&state_cache, StateChange::GroupChange { groups });
+
+        // Both halves of the pair and its sub share the room name
+        for id in ["RINCON_48A6B8E0A1B201400", "RINCON_48A6B8E0C3D401400", "RINCON_5CAAFD0E1F2A01400"] {
+            assert_eq!(state_cache.display_name(&SpeakerId::new(id)).as_deref(), Some("Living Room"));
+        }
+        // Home theater satellites resolve through their soundbar
+        assert_eq!(
+            state_cache.display_name(&SpeakerId::new("uuid:RINCON_7828CAFB9D9C01400")).as_deref(),
+            Some("Basement")
+        );
+
+        let rooms = state_cache.rooms();
+        assert_eq!(rooms.keys().collect::<Vec<_>>(), vec!["Basement", "Living Room"]);
+        assert_eq!(rooms["Basement"].len(), 4);
+        assert_eq!(rooms["Living Room"].len(), 3);
+    }
 }
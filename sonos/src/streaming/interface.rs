@@ -1,5 +1,8 @@
 use super::subscription::SubscriptionError;
+use super::types::{BackoffJitter, OverflowPolicy};
 use crate::model::SpeakerId;
+use std::net::IpAddr;
+use std::path::PathBuf;
 use std::time::Duration;
 
 /// Simplified error type for the public streaming interface
@@ -24,6 +27,10 @@ pub enum StreamError {
 
     #[error("Shutdown failed")]
     ShutdownFailed,
+
+    /// Every speaker passed to the builder failed to subscribe; holds the error for each one
+    #[error("All {} speakers failed to subscribe", .0.len())]
+    AllSpeakersFailed(Vec<(SpeakerId, StreamError)>),
 }
 
 impl From<SubscriptionError> for StreamError {
@@ -58,6 +65,10 @@ impl From<SubscriptionError> for StreamError {
                     msg
                 ))
             }
+            SubscriptionError::NoCallbackPort { .. } => StreamError::InitializationFailed(format!(
+                "{}. Try a different port range, or (0, 0) for an OS-assigned port.",
+                err
+            )),
             SubscriptionError::HttpError(msg) => StreamError::NetworkError(msg),
             SubscriptionError::Timeout(msg) => StreamError::NetworkError(format!(
                 "Operation timed out: {}. Check network connectivity.",
@@ -156,6 +167,9 @@ pub struct StreamStats {
 
     /// Number of successful subscription renewals
     pub successful_renewals: u64,
+
+    /// Number of events discarded because a bounded event channel was full
+    pub events_dropped: u64,
 }
 
 impl StreamStats {
@@ -167,6 +181,7 @@ impl StreamStats {
             total_events_received: 0,
             subscription_errors: 0,
             successful_renewals: 0,
+            events_dropped: 0,
         }
     }
 
@@ -200,12 +215,39 @@ pub struct ConfigOverrides {
     /// Override the default retry backoff duration
     pub retry_backoff: Option<Duration>,
 
+    /// Randomness added to retry backoff
+    pub retry_jitter: Option<BackoffJitter>,
+
     /// Override the default callback server port range (start, end)
     pub callback_port_range: Option<(u16, u16)>,
 
+    /// Override the local address the callback server binds to and advertises
+    pub callback_bind_address: Option<IpAddr>,
+
+    /// Write raw events to this file as JSON lines before parsing
+    pub raw_event_log: Option<PathBuf>,
+
+    /// Override the `User-Agent` sent on requests to speakers
+    pub user_agent: Option<String>,
+
+    /// Additional headers sent on every request to speakers
+    pub extra_headers: Vec<(String, String)>,
+
     /// Override the default buffer size for event processing
     pub buffer_size: Option<usize>,
 
+    /// Override the number of threads parsing raw events
+    pub parse_concurrency: Option<usize>,
+
+    /// Override the timeout for speaker reachability checks
+    pub connect_timeout: Option<Duration>,
+
+    /// Bound the event channel to this many queued events
+    pub event_channel_capacity: Option<usize>,
+
+    /// Behaviour of the bounded event channel once it is full
+    pub overflow_policy: Option<OverflowPolicy>,
+
     /// Override the default maximum retry attempts
     pub max_retry_attempts: Option<u32>,
 }
@@ -228,18 +270,67 @@ impl ConfigOverrides {
         self
     }
 
+    /// Set the retry jitter override
+    pub fn with_retry_jitter(mut self, jitter: BackoffJitter) -> Self {
+        self.retry_jitter = Some(jitter);
+        self
+    }
+
     /// Set the callback port range override
     pub fn with_callback_port_range(mut self, start: u16, end: u16) -> Self {
         self.callback_port_range = Some((start, end));
         self
     }
 
+    /// Set the callback bind address override
+    pub fn with_callback_bind_address(mut self, address: IpAddr) -> Self {
+        self.callback_bind_address = Some(address);
+        self
+    }
+
+    /// Set the raw event log path
+    pub fn with_raw_event_log(mut self, path: PathBuf) -> Self {
+        self.raw_event_log = Some(path);
+        self
+    }
+
+    /// Set the User-Agent override
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Add a header sent on every request to speakers
+    pub fn with_http_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extra_headers.push((name.into(), value.into()));
+        self
+    }
+
     /// Set the buffer size override
     pub fn with_buffer_size(mut self, size: usize) -> Self {
         self.buffer_size = Some(size);
         self
     }
 
+    /// Set the parse concurrency override
+    pub fn with_parse_concurrency(mut self, workers: usize) -> Self {
+        self.parse_concurrency = Some(workers);
+        self
+    }
+
+    /// Set the connect timeout override
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Bound the event channel and set what happens when it fills up
+    pub fn with_event_channel_capacity(mut self, capacity: usize, policy: OverflowPolicy) -> Self {
+        self.event_channel_capacity = Some(capacity);
+        self.overflow_policy = Some(policy);
+        self
+    }
+
     /// Set the maximum retry attempts override
     pub fn with_max_retry_attempts(mut self, attempts: u32) -> Self {
         self.max_retry_attempts = Some(attempts);
@@ -287,6 +378,28 @@ impl ConfigOverrides {
             }
         }
 
+        if let Some(workers) = self.parse_concurrency {
+            if workers == 0 || workers > 64 {
+                return Err(StreamError::ConfigurationError(
+                    "Parse concurrency must be between 1 and 64".to_string(),
+                ));
+            }
+        }
+
+        if let Some(timeout) = self.connect_timeout {
+            if timeout.is_zero() || timeout > Duration::from_secs(30) {
+                return Err(StreamError::ConfigurationError(
+                    "Connect timeout must be between 0 and 30 seconds".to_string(),
+                ));
+            }
+        }
+
+        if self.event_channel_capacity == Some(0) {
+            return Err(StreamError::ConfigurationError(
+                "Event channel capacity must be greater than 0".to_string(),
+            ));
+        }
+
         if let Some(attempts) = self.max_retry_attempts {
             if attempts > 10 {
                 return Err(StreamError::ConfigurationError(
@@ -426,5 +539,10 @@ mod tests {
         // Invalid retry attempts (too many)
         let invalid_config = ConfigOverrides::new().with_max_retry_attempts(15);
         assert!(invalid_config.validate().is_err());
+
+        // Invalid event channel capacity (zero)
+        let invalid_config =
+            ConfigOverrides::new().with_event_channel_capacity(0, OverflowPolicy::DropOldest);
+        assert!(invalid_config.validate().is_err());
     }
 }
@@ -1,16 +1,203 @@
-use std::collections::HashMap;
-use std::sync::{mpsc, Arc, RwLock};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex, RwLock};
 use std::thread::{self, JoinHandle};
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc as tokio_mpsc;
 
 use super::av_transport::AVTransportSubscription;
 use super::callback_server::CallbackServer;
+use super::content_directory::ContentDirectorySubscription;
+use super::event_channel::EventSender;
 use super::rendering_control::RenderingControlSubscription;
 use super::subscription::{ServiceSubscription, SubscriptionError, SubscriptionResult};
-use super::types::{RawEvent, ServiceType, StreamConfig, SubscriptionConfig, SubscriptionId, SubscriptionScope};
+use super::types::{BackoffJitter, RawEvent, ServiceType, StreamConfig, StreamCounters, SubscriptionConfig, SubscriptionId, SubscriptionScope};
 use crate::model::{Speaker, SpeakerId, StateChange};
 
+/// Callback invoked once a subscription could not be renewed after all retries
+pub type RenewalFailureHandler = Arc<dyn Fn(SubscriptionId, ServiceType, SpeakerId) + Send + Sync>;
+
+/// Builds the subscription for a speaker and service in place of the real UPnP
+/// implementations, given the callback URL and config the manager would use
+pub type SubscriptionFactory = Arc<
+    dyn Fn(&Speaker, ServiceType, String, SubscriptionConfig) -> SubscriptionResult<Box<dyn ServiceSubscription>>
+        + Send
+        + Sync,
+>;
+
+/// Appends raw events to a JSON-lines file before they are parsed
+struct RawEventLog {
+    file: Mutex<File>,
+}
+
+/// One line of the raw event log
+#[derive(serde::Serialize)]
+struct RawEventRecord<'a> {
+    subscription_id: String,
+    service: Option<ServiceType>,
+    timestamp_ms: u128,
+    event_xml: &'a str,
+}
+
+impl RawEventLog {
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { file: Mutex::new(file) })
+    }
+
+    fn record(&self, raw_event: &RawEvent, service: Option<ServiceType>) {
+        let record = RawEventRecord {
+            subscription_id: raw_event.subscription_id.to_string(),
+            service,
+            timestamp_ms: raw_event
+                .timestamp
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_millis())
+                .unwrap_or(0),
+            event_xml: &raw_event.event_xml,
+        };
+
+        let line = match serde_json::to_string(&record) {
+            Ok(line) => line,
+            Err(e) => {
+                log::warn!("Failed to serialize raw event: {}", e);
+                return;
+            }
+        };
+
+        let Ok(mut file) = self.file.lock() else {
+            log::error!("Failed to acquire lock on raw event log");
+            return;
+        };
+        if let Err(e) = writeln!(file, "{}", line) {
+            log::warn!("Failed to write raw event log: {}", e);
+        }
+    }
+}
+
+/// How an event's `SEQ` relates to the previous one on its subscription
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SeqStep {
+    /// The expected next event
+    InOrder,
+    /// This many events were skipped
+    Gap(u32),
+    /// SEQ went back to 0: the speaker re-registered the subscription (e.g. after
+    /// a reboot) and this is a fresh initial event carrying its full state
+    Reset,
+}
+
+/// Last `SEQ` seen on each subscription, used to notice dropped events
+///
+/// Each worker keeps its own tracker; subscriptions are pinned to one worker
+/// so no locking is needed.
+#[derive(Default)]
+struct SeqTracker {
+    last_seq: HashMap<SubscriptionId, u32>,
+}
+
+impl SeqTracker {
+    /// Record `seq` for a subscription and classify it against the last one
+    ///
+    /// The first event after subscribing should carry SEQ 0, so a higher first
+    /// SEQ means the events before it were missed. The counter wraps from
+    /// `u32::MAX` back to 1, and any other SEQ at or below the last one (a
+    /// resent event) just becomes the new baseline.
+    fn observe(&mut self, subscription_id: SubscriptionId, seq: u32) -> SeqStep {
+        let expected = match self.last_seq.insert(subscription_id, seq) {
+            None => 0,
+            Some(_) if seq == 0 => return SeqStep::Reset,
+            Some(u32::MAX) => 1,
+            Some(last) => last + 1,
+        };
+        if seq > expected {
+            SeqStep::Gap(seq - expected)
+        } else {
+            SeqStep::InOrder
+        }
+    }
+
+    fn forget(&mut self, subscription_id: &SubscriptionId) {
+        self.last_seq.remove(subscription_id);
+    }
+}
+
+/// Fixed set of threads that parse raw events
+///
+/// Each subscription is pinned to one worker so its events are parsed in the
+/// order they arrived. Events queue on the worker's channel while it is busy,
+/// so an event storm never runs more than `parse_concurrency` parses at once.
+struct RawEventWorkers {
+    senders: Vec<mpsc::Sender<RawEvent>>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl RawEventWorkers {
+    fn start(
+        workers: usize,
+        subscriptions: Arc<RwLock<HashMap<SubscriptionId, Box<dyn ServiceSubscription>>>>,
+        event_sender: EventSender,
+        raw_event_log: Option<Arc<RawEventLog>>,
+    ) -> Self {
+        let mut senders = Vec::with_capacity(workers);
+        let mut handles = Vec::with_capacity(workers);
+
+        for index in 0..workers.max(1) {
+            let (sender, receiver) = mpsc::channel::<RawEvent>();
+            let subscriptions = Arc::clone(&subscriptions);
+            let event_sender = event_sender.clone();
+            let raw_event_log = raw_event_log.clone();
+
+            let handle = thread::Builder::new()
+                .name(format!("sonos-event-parser-{}", index))
+                .spawn(move || {
+                    let mut seq_tracker = SeqTracker::default();
+                    for raw_event in receiver {
+                        SubscriptionManager::process_raw_event(
+                            &subscriptions,
+                            &event_sender,
+                            raw_event_log.as_deref(),
+                            &mut seq_tracker,
+                            raw_event,
+                        );
+                    }
+                })
+                .expect("failed to spawn raw event parser thread");
+
+            senders.push(sender);
+            handles.push(handle);
+        }
+
+        Self { senders, handles }
+    }
+
+    /// Queue an event on the worker that owns its subscription
+    fn dispatch(&self, raw_event: RawEvent) {
+        let mut hasher = DefaultHasher::new();
+        raw_event.subscription_id.hash(&mut hasher);
+        let index = (hasher.finish() % self.senders.len() as u64) as usize;
+
+        if self.senders[index].send(raw_event).is_err() {
+            log::error!("Raw event parser {} has stopped; dropping event", index);
+        }
+    }
+
+    /// Close the queues and wait for the workers to finish what is already queued
+    fn shutdown(self) {
+        drop(self.senders);
+        for handle in self.handles {
+            if handle.join().is_err() {
+                log::error!("Raw event parser thread panicked");
+            }
+        }
+    }
+}
+
 /// Manages UPnP subscriptions across multiple speakers
 ///
 /// The SubscriptionManager coordinates subscriptions for all discovered speakers,
@@ -19,12 +206,17 @@ use crate::model::{Speaker, SpeakerId, StateChange};
 pub struct SubscriptionManager {
     /// Configuration for the subscription system
     config: StreamConfig,
+    /// Services subscribed for each speaker; starts as `config.enabled_services`
+    /// and changes with `enable_service`/`disable_service`
+    enabled_services: RwLock<Vec<ServiceType>>,
     /// Channel sender for forwarding events to the EventStream
-    event_sender: mpsc::Sender<StateChange>,
+    event_sender: EventSender,
     /// Thread-safe storage for speakers and their subscriptions
     speakers: Arc<RwLock<HashMap<SpeakerId, Speaker>>>,
     /// Thread-safe storage for active subscriptions
     subscriptions: Arc<RwLock<HashMap<SubscriptionId, Box<dyn ServiceSubscription>>>>,
+    /// Speakers that refused every subscription with 503 (bonded satellites)
+    satellite_speakers: RwLock<HashSet<SpeakerId>>,
     /// Registry of active network-wide subscriptions (service_type -> subscription_id)
     network_subscriptions: Arc<RwLock<HashMap<ServiceType, SubscriptionId>>>,
     /// HTTP callback server for receiving UPnP events
@@ -35,6 +227,14 @@ pub struct SubscriptionManager {
     raw_event_sender: Option<tokio_mpsc::UnboundedSender<RawEvent>>,
     /// Shutdown signal for background threads
     shutdown_sender: Option<mpsc::Sender<()>>,
+    /// Event, error, and renewal counters reported through StreamStats
+    counters: Arc<StreamCounters>,
+    /// Cleared when the stream starts draining so no new speakers are subscribed
+    accepting_speakers: AtomicBool,
+    /// Optional hook called by the management thread when a renewal gives up
+    renewal_failure_handler: Arc<RwLock<Option<RenewalFailureHandler>>>,
+    /// Injected subscription constructor, used instead of the UPnP services when set
+    subscription_factory: Option<SubscriptionFactory>,
 }
 
 impl SubscriptionManager {
@@ -50,67 +250,112 @@ impl SubscriptionManager {
     /// Returns a new SubscriptionManager instance or an error if initialization fails.
     pub fn new(
         config: StreamConfig,
-        event_sender: mpsc::Sender<StateChange>,
+        event_sender: impl Into<EventSender>,
     ) -> SubscriptionResult<Self> {
+        let event_sender = event_sender.into();
+
         // Validate configuration
         config
             .validate()
             .map_err(|e| SubscriptionError::InvalidConfiguration(e))?;
 
         // Create callback server for receiving UPnP events
-        println!("🌐 Creating callback server for port range {:?}...", config.callback_port_range);
+        log::debug!("Creating callback server for port range {:?}", config.callback_port_range);
         let (raw_event_sender, raw_event_receiver) = tokio_mpsc::unbounded_channel();
         let mut callback_server =
-            CallbackServer::new(config.callback_port_range, raw_event_sender.clone())
-                .map_err(|e| SubscriptionError::CallbackServerError(e.to_string()))?;
+            CallbackServer::new(
+                config.callback_port_range,
+                config.callback_bind_address,
+                raw_event_sender.clone(),
+            )?;
 
-        println!("🚀 Starting callback server on port {}...", callback_server.port());
         // Start the callback server
         callback_server
             .start()
             .map_err(|e| SubscriptionError::CallbackServerError(e.to_string()))?;
 
-        println!("✅ Callback server started successfully");
-        println!("📡 Base URL: {}", callback_server.base_url());
-        log::info!("Callback server started on port {}", callback_server.port());
+        log::info!("Callback server started at {}", callback_server.base_url());
 
         let speakers = Arc::new(RwLock::new(HashMap::new()));
         let subscriptions = Arc::new(RwLock::new(HashMap::new()));
         let network_subscriptions = Arc::new(RwLock::new(HashMap::new()));
         let callback_server_arc = Arc::new(RwLock::new(Some(callback_server)));
+        let counters = Arc::new(StreamCounters::default());
+        let renewal_failure_handler = Arc::new(RwLock::new(None));
+        let raw_event_log = match &config.raw_event_log {
+            Some(path) => Some(Arc::new(RawEventLog::open(path).map_err(|e| {
+                SubscriptionError::InvalidConfiguration(format!(
+                    "Cannot open raw event log {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?)),
+            None => None,
+        };
 
         // Create shutdown channel for background threads
         let (shutdown_sender, shutdown_receiver) = mpsc::channel();
 
         // Start background thread for processing raw events and subscription management
-        let management_thread = Self::start_management_thread(
+        let workers = RawEventWorkers::start(
+            config.parse_concurrency,
             Arc::clone(&subscriptions),
             event_sender.clone(),
+            raw_event_log,
+        );
+        let management_thread = Self::start_management_thread(
+            Arc::clone(&subscriptions),
+            workers,
             raw_event_receiver,
             shutdown_receiver,
             config.clone(),
+            Arc::clone(&counters),
+            Arc::clone(&renewal_failure_handler),
         );
 
         Ok(Self {
+            enabled_services: RwLock::new(config.enabled_services.clone()),
             config,
             event_sender,
             speakers,
             subscriptions,
+            satellite_speakers: RwLock::new(HashSet::new()),
             network_subscriptions,
             callback_server: callback_server_arc,
             management_thread: Some(management_thread),
             raw_event_sender: Some(raw_event_sender),
             shutdown_sender: Some(shutdown_sender),
+            counters,
+            accepting_speakers: AtomicBool::new(true),
+            renewal_failure_handler,
+            subscription_factory: None,
         })
     }
 
+    /// Create a SubscriptionManager whose subscriptions come from `factory`
+    ///
+    /// Everything else, including the callback server and event routing, works
+    /// as in [`new`](Self::new). Used to drive the manager with mock
+    /// subscriptions instead of real devices.
+    pub fn with_subscription_factory(
+        config: StreamConfig,
+        event_sender: impl Into<EventSender>,
+        factory: SubscriptionFactory,
+    ) -> SubscriptionResult<Self> {
+        let mut manager = Self::new(config, event_sender)?;
+        manager.subscription_factory = Some(factory);
+        Ok(manager)
+    }
+
     /// Start the background thread for subscription management and event processing
     fn start_management_thread(
         subscriptions: Arc<RwLock<HashMap<SubscriptionId, Box<dyn ServiceSubscription>>>>,
-        event_sender: mpsc::Sender<StateChange>,
+        workers: RawEventWorkers,
         mut raw_event_receiver: tokio_mpsc::UnboundedReceiver<RawEvent>,
         shutdown_receiver: mpsc::Receiver<()>,
         config: StreamConfig,
+        counters: Arc<StreamCounters>,
+        renewal_failure_handler: Arc<RwLock<Option<RenewalFailureHandler>>>,
     ) -> JoinHandle<()> {
         thread::spawn(move || {
             let rt = tokio::runtime::Runtime::new().unwrap();
@@ -123,18 +368,13 @@ impl SubscriptionManager {
                     tokio::select! {
                         // Process raw events from callback server
                         Some(raw_event) = raw_event_receiver.recv() => {
-                            let subscriptions_clone = Arc::clone(&subscriptions);
-                            let event_sender_clone = event_sender.clone();
-                            
-                            // Use spawn_blocking to handle potentially blocking XML parsing
-                            tokio::task::spawn_blocking(move || {
-                                Self::process_raw_event(&subscriptions_clone, &event_sender_clone, raw_event);
-                            });
+                            // XML parsing runs on the bounded worker pool, not this loop
+                            workers.dispatch(raw_event);
                         }
 
                         // Periodic subscription renewal check
                         _ = renewal_interval.tick() => {
-                            Self::check_subscription_renewals(&subscriptions, &config);
+                            Self::check_subscription_renewals(&subscriptions, &config, &counters, &renewal_failure_handler);
                         }
 
                         // Check for shutdown signal periodically
@@ -157,6 +397,8 @@ impl SubscriptionManager {
                     }
                 }
             });
+
+            workers.shutdown();
         })
     }
 
@@ -166,7 +408,9 @@ impl SubscriptionManager {
     /// Console output has been replaced with logging to prevent blocking I/O.
     fn process_raw_event(
         subscriptions: &Arc<RwLock<HashMap<SubscriptionId, Box<dyn ServiceSubscription>>>>,
-        event_sender: &mpsc::Sender<StateChange>,
+        event_sender: &EventSender,
+        raw_event_log: Option<&RawEventLog>,
+        seq_tracker: &mut SeqTracker,
         raw_event: RawEvent,
     ) {
         log::debug!("Processing raw event in subscription manager");
@@ -181,6 +425,13 @@ impl SubscriptionManager {
             }
         };
 
+        if let Some(raw_event_log) = raw_event_log {
+            let service = subscriptions_guard
+                .get(&raw_event.subscription_id)
+                .map(|subscription| subscription.service_type());
+            raw_event_log.record(&raw_event, service);
+        }
+
         log::debug!("Current subscriptions in manager: {}", subscriptions_guard.len());
         for (id, subscription) in subscriptions_guard.iter() {
             log::debug!("Subscription {} -> Speaker: {:?}, Service: {:?}, Active: {}", 
@@ -189,7 +440,39 @@ impl SubscriptionManager {
 
         if let Some(subscription) = subscriptions_guard.get(&raw_event.subscription_id) {
             log::debug!("Found subscription in manager, parsing event");
-            
+
+            if let Some(seq) = raw_event.seq {
+                match seq_tracker.observe(raw_event.subscription_id, seq) {
+                    SeqStep::InOrder => {}
+                    SeqStep::Gap(gap) => {
+                        log::warn!(
+                            "[{:?}] Missed {} event(s) on subscription {} (SEQ jumped to {})",
+                            subscription.service_type(),
+                            gap,
+                            raw_event.subscription_id,
+                            seq
+                        );
+                        let missed = StateChange::EventsMissed {
+                            speaker_id: subscription.speaker_id().clone(),
+                            service: subscription.service_type(),
+                            gap,
+                        };
+                        if let Err(e) = event_sender.send(missed) {
+                            log::error!("Failed to send missed events notice: {}", e);
+                        }
+                    }
+                    SeqStep::Reset => {
+                        // Parsing this initial event below re-primes the service's full state
+                        log::info!(
+                            "[{:?}] Subscription {} was re-registered by {:?} (SEQ reset to 0)",
+                            subscription.service_type(),
+                            raw_event.subscription_id,
+                            subscription.speaker_id()
+                        );
+                    }
+                }
+            }
+
             match subscription.parse_event(&raw_event.event_xml) {
                 Ok(state_changes) => {
                     log::debug!("Successfully parsed {} state changes", state_changes.len());
@@ -230,6 +513,7 @@ impl SubscriptionManager {
                 }
             }
         } else {
+            seq_tracker.forget(&raw_event.subscription_id);
             log::warn!(
                 "Received event for unknown subscription: {}",
                 raw_event.subscription_id
@@ -243,6 +527,8 @@ impl SubscriptionManager {
     fn check_subscription_renewals(
         subscriptions: &Arc<RwLock<HashMap<SubscriptionId, Box<dyn ServiceSubscription>>>>,
         config: &StreamConfig,
+        counters: &StreamCounters,
+        renewal_failure_handler: &RwLock<Option<RenewalFailureHandler>>,
     ) {
         let mut subscriptions_guard = match subscriptions.write() {
             Ok(guard) => guard,
@@ -254,6 +540,7 @@ impl SubscriptionManager {
 
         let mut renewals_needed = Vec::new();
         let mut expired_subscriptions = Vec::new();
+        let mut failed_renewals = Vec::new();
 
         // Collect subscriptions that need renewal or have expired
         for (id, subscription) in subscriptions_guard.iter() {
@@ -273,6 +560,7 @@ impl SubscriptionManager {
                 let service_scope = service_type.subscription_scope();
                 
                 if Self::renew_subscription_with_retry(subscription, subscription_id, config) {
+                    StreamCounters::increment(&counters.successful_renewals);
                     log::debug!("[{:?}] Successfully renewed {:?} subscription {}", service_scope, service_type, subscription_id);
                 } else {
                     log::warn!(
@@ -281,8 +569,10 @@ impl SubscriptionManager {
                         service_type,
                         subscription_id
                     );
+                    StreamCounters::increment(&counters.subscription_errors);
                     // Mark subscription as inactive
                     let _ = subscription.on_subscription_state_changed(false);
+                    failed_renewals.push((subscription_id, service_type, subscription.speaker_id().clone()));
                 }
             }
         }
@@ -297,6 +587,17 @@ impl SubscriptionManager {
                 let _ = subscription.on_subscription_state_changed(false);
             }
         }
+
+        // Release the lock before calling out so the handler can query the manager
+        drop(subscriptions_guard);
+        if failed_renewals.is_empty() {
+            return;
+        }
+        if let Some(handler) = renewal_failure_handler.read().unwrap().as_ref() {
+            for (subscription_id, service_type, speaker_id) in failed_renewals {
+                handler(subscription_id, service_type, speaker_id);
+            }
+        }
     }
 
     /// Check if a subscription has expired based on configuration
@@ -315,11 +616,12 @@ impl SubscriptionManager {
         false
     }
 
-    /// Get the callback URL for a specific subscription
-    fn get_callback_url(&self, subscription_id: SubscriptionId) -> String {
+    /// Get the callback URL for a specific subscription, addressed so the
+    /// speaker at `speaker_ip` can route back to it
+    fn get_callback_url(&self, subscription_id: SubscriptionId, speaker_ip: &str) -> String {
         let callback_server = self.callback_server.read().unwrap();
         if let Some(server) = callback_server.as_ref() {
-            format!("{}/callback/{}", server.base_url(), subscription_id)
+            format!("{}/callback/{}", server.base_url_for(speaker_ip), subscription_id)
         } else {
             format!("http://127.0.0.1:8080/callback/{}", subscription_id)
         }
@@ -330,9 +632,12 @@ impl SubscriptionManager {
         &self,
         speaker: &Speaker,
     ) -> SubscriptionResult<Vec<SubscriptionId>> {
+        let enabled_services = self.enabled_services();
+
         // Check if this speaker already has all required PerSpeaker subscriptions
-        let per_speaker_services: Vec<ServiceType> = self.config.enabled_services.iter()
+        let per_speaker_services: Vec<ServiceType> = enabled_services.iter()
             .filter(|s| s.subscription_scope() == SubscriptionScope::PerSpeaker)
+            .filter(|s| speaker.supports_service(**s))
             .cloned()
             .collect();
 
@@ -359,10 +664,20 @@ impl SubscriptionManager {
         let mut subscription_ids = Vec::new();
         let mut satellite_errors = 0;
         let mut total_attempts = 0;
+        let mut first_failure: Option<SubscriptionError> = None;
         let subscription_config = SubscriptionConfig::from_stream_config(&self.config);
 
         // Process PerSpeaker services first to avoid conflicts with NetworkWide logic
-        for service_type in &self.config.enabled_services {
+        for service_type in &enabled_services {
+            if !speaker.supports_service(*service_type) {
+                log::debug!(
+                    "Speaker {} does not advertise {:?}, skipping subscription",
+                    speaker.name,
+                    service_type
+                );
+                continue;
+            }
+
             if service_type.subscription_scope() == SubscriptionScope::PerSpeaker {
                 total_attempts += 1;
                 
@@ -390,6 +705,7 @@ impl SubscriptionManager {
                         );
                     }
                     Err(e) => {
+                        first_failure.get_or_insert_with(|| e.clone());
                         // Use isolated error handling for PerSpeaker services
                         self.handle_service_failure(*service_type, &speaker.name, e);
                     }
@@ -398,11 +714,15 @@ impl SubscriptionManager {
         }
 
         // Then process NetworkWide services separately
-        for service_type in &self.config.enabled_services {
+        for service_type in &enabled_services {
+            if !speaker.supports_service(*service_type) {
+                continue;
+            }
+
             if service_type.subscription_scope() == SubscriptionScope::NetworkWide {
                 total_attempts += 1;
                 // Simplified network-wide service handling
-                println!("🌐 Attempting to create {:?} network-wide subscription for speaker {}", service_type, speaker.name);
+                log::debug!("Attempting to create {:?} network-wide subscription for speaker {}", service_type, speaker.name);
                 match self.create_simple_network_wide_subscription(speaker, *service_type, subscription_config.clone()) {
                     Ok(Some(subscription_id)) => {
                         subscription_ids.push(subscription_id);
@@ -429,6 +749,7 @@ impl SubscriptionManager {
                         );
                     }
                     Err(e) => {
+                        first_failure.get_or_insert_with(|| e.clone());
                         // Use isolated error handling for NetworkWide services
                         self.handle_service_failure(*service_type, &speaker.name, e);
                     }
@@ -438,9 +759,17 @@ impl SubscriptionManager {
 
         // If all services returned 503 (satellite speaker error), propagate that error
         if satellite_errors == total_attempts && total_attempts > 0 {
+            self.satellite_speakers.write().unwrap().insert(speaker.get_id().clone());
             return Err(SubscriptionError::SatelliteSpeaker);
         }
 
+        // A speaker where nothing could be subscribed is a failure, not an empty success
+        if subscription_ids.is_empty() {
+            if let Some(error) = first_failure {
+                return Err(error);
+            }
+        }
+
         Ok(subscription_ids)
     }
 
@@ -606,6 +935,7 @@ impl SubscriptionManager {
     ) -> SubscriptionResult<SubscriptionId> {
         let max_attempts = self.config.retry_attempts;
         let base_backoff = self.config.retry_backoff;
+        let mut previous_backoff = base_backoff;
 
         for attempt in 0..max_attempts {
             match self.try_create_subscription_for_service(speaker, service_type, config.clone()) {
@@ -644,8 +974,13 @@ impl SubscriptionManager {
                     }
 
                     if attempt < max_attempts - 1 {
-                        let backoff_duration =
-                            Self::calculate_backoff_duration(attempt, base_backoff);
+                        let backoff_duration = Self::calculate_backoff_duration(
+                            attempt,
+                            base_backoff,
+                            self.config.retry_jitter,
+                            previous_backoff,
+                        );
+                        previous_backoff = backoff_duration;
                         log::warn!(
                             "Failed to create {:?} subscription for {} (attempt {}/{}): {}. Retrying in {:?}",
                             service_type,
@@ -712,53 +1047,55 @@ impl SubscriptionManager {
 
         // Generate subscription ID and callback URL
         let subscription_id = SubscriptionId::new();
-        let callback_url = self.get_callback_url(subscription_id);
+        let callback_url = self.get_callback_url(subscription_id, &speaker.ip_address);
         
-        println!("📡 Creating subscription with callback URL: {}", callback_url);
+        log::debug!("Creating subscription with callback URL: {}", callback_url);
 
         // Create the appropriate subscription based on service type
-        let mut subscription: Box<dyn ServiceSubscription> = match service_type {
-            ServiceType::AVTransport => Box::new(AVTransportSubscription::new(
-                speaker.clone(),
-                callback_url,
-                config,
-            )?),
-            ServiceType::RenderingControl => Box::new(RenderingControlSubscription::new(
-                speaker.clone(),
-                callback_url,
-                config,
-            )?),
-            ServiceType::ContentDirectory => {
-                // TODO: Implement ContentDirectorySubscription in future tasks
-                return Err(SubscriptionError::ServiceNotSupported {
-                    service: service_type,
-                });
-            }
-            ServiceType::ZoneGroupTopology => {
-                // Simplified ZoneGroupTopology subscription - just use the current speaker
-                // No complex network speaker management needed
-                use super::zone_group_topology::ZoneGroupTopologySubscription;
-                Box::new(ZoneGroupTopologySubscription::new(
+        let mut subscription: Box<dyn ServiceSubscription> = if let Some(factory) = &self.subscription_factory {
+            factory(speaker, service_type, callback_url, config)?
+        } else {
+            match service_type {
+                ServiceType::AVTransport => Box::new(AVTransportSubscription::new(
+                    speaker.clone(),
+                    callback_url,
+                    config,
+                )?),
+                ServiceType::RenderingControl => Box::new(RenderingControlSubscription::new(
                     speaker.clone(),
                     callback_url,
                     config,
-                )?)
+                )?),
+                ServiceType::ContentDirectory => Box::new(ContentDirectorySubscription::new(
+                    speaker.clone(),
+                    callback_url,
+                    config,
+                )?),
+                ServiceType::ZoneGroupTopology => {
+                    // Simplified ZoneGroupTopology subscription - just use the current speaker
+                    // No complex network speaker management needed
+                    use super::zone_group_topology::ZoneGroupTopologySubscription;
+                    Box::new(ZoneGroupTopologySubscription::new(
+                        speaker.clone(),
+                        callback_url,
+                        config,
+                    )?)
+                }
             }
         };
 
         // Establish the subscription with the device
-        println!("🔗 Attempting to subscribe to {:?} service on speaker {}", service_type, speaker.name);
+        log::debug!("Attempting to subscribe to {:?} service on speaker {}", service_type, speaker.name);
         let _actual_subscription_id = subscription.subscribe()?;
-        println!("✅ Successfully subscribed to {:?} service, got SID from device", service_type);
+        log::debug!("Subscribed to {:?} service on speaker {}", service_type, speaker.name);
 
         // Register with callback server using the original subscription ID (from callback URL)
         if let Some(callback_server) = self.callback_server.read().unwrap().as_ref() {
             let callback_path = format!("/callback/{}", subscription_id);
-            println!("📝 Registering subscription {} with callback path: {}", subscription_id, callback_path);
+            log::debug!("Registering subscription {} with callback path: {}", subscription_id, callback_path);
             callback_server.register_subscription(subscription_id, callback_path)?;
-            println!("✅ Successfully registered subscription {} with callback server", subscription_id);
         } else {
-            println!("❌ No callback server available for subscription registration!");
+            log::warn!("No callback server available for subscription registration!");
         }
 
         // Store the subscription using the original subscription ID
@@ -782,16 +1119,14 @@ impl SubscriptionManager {
         Ok(subscription_id)
     }
 
-    /// Calculate exponential backoff duration
-    fn calculate_backoff_duration(attempt: u32, base_duration: Duration) -> Duration {
-        let multiplier = 2_u64.pow(attempt);
-        let backoff_ms = base_duration.as_millis() as u64 * multiplier;
-
-        // Cap at 30 seconds to avoid excessive delays
-        let max_backoff_ms = 30_000;
-        let capped_backoff_ms = backoff_ms.min(max_backoff_ms);
-
-        Duration::from_millis(capped_backoff_ms)
+    /// Calculate exponential backoff duration, with `jitter` applied
+    fn calculate_backoff_duration(
+        attempt: u32,
+        base_duration: Duration,
+        jitter: BackoffJitter,
+        previous: Duration,
+    ) -> Duration {
+        jitter.backoff(attempt, base_duration, previous)
     }
 
     /// Remove all subscriptions for a speaker
@@ -842,6 +1177,219 @@ impl SubscriptionManager {
         Ok(())
     }
 
+    /// Managed speakers that have no active per-speaker subscription
+    ///
+    /// Covers speakers whose subscriptions were marked inactive after failed
+    /// renewals as well as speakers that could not be subscribed to at all.
+    /// Satellites and speakers without any enabled per-speaker service are
+    /// never expected to have one, so they are left out.
+    pub(crate) fn inactive_speakers(&self) -> Vec<Speaker> {
+        let per_speaker_services: Vec<ServiceType> = self
+            .enabled_services()
+            .into_iter()
+            .filter(|service| service.subscription_scope() == SubscriptionScope::PerSpeaker)
+            .collect();
+        let satellite_speakers = self.satellite_speakers.read().unwrap();
+        let subscriptions = self.subscriptions.read().unwrap();
+        self.speakers
+            .read()
+            .unwrap()
+            .values()
+            .filter(|speaker| !satellite_speakers.contains(speaker.get_id()))
+            .filter(|speaker| per_speaker_services.iter().any(|service| speaker.supports_service(*service)))
+            .filter(|speaker| {
+                !subscriptions.values().any(|sub| {
+                    sub.speaker_id() == speaker.get_id()
+                        && sub.subscription_scope() == SubscriptionScope::PerSpeaker
+                        && sub.is_active()
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Events the bounded event channel has discarded under `OverflowPolicy::DropOldest`
+    pub(crate) fn dropped_events(&self) -> u64 {
+        self.event_sender.dropped()
+    }
+
+    /// Timeout used for speaker reachability checks, from [`StreamConfig::connect_timeout`]
+    pub fn connect_timeout(&self) -> Duration {
+        self.config.connect_timeout
+    }
+
+    /// Whether the speaker answers within the configured connect timeout
+    ///
+    /// Blocks the calling thread for up to [`connect_timeout`](Self::connect_timeout).
+    /// Use [`check_speakers_reachable`](Self::check_speakers_reachable) to check
+    /// many speakers at once.
+    pub fn is_speaker_reachable(&self, speaker: &Speaker) -> bool {
+        Self::check_speaker_connectivity(speaker, self.config.connect_timeout)
+    }
+
+    /// Check every speaker concurrently, returning whether each one is reachable
+    ///
+    /// The whole batch takes about one connect timeout, however many speakers
+    /// are checked.
+    pub async fn check_speakers_reachable(&self, speakers: &[Speaker]) -> Vec<(SpeakerId, bool)> {
+        let timeout = self.config.connect_timeout;
+        futures_util::future::join_all(speakers.iter().map(|speaker| async move {
+            (
+                speaker.get_id().clone(),
+                Self::check_speaker_connectivity_async(speaker, timeout).await,
+            )
+        }))
+        .await
+    }
+
+    /// Async version of the reachability check that does not block a thread
+    pub async fn check_speaker_connectivity_async(speaker: &Speaker, timeout: Duration) -> bool {
+        let address = (speaker.ip_address.as_str(), speaker.port);
+        matches!(
+            tokio::time::timeout(timeout, tokio::net::TcpStream::connect(address)).await,
+            Ok(Ok(_))
+        )
+    }
+
+    /// Whether the speaker accepts TCP connections on its UPnP port
+    pub(crate) fn check_speaker_connectivity(speaker: &Speaker, timeout: Duration) -> bool {
+        use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+
+        let addrs: Vec<SocketAddr> = match (speaker.ip_address.as_str(), speaker.port).to_socket_addrs() {
+            Ok(addrs) => addrs.collect(),
+            Err(_) => return false,
+        };
+        addrs
+            .iter()
+            .any(|addr| TcpStream::connect_timeout(addr, timeout).is_ok())
+    }
+
+    /// Drop a speaker's subscriptions and subscribe again from scratch
+    ///
+    /// The old subscriptions are unsubscribed and unregistered from the callback
+    /// server first, so the new ones never collide with stale registrations.
+    pub(crate) fn recreate_subscriptions_for_speaker(
+        &self,
+        speaker: &Speaker,
+    ) -> SubscriptionResult<Vec<SubscriptionId>> {
+        // Clear the network registry too, or a network-wide subscription anchored
+        // to this speaker would be "reused" after its subscription is gone
+        self.handle_network_wide_speaker_removal(speaker.get_id())?;
+        self.remove_subscriptions_for_speaker(speaker.get_id())?;
+        self.create_subscriptions_for_speaker(speaker)
+    }
+
+    /// Re-subscribe every inactive speaker that answers again
+    ///
+    /// Returns the number of speakers whose subscriptions were recreated.
+    pub(crate) fn reconnect_inactive_speakers(&self, connect_timeout: Duration) -> usize {
+        self.recover_inactive_speakers(connect_timeout).successful_recoveries
+    }
+
+    /// Try to re-subscribe every inactive speaker, recording why each failure failed
+    fn recover_inactive_speakers(&self, connect_timeout: Duration) -> RecoveryReport {
+        let mut report = RecoveryReport {
+            total_attempts: 0,
+            successful_recoveries: 0,
+            failed_recoveries: Vec::new(),
+        };
+        for speaker in self.inactive_speakers() {
+            report.total_attempts += 1;
+            if !Self::check_speaker_connectivity(&speaker, connect_timeout) {
+                log::debug!("Speaker {} is still unreachable", speaker.name);
+                report.failed_recoveries.push(FailedRecovery {
+                    speaker_id: speaker.id.clone(),
+                    speaker_name: speaker.name.clone(),
+                    error: "Speaker is unreachable".to_string(),
+                });
+                continue;
+            }
+
+            match self.recreate_subscriptions_for_speaker(&speaker) {
+                Ok(subscription_ids) => {
+                    log::info!(
+                        "Reconnected speaker {} with {} subscriptions",
+                        speaker.name,
+                        subscription_ids.len()
+                    );
+                    report.successful_recoveries += 1;
+                }
+                Err(e) => {
+                    log::debug!("Failed to reconnect speaker {}: {}", speaker.name, e);
+                    report.failed_recoveries.push(FailedRecovery {
+                        speaker_id: speaker.id.clone(),
+                        speaker_name: speaker.name.clone(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+        report
+    }
+
+    /// Check speakers and subscriptions, re-subscribing inactive speakers that answer
+    ///
+    /// Counts are taken before recovery, so the report shows what was wrong.
+    /// `services` breaks subscriptions down per service, which shows e.g. that
+    /// ZoneGroupTopology is down network-wide while RenderingControl is fine.
+    pub fn health_check_and_recover(&self) -> HealthCheckReport {
+        let speakers = self.speakers();
+        let reachable_speakers = speakers
+            .iter()
+            .filter(|speaker| Self::check_speaker_connectivity(speaker, self.config.connect_timeout))
+            .count();
+        let services = self.service_health();
+        let (total_subscriptions, active_subscriptions) = {
+            let subscriptions = self.subscriptions.read().unwrap();
+            (subscriptions.len(), subscriptions.values().filter(|sub| sub.is_active()).count())
+        };
+
+        let recovery_attempted = active_subscriptions < total_subscriptions;
+        let recovery_report =
+            recovery_attempted.then(|| self.recover_inactive_speakers(self.config.connect_timeout));
+
+        HealthCheckReport {
+            total_speakers: speakers.len(),
+            reachable_speakers,
+            total_subscriptions,
+            active_subscriptions,
+            services,
+            recovery_attempted,
+            recovery_report,
+        }
+    }
+
+    /// Active and failed subscription counts for every enabled or subscribed service
+    fn service_health(&self) -> HashMap<ServiceType, ServiceHealth> {
+        let mut services: HashMap<ServiceType, ServiceHealth> = self
+            .enabled_services()
+            .into_iter()
+            .map(|service_type| (service_type, ServiceHealth::default()))
+            .collect();
+
+        for subscription in self.subscriptions.read().unwrap().values() {
+            let health = services.entry(subscription.service_type()).or_default();
+            if subscription.is_active() {
+                health.active += 1;
+            } else {
+                health.failed += 1;
+            }
+        }
+
+        let network_subscriptions = self.network_subscriptions.read().unwrap();
+        let subscriptions = self.subscriptions.read().unwrap();
+        for (service_type, health) in services.iter_mut() {
+            if service_type.subscription_scope() == SubscriptionScope::NetworkWide {
+                health.missing_representative = !network_subscriptions
+                    .get(service_type)
+                    .and_then(|id| subscriptions.get(id))
+                    .is_some_and(|subscription| subscription.is_active());
+            }
+        }
+
+        services
+    }
+
     /// Add a speaker to the subscription manager
     ///
     /// This method will create subscriptions for all enabled services for the given speaker.
@@ -856,6 +1404,13 @@ impl SubscriptionManager {
     ///
     /// Returns Ok(()) if subscriptions were created successfully, or an error if the operation failed.
     pub fn add_speaker(&self, speaker: &Speaker) -> SubscriptionResult<()> {
+        if !self.accepting_speakers.load(Ordering::SeqCst) {
+            return Err(SubscriptionError::SubscriptionFailed(format!(
+                "Stream is shutting down, not adding speaker {}",
+                speaker.name
+            )));
+        }
+
         let speaker_id = speaker.get_id();
 
         // Check if speaker already exists
@@ -883,9 +1438,8 @@ impl SubscriptionManager {
         }
 
         // Create subscriptions for all enabled services (only for new speakers)
-        println!("🔧 Creating subscriptions for speaker: {}", speaker.name);
+        log::debug!("Creating subscriptions for speaker: {}", speaker.name);
         let subscription_ids = self.create_subscriptions_for_speaker(&speaker)?;
-        println!("🎯 Created {} subscriptions for speaker: {}", subscription_ids.len(), speaker.name);
 
         log::info!(
             "Added new speaker {} with {} subscriptions",
@@ -913,6 +1467,7 @@ impl SubscriptionManager {
             let mut speakers = self.speakers.write().unwrap();
             speakers.remove(&speaker_id).map(|s| s.name)
         };
+        self.satellite_speakers.write().unwrap().remove(speaker_id);
 
         if speaker_name.is_none() {
             log::debug!("Speaker {:?} not found for removal", speaker_id);
@@ -981,6 +1536,68 @@ impl SubscriptionManager {
         Ok(())
     }
 
+    /// Move network-wide subscriptions off a coordinator that left the network
+    ///
+    /// Called for every `CoordinatorChanged` event. A network-wide subscription
+    /// anchored to `old_coordinator` is only recreated on `new_coordinator` when
+    /// the old coordinator is gone: no longer managed, or its subscription has
+    /// gone inactive. While it is still present the subscription is left alone,
+    /// since recreating it would drop events for no benefit.
+    ///
+    /// Returns the number of subscriptions migrated.
+    pub(crate) fn handle_coordinator_change(
+        &self,
+        old_coordinator: &SpeakerId,
+        new_coordinator: &SpeakerId,
+    ) -> SubscriptionResult<usize> {
+        let Some(new_speaker) = self.speakers.read().unwrap().get(new_coordinator).cloned() else {
+            log::debug!("New coordinator {:?} is not managed, not migrating", new_coordinator);
+            return Ok(0);
+        };
+        let old_coordinator_present = self.speakers.read().unwrap().contains_key(old_coordinator);
+
+        let network_subscriptions: Vec<(ServiceType, SubscriptionId)> = {
+            let network_subscriptions = self.network_subscriptions.read().unwrap();
+            network_subscriptions.iter().map(|(&service_type, &sub_id)| (service_type, sub_id)).collect()
+        };
+
+        let mut migrated = 0;
+        for (service_type, subscription_id) in network_subscriptions {
+            let anchor_is_active = {
+                let subscriptions = self.subscriptions.read().unwrap();
+                match subscriptions.get(&subscription_id) {
+                    Some(sub) if sub.speaker_id() == old_coordinator => sub.is_active(),
+                    _ => continue,
+                }
+            };
+
+            if old_coordinator_present && anchor_is_active {
+                log::debug!(
+                    "{:?} network-wide subscription stays on {:?}, which is still present",
+                    service_type,
+                    old_coordinator
+                );
+                continue;
+            }
+
+            log::info!(
+                "Migrating {:?} network-wide subscription from {:?} to {}",
+                service_type,
+                old_coordinator,
+                new_speaker.name
+            );
+            self.remove_subscription(subscription_id)?;
+            self.cleanup_inactive_network_subscription(service_type);
+
+            let config = SubscriptionConfig::from_stream_config(&self.config);
+            if self.create_simple_network_wide_subscription(&new_speaker, service_type, config)?.is_some() {
+                migrated += 1;
+            }
+        }
+
+        Ok(migrated)
+    }
+
     /// Shutdown the subscription manager
     ///
     /// This method cleanly shuts down all subscriptions and releases resources.
@@ -1035,16 +1652,176 @@ impl SubscriptionManager {
         Ok(())
     }
 
+    /// Services currently subscribed for each speaker
+    pub fn enabled_services(&self) -> Vec<ServiceType> {
+        self.enabled_services.read().unwrap().clone()
+    }
+
+    /// Start subscribing to `service_type` on every managed speaker
+    ///
+    /// A network-wide service gets a single subscription through the first
+    /// speaker that accepts it. Per-speaker failures are reported the same way
+    /// as in `add_speaker`; an error is only returned when no subscription could
+    /// be created at all, and the service is then left disabled so a later call
+    /// tries again. Enabling a service that is already on does nothing.
+    pub fn enable_service(&self, service_type: ServiceType) -> SubscriptionResult<()> {
+        {
+            let mut enabled_services = self.enabled_services.write().unwrap();
+            if enabled_services.contains(&service_type) {
+                return Ok(());
+            }
+            enabled_services.push(service_type);
+        }
+
+        let speakers: Vec<Speaker> = self
+            .speakers()
+            .into_iter()
+            .filter(|speaker| speaker.supports_service(service_type))
+            .collect();
+        let subscription_config = SubscriptionConfig::from_stream_config(&self.config);
+        let mut created = 0;
+        let mut first_failure = None;
+
+        for speaker in &speakers {
+            let result = match service_type.subscription_scope() {
+                SubscriptionScope::PerSpeaker => self
+                    .create_subscription_for_service(speaker, service_type, subscription_config.clone())
+                    .map(Some),
+                SubscriptionScope::NetworkWide => {
+                    self.create_simple_network_wide_subscription(speaker, service_type, subscription_config.clone())
+                }
+            };
+
+            match result {
+                Ok(_) => {
+                    created += 1;
+                    if service_type.subscription_scope() == SubscriptionScope::NetworkWide {
+                        break;
+                    }
+                }
+                Err(SubscriptionError::SatelliteSpeaker) => {
+                    log::debug!("Speaker {} skipped for {:?} (likely satellite speaker)", speaker.name, service_type);
+                }
+                Err(e) => {
+                    first_failure.get_or_insert_with(|| e.clone());
+                    if service_type.subscription_scope() == SubscriptionScope::PerSpeaker {
+                        self.handle_service_failure(service_type, &speaker.name, e);
+                    }
+                }
+            }
+        }
+
+        if let Some(error) = first_failure.filter(|_| created == 0) {
+            self.enabled_services.write().unwrap().retain(|service| *service != service_type);
+            log::warn!("Failed to enable {:?}: {}", service_type, error);
+            return Err(error);
+        }
+
+        log::info!("Enabled {:?} with {} new subscription(s)", service_type, created);
+        Ok(())
+    }
+
+    /// Stop subscribing to `service_type` and tear down its subscriptions
+    ///
+    /// For a network-wide service this removes the single shared subscription.
+    /// Disabling a service that is already off does nothing.
+    pub fn disable_service(&self, service_type: ServiceType) -> SubscriptionResult<()> {
+        self.enabled_services.write().unwrap().retain(|service| *service != service_type);
+
+        let subscription_ids: Vec<SubscriptionId> = self
+            .subscriptions
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(_, subscription)| subscription.service_type() == service_type)
+            .map(|(id, _)| *id)
+            .collect();
+
+        for subscription_id in &subscription_ids {
+            self.remove_subscription(*subscription_id)?;
+        }
+        if service_type.subscription_scope() == SubscriptionScope::NetworkWide {
+            self.cleanup_inactive_network_subscription(service_type);
+        }
+
+        log::info!("Disabled {:?}, removed {} subscription(s)", service_type, subscription_ids.len());
+        Ok(())
+    }
+
     /// Get the number of active subscriptions
     pub fn subscription_count(&self) -> usize {
         self.subscriptions.read().unwrap().len()
     }
 
+    /// Per-subscription details for monitoring and debugging
+    pub fn get_subscription_info(&self) -> Vec<SubscriptionInfo> {
+        let speakers = self.speakers.read().unwrap();
+        self.subscriptions
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(id, subscription)| SubscriptionInfo {
+                id: *id,
+                speaker_id: subscription.speaker_id().clone(),
+                speaker_name: speakers
+                    .get(subscription.speaker_id())
+                    .map(|speaker| speaker.name.clone())
+                    .unwrap_or_default(),
+                service_type: subscription.service_type(),
+                is_active: subscription.is_active(),
+                last_renewal: subscription.last_renewal(),
+                needs_renewal: subscription.needs_renewal(),
+            })
+            .collect()
+    }
+
     /// Get the number of managed speakers
     pub fn speaker_count(&self) -> usize {
         self.speakers.read().unwrap().len()
     }
 
+    /// Snapshot of the managed speakers
+    pub(crate) fn speakers(&self) -> Vec<Speaker> {
+        self.speakers.read().unwrap().values().cloned().collect()
+    }
+
+    /// Refuse any further `add_speaker` calls
+    pub(crate) fn stop_accepting_speakers(&self) {
+        self.accepting_speakers.store(false, Ordering::SeqCst);
+    }
+
+    /// Port the callback server is listening on, if it is running
+    pub fn callback_server_port(&self) -> Option<u16> {
+        self.callback_server
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|server| server.port())
+    }
+
+    /// Base URL speakers send events to, if the callback server is running
+    pub fn callback_base_url(&self) -> Option<String> {
+        self.callback_server
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|server| server.base_url())
+    }
+
+    /// Register a callback for subscriptions that could not be renewed
+    ///
+    /// The handler runs on the management thread after every renewal retry has
+    /// failed and the subscription has been marked inactive. Replaces any
+    /// previously registered handler.
+    pub fn on_renewal_failure(&self, handler: RenewalFailureHandler) {
+        *self.renewal_failure_handler.write().unwrap() = Some(handler);
+    }
+
+    /// Shared counters backing StreamStats
+    pub(crate) fn counters(&self) -> Arc<StreamCounters> {
+        Arc::clone(&self.counters)
+    }
+
     /// Enhanced renewal with retry logic
     fn renew_subscription_with_retry(
         subscription: &mut Box<dyn ServiceSubscription>,
@@ -1053,6 +1830,7 @@ impl SubscriptionManager {
     ) -> bool {
         let max_attempts = config.retry_attempts.max(1);
         let base_backoff = config.retry_backoff;
+        let mut previous_backoff = base_backoff;
 
         for attempt in 0..max_attempts {
             match subscription.renew() {
@@ -1068,8 +1846,13 @@ impl SubscriptionManager {
                 }
                 Err(e) => {
                     if attempt < max_attempts - 1 {
-                        let backoff_duration =
-                            Self::calculate_backoff_duration(attempt, base_backoff);
+                        let backoff_duration = Self::calculate_backoff_duration(
+                            attempt,
+                            base_backoff,
+                            config.retry_jitter,
+                            previous_backoff,
+                        );
+                        previous_backoff = backoff_duration;
                         log::warn!(
                             "Failed to renew subscription {} (attempt {}/{}): {}. Retrying in {:?}",
                             subscription_id,
@@ -1140,10 +1923,24 @@ pub struct HealthCheckReport {
     pub reachable_speakers: usize,
     pub total_subscriptions: usize,
     pub active_subscriptions: usize,
+    /// Subscription health for each service
+    pub services: HashMap<ServiceType, ServiceHealth>,
     pub recovery_attempted: bool,
     pub recovery_report: Option<RecoveryReport>,
 }
 
+/// Subscription health of a single service
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServiceHealth {
+    /// Subscriptions currently receiving events
+    pub active: usize,
+    /// Subscriptions that have gone inactive
+    pub failed: usize,
+    /// Network-wide service with no active subscription on any speaker;
+    /// always false for per-speaker services
+    pub missing_representative: bool,
+}
+
 /// Information about a representative speaker for network-wide services
 #[derive(Debug, Clone)]
 pub struct RepresentativeSpeakerInfo {
@@ -1175,6 +1972,9 @@ mod tests {
             port: 1400,
             model_name: "Test Model".to_string(),
             satellites: vec![],
+            supported_services: vec![],
+            software_version: None,
+            hardware_version: None,
         }
     }
 
@@ -1225,7 +2025,7 @@ mod tests {
         let manager = SubscriptionManager::new(config, sender).unwrap();
 
         let subscription_id = super::super::types::SubscriptionId::new();
-        let callback_url = manager.get_callback_url(subscription_id);
+        let callback_url = manager.get_callback_url(subscription_id, "127.0.0.1");
 
         assert!(callback_url.starts_with("http://127.0.0.1:"));
         assert!(callback_url.contains(&format!("/callback/{}", subscription_id)));
@@ -1236,19 +2036,50 @@ mod tests {
         let base_duration = Duration::from_millis(100);
 
         // Test exponential backoff
-        let backoff_0 = SubscriptionManager::calculate_backoff_duration(0, base_duration);
+        let backoff_0 = SubscriptionManager::calculate_backoff_duration(0, base_duration, BackoffJitter::None, base_duration);
         assert_eq!(backoff_0, Duration::from_millis(100)); // 100 * 2^0 = 100
 
-        let backoff_1 = SubscriptionManager::calculate_backoff_duration(1, base_duration);
+        let backoff_1 = SubscriptionManager::calculate_backoff_duration(1, base_duration, BackoffJitter::None, base_duration);
         assert_eq!(backoff_1, Duration::from_millis(200)); // 100 * 2^1 = 200
 
-        let backoff_2 = SubscriptionManager::calculate_backoff_duration(2, base_duration);
+        let backoff_2 = SubscriptionManager::calculate_backoff_duration(2, base_duration, BackoffJitter::None, base_duration);
         assert_eq!(backoff_2, Duration::from_millis(400)); // 100 * 2^2 = 400
 
         // Test capping at 30 seconds
-        let backoff_large = SubscriptionManager::calculate_backoff_duration(20, base_duration);
+        let backoff_large = SubscriptionManager::calculate_backoff_duration(20, base_duration, BackoffJitter::None, base_duration);
         assert_eq!(backoff_large, Duration::from_millis(30_000)); // Capped at 30 seconds
     }
+
+    #[test]
+    fn test_calculate_backoff_duration_jitter() {
+        let base = Duration::from_millis(100);
+        let backoffs = |jitter, attempt, previous| -> Vec<Duration> {
+            (0..50)
+                .map(|_| SubscriptionManager::calculate_backoff_duration(attempt, base, jitter, previous))
+                .collect()
+        };
+
+        // Without jitter every call for an attempt waits the same time
+        let fixed = backoffs(BackoffJitter::None, 3, base);
+        assert!(fixed.iter().all(|backoff| *backoff == Duration::from_millis(800)));
+
+        let full = backoffs(BackoffJitter::Full, 3, base);
+        assert!(full.iter().all(|backoff| *backoff <= Duration::from_millis(800)));
+        assert!(full.iter().any(|backoff| *backoff != full[0]));
+
+        let previous = Duration::from_millis(400);
+        let decorrelated = backoffs(BackoffJitter::Decorrelated, 3, previous);
+        assert!(decorrelated
+            .iter()
+            .all(|backoff| *backoff >= base && *backoff <= Duration::from_millis(1200)));
+        assert!(decorrelated.iter().any(|backoff| *backoff != decorrelated[0]));
+
+        // Jitter never exceeds the 30 second cap
+        let capped = backoffs(BackoffJitter::Decorrelated, 20, Duration::from_secs(25));
+        assert!(capped.iter().all(|backoff| *backoff <= Duration::from_secs(30)));
+        let capped = backoffs(BackoffJitter::Full, 20, base);
+        assert!(capped.iter().all(|backoff| *backoff <= Duration::from_secs(30)));
+    }
 }
 
 #[cfg(test)]
@@ -1266,6 +2097,9 @@ mod network_tests {
             port: 1400,
             model_name: "Test Speaker".to_string(),
             satellites: vec![],
+            supported_services: vec![],
+            software_version: None,
+            hardware_version: None,
         }
     }
 
@@ -1275,4 +2109,732 @@ mod network_tests {
         SubscriptionManager::new(config, event_sender).unwrap()
     }
 
+    /// Subscription that is due for renewal but can never be renewed
+    struct UnrenewableSubscription {
+        speaker_id: SpeakerId,
+        subscription_id: SubscriptionId,
+        active: bool,
+        config: SubscriptionConfig,
+    }
+
+    impl ServiceSubscription for UnrenewableSubscription {
+        fn service_type(&self) -> ServiceType {
+            ServiceType::AVTransport
+        }
+
+        fn speaker_id(&self) -> &SpeakerId {
+            &self.speaker_id
+        }
+
+        fn subscribe(&mut self) -> SubscriptionResult<SubscriptionId> {
+            Ok(self.subscription_id)
+        }
+
+        fn unsubscribe(&mut self) -> SubscriptionResult<()> {
+            Ok(())
+        }
+
+        fn renew(&mut self) -> SubscriptionResult<()> {
+            Err(SubscriptionError::NetworkError("speaker unreachable".to_string()))
+        }
+
+        fn parse_event(&self, _event_xml: &str) -> SubscriptionResult<Vec<StateChange>> {
+            Ok(vec![])
+        }
+
+        fn is_active(&self) -> bool {
+            self.active
+        }
+
+        fn last_renewal(&self) -> Option<SystemTime> {
+            // Well past the renewal threshold
+            Some(SystemTime::now() - Duration::from_secs(self.config.timeout_seconds as u64))
+        }
+
+        fn subscription_id(&self) -> Option<SubscriptionId> {
+            Some(self.subscription_id)
+        }
+
+        fn get_config(&self) -> &SubscriptionConfig {
+            &self.config
+        }
+
+        fn callback_url(&self) -> &str {
+            "http://127.0.0.1:8080/callback"
+        }
+
+        fn on_subscription_state_changed(&mut self, active: bool) -> SubscriptionResult<()> {
+            self.active = active;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_reconnect_skips_unreachable_speakers() {
+        let manager = create_test_manager();
+        let closed_port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let speaker = Speaker {
+            port: closed_port,
+            ..create_test_speaker("uuid:RINCON_123456789::1", "127.0.0.1", "Kitchen")
+        };
+        manager
+            .speakers
+            .write()
+            .unwrap()
+            .insert(speaker.id.clone(), speaker.clone());
+
+        let subscription_id = SubscriptionId::new();
+        manager.subscriptions.write().unwrap().insert(
+            subscription_id,
+            Box::new(UnrenewableSubscription {
+                speaker_id: speaker.id.clone(),
+                subscription_id,
+                active: false,
+                config: SubscriptionConfig::default(),
+            }),
+        );
+
+        assert_eq!(manager.inactive_speakers(), vec![speaker]);
+        assert_eq!(manager.reconnect_inactive_speakers(Duration::from_millis(100)), 0);
+        // The stale subscription is left alone until the speaker answers again
+        assert_eq!(manager.subscription_count(), 1);
+    }
+
+    #[test]
+    fn test_reconnect_resubscribes_speaker_once_reachable_again() {
+        use crate::mock::{MockSpeaker, MockSubscription};
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let speaker = MockSpeaker::builder().id("uuid:RINCON_KITCHEN::1").name("Kitchen").port(port).build();
+        let template = MockSubscription::new(ServiceType::ZoneGroupTopology, speaker.id.clone())
+            .with_events(vec![StateChange::GroupChange { groups: vec![] }]);
+
+        let config = StreamConfig::default()
+            .with_enabled_services(vec![ServiceType::RenderingControl, ServiceType::ZoneGroupTopology]);
+        let (sender, receiver) = mpsc::channel();
+        let manager =
+            SubscriptionManager::with_subscription_factory(config, sender, MockSubscription::factory(template)).unwrap();
+        manager.add_speaker(&speaker).unwrap();
+        let old_topology_id = manager.network_subscriptions.read().unwrap()[&ServiceType::ZoneGroupTopology];
+
+        // The speaker drops off the network and its subscriptions lapse
+        for subscription in manager.subscriptions.write().unwrap().values_mut() {
+            subscription.unsubscribe().unwrap();
+        }
+        assert_eq!(manager.inactive_speakers(), vec![speaker.clone()]);
+        assert_eq!(manager.reconnect_inactive_speakers(Duration::from_millis(100)), 0);
+
+        // ...and comes back
+        let _listener = std::net::TcpListener::bind(("127.0.0.1", port)).unwrap();
+        assert_eq!(manager.reconnect_inactive_speakers(Duration::from_millis(500)), 1);
+        assert!(manager.inactive_speakers().is_empty());
+        assert_eq!(manager.subscription_count(), 2);
+        assert!(manager.get_subscription_info().iter().all(|info| info.is_active));
+
+        // The topology anchor was recreated rather than left pointing at the old subscription
+        let topology_id = manager.network_subscriptions.read().unwrap()[&ServiceType::ZoneGroupTopology];
+        assert_ne!(topology_id, old_topology_id);
+        manager
+            .raw_event_sender
+            .as_ref()
+            .unwrap()
+            .send(RawEvent::new(topology_id, "<e:propertyset/>".to_string()))
+            .unwrap();
+        assert!(matches!(
+            receiver.recv_timeout(Duration::from_secs(2)).unwrap(),
+            StateChange::GroupChange { .. }
+        ));
+    }
+
+    #[test]
+    fn test_inactive_speakers_ignores_speakers_without_per_speaker_services() {
+        use crate::mock::{MockSpeaker, MockSubscription};
+
+        let office = MockSpeaker::builder().id("uuid:RINCON_OFFICE::1").name("Office").build();
+        let template = MockSubscription::new(ServiceType::ZoneGroupTopology, office.id.clone());
+        let config = StreamConfig::default().with_enabled_services(vec![ServiceType::ZoneGroupTopology]);
+        let (sender, _receiver) = mpsc::channel();
+        let manager =
+            SubscriptionManager::with_subscription_factory(config, sender, MockSubscription::factory(template)).unwrap();
+
+        manager.add_speaker(&office).unwrap();
+        manager.add_speaker(&MockSpeaker::builder().id("uuid:RINCON_KITCHEN::1").build()).unwrap();
+
+        // Only one of them anchors the topology subscription; neither is inactive
+        assert_eq!(manager.subscription_count(), 1);
+        assert!(manager.inactive_speakers().is_empty());
+    }
+
+    #[test]
+    fn test_renewal_failure_handler_fires_once() {
+        let config = StreamConfig::default().with_retry_backoff(Duration::from_millis(1));
+        let (sender, _receiver) = mpsc::channel();
+        let manager = SubscriptionManager::new(config, sender).unwrap();
+
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let calls_clone = Arc::clone(&calls);
+        manager.on_renewal_failure(Arc::new(move |subscription_id, service, speaker_id| {
+            calls_clone
+                .lock()
+                .unwrap()
+                .push((subscription_id, service, speaker_id));
+        }));
+
+        let subscription_id = SubscriptionId::new();
+        let speaker_id = SpeakerId::new("uuid:RINCON_123456789::1");
+        manager.subscriptions.write().unwrap().insert(
+            subscription_id,
+            Box::new(UnrenewableSubscription {
+                speaker_id: speaker_id.clone(),
+                subscription_id,
+                active: true,
+                config: SubscriptionConfig::default(),
+            }),
+        );
+
+        // The second check must not report the now-inactive subscription again
+        for _ in 0..2 {
+            SubscriptionManager::check_subscription_renewals(
+                &manager.subscriptions,
+                &manager.config,
+                &manager.counters,
+                &manager.renewal_failure_handler,
+            );
+        }
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(
+            *calls,
+            vec![(subscription_id, ServiceType::AVTransport, speaker_id)]
+        );
+    }
+
+    #[test]
+    fn test_seq_gap_is_reported_once() {
+        let speaker_id = SpeakerId::new("uuid:RINCON_123456789::1");
+        let subscription_id = SubscriptionId::new();
+        let mut subscriptions: HashMap<SubscriptionId, Box<dyn ServiceSubscription>> = HashMap::new();
+        subscriptions.insert(
+            subscription_id,
+            Box::new(UnrenewableSubscription {
+                speaker_id: speaker_id.clone(),
+                subscription_id,
+                active: true,
+                config: SubscriptionConfig::default(),
+            }),
+        );
+        let subscriptions = Arc::new(RwLock::new(subscriptions));
+
+        let (sender, receiver) = mpsc::channel();
+        let event_sender = EventSender::from(sender);
+        let mut seq_tracker = SeqTracker::default();
+        for seq in [0, 1, 3] {
+            SubscriptionManager::process_raw_event(
+                &subscriptions,
+                &event_sender,
+                None,
+                &mut seq_tracker,
+                RawEvent::new(subscription_id, "<e:propertyset/>".to_string()).with_seq(seq),
+            );
+        }
+        drop(event_sender);
+
+        let missed: Vec<_> = receiver
+            .iter()
+            .filter_map(|change| match change {
+                StateChange::EventsMissed { speaker_id, service, gap } => Some((speaker_id, service, gap)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(missed, vec![(speaker_id, ServiceType::AVTransport, 1)]);
+    }
+
+    #[test]
+    fn test_seq_reset_to_zero_is_a_reregistration_not_a_gap() {
+        let speaker_id = SpeakerId::new("uuid:RINCON_123456789::1");
+        let subscription_id = SubscriptionId::new();
+        let mut subscriptions: HashMap<SubscriptionId, Box<dyn ServiceSubscription>> = HashMap::new();
+        subscriptions.insert(
+            subscription_id,
+            Box::new(UnrenewableSubscription {
+                speaker_id,
+                subscription_id,
+                active: true,
+                config: SubscriptionConfig::default(),
+            }),
+        );
+        let subscriptions = Arc::new(RwLock::new(subscriptions));
+
+        let (sender, receiver) = mpsc::channel();
+        let event_sender = EventSender::from(sender);
+        let mut seq_tracker = SeqTracker::default();
+
+        // Counting resumes from the reset, so 1 is in order again
+        for seq in (0..=5).chain([0, 1]) {
+            SubscriptionManager::process_raw_event(
+                &subscriptions,
+                &event_sender,
+                None,
+                &mut seq_tracker,
+                RawEvent::new(subscription_id, "<e:propertyset/>".to_string()).with_seq(seq),
+            );
+        }
+        drop(event_sender);
+
+        assert!(!receiver
+            .iter()
+            .any(|change| matches!(change, StateChange::EventsMissed { .. })));
+        let mut tracker = SeqTracker::default();
+        tracker.observe(subscription_id, 4);
+        assert_eq!(tracker.observe(subscription_id, 5), SeqStep::InOrder);
+        assert_eq!(tracker.observe(subscription_id, 0), SeqStep::Reset);
+    }
+
+    #[test]
+    fn test_seq_above_zero_on_first_event_is_a_gap() {
+        let subscription_id = SubscriptionId::new();
+        let mut tracker = SeqTracker::default();
+
+        // Events 0 through 2 never arrived
+        assert_eq!(tracker.observe(subscription_id, 3), SeqStep::Gap(3));
+        assert_eq!(tracker.observe(subscription_id, 4), SeqStep::InOrder);
+
+        let other_subscription = SubscriptionId::new();
+        assert_eq!(tracker.observe(other_subscription, 0), SeqStep::InOrder);
+    }
+
+    #[test]
+    fn test_seq_tracker_wraps_and_rebaselines() {
+        let subscription_id = SubscriptionId::new();
+        let mut tracker = SeqTracker::default();
+
+        assert_eq!(tracker.observe(subscription_id, 0), SeqStep::InOrder);
+        assert_eq!(tracker.observe(subscription_id, u32::MAX), SeqStep::Gap(u32::MAX - 1));
+        assert_eq!(tracker.observe(subscription_id, 1), SeqStep::InOrder);
+        assert_eq!(tracker.observe(subscription_id, 1), SeqStep::InOrder);
+        assert_eq!(tracker.observe(subscription_id, 4), SeqStep::Gap(2));
+    }
+
+    #[test]
+    fn test_raw_event_log_writes_json_line() {
+        let path = std::env::temp_dir().join(format!("sonos-raw-events-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let config = StreamConfig::default().with_raw_event_log(path.clone());
+        let (sender, _receiver) = mpsc::channel();
+        let manager = SubscriptionManager::new(config, sender).unwrap();
+
+        let subscription_id = SubscriptionId::new();
+        manager.subscriptions.write().unwrap().insert(
+            subscription_id,
+            Box::new(UnrenewableSubscription {
+                speaker_id: SpeakerId::new("uuid:RINCON_123456789::1"),
+                subscription_id,
+                active: true,
+                config: SubscriptionConfig::default(),
+            }),
+        );
+
+        let event_xml = "<e:propertyset><Unexpected/></e:propertyset>";
+        manager
+            .raw_event_sender
+            .as_ref()
+            .unwrap()
+            .send(RawEvent::new(subscription_id, event_xml.to_string()))
+            .unwrap();
+
+        // Events are logged on the management thread
+        let deadline = std::time::Instant::now() + Duration::from_secs(2);
+        let contents = loop {
+            let contents = std::fs::read_to_string(&path).unwrap_or_default();
+            if !contents.is_empty() || std::time::Instant::now() > deadline {
+                break contents;
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+        let _ = std::fs::remove_file(&path);
+
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 1);
+        let record: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(record["subscription_id"], subscription_id.to_string());
+        assert_eq!(record["service"], "AVTransport");
+        assert_eq!(record["event_xml"], event_xml);
+        assert!(record["timestamp_ms"].as_u64().unwrap() > 0);
+    }
+
+    #[test]
+    fn test_get_subscription_info() {
+        let config = StreamConfig::default();
+        let (sender, _receiver) = mpsc::channel();
+        let manager = SubscriptionManager::new(config, sender).unwrap();
+
+        let speaker = create_test_speaker("uuid:RINCON_123456789::1", "192.168.1.100", "Test Speaker");
+        manager
+            .speakers
+            .write()
+            .unwrap()
+            .insert(speaker.id.clone(), speaker.clone());
+
+        let subscription_id = SubscriptionId::new();
+        manager.subscriptions.write().unwrap().insert(
+            subscription_id,
+            Box::new(UnrenewableSubscription {
+                speaker_id: speaker.id.clone(),
+                subscription_id,
+                active: true,
+                config: SubscriptionConfig::default(),
+            }),
+        );
+
+        let info = manager.get_subscription_info();
+        assert_eq!(info.len(), manager.subscription_count());
+        assert_eq!(info[0].id, subscription_id);
+        assert_eq!(info[0].speaker_id, speaker.id);
+        assert_eq!(info[0].speaker_name, "Test Speaker");
+        assert_eq!(info[0].service_type, ServiceType::AVTransport);
+        assert!(info[0].is_active);
+    }
+
+    #[test]
+    fn test_mock_subscriptions_drive_add_event_remove_cycle() {
+        use crate::mock::{MockSpeaker, MockSubscription};
+
+        let speaker = MockSpeaker::builder()
+            .id("uuid:RINCON_KITCHEN::1")
+            .name("Kitchen")
+            .services(&[ServiceType::AVTransport, ServiceType::RenderingControl])
+            .build();
+        let template = MockSubscription::new(ServiceType::RenderingControl, speaker.id.clone())
+            .with_events(vec![StateChange::VolumeChanged { speaker_id: speaker.id.clone(), volume: 42 }]);
+
+        let config = StreamConfig::default()
+            .with_enabled_services(vec![ServiceType::AVTransport, ServiceType::RenderingControl]);
+        let (sender, receiver) = mpsc::channel();
+        let manager =
+            SubscriptionManager::with_subscription_factory(config, sender, MockSubscription::factory(template)).unwrap();
+
+        manager.add_speaker(&speaker).unwrap();
+        let info = manager.get_subscription_info();
+        assert_eq!(info.len(), 2);
+        assert!(info.iter().all(|info| info.is_active && info.speaker_name == "Kitchen"));
+
+        let rendering_control = info
+            .iter()
+            .find(|info| info.service_type == ServiceType::RenderingControl)
+            .unwrap();
+        manager
+            .raw_event_sender
+            .as_ref()
+            .unwrap()
+            .send(RawEvent::new(rendering_control.id, "<e:propertyset/>".to_string()))
+            .unwrap();
+
+        match receiver.recv_timeout(Duration::from_secs(2)).unwrap() {
+            StateChange::VolumeChanged { speaker_id, volume } => {
+                assert_eq!(speaker_id, speaker.id);
+                assert_eq!(volume, 42);
+            }
+            other => panic!("Expected VolumeChanged, got {:?}", other),
+        }
+
+        manager.remove_speaker(&speaker.id).unwrap();
+        assert_eq!(manager.subscription_count(), 0);
+        assert_eq!(manager.speaker_count(), 0);
+    }
+
+    #[test]
+    fn test_mock_subscription_failure_is_reported_by_add_speaker() {
+        use crate::mock::{MockSpeaker, MockSubscription};
+
+        let speaker = MockSpeaker::builder().build();
+        let template = MockSubscription::new(ServiceType::AVTransport, speaker.id.clone()).failing_subscribe();
+
+        let config = StreamConfig::default().with_enabled_services(vec![ServiceType::AVTransport]);
+        let (sender, _receiver) = mpsc::channel();
+        let manager =
+            SubscriptionManager::with_subscription_factory(config, sender, MockSubscription::factory(template)).unwrap();
+
+        assert!(manager.add_speaker(&speaker).is_err());
+        assert_eq!(manager.subscription_count(), 0);
+    }
+
+    #[test]
+    fn test_health_check_reports_each_service() {
+        use crate::mock::{MockSpeaker, MockSubscription};
+
+        let closed_port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let kitchen = MockSpeaker::builder().id("uuid:RINCON_KITCHEN::1").name("Kitchen").port(closed_port).build();
+        let office = MockSpeaker::builder().id("uuid:RINCON_OFFICE::1").name("Office").port(closed_port).build();
+        let template = MockSubscription::new(ServiceType::AVTransport, kitchen.id.clone());
+
+        let config = StreamConfig::default().with_enabled_services(vec![
+            ServiceType::AVTransport,
+            ServiceType::RenderingControl,
+            ServiceType::ZoneGroupTopology,
+        ]);
+        let (sender, _receiver) = mpsc::channel();
+        let manager =
+            SubscriptionManager::with_subscription_factory(config, sender, MockSubscription::factory(template)).unwrap();
+        manager.add_speaker(&kitchen).unwrap();
+        manager.add_speaker(&office).unwrap();
+
+        // Knock out the network-wide topology subscription and the kitchen's AVTransport
+        for subscription in manager.subscriptions.write().unwrap().values_mut() {
+            let kitchen_transport = subscription.service_type() == ServiceType::AVTransport
+                && subscription.speaker_id() == &kitchen.id;
+            if kitchen_transport || subscription.service_type() == ServiceType::ZoneGroupTopology {
+                subscription.unsubscribe().unwrap();
+            }
+        }
+
+        let report = manager.health_check_and_recover();
+
+        assert_eq!(report.total_subscriptions, 5);
+        assert_eq!(report.active_subscriptions, 3);
+        assert_eq!(
+            report.services[&ServiceType::AVTransport],
+            ServiceHealth { active: 1, failed: 1, missing_representative: false }
+        );
+        assert_eq!(
+            report.services[&ServiceType::RenderingControl],
+            ServiceHealth { active: 2, failed: 0, missing_representative: false }
+        );
+        assert_eq!(
+            report.services[&ServiceType::ZoneGroupTopology],
+            ServiceHealth { active: 0, failed: 1, missing_representative: true }
+        );
+
+        // Neither speaker answers, so recovery is attempted but fails
+        assert!(report.recovery_attempted);
+        let recovery = report.recovery_report.unwrap();
+        assert_eq!(recovery.successful_recoveries, 0);
+        assert_eq!(report.reachable_speakers, 0);
+    }
+
+    /// Subscription whose parsing is slow and records how many parses overlap
+    struct SlowParseSubscription {
+        speaker_id: SpeakerId,
+        subscription_id: SubscriptionId,
+        config: SubscriptionConfig,
+        in_flight: Arc<std::sync::atomic::AtomicUsize>,
+        max_in_flight: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl ServiceSubscription for SlowParseSubscription {
+        fn service_type(&self) -> ServiceType {
+            ServiceType::RenderingControl
+        }
+
+        fn speaker_id(&self) -> &SpeakerId {
+            &self.speaker_id
+        }
+
+        fn subscribe(&mut self) -> SubscriptionResult<SubscriptionId> {
+            Ok(self.subscription_id)
+        }
+
+        fn unsubscribe(&mut self) -> SubscriptionResult<()> {
+            Ok(())
+        }
+
+        fn renew(&mut self) -> SubscriptionResult<()> {
+            Ok(())
+        }
+
+        fn parse_event(&self, event_xml: &str) -> SubscriptionResult<Vec<StateChange>> {
+            let current = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(current, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(5));
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            Ok(vec![StateChange::VolumeChanged {
+                speaker_id: self.speaker_id.clone(),
+                volume: event_xml.parse().unwrap(),
+            }])
+        }
+
+        fn is_active(&self) -> bool {
+            true
+        }
+
+        fn last_renewal(&self) -> Option<SystemTime> {
+            Some(SystemTime::now())
+        }
+
+        fn subscription_id(&self) -> Option<SubscriptionId> {
+            Some(self.subscription_id)
+        }
+
+        fn get_config(&self) -> &SubscriptionConfig {
+            &self.config
+        }
+
+        fn callback_url(&self) -> &str {
+            "http://127.0.0.1:8080/callback"
+        }
+
+        fn on_subscription_state_changed(&mut self, _active: bool) -> SubscriptionResult<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_raw_event_parsing_respects_parse_concurrency() {
+        const SPEAKERS: usize = 6;
+        const EVENTS_PER_SPEAKER: u8 = 20;
+
+        let config = StreamConfig::default().with_parse_concurrency(2).unwrap();
+        let (sender, receiver) = mpsc::channel();
+        let manager = SubscriptionManager::new(config, sender).unwrap();
+
+        let in_flight = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let max_in_flight = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let subscription_ids: Vec<SubscriptionId> = (0..SPEAKERS)
+            .map(|i| {
+                let subscription_id = SubscriptionId::new();
+                manager.subscriptions.write().unwrap().insert(
+                    subscription_id,
+                    Box::new(SlowParseSubscription {
+                        speaker_id: SpeakerId::new(format!("uuid:RINCON_00000000000{}::1", i)),
+                        subscription_id,
+                        config: SubscriptionConfig::default(),
+                        in_flight: Arc::clone(&in_flight),
+                        max_in_flight: Arc::clone(&max_in_flight),
+                    }),
+                );
+                subscription_id
+            })
+            .collect();
+
+        let raw_event_sender = manager.raw_event_sender.as_ref().unwrap();
+        for volume in 0..EVENTS_PER_SPEAKER {
+            for subscription_id in &subscription_ids {
+                raw_event_sender
+                    .send(RawEvent::new(*subscription_id, volume.to_string()))
+                    .unwrap();
+            }
+        }
+
+        let mut volumes: HashMap<SpeakerId, Vec<u8>> = HashMap::new();
+        for _ in 0..SPEAKERS * EVENTS_PER_SPEAKER as usize {
+            match receiver.recv_timeout(Duration::from_secs(5)).unwrap() {
+                StateChange::VolumeChanged { speaker_id, volume } => {
+                    volumes.entry(speaker_id).or_default().push(volume)
+                }
+                other => panic!("unexpected change {:?}", other),
+            }
+        }
+
+        assert!(max_in_flight.load(Ordering::SeqCst) <= 2);
+        assert_eq!(volumes.len(), SPEAKERS);
+        let expected: Vec<u8> = (0..EVENTS_PER_SPEAKER).collect();
+        for received in volumes.values() {
+            assert_eq!(received, &expected);
+        }
+    }
+
+    #[test]
+    fn test_is_speaker_reachable_gives_up_within_connect_timeout() {
+        let config = StreamConfig::minimal()
+            .with_connect_timeout(Duration::from_millis(200))
+            .unwrap();
+        let (event_sender, _) = mpsc::channel();
+        let manager = SubscriptionManager::new(config, event_sender).unwrap();
+        // Reserved TEST-NET-1 address, never routed
+        let speaker = create_test_speaker("uuid:RINCON_UNROUTABLE::1", "192.0.2.1", "Attic");
+
+        let started = std::time::Instant::now();
+        assert!(!manager.is_speaker_reachable(&speaker));
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_check_speakers_reachable_runs_concurrently() {
+        let timeout = Duration::from_millis(200);
+        let config = StreamConfig::minimal().with_connect_timeout(timeout).unwrap();
+        let (event_sender, _) = mpsc::channel();
+        let manager = SubscriptionManager::new(config, event_sender).unwrap();
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let reachable = Speaker {
+            port: listener.local_addr().unwrap().port(),
+            ..create_test_speaker("uuid:RINCON_REACHABLE::1", "127.0.0.1", "Kitchen")
+        };
+        let mut speakers: Vec<Speaker> = (0..20)
+            .map(|i| {
+                create_test_speaker(&format!("uuid:RINCON_UNROUTABLE{}::1", i), "192.0.2.1", "Attic")
+            })
+            .collect();
+        speakers.push(reachable.clone());
+
+        let started = std::time::Instant::now();
+        let results = manager.check_speakers_reachable(&speakers).await;
+
+        // Serially this would take 20 timeouts
+        assert!(started.elapsed() < timeout * 5);
+        assert_eq!(results.len(), 21);
+        for (speaker_id, is_reachable) in results {
+            assert_eq!(is_reachable, &speaker_id == reachable.get_id());
+        }
+    }
+
+    #[test]
+    fn test_coordinator_change_migrates_only_from_departed_coordinator() {
+        use crate::mock::{MockSpeaker, MockSubscription};
+
+        let living_room = MockSpeaker::builder()
+            .id("uuid:RINCON_LIVING::1")
+            .name("Living Room")
+            .services(&[ServiceType::ZoneGroupTopology])
+            .build();
+        let kitchen = MockSpeaker::builder()
+            .id("uuid:RINCON_KITCHEN::1")
+            .name("Kitchen")
+            .services(&[ServiceType::ZoneGroupTopology])
+            .build();
+        let template = MockSubscription::new(ServiceType::ZoneGroupTopology, living_room.id.clone());
+        let config = StreamConfig::default().with_enabled_services(vec![ServiceType::ZoneGroupTopology]);
+        let (sender, _receiver) = mpsc::channel();
+        let manager =
+            SubscriptionManager::with_subscription_factory(config, sender, MockSubscription::factory(template)).unwrap();
+
+        manager.add_speaker(&living_room).unwrap();
+        manager.add_speaker(&kitchen).unwrap();
+        let anchor = |manager: &SubscriptionManager| {
+            let info = manager.get_subscription_info();
+            assert_eq!(info.len(), 1);
+            info[0].clone()
+        };
+        let original = anchor(&manager);
+        assert_eq!(original.speaker_id, living_room.id);
+
+        // Living room is still around, so nothing is recreated
+        assert_eq!(manager.handle_coordinator_change(&living_room.id, &kitchen.id).unwrap(), 0);
+        assert_eq!(anchor(&manager).id, original.id);
+
+        // Living room dropped off the network and its subscription lapsed
+        manager
+            .subscriptions
+            .write()
+            .unwrap()
+            .get_mut(&original.id)
+            .unwrap()
+            .unsubscribe()
+            .unwrap();
+        assert_eq!(manager.handle_coordinator_change(&living_room.id, &kitchen.id).unwrap(), 1);
+
+        let migrated = anchor(&manager);
+        assert_ne!(migrated.id, original.id);
+        assert_eq!(migrated.speaker_id, kitchen.id);
+        assert!(migrated.is_active);
+
+        // Changes not involving the anchor leave it in place
+        assert_eq!(manager.handle_coordinator_change(&living_room.id, &kitchen.id).unwrap(), 0);
+        assert_eq!(anchor(&manager).id, migrated.id);
+    }
 }
@@ -6,7 +6,9 @@ mod manager;
 mod callback_server;
 mod av_transport;
 mod rendering_control;
+mod content_directory;
 mod zone_group_topology;
+mod event_channel;
 
 // Public interface modules
 pub mod interface;
@@ -17,9 +19,15 @@ pub use interface::{
     StreamError, LifecycleHandlers, StreamStats
 };
 pub use builder::{EventStreamBuilder, ActiveEventStream};
+pub use manager::{
+    FailedRecovery, HealthCheckReport, RecoveryReport, ServiceHealth, SubscriptionFactory, SubscriptionInfo,
+};
 
 // Re-export essential types needed by the public interface
-pub use types::{ServiceType, SubscriptionScope, SubscriptionConfig};
+pub use types::{
+    ServiceType, SubscriptionScope, SubscriptionConfig, SubscriptionId, OverflowPolicy, BackoffJitter,
+    MIN_SUBSCRIPTION_TIMEOUT_SECS, MAX_SUBSCRIPTION_TIMEOUT_SECS,
+};
 
 // Internal re-exports for use within the streaming module
 // (Currently no internal re-exports needed)
@@ -36,7 +36,9 @@ impl RenderingControlSubscription {
         callback_url: String,
         config: SubscriptionConfig,
     ) -> SubscriptionResult<Self> {
+        config.validate().map_err(SubscriptionError::InvalidConfiguration)?;
         let soap_client = SoapClient::new(std::time::Duration::from_secs(30))
+            .and_then(|client| client.with_headers(&config.user_agent, &config.extra_headers))
             .map_err(|e| SubscriptionError::NetworkError(e.to_string()))?;
 
         Ok(Self {
@@ -62,19 +64,15 @@ impl RenderingControlSubscription {
         let event_sub_url = ServiceType::RenderingControl.event_sub_url();
         let full_url = format!("{}{}", device_url, event_sub_url);
 
-        println!(
-            "📡 Sending RenderingControl SUBSCRIBE request to: {}",
-            full_url
+        log::debug!(
+            "Sending RenderingControl SUBSCRIBE request to {} (callback {})",
+            full_url,
+            self.callback_url
         );
-        println!("   Callback URL: {}", self.callback_url);
 
         // Create HTTP client for subscription requests with timeout
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .build()
-            .map_err(|e| SubscriptionError::NetworkError(e.to_string()))?;
+        let client = self.config.http_client()?;
 
-        println!("🔄 Making HTTP SUBSCRIBE request...");
         let response = client
             .request(
                 reqwest::Method::from_bytes(b"SUBSCRIBE").unwrap(),
@@ -89,7 +87,7 @@ impl RenderingControlSubscription {
             .header("TIMEOUT", format!("Second-{}", self.config.timeout_seconds))
             .send()
             .map_err(|e| {
-                println!("❌ HTTP request failed: {}", e);
+                log::debug!("RenderingControl SUBSCRIBE request failed: {}", e);
                 SubscriptionError::NetworkError(e.to_string())
             })?;
 
@@ -128,10 +126,7 @@ impl RenderingControlSubscription {
         let event_sub_url = ServiceType::RenderingControl.event_sub_url();
         let full_url = format!("{}{}", device_url, event_sub_url);
 
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .build()
-            .map_err(|e| SubscriptionError::NetworkError(e.to_string()))?;
+        let client = self.config.http_client()?;
 
         let response = client
             .request(
@@ -162,10 +157,7 @@ impl RenderingControlSubscription {
         let event_sub_url = ServiceType::RenderingControl.event_sub_url();
         let full_url = format!("{}{}", device_url, event_sub_url);
 
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .build()
-            .map_err(|e| SubscriptionError::NetworkError(e.to_string()))?;
+        let client = self.config.http_client()?;
 
         let response = client
             .request(
@@ -250,7 +242,7 @@ impl ServiceSubscription for RenderingControlSubscription {
         
         // Validate input
         if event_xml.is_empty() {
-            println!("⚠️  Received empty event XML, returning no changes");
+            log::debug!("Received empty RenderingControl event XML, returning no changes");
             return Ok(Vec::new());
         }
 
@@ -271,9 +263,44 @@ impl ServiceSubscription for RenderingControlSubscription {
               }),
               None => {}
             }
+
+            if let Some(bass) = parser.get_bass() {
+              changes.push(StateChange::BassChanged {
+                speaker_id: self.speaker_id().clone(),
+                bass
+              });
+            }
+
+            if let Some(treble) = parser.get_treble() {
+              changes.push(StateChange::TrebleChanged {
+                speaker_id: self.speaker_id().clone(),
+                treble
+              });
+            }
+
+            if let Some(loudness) = parser.get_loudness() {
+              changes.push(StateChange::LoudnessChanged {
+                speaker_id: self.speaker_id().clone(),
+                loudness
+              });
+            }
+
+            if let Some(gain) = parser.get_sub_gain() {
+              changes.push(StateChange::SubGainChanged {
+                speaker_id: self.speaker_id().clone(),
+                gain
+              });
+            }
+
+            if let Some(enabled) = parser.get_sub_enabled() {
+              changes.push(StateChange::SubEnabledChanged {
+                speaker_id: self.speaker_id().clone(),
+                enabled
+              });
+            }
           }
           Err(e) => {
-            println!("no parser, {:?}", e);
+            log::warn!("Failed to parse RenderingControl event: {:?}", e);
           }
         }
 
@@ -325,6 +352,9 @@ mod tests {
             port: 1400,
             model_name: "Test Model".to_string(),
             satellites: vec![],
+            supported_services: vec![],
+            software_version: None,
+            hardware_version: None,
         }
     }
 
@@ -602,7 +632,7 @@ mod tests {
         "#;
 
         let changes = subscription.parse_event(event_xml).unwrap();
-        assert_eq!(changes.len(), 2);
+        assert_eq!(changes.len(), 4);
 
         // Verify volume change
         let volume_change = changes.iter().find(|c| matches!(c, StateChange::VolumeChanged { .. }));
@@ -619,6 +649,28 @@ mod tests {
             assert_eq!(*speaker_id, speaker.id);
             assert_eq!(*muted, true);
         }
+
+        // Bass and treble are reported alongside volume
+        assert!(changes.iter().any(|c| matches!(c, StateChange::BassChanged { bass: 0, .. })));
+        assert!(changes.iter().any(|c| matches!(c, StateChange::TrebleChanged { treble: 0, .. })));
+    }
+
+    #[test]
+    fn test_parse_event_sub_gain_and_enabled() {
+        let speaker = create_test_speaker();
+        let subscription = RenderingControlSubscription::new(
+            speaker.clone(),
+            "http://localhost:8080/callback".to_string(),
+            SubscriptionConfig::default(),
+        )
+        .unwrap();
+
+        let event_xml = r#"<e:propertyset xmlns:e="urn:schemas-upnp-org:event-1-0"><e:property><LastChange>&lt;Event xmlns="urn:schemas-upnp-org:metadata-1-0/RCS/"&gt;&lt;InstanceID val="0"&gt;&lt;SubGain val="4"/&gt;&lt;SubEnabled val="1"/&gt;&lt;/InstanceID&gt;&lt;/Event&gt;</LastChange></e:property></e:propertyset>"#;
+
+        let changes = subscription.parse_event(event_xml).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert!(changes.iter().any(|c| matches!(c, StateChange::SubGainChanged { gain: 4, .. })));
+        assert!(changes.iter().any(|c| matches!(c, StateChange::SubEnabledChanged { enabled: true, .. })));
     }
 
     #[test]
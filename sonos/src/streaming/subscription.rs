@@ -17,6 +17,15 @@ pub enum SubscriptionError {
     #[error("Callback server error: {0}")]
     CallbackServerError(String),
 
+    /// Every port in the callback range failed to bind; holds the reason for each one
+    #[error(
+        "No available callback port in range {}..={}: {}",
+        .range.0,
+        .range.1,
+        .failures.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    NoCallbackPort { range: (u16, u16), failures: Vec<PortBindFailure> },
+
     #[error("Network communication error: {0}")]
     NetworkError(String),
 
@@ -52,6 +61,44 @@ pub enum SubscriptionError {
     RegistryCorruption { message: String },
 }
 
+/// A callback port that could not be bound, and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortBindFailure {
+    pub port: u16,
+    pub reason: PortBindFailureReason,
+}
+
+/// Why binding a callback port failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PortBindFailureReason {
+    /// Another socket is already listening on the port
+    AddressInUse,
+    /// The process may not bind the port, e.g. a privileged port without root
+    PermissionDenied,
+    /// Any other bind error, with the OS message
+    Other(String),
+}
+
+impl From<&std::io::Error> for PortBindFailureReason {
+    fn from(err: &std::io::Error) -> Self {
+        match err.kind() {
+            std::io::ErrorKind::AddrInUse => PortBindFailureReason::AddressInUse,
+            std::io::ErrorKind::PermissionDenied => PortBindFailureReason::PermissionDenied,
+            _ => PortBindFailureReason::Other(err.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for PortBindFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.reason {
+            PortBindFailureReason::AddressInUse => write!(f, "{} (address in use)", self.port),
+            PortBindFailureReason::PermissionDenied => write!(f, "{} (permission denied)", self.port),
+            PortBindFailureReason::Other(message) => write!(f, "{} ({})", self.port, message),
+        }
+    }
+}
+
 impl From<reqwest::Error> for SubscriptionError {
     fn from(err: reqwest::Error) -> Self {
         if err.is_timeout() {
@@ -1,6 +1,13 @@
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, SystemTime};
 use uuid::Uuid;
 
+use super::subscription::{SubscriptionError, SubscriptionResult};
+use crate::transport::{capped_backoff, request_headers, DEFAULT_USER_AGENT, MAX_BACKOFF};
+use rand::Rng;
+
 /// Indicates the scope of a service subscription
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SubscriptionScope {
@@ -11,7 +18,7 @@ pub enum SubscriptionScope {
 }
 
 /// Represents different UPnP service types that can be subscribed to
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum ServiceType {
     AVTransport,
     RenderingControl,
@@ -19,7 +26,53 @@ pub enum ServiceType {
     ZoneGroupTopology,
 }
 
+/// Device paths and identifiers of one UPnP service on a Sonos speaker
+struct ServiceEndpoints {
+    service_id: &'static str,
+    control_url: &'static str,
+    event_sub_url: &'static str,
+    scpd_url: &'static str,
+}
+
 impl ServiceType {
+    /// Every service type this crate handles
+    pub const ALL: [ServiceType; 4] = [
+        ServiceType::AVTransport,
+        ServiceType::RenderingControl,
+        ServiceType::ContentDirectory,
+        ServiceType::ZoneGroupTopology,
+    ];
+
+    /// The paths Sonos devices publish for this service, as listed in their device description
+    fn endpoints(&self) -> &'static ServiceEndpoints {
+        match self {
+            ServiceType::AVTransport => &ServiceEndpoints {
+                service_id: "urn:upnp-org:serviceId:AVTransport",
+                control_url: "/MediaRenderer/AVTransport/Control",
+                event_sub_url: "/MediaRenderer/AVTransport/Event",
+                scpd_url: "/xml/AVTransport1.xml",
+            },
+            ServiceType::RenderingControl => &ServiceEndpoints {
+                service_id: "urn:upnp-org:serviceId:RenderingControl",
+                control_url: "/MediaRenderer/RenderingControl/Control",
+                event_sub_url: "/MediaRenderer/RenderingControl/Event",
+                scpd_url: "/xml/RenderingControl1.xml",
+            },
+            ServiceType::ContentDirectory => &ServiceEndpoints {
+                service_id: "urn:upnp-org:serviceId:ContentDirectory",
+                control_url: "/MediaServer/ContentDirectory/Control",
+                event_sub_url: "/MediaServer/ContentDirectory/Event",
+                scpd_url: "/xml/ContentDirectory1.xml",
+            },
+            ServiceType::ZoneGroupTopology => &ServiceEndpoints {
+                service_id: "urn:upnp-org:serviceId:ZoneGroupTopology",
+                control_url: "/ZoneGroupTopology/Control",
+                event_sub_url: "/ZoneGroupTopology/Event",
+                scpd_url: "/xml/ZoneGroupTopology1.xml",
+            },
+        }
+    }
+
     /// Get the UPnP service type string for SOAP requests
     pub fn service_type_urn(&self) -> &'static str {
         match self {
@@ -30,24 +83,53 @@ impl ServiceType {
         }
     }
 
+    /// Look up the service type for a UPnP service type string
+    ///
+    /// Returns `None` for services this crate does not handle, such as AlarmClock.
+    pub fn from_urn(urn: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|service_type| service_type.service_type_urn() == urn)
+    }
+
+    /// Look up the service type for a UPnP service ID such as
+    /// `urn:upnp-org:serviceId:AVTransport`
+    ///
+    /// Bare names like `AVTransport` are accepted too, since some devices and
+    /// event headers leave off the prefix.
+    pub fn from_service_id(service_id: &str) -> Option<Self> {
+        let service_id = service_id.trim();
+        Self::ALL.into_iter().find(|service_type| {
+            let full_id = service_type.service_id();
+            full_id == service_id || full_id.rsplit(':').next() == Some(service_id)
+        })
+    }
+
+    /// Look up the service type whose event subscription path is `path`
+    pub fn from_event_sub_url(path: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|service_type| service_type.event_sub_url() == path)
+    }
+
+    /// Get the UPnP service ID, e.g. `urn:upnp-org:serviceId:AVTransport`
+    pub fn service_id(&self) -> &'static str {
+        self.endpoints().service_id
+    }
+
     /// Get the control URL path for this service
     pub fn control_url(&self) -> &'static str {
-        match self {
-            ServiceType::AVTransport => "/MediaRenderer/AVTransport/Control",
-            ServiceType::RenderingControl => "/MediaRenderer/RenderingControl/Control",
-            ServiceType::ContentDirectory => "/MediaServer/ContentDirectory/Control",
-            ServiceType::ZoneGroupTopology => "/ZoneGroupTopology/Control",
-        }
+        self.endpoints().control_url
     }
 
     /// Get the event subscription URL path for this service
     pub fn event_sub_url(&self) -> &'static str {
-        match self {
-            ServiceType::AVTransport => "/MediaRenderer/AVTransport/Event",
-            ServiceType::RenderingControl => "/MediaRenderer/RenderingControl/Event",
-            ServiceType::ContentDirectory => "/MediaServer/ContentDirectory/Event",
-            ServiceType::ZoneGroupTopology => "/ZoneGroupTopology/Event",
-        }
+        self.endpoints().event_sub_url
+    }
+
+    /// Get the path of the service description (SCPD) document
+    pub fn scpd_url(&self) -> &'static str {
+        self.endpoints().scpd_url
     }
 
     /// Get the subscription scope for this service type
@@ -99,6 +181,48 @@ impl std::fmt::Display for SubscriptionId {
     }
 }
 
+/// What a bounded event channel does when a new event arrives while it is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Discard the oldest queued event and count it as dropped
+    #[default]
+    DropOldest,
+    /// Hold the producer until the consumer makes room
+    Block,
+}
+
+/// Randomness added to retry backoff, so speakers that failed together
+/// (after a router reboot, say) don't all retry at the same moment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackoffJitter {
+    /// Wait exactly `retry_backoff * 2^attempt`
+    #[default]
+    None,
+    /// Wait a random time between zero and `retry_backoff * 2^attempt`
+    Full,
+    /// Wait a random time between `retry_backoff` and three times the previous wait
+    Decorrelated,
+}
+
+impl BackoffJitter {
+    /// Wait before retry `attempt`; `previous` is the last wait, or `base` before the first retry
+    ///
+    /// Every strategy stays within the 30 second cap.
+    pub(crate) fn backoff(self, attempt: u32, base: Duration, previous: Duration) -> Duration {
+        match self {
+            BackoffJitter::None => capped_backoff(attempt, base),
+            BackoffJitter::Full => {
+                rand::thread_rng().gen_range(Duration::ZERO..=capped_backoff(attempt, base))
+            }
+            BackoffJitter::Decorrelated => {
+                let base = base.min(MAX_BACKOFF);
+                let upper = previous.saturating_mul(3).clamp(base, MAX_BACKOFF);
+                rand::thread_rng().gen_range(base..=upper)
+            }
+        }
+    }
+}
+
 /// Configuration for the overall streaming system
 #[derive(Debug, Clone)]
 pub struct StreamConfig {
@@ -110,10 +234,29 @@ pub struct StreamConfig {
     pub retry_attempts: u32,
     /// Base duration for exponential backoff retry strategy
     pub retry_backoff: Duration,
+    /// Randomness added to each retry backoff
+    pub retry_jitter: BackoffJitter,
     /// List of service types to enable for streaming
     pub enabled_services: Vec<ServiceType>,
     /// Port range for the HTTP callback server (start, end)
     pub callback_port_range: (u16, u16),
+    /// Local address the callback server listens on and advertises to speakers;
+    /// `None` listens on all IPv4 interfaces and advertises a detected address
+    pub callback_bind_address: Option<IpAddr>,
+    /// File that receives every raw event as a JSON line before parsing; off when `None`
+    pub raw_event_log: Option<PathBuf>,
+    /// Number of worker threads parsing raw events; events queue while all are busy
+    pub parse_concurrency: usize,
+    /// Upper bound on each TCP reachability check against a speaker
+    pub connect_timeout: Duration,
+    /// Most state changes queued for the consumer; unbounded when `None`
+    pub event_channel_capacity: Option<usize>,
+    /// Behaviour of a bounded event channel once it is full
+    pub overflow_policy: OverflowPolicy,
+    /// `User-Agent` sent on SUBSCRIBE, renewal and SOAP requests to speakers
+    pub user_agent: String,
+    /// Additional headers sent on every request to speakers
+    pub extra_headers: Vec<(String, String)>,
 }
 
 impl Default for StreamConfig {
@@ -123,12 +266,21 @@ impl Default for StreamConfig {
             subscription_timeout: Duration::from_secs(1800), // 30 minutes
             retry_attempts: 3,
             retry_backoff: Duration::from_secs(1),
+            retry_jitter: BackoffJitter::None,
             enabled_services: vec![
                 ServiceType::AVTransport, 
                 ServiceType::RenderingControl, 
                 ServiceType::ZoneGroupTopology
             ],
             callback_port_range: (8080, 8090),
+            callback_bind_address: None,
+            raw_event_log: None,
+            parse_concurrency: 4,
+            connect_timeout: Duration::from_secs(2),
+            event_channel_capacity: None,
+            overflow_policy: OverflowPolicy::DropOldest,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            extra_headers: Vec::new(),
         }
     }
 }
@@ -146,8 +298,17 @@ impl StreamConfig {
             subscription_timeout: Duration::from_secs(300), // 5 minutes
             retry_attempts: 1,
             retry_backoff: Duration::from_millis(500),
+            retry_jitter: BackoffJitter::None,
             enabled_services: vec![ServiceType::AVTransport],
             callback_port_range: (8080, 8085),
+            callback_bind_address: None,
+            raw_event_log: None,
+            parse_concurrency: 1,
+            connect_timeout: Duration::from_secs(1),
+            event_channel_capacity: None,
+            overflow_policy: OverflowPolicy::DropOldest,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            extra_headers: Vec::new(),
         }
     }
 
@@ -158,6 +319,7 @@ impl StreamConfig {
             subscription_timeout: Duration::from_secs(3600), // 1 hour
             retry_attempts: 5,
             retry_backoff: Duration::from_secs(2),
+            retry_jitter: BackoffJitter::None,
             enabled_services: vec![
                 ServiceType::AVTransport,
                 ServiceType::RenderingControl,
@@ -165,6 +327,14 @@ impl StreamConfig {
                 ServiceType::ContentDirectory,
             ],
             callback_port_range: (8080, 8100),
+            callback_bind_address: None,
+            raw_event_log: None,
+            parse_concurrency: 8,
+            connect_timeout: Duration::from_secs(3),
+            event_channel_capacity: None,
+            overflow_policy: OverflowPolicy::DropOldest,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            extra_headers: Vec::new(),
         }
     }
 
@@ -175,8 +345,17 @@ impl StreamConfig {
             subscription_timeout: Duration::from_secs(1800), // 30 minutes
             retry_attempts: 3,
             retry_backoff: Duration::from_secs(1),
+            retry_jitter: BackoffJitter::None,
             enabled_services: vec![ServiceType::AVTransport, ServiceType::RenderingControl],
             callback_port_range: (8080, 8090),
+            callback_bind_address: None,
+            raw_event_log: None,
+            parse_concurrency: 4,
+            connect_timeout: Duration::from_secs(2),
+            event_channel_capacity: None,
+            overflow_policy: OverflowPolicy::DropOldest,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            extra_headers: Vec::new(),
         }
     }
 
@@ -219,6 +398,12 @@ impl StreamConfig {
         self
     }
 
+    /// Set the randomness added to retry backoff
+    pub fn with_retry_jitter(mut self, jitter: BackoffJitter) -> Self {
+        self.retry_jitter = jitter;
+        self
+    }
+
     /// Set the enabled services
     pub fn with_enabled_services(mut self, services: Vec<ServiceType>) -> Self {
         self.enabled_services = services;
@@ -226,7 +411,14 @@ impl StreamConfig {
     }
 
     /// Set the callback port range with validation
+    ///
+    /// `(0, 0)` asks the OS for an ephemeral port, for hosts where no fixed
+    /// range is reliably free.
     pub fn with_callback_port_range(mut self, start: u16, end: u16) -> Result<Self, String> {
+        if (start, end) == (0, 0) {
+            self.callback_port_range = (0, 0);
+            return Ok(self);
+        }
         if start >= end {
             return Err("Port range start must be less than end".to_string());
         }
@@ -242,6 +434,71 @@ impl StreamConfig {
         Ok(self)
     }
 
+    /// Listen for events on a specific local address, IPv4 or IPv6.
+    ///
+    /// The address is also used in the `CALLBACK` URL sent to speakers, so it must
+    /// be one they can route to.
+    pub fn with_callback_bind_address(mut self, address: IpAddr) -> Self {
+        self.callback_bind_address = Some(address);
+        self
+    }
+
+    /// Append each raw event to `path` as a JSON line before it is parsed
+    pub fn with_raw_event_log(mut self, path: PathBuf) -> Self {
+        self.raw_event_log = Some(path);
+        self
+    }
+
+    /// Replace the default `sonos-rs/<version>` User-Agent
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Send an additional header on every request to speakers
+    pub fn with_extra_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extra_headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Set the number of raw event parsing workers with validation
+    pub fn with_parse_concurrency(mut self, workers: usize) -> Result<Self, String> {
+        if workers == 0 {
+            return Err("Parse concurrency must be greater than 0".to_string());
+        }
+        if workers > 64 {
+            return Err("Parse concurrency too large (max 64)".to_string());
+        }
+        self.parse_concurrency = workers;
+        Ok(self)
+    }
+
+    /// Set the speaker reachability timeout with validation
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Result<Self, String> {
+        if timeout.is_zero() {
+            return Err("Connect timeout must be greater than 0".to_string());
+        }
+        if timeout > Duration::from_secs(30) {
+            return Err("Connect timeout too long (max 30 seconds)".to_string());
+        }
+        self.connect_timeout = timeout;
+        Ok(self)
+    }
+
+    /// Bound the event channel to `capacity` events, or leave it unbounded with `None`
+    pub fn with_event_channel_capacity(
+        mut self,
+        capacity: Option<usize>,
+        policy: OverflowPolicy,
+    ) -> Result<Self, String> {
+        if capacity == Some(0) {
+            return Err("Event channel capacity must be greater than 0".to_string());
+        }
+        self.event_channel_capacity = capacity;
+        self.overflow_policy = policy;
+        Ok(self)
+    }
+
     /// Validate the entire configuration
     pub fn validate(&self) -> Result<(), String> {
         if self.buffer_size == 0 {
@@ -253,16 +510,34 @@ impl StreamConfig {
         if self.retry_attempts > 10 {
             return Err("Too many retry attempts (max 10)".to_string());
         }
-        if self.callback_port_range.0 >= self.callback_port_range.1 {
+        if self.callback_port_range != (0, 0)
+            && self.callback_port_range.0 >= self.callback_port_range.1
+        {
             return Err("Invalid port range".to_string());
         }
         if self.enabled_services.is_empty() {
             return Err("At least one service must be enabled".to_string());
         }
+        if self.parse_concurrency == 0 || self.parse_concurrency > 64 {
+            return Err("Parse concurrency must be between 1 and 64".to_string());
+        }
+        if self.connect_timeout.is_zero() || self.connect_timeout > Duration::from_secs(30) {
+            return Err("Connect timeout must be between 0 and 30 seconds".to_string());
+        }
+        if self.event_channel_capacity == Some(0) {
+            return Err("Event channel capacity must be greater than 0".to_string());
+        }
+        request_headers(&self.user_agent, &self.extra_headers)?;
         Ok(())
     }
 }
 
+/// Shortest subscription timeout Sonos speakers accept reliably
+pub const MIN_SUBSCRIPTION_TIMEOUT_SECS: u32 = 300;
+
+/// Longest subscription timeout we ask for
+pub const MAX_SUBSCRIPTION_TIMEOUT_SECS: u32 = 86400;
+
 /// Configuration for individual service subscriptions
 #[derive(Debug, Clone)]
 pub struct SubscriptionConfig {
@@ -274,6 +549,10 @@ pub struct SubscriptionConfig {
     pub max_retry_attempts: u32,
     /// Base duration for exponential backoff
     pub retry_backoff_base: Duration,
+    /// `User-Agent` sent on SUBSCRIBE, renewal and UNSUBSCRIBE requests
+    pub user_agent: String,
+    /// Additional headers sent on every subscription request
+    pub extra_headers: Vec<(String, String)>,
 }
 
 impl Default for SubscriptionConfig {
@@ -283,20 +562,100 @@ impl Default for SubscriptionConfig {
             renewal_threshold: Duration::from_secs(300), // 5 minutes before expiry
             max_retry_attempts: 3,
             retry_backoff_base: Duration::from_secs(1),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            extra_headers: Vec::new(),
         }
     }
 }
 
 impl SubscriptionConfig {
     /// Create a new SubscriptionConfig from StreamConfig
+    ///
+    /// The stream timeout is clamped to the range Sonos accepts, and the renewal
+    /// threshold is capped at half the timeout so short subscriptions are not
+    /// renewed continuously.
     pub fn from_stream_config(stream_config: &StreamConfig) -> Self {
+        let requested = stream_config.subscription_timeout.as_secs();
+        let timeout_seconds = requested.clamp(
+            MIN_SUBSCRIPTION_TIMEOUT_SECS as u64,
+            MAX_SUBSCRIPTION_TIMEOUT_SECS as u64,
+        ) as u32;
+        if timeout_seconds as u64 != requested {
+            log::warn!(
+                "Subscription timeout of {}s is outside {}-{}s, using {}s",
+                requested,
+                MIN_SUBSCRIPTION_TIMEOUT_SECS,
+                MAX_SUBSCRIPTION_TIMEOUT_SECS,
+                timeout_seconds
+            );
+        }
+
         Self {
-            timeout_seconds: stream_config.subscription_timeout.as_secs() as u32,
-            renewal_threshold: Duration::from_secs(300),
+            timeout_seconds,
+            renewal_threshold: Duration::from_secs(300.min(timeout_seconds as u64 / 2)),
             max_retry_attempts: stream_config.retry_attempts,
             retry_backoff_base: stream_config.retry_backoff,
+            user_agent: stream_config.user_agent.clone(),
+            extra_headers: stream_config.extra_headers.clone(),
         }
     }
+
+    /// HTTP client for subscription requests, carrying the configured headers
+    pub(crate) fn http_client(&self) -> SubscriptionResult<reqwest::blocking::Client> {
+        let headers = request_headers(&self.user_agent, &self.extra_headers)
+            .map_err(SubscriptionError::InvalidConfiguration)?;
+
+        reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .default_headers(headers)
+            .build()
+            .map_err(|e| SubscriptionError::NetworkError(e.to_string()))
+    }
+
+    /// Validate the timeout sent in the `TIMEOUT: Second-N` header
+    pub fn validate(&self) -> Result<(), String> {
+        if self.timeout_seconds < MIN_SUBSCRIPTION_TIMEOUT_SECS {
+            return Err(format!(
+                "Subscription timeout {}s is below the Sonos minimum of {}s",
+                self.timeout_seconds, MIN_SUBSCRIPTION_TIMEOUT_SECS
+            ));
+        }
+        if self.timeout_seconds > MAX_SUBSCRIPTION_TIMEOUT_SECS {
+            return Err(format!(
+                "Subscription timeout {}s is above the maximum of {}s",
+                self.timeout_seconds, MAX_SUBSCRIPTION_TIMEOUT_SECS
+            ));
+        }
+        if self.renewal_threshold.as_secs() >= self.timeout_seconds as u64 {
+            return Err(format!(
+                "Renewal threshold {}s must be shorter than the {}s subscription timeout",
+                self.renewal_threshold.as_secs(),
+                self.timeout_seconds
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Running totals shared between the subscription manager and the event processing loop
+#[derive(Debug, Default)]
+pub(crate) struct StreamCounters {
+    /// Events delivered to the event processing loop
+    pub events_received: AtomicU64,
+    /// Subscription error events and failed renewals
+    pub subscription_errors: AtomicU64,
+    /// Renewals that succeeded (possibly after retries)
+    pub successful_renewals: AtomicU64,
+}
+
+impl StreamCounters {
+    pub fn increment(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn get(counter: &AtomicU64) -> u64 {
+        counter.load(Ordering::Relaxed)
+    }
 }
 
 /// Raw event data received from UPnP notifications
@@ -306,6 +665,8 @@ pub struct RawEvent {
     pub subscription_id: SubscriptionId,
     /// The raw XML content of the event
     pub event_xml: String,
+    /// The `SEQ` header, counting events sent on this subscription from 0
+    pub seq: Option<u32>,
     /// Timestamp when the event was received
     pub timestamp: SystemTime,
 }
@@ -316,15 +677,38 @@ impl RawEvent {
         Self {
             subscription_id,
             event_xml,
+            seq: None,
             timestamp: SystemTime::now(),
         }
     }
+
+    /// Attach the event's `SEQ` header value
+    pub fn with_seq(mut self, seq: u32) -> Self {
+        self.seq = Some(seq);
+        self
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_service_type_from_urn() {
+        assert_eq!(
+            ServiceType::from_urn("urn:schemas-upnp-org:service:AVTransport:1"),
+            Some(ServiceType::AVTransport)
+        );
+        assert_eq!(
+            ServiceType::from_urn("urn:schemas-upnp-org:service:ZoneGroupTopology:1"),
+            Some(ServiceType::ZoneGroupTopology)
+        );
+        assert_eq!(
+            ServiceType::from_urn("urn:schemas-upnp-org:service:AlarmClock:1"),
+            None
+        );
+    }
+
     #[test]
     fn test_service_type_urns() {
         assert_eq!(
@@ -361,6 +745,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_service_type_url_table() {
+        let table = [
+            (
+                ServiceType::AVTransport,
+                "/MediaRenderer/AVTransport/Control",
+                "/MediaRenderer/AVTransport/Event",
+                "/xml/AVTransport1.xml",
+            ),
+            (
+                ServiceType::RenderingControl,
+                "/MediaRenderer/RenderingControl/Control",
+                "/MediaRenderer/RenderingControl/Event",
+                "/xml/RenderingControl1.xml",
+            ),
+            (
+                ServiceType::ContentDirectory,
+                "/MediaServer/ContentDirectory/Control",
+                "/MediaServer/ContentDirectory/Event",
+                "/xml/ContentDirectory1.xml",
+            ),
+            (
+                ServiceType::ZoneGroupTopology,
+                "/ZoneGroupTopology/Control",
+                "/ZoneGroupTopology/Event",
+                "/xml/ZoneGroupTopology1.xml",
+            ),
+        ];
+
+        assert_eq!(table.len(), ServiceType::ALL.len());
+        for (service_type, control_url, event_sub_url, scpd_url) in table {
+            assert_eq!(service_type.control_url(), control_url);
+            assert_eq!(service_type.event_sub_url(), event_sub_url);
+            assert_eq!(service_type.scpd_url(), scpd_url);
+            assert_eq!(ServiceType::from_event_sub_url(event_sub_url), Some(service_type));
+        }
+    }
+
+    #[test]
+    fn test_service_type_from_service_id() {
+        for service_type in ServiceType::ALL {
+            assert_eq!(
+                ServiceType::from_service_id(service_type.service_id()),
+                Some(service_type)
+            );
+        }
+        assert_eq!(
+            ServiceType::from_service_id("urn:upnp-org:serviceId:RenderingControl"),
+            Some(ServiceType::RenderingControl)
+        );
+        assert_eq!(
+            ServiceType::from_service_id("ZoneGroupTopology"),
+            Some(ServiceType::ZoneGroupTopology)
+        );
+        assert_eq!(ServiceType::from_service_id("urn:upnp-org:serviceId:AlarmClock"), None);
+        assert_eq!(ServiceType::from_service_id(""), None);
+        assert_eq!(ServiceType::from_event_sub_url("/AlarmClock/Event"), None);
+    }
+
     #[test]
     fn test_service_type_subscription_scope() {
         assert_eq!(
@@ -422,6 +865,27 @@ mod tests {
         // Test invalid port range
         let invalid_config = StreamConfig::default().with_callback_port_range(8080, 8080);
         assert!(invalid_config.is_err());
+        assert!(StreamConfig::default().with_callback_port_range(0, 0).unwrap().validate().is_ok());
+
+        // Test invalid parse concurrency
+        assert!(StreamConfig::default().with_parse_concurrency(0).is_err());
+        assert!(StreamConfig::default().with_parse_concurrency(65).is_err());
+
+        // Test invalid connect timeout
+        assert!(StreamConfig::default().with_connect_timeout(Duration::ZERO).is_err());
+        assert!(StreamConfig::default()
+            .with_connect_timeout(Duration::from_secs(31))
+            .is_err());
+
+        // Test invalid event channel capacity
+        assert!(StreamConfig::default()
+            .with_event_channel_capacity(Some(0), OverflowPolicy::Block)
+            .is_err());
+        let bounded = StreamConfig::default()
+            .with_event_channel_capacity(Some(16), OverflowPolicy::Block)
+            .unwrap();
+        assert_eq!(bounded.event_channel_capacity, Some(16));
+        assert_eq!(bounded.overflow_policy, OverflowPolicy::Block);
     }
 
     #[test]
@@ -433,6 +897,73 @@ mod tests {
         assert_eq!(sub_config.max_retry_attempts, 3);
     }
 
+    #[test]
+    fn test_subscription_config_validate_timeout_bounds() {
+        let config = |timeout_seconds| SubscriptionConfig {
+            timeout_seconds,
+            renewal_threshold: Duration::from_secs(60),
+            ..SubscriptionConfig::default()
+        };
+
+        let below_min = config(MIN_SUBSCRIPTION_TIMEOUT_SECS - 1).validate().unwrap_err();
+        assert!(below_min.contains("below the Sonos minimum"));
+
+        assert!(config(MIN_SUBSCRIPTION_TIMEOUT_SECS).validate().is_ok());
+        assert!(config(MAX_SUBSCRIPTION_TIMEOUT_SECS).validate().is_ok());
+
+        let above_max = config(MAX_SUBSCRIPTION_TIMEOUT_SECS + 1).validate().unwrap_err();
+        assert!(above_max.contains("above the maximum"));
+    }
+
+    #[test]
+    fn test_subscription_config_rejects_threshold_past_timeout() {
+        let config = SubscriptionConfig {
+            timeout_seconds: MIN_SUBSCRIPTION_TIMEOUT_SECS,
+            renewal_threshold: Duration::from_secs(MIN_SUBSCRIPTION_TIMEOUT_SECS as u64),
+            ..SubscriptionConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_stream_config_headers_flow_into_subscription_config() {
+        let stream_config = StreamConfig::default();
+        assert!(stream_config.user_agent.starts_with("sonos-rs/"));
+
+        let stream_config = stream_config
+            .with_user_agent("home-dashboard/2.1")
+            .with_extra_header("X-Request-Source", "kitchen-panel");
+        assert!(stream_config.validate().is_ok());
+
+        let config = SubscriptionConfig::from_stream_config(&stream_config);
+        assert_eq!(config.user_agent, "home-dashboard/2.1");
+        assert_eq!(
+            config.extra_headers,
+            vec![("X-Request-Source".to_string(), "kitchen-panel".to_string())]
+        );
+
+        let invalid = StreamConfig::default().with_extra_header("Bad Header", "value");
+        assert!(invalid.validate().unwrap_err().contains("Invalid header name"));
+    }
+
+    #[test]
+    fn test_subscription_config_from_stream_config_clamps_timeout() {
+        let short = StreamConfig::default()
+            .with_subscription_timeout(Duration::from_secs(60))
+            .unwrap();
+        let sub_config = SubscriptionConfig::from_stream_config(&short);
+        assert_eq!(sub_config.timeout_seconds, MIN_SUBSCRIPTION_TIMEOUT_SECS);
+        assert!(sub_config.validate().is_ok());
+
+        let at_min = StreamConfig::default()
+            .with_subscription_timeout(Duration::from_secs(MIN_SUBSCRIPTION_TIMEOUT_SECS as u64))
+            .unwrap();
+        let sub_config = SubscriptionConfig::from_stream_config(&at_min);
+        assert_eq!(sub_config.timeout_seconds, MIN_SUBSCRIPTION_TIMEOUT_SECS);
+        assert_eq!(sub_config.renewal_threshold, Duration::from_secs(150));
+        assert!(sub_config.validate().is_ok());
+    }
+
     #[test]
     fn test_raw_event() {
         let sub_id = SubscriptionId::new();
@@ -4,6 +4,7 @@ use crate::group::Group;
 use crate::model::{Speaker, SpeakerId, StateChange};
 use crate::service::zone_group_topology::parser::ZoneGroupTopologyParser;
 
+use std::sync::Mutex;
 use std::time::SystemTime;
 
 /// ZoneGroupTopology service subscription implementation
@@ -27,6 +28,8 @@ pub struct ZoneGroupTopologySubscription {
     active: bool,
     /// Timestamp of the last successful renewal
     last_renewal: Option<SystemTime>,
+    /// Groups from the last event, used to drop repeats of an unchanged topology
+    last_zone_groups: Mutex<Option<Vec<Group>>>,
 }
 
 impl ZoneGroupTopologySubscription {
@@ -41,6 +44,7 @@ impl ZoneGroupTopologySubscription {
         callback_url: String,
         config: SubscriptionConfig,
     ) -> SubscriptionResult<Self> {
+        config.validate().map_err(SubscriptionError::InvalidConfiguration)?;
         Ok(Self {
             representative_speaker,
             subscription_id: None,
@@ -49,9 +53,43 @@ impl ZoneGroupTopologySubscription {
             config,
             active: false,
             last_renewal: None,
+            last_zone_groups: Mutex::new(None),
         })
     }
 
+    /// Record `groups` as the latest topology and diff it against the previous one
+    ///
+    /// Returns `None` when nothing changed: Sonos sends the full ZoneGroupState on
+    /// almost every change, so the same topology often arrives several times in a
+    /// row. Otherwise returns a `CoordinatorChanged` for every group that kept its
+    /// members but moved its coordinator, which Sonos reports as a new group ID.
+    fn detect_topology_changes(&self, groups: &[Group]) -> Option<Vec<StateChange>> {
+        let Ok(mut last_zone_groups) = self.last_zone_groups.lock() else {
+            log::error!("Failed to acquire lock on last zone groups");
+            return Some(Vec::new());
+        };
+
+        let mut changes = Vec::new();
+        if let Some(last) = last_zone_groups.as_ref() {
+            if Group::same_topologies(last, groups) {
+                return None;
+            }
+
+            for (previous, group) in Group::match_groups(last, groups) {
+                if previous.get_coordinator_id() != group.get_coordinator_id() {
+                    changes.push(StateChange::CoordinatorChanged {
+                        group_id: group.stable_id(),
+                        old_coordinator: previous.get_coordinator_id().clone(),
+                        new_coordinator: group.get_coordinator_id().clone(),
+                    });
+                }
+            }
+        }
+
+        *last_zone_groups = Some(groups.to_vec());
+        Some(changes)
+    }
+
     /// Get the device URL for the representative speaker
     fn device_url(&self) -> String {
         format!(
@@ -66,19 +104,15 @@ impl ZoneGroupTopologySubscription {
         let event_sub_url = ServiceType::ZoneGroupTopology.event_sub_url();
         let full_url = format!("{}{}", device_url, event_sub_url);
 
-        println!(
-            "📡 Sending ZoneGroupTopology SUBSCRIBE request to: {}",
-            full_url
+        log::debug!(
+            "Sending ZoneGroupTopology SUBSCRIBE request to {} (callback {})",
+            full_url,
+            self.callback_url
         );
-        println!("   Callback URL: {}", self.callback_url);
 
         // Create HTTP client for subscription requests with timeout
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .build()
-            .map_err(|e| SubscriptionError::NetworkError(e.to_string()))?;
+        let client = self.config.http_client()?;
 
-        println!("🔄 Making HTTP SUBSCRIBE request...");
         let response = client
             .request(
                 reqwest::Method::from_bytes(b"SUBSCRIBE").unwrap(),
@@ -96,7 +130,7 @@ impl ZoneGroupTopologySubscription {
             .header("TIMEOUT", format!("Second-{}", self.config.timeout_seconds))
             .send()
             .map_err(|e| {
-                println!("❌ HTTP request failed: {}", e);
+                log::debug!("ZoneGroupTopology SUBSCRIBE request failed: {}", e);
                 SubscriptionError::NetworkError(e.to_string())
             })?;
 
@@ -135,10 +169,7 @@ impl ZoneGroupTopologySubscription {
         let event_sub_url = ServiceType::ZoneGroupTopology.event_sub_url();
         let full_url = format!("{}{}", device_url, event_sub_url);
 
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .build()
-            .map_err(|e| SubscriptionError::NetworkError(e.to_string()))?;
+        let client = self.config.http_client()?;
 
         let response = client
             .request(
@@ -172,10 +203,7 @@ impl ZoneGroupTopologySubscription {
         let event_sub_url = ServiceType::ZoneGroupTopology.event_sub_url();
         let full_url = format!("{}{}", device_url, event_sub_url);
 
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .build()
-            .map_err(|e| SubscriptionError::NetworkError(e.to_string()))?;
+        let client = self.config.http_client()?;
 
         let response = client
             .request(
@@ -230,8 +258,8 @@ impl ServiceSubscription for ZoneGroupTopologySubscription {
         self.active = true;
         self.last_renewal = Some(SystemTime::now());
 
-        println!(
-            "✅ ZoneGroupTopology subscription established with ID: {}",
+        log::debug!(
+            "ZoneGroupTopology subscription established with ID: {}",
             subscription_id
         );
         Ok(subscription_id)
@@ -247,7 +275,7 @@ impl ServiceSubscription for ZoneGroupTopologySubscription {
         self.active = false;
         self.last_renewal = None;
 
-        println!("✅ ZoneGroupTopology subscription terminated");
+        log::debug!("ZoneGroupTopology subscription terminated");
         Ok(())
     }
 
@@ -259,7 +287,7 @@ impl ServiceSubscription for ZoneGroupTopologySubscription {
         if let Some(upnp_sid) = &self.upnp_sid {
             self.send_renewal_request(upnp_sid)?;
             self.last_renewal = Some(SystemTime::now());
-            println!("✅ ZoneGroupTopology subscription renewed");
+            log::debug!("ZoneGroupTopology subscription renewed");
             Ok(())
         } else {
             Err(SubscriptionError::SubscriptionExpired)
@@ -271,29 +299,41 @@ impl ServiceSubscription for ZoneGroupTopologySubscription {
 
         match ZoneGroupTopologyParser::from_xml(event_xml) {
             Ok(parser) => {
-                println!("🔍 Parsing ZoneGroupTopology event...");
+                log::debug!("Parsing ZoneGroupTopology event...");
 
                 // Detect changes and generate appropriate StateChange events directly from parser
                 if let Some(zone_group_property) = parser.zone_group_state() {
                     if let Some(zone_group_state) = &zone_group_property.zone_group_state {
-                        changes.push(StateChange::GroupChange {
-                            groups: zone_group_state
-                                .zone_groups
-                                .zone_groups
-                                .iter()
-                                .filter_map(|zone_group| Group::from_zone_group(zone_group).ok())
-                                .collect(),
-                        });
+                        let groups: Vec<Group> = zone_group_state
+                            .zone_groups
+                            .zone_groups
+                            .iter()
+                            .filter_map(|zone_group| Group::from_zone_group(zone_group).ok())
+                            .collect();
+
+                        let Some(coordinator_changes) = self.detect_topology_changes(&groups) else {
+                            log::debug!("ZoneGroupTopology unchanged, skipping duplicate event");
+                            return Ok(changes);
+                        };
+
+                        changes.push(StateChange::GroupChange { groups });
+                        changes.extend(coordinator_changes);
+                        changes.extend(zone_group_state.vanished_devices.devices.iter().map(
+                            |device| StateChange::SpeakerVanished {
+                                speaker_id: device.uuid.clone(),
+                                reason: device.reason.clone(),
+                            },
+                        ));
                     }
                 }
 
-                println!(
-                    "✅ Generated {} state changes from ZoneGroupTopology event",
+                log::debug!(
+                    "Generated {} state changes from ZoneGroupTopology event",
                     changes.len()
                 );
             }
             Err(e) => {
-                println!("❌ Failed to parse ZoneGroupTopology XML: {}", e);
+                log::warn!("Failed to parse ZoneGroupTopology XML: {}", e);
                 // Log the error but don't fail completely - return subscription error
                 changes.push(StateChange::SubscriptionError {
                     speaker_id: self.speaker_id().clone(),
@@ -332,6 +372,10 @@ impl ServiceSubscription for ZoneGroupTopologySubscription {
             self.subscription_id = None;
             self.upnp_sid = None;
             self.last_renewal = None;
+            // A new subscription starts with a full topology event that must get through
+            if let Ok(mut last_zone_groups) = self.last_zone_groups.lock() {
+                *last_zone_groups = None;
+            }
         }
         Ok(())
     }
@@ -351,6 +395,9 @@ mod tests {
             port: 1400,
             model_name: "Test Model".to_string(),
             satellites: vec![],
+            supported_services: vec![],
+            software_version: None,
+            hardware_version: None,
         }
     }
 
@@ -376,4 +423,86 @@ mod tests {
         assert!(!sub.is_active());
         assert!(sub.subscription_id().is_none());
     }
+
+    #[test]
+    fn test_parse_event_reports_vanished_speaker() {
+        let subscription = ZoneGroupTopologySubscription::new(
+            create_test_speaker("123456789", "192.168.1.100"),
+            "http://localhost:8080/callback/test".to_string(),
+            SubscriptionConfig::default(),
+        )
+        .unwrap();
+
+        let xml = include_str!("../../tests/fixtures/zone_group_topology_vanished_event.xml");
+        let changes = subscription.parse_event(xml).unwrap();
+
+        assert_eq!(changes.len(), 2);
+        assert!(matches!(changes[0], StateChange::GroupChange { .. }));
+        match &changes[1] {
+            StateChange::SpeakerVanished { speaker_id, reason } => {
+                assert_eq!(speaker_id, &SpeakerId::new("RINCON_000E583FEE3401400"));
+                assert_eq!(reason, "powered off");
+            }
+            other => panic!("Expected SpeakerVanished, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_event_skips_unchanged_topology() {
+        let mut subscription = ZoneGroupTopologySubscription::new(
+            create_test_speaker("123456789", "192.168.1.100"),
+            "http://localhost:8080/callback/test".to_string(),
+            SubscriptionConfig::default(),
+        )
+        .unwrap();
+
+        let xml = include_str!("../../tests/fixtures/zone_group_topology_vanished_event.xml");
+        assert!(!subscription.parse_event(xml).unwrap().is_empty());
+        assert!(subscription.parse_event(xml).unwrap().is_empty());
+
+        // Resubscribing delivers the topology again
+        subscription.on_subscription_state_changed(false).unwrap();
+        assert!(!subscription.parse_event(xml).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_coordinator_swap_is_reported_as_coordinator_change() {
+        use crate::group::SpeakerRef;
+        use crate::model::GroupId;
+
+        let subscription = ZoneGroupTopologySubscription::new(
+            create_test_speaker("123456789", "192.168.1.100"),
+            "http://localhost:8080/callback/test".to_string(),
+            SubscriptionConfig::default(),
+        )
+        .unwrap();
+        let living_room = SpeakerId::new("RINCON_LIVING");
+        let kitchen = SpeakerId::new("RINCON_KITCHEN");
+        let members = vec![
+            SpeakerRef::new(living_room.clone(), vec![]),
+            SpeakerRef::new(kitchen.clone(), vec![]),
+        ];
+
+        let before = vec![Group::new(GroupId::new("RINCON_LIVING:1"), living_room.clone(), members.clone())];
+        assert!(subscription.detect_topology_changes(&before).unwrap().is_empty());
+
+        // Sonos gives the group a new ID named after the new coordinator
+        let after = vec![Group::new(GroupId::new("RINCON_KITCHEN:2"), kitchen.clone(), members)];
+        let changes = subscription.detect_topology_changes(&after).unwrap();
+
+        assert_eq!(changes.len(), 1);
+        match &changes[0] {
+            StateChange::CoordinatorChanged {
+                group_id,
+                old_coordinator,
+                new_coordinator,
+            } => {
+                assert_eq!(group_id, &GroupId::from_members([&living_room, &kitchen]));
+                assert_eq!(old_coordinator, &living_room);
+                assert_eq!(new_coordinator, &kitchen);
+            }
+            other => panic!("Expected CoordinatorChanged, got {:?}", other),
+        }
+    }
+
 }
@@ -0,0 +1,380 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{GroupId, SonosError, Speaker, SpeakerController, SpeakerId, group::{Group, SpeakerRef}, service::zone_group_topology::parser::ZoneGroupState};
+
+/// Authoritative view of how speakers are grouped on the network
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Topology {
+  groups: Vec<Group>,
+}
+
+impl Topology {
+  pub fn new(groups: Vec<Group>) -> Self {
+    Self { groups }
+  }
+
+  pub fn from_zone_group_state(state: &ZoneGroupState) -> Result<Self, SonosError> {
+    let groups = state.zone_groups.zone_groups
+      .iter()
+      .map(Group::from_zone_group)
+      .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Self { groups })
+  }
+
+  /// Fetch the topology from the first of `speakers` that reports one
+  ///
+  /// Speakers are tried in order, so one offline speaker or a bonded satellite
+  /// answering 503 does not fail the lookup. Returns the last error when no
+  /// speaker reports a topology with at least one group.
+  pub fn from_speakers(speakers: &[Speaker]) -> Result<Self, SonosError> {
+    let mut last_error = SonosError::DeviceNotFound("No speakers to query for topology".to_string());
+
+    for speaker in speakers {
+      match SpeakerController::new(speaker).and_then(|controller| controller.get_topology()) {
+        Ok(topology) if !topology.groups.is_empty() => return Ok(topology),
+        Ok(_) => {
+          log::debug!("{} reported an empty topology, trying the next speaker", speaker.name);
+          last_error = SonosError::InvalidState(format!("{} reported no groups", speaker.name));
+        }
+        Err(e) => {
+          log::debug!("Topology from {} failed ({}), trying the next speaker", speaker.name, e);
+          last_error = e;
+        }
+      }
+    }
+
+    Err(last_error)
+  }
+
+  pub fn get_groups(&self) -> &[Group] {
+    &self.groups
+  }
+
+  /// Find the group a speaker (or one of its satellites) belongs to
+  pub fn get_group_for_speaker(&self, speaker_id: &SpeakerId) -> Option<&Group> {
+    self.groups.iter().find(|group| {
+      group.get_members().iter().any(|member| {
+        member.get_id() == speaker_id || member.get_satellites().contains(speaker_id)
+      })
+    })
+  }
+
+  pub fn get_group(&self, group_id: &GroupId) -> Option<&Group> {
+    self.groups.iter().find(|group| group.get_id() == group_id)
+  }
+
+  /// Coordinator of the group `speaker_id` is in; satellites resolve through their parent
+  pub fn coordinator_for(&self, speaker_id: &SpeakerId) -> Option<&SpeakerRef> {
+    let group = self.get_group_for_speaker(speaker_id)?;
+    group.get_members().iter().find(|member| member.get_id() == group.get_coordinator_id())
+  }
+
+  /// Members of a group, with satellites nested under their parent; empty for unknown groups
+  pub fn group_members(&self, group_id: &GroupId) -> Vec<&SpeakerRef> {
+    self.get_group(group_id)
+      .map(|group| group.get_members().iter().collect())
+      .unwrap_or_default()
+  }
+
+  /// Every member and satellite ID, in group order
+  fn speaker_ids(&self) -> impl Iterator<Item = &SpeakerId> {
+    self.groups.iter().flat_map(|group| {
+      group.get_members().iter().flat_map(|member| {
+        std::iter::once(member.get_id()).chain(member.get_satellites())
+      })
+    })
+  }
+}
+
+/// A speaker whose group differs between two topologies.
+///
+/// `None` means the speaker was not part of any group on that side, i.e. it
+/// appeared or vanished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpeakerMove {
+  pub speaker_id: SpeakerId,
+  pub from: Option<GroupId>,
+  pub to: Option<GroupId>,
+}
+
+/// A group present in both topologies whose coordinator changed
+///
+/// `group_id` is the group's ID in the newer topology.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoordinatorChange {
+  pub group_id: GroupId,
+  pub from: SpeakerId,
+  pub to: SpeakerId,
+}
+
+/// Differences between two topology snapshots, as computed by [`diff`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TopologyDiff {
+  pub added_groups: Vec<GroupId>,
+  pub removed_groups: Vec<GroupId>,
+  pub moved_speakers: Vec<SpeakerMove>,
+  pub coordinator_changes: Vec<CoordinatorChange>,
+}
+
+impl TopologyDiff {
+  pub fn is_empty(&self) -> bool {
+    self.added_groups.is_empty()
+      && self.removed_groups.is_empty()
+      && self.moved_speakers.is_empty()
+      && self.coordinator_changes.is_empty()
+  }
+}
+
+/// Compare two topology snapshots, e.g. a cached one against a fresh discovery.
+///
+/// Groups are paired with [`Group::match_groups`], as on the ZoneGroupTopology
+/// event stream, so a coordinator swap within the same members is a coordinator
+/// change rather than a removed and an added group. Speaker membership follows
+/// the same rules as `StateCache::reconcile`: satellites count as members of
+/// their parent's group.
+pub fn diff(old: &Topology, new: &Topology) -> TopologyDiff {
+  let mut result = TopologyDiff::default();
+  let pairs = Group::match_groups(&old.groups, &new.groups);
+  let paired = |previous: &Group, group: &Group| {
+    pairs.iter().any(|(p, g)| std::ptr::eq(*p, previous) && std::ptr::eq(*g, group))
+  };
+
+  for &(previous, group) in &pairs {
+    if previous.get_coordinator_id() != group.get_coordinator_id() {
+      result.coordinator_changes.push(CoordinatorChange {
+        group_id: group.get_id().clone(),
+        from: previous.get_coordinator_id().clone(),
+        to: group.get_coordinator_id().clone(),
+      });
+    }
+  }
+
+  result.added_groups = new.groups
+    .iter()
+    .filter(|group| !pairs.iter().any(|(_, g)| std::ptr::eq(*g, *group)))
+    .map(|group| group.get_id().clone())
+    .collect();
+  result.removed_groups = old.groups
+    .iter()
+    .filter(|group| !pairs.iter().any(|(p, _)| std::ptr::eq(*p, *group)))
+    .map(|group| group.get_id().clone())
+    .collect();
+
+  let mut speaker_ids: Vec<&SpeakerId> = Vec::new();
+  for speaker_id in old.speaker_ids().chain(new.speaker_ids()) {
+    if !speaker_ids.contains(&speaker_id) {
+      speaker_ids.push(speaker_id);
+    }
+  }
+
+  for speaker_id in speaker_ids {
+    let from = old.get_group_for_speaker(speaker_id);
+    let to = new.get_group_for_speaker(speaker_id);
+    let stayed = match (from, to) {
+      (Some(from), Some(to)) => paired(from, to),
+      (None, None) => true,
+      _ => false,
+    };
+    if !stayed {
+      result.moved_speakers.push(SpeakerMove {
+        speaker_id: speaker_id.clone(),
+        from: from.map(|group| group.get_id().clone()),
+        to: to.map(|group| group.get_id().clone()),
+      });
+    }
+  }
+
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parsed_fixture() -> Topology {
+    let xml = include_str!("../tests/fixtures/topology.xml");
+    let state: ZoneGroupState = crate::xml_decode::xml_decode::parse(xml).unwrap();
+    Topology::from_zone_group_state(&state).unwrap()
+  }
+
+  #[test]
+  fn test_coordinator_for_member_and_satellite() {
+    let topology = parsed_fixture();
+    assert_eq!(topology.get_groups().len(), 3);
+
+    let basement = SpeakerId::new("RINCON_5CAAFDAE58BD01400");
+    let coordinator = topology.coordinator_for(&basement).unwrap();
+    assert_eq!(coordinator.get_id(), &basement);
+
+    // Surround satellites belong to the soundbar's group
+    let rear = SpeakerId::new("RINCON_7828CA128F0001400");
+    assert_eq!(topology.coordinator_for(&rear).unwrap().get_id(), &basement);
+
+    let living_room = SpeakerId::new("RINCON_804AF2AA2FA201400");
+    assert_eq!(topology.coordinator_for(&living_room).unwrap().get_id(), &living_room);
+
+    assert!(topology.coordinator_for(&SpeakerId::new("RINCON_UNKNOWN")).is_none());
+  }
+
+  #[test]
+  fn test_serde_round_trip() {
+    let topology = parsed_fixture();
+
+    let json = serde_json::to_string(&topology).unwrap();
+    let restored: Topology = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(format!("{:?}", restored), format!("{:?}", topology));
+
+    let rear = SpeakerId::new("RINCON_7828CA128F0001400");
+    let coordinator = restored.coordinator_for(&rear).unwrap();
+    assert_eq!(coordinator.get_id(), &SpeakerId::new("RINCON_5CAAFDAE58BD01400"));
+    assert!(coordinator.device_url().is_some());
+  }
+
+  #[test]
+  fn test_group_members() {
+    let topology = parsed_fixture();
+
+    let members = topology.group_members(&GroupId::new("RINCON_804AF2AA2FA201400:1331296849"));
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0].get_id(), &SpeakerId::new("RINCON_5CAAFDAE58BD01400"));
+    assert_eq!(
+      members[0].get_satellites(),
+      &[SpeakerId::new("RINCON_7828CA128F0001400"), SpeakerId::new("RINCON_7828CAFB9D9C01400")]
+    );
+
+    assert!(topology.group_members(&GroupId::new("RINCON_UNKNOWN:1")).is_empty());
+  }
+
+  fn group(id: &str, coordinator: &str, members: &[(&str, &[&str])]) -> Group {
+    Group::new(
+      GroupId::new(id),
+      SpeakerId::new(coordinator),
+      members
+        .iter()
+        .map(|(member, satellites)| {
+          SpeakerRef::new(
+            SpeakerId::new(*member),
+            satellites.iter().map(|satellite| SpeakerId::new(*satellite)).collect(),
+          )
+        })
+        .collect(),
+    )
+  }
+
+  #[test]
+  fn test_diff_identical_topologies_is_empty() {
+    let topology = Topology::new(vec![
+      group("RINCON_A:1", "RINCON_A", &[("RINCON_A", &["RINCON_SUB"])]),
+      group("RINCON_B:1", "RINCON_B", &[("RINCON_B", &[])]),
+    ]);
+
+    assert!(diff(&topology, &topology).is_empty());
+  }
+
+  #[test]
+  fn test_diff_speaker_moved_between_groups() {
+    let old = Topology::new(vec![
+      group("RINCON_A:1", "RINCON_A", &[("RINCON_A", &[]), ("RINCON_B", &[])]),
+      group("RINCON_C:1", "RINCON_C", &[("RINCON_C", &[])]),
+    ]);
+    let new = Topology::new(vec![
+      group("RINCON_A:1", "RINCON_A", &[("RINCON_A", &[])]),
+      group("RINCON_C:1", "RINCON_C", &[("RINCON_C", &[]), ("RINCON_B", &[])]),
+    ]);
+
+    let changes = diff(&old, &new);
+    assert!(changes.added_groups.is_empty());
+    assert!(changes.removed_groups.is_empty());
+    assert!(changes.coordinator_changes.is_empty());
+    assert_eq!(
+      changes.moved_speakers,
+      vec![SpeakerMove {
+        speaker_id: SpeakerId::new("RINCON_B"),
+        from: Some(GroupId::new("RINCON_A:1")),
+        to: Some(GroupId::new("RINCON_C:1")),
+      }]
+    );
+  }
+
+  #[test]
+  fn test_diff_coordinator_change() {
+    let old = Topology::new(vec![group("RINCON_A:1", "RINCON_A", &[("RINCON_A", &[]), ("RINCON_B", &[])])]);
+    let new = Topology::new(vec![group("RINCON_A:1", "RINCON_B", &[("RINCON_A", &[]), ("RINCON_B", &[])])]);
+
+    let changes = diff(&old, &new);
+    assert!(changes.moved_speakers.is_empty());
+    assert_eq!(
+      changes.coordinator_changes,
+      vec![CoordinatorChange {
+        group_id: GroupId::new("RINCON_A:1"),
+        from: SpeakerId::new("RINCON_A"),
+        to: SpeakerId::new("RINCON_B"),
+      }]
+    );
+  }
+
+  #[test]
+  fn test_diff_coordinator_swap_keeps_the_group() {
+    // Sonos renames the group after its new coordinator
+    let old = Topology::new(vec![group("RINCON_A:1", "RINCON_A", &[("RINCON_A", &[]), ("RINCON_B", &[])])]);
+    let new = Topology::new(vec![group("RINCON_B:4", "RINCON_B", &[("RINCON_B", &[]), ("RINCON_A", &[])])]);
+
+    let changes = diff(&old, &new);
+    assert!(changes.added_groups.is_empty());
+    assert!(changes.removed_groups.is_empty());
+    assert!(changes.moved_speakers.is_empty());
+    assert_eq!(
+      changes.coordinator_changes,
+      vec![CoordinatorChange {
+        group_id: GroupId::new("RINCON_B:4"),
+        from: SpeakerId::new("RINCON_A"),
+        to: SpeakerId::new("RINCON_B"),
+      }]
+    );
+  }
+
+  #[test]
+  fn test_diff_added_and_removed_groups_move_satellites_with_parent() {
+    let old = Topology::new(vec![
+      group("RINCON_A:1", "RINCON_A", &[("RINCON_A", &[]), ("RINCON_B", &["RINCON_SUB"])]),
+    ]);
+    let new = Topology::new(vec![
+      group("RINCON_A:2", "RINCON_A", &[("RINCON_A", &[])]),
+      group("RINCON_B:1", "RINCON_B", &[("RINCON_B", &["RINCON_SUB"])]),
+    ]);
+
+    let changes = diff(&old, &new);
+    assert_eq!(changes.added_groups, vec![GroupId::new("RINCON_A:2"), GroupId::new("RINCON_B:1")]);
+    assert_eq!(changes.removed_groups, vec![GroupId::new("RINCON_A:1")]);
+    assert_eq!(
+      changes
+        .moved_speakers
+        .iter()
+        .map(|moved| (moved.speaker_id.as_str(), moved.to.as_ref().map(|id| id.as_str())))
+        .collect::<Vec<_>>(),
+      vec![
+        ("RINCON_A", Some("RINCON_A:2")),
+        ("RINCON_B", Some("RINCON_B:1")),
+        ("RINCON_SUB", Some("RINCON_B:1")),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_diff_vanished_speaker_moves_to_none() {
+    let old = Topology::new(vec![
+      group("RINCON_A:1", "RINCON_A", &[("RINCON_A", &[])]),
+      group("RINCON_B:1", "RINCON_B", &[("RINCON_B", &[])]),
+    ]);
+    let new = Topology::new(vec![group("RINCON_A:1", "RINCON_A", &[("RINCON_A", &[])])]);
+
+    let changes = diff(&old, &new);
+    assert_eq!(changes.removed_groups, vec![GroupId::new("RINCON_B:1")]);
+    assert_eq!(
+      changes.moved_speakers,
+      vec![SpeakerMove { speaker_id: SpeakerId::new("RINCON_B"), from: Some(GroupId::new("RINCON_B:1")), to: None }]
+    );
+  }
+}
@@ -1,5 +1,6 @@
 use crate::error::{Result, SonosError};
 use crate::model::{Speaker, SpeakerId};
+use crate::streaming::ServiceType;
 use serde::Deserialize;
 
 /// UPnP device description root element
@@ -17,14 +18,60 @@ pub struct Device {
   pub manufacturer: String,
   pub manufacturer_url: Option<String>,
   pub model_description: Option<String>,
+  /// Empty when the description leaves it out; see [`Device::model`]
+  #[serde(default)]
   pub model_name: String,
   pub model_number: Option<String>,
   pub model_url: Option<String>,
   pub serial_number: Option<String>,
+  pub software_version: Option<String>,
+  pub hardware_version: Option<String>,
   #[serde(rename = "UDN")]
   pub udn: SpeakerId,
   pub room_name: Option<String>,
   pub display_name: Option<String>,
+  #[serde(default)]
+  pub service_list: ServiceList,
+  #[serde(default)]
+  pub device_list: DeviceList,
+}
+
+/// Services advertised by a device in its `<serviceList>`
+#[derive(Debug, Default, Deserialize)]
+pub struct ServiceList {
+  #[serde(rename = "service", default)]
+  pub services: Vec<Service>,
+}
+
+/// A single `<service>` entry of a device description
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Service {
+  pub service_type: String,
+  pub service_id: Option<String>,
+  #[serde(rename = "controlURL")]
+  pub control_url: Option<String>,
+  #[serde(rename = "eventSubURL")]
+  pub event_sub_url: Option<String>,
+}
+
+/// Embedded devices listed in `<deviceList>`
+///
+/// Sonos players expose AVTransport and RenderingControl on an embedded
+/// MediaRenderer, and ContentDirectory on an embedded MediaServer.
+#[derive(Debug, Default, Deserialize)]
+pub struct DeviceList {
+  #[serde(rename = "device", default)]
+  pub devices: Vec<EmbeddedDevice>,
+}
+
+/// An embedded UPnP device; only the parts needed to find its services
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddedDevice {
+  pub device_type: String,
+  #[serde(default)]
+  pub service_list: ServiceList,
 }
 
 impl Device {
@@ -47,11 +94,46 @@ impl Device {
         .unwrap_or_else(|| "Unknown".to_string()),
       ip_address,
       port: 1400,
-      model_name: self.model_name.clone(),
+      model_name: self.model().to_string(),
       satellites: vec![],
+      supported_services: self.supported_services(),
+      software_version: self.software_version.clone(),
+      hardware_version: self.hardware_version.clone(),
     }
   }
 
+  /// `modelName`, falling back to `displayName` (`One` for a Sonos One) when it is empty
+  ///
+  /// Returns an empty string when the description has neither.
+  pub fn model(&self) -> &str {
+    match self.model_name.trim() {
+      "" => self.display_name.as_deref().unwrap_or_default().trim(),
+      model_name => model_name,
+    }
+  }
+
+  /// Services this device advertises, including those on embedded devices
+  ///
+  /// Only services this crate knows how to subscribe to are returned, in the
+  /// order they first appear in the description.
+  pub fn supported_services(&self) -> Vec<ServiceType> {
+    let embedded = self
+      .device_list
+      .devices
+      .iter()
+      .flat_map(|device| device.service_list.services.iter());
+
+    let mut supported = Vec::new();
+    for service in self.service_list.services.iter().chain(embedded) {
+      if let Some(service_type) = ServiceType::from_urn(&service.service_type) {
+        if !supported.contains(&service_type) {
+          supported.push(service_type);
+        }
+      }
+    }
+    supported
+  }
+
   /// Check if this device is a Sonos speaker
   pub fn is_sonos_speaker(&self) -> bool {
     self.manufacturer.to_lowercase().contains("sonos")
@@ -99,4 +181,67 @@ mod tests {
     assert_eq!(device.room_name, Some("Living Room".to_string()));
     assert!(device.is_sonos_speaker());
   }
+
+  #[test]
+  fn test_device_supported_services() {
+    let xml = include_str!("../../tests/fixtures/sonos_one_device_description.xml");
+
+    let device = Device::from_xml(xml).unwrap();
+
+    assert_eq!(device.udn, SpeakerId::new("uuid:RINCON_000E58A0123456"));
+    assert_eq!(device.service_list.services.len(), 4);
+    assert_eq!(device.device_list.devices.len(), 2);
+    assert_eq!(
+      device.supported_services(),
+      vec![
+        ServiceType::ZoneGroupTopology,
+        ServiceType::ContentDirectory,
+        ServiceType::RenderingControl,
+        ServiceType::AVTransport,
+      ]
+    );
+
+    let speaker = device.to_speaker("192.168.1.100".to_string());
+    assert_eq!(speaker.supported_services, device.supported_services());
+  }
+
+  #[test]
+  fn test_device_description_versions_and_model() {
+    let xml = include_str!("../../tests/fixtures/sonos_one_device_description.xml");
+
+    let device = Device::from_xml(xml).unwrap();
+
+    assert_eq!(device.model_name, "Sonos One");
+    assert_eq!(device.display_name.as_deref(), Some("One"));
+    assert_eq!(device.software_version.as_deref(), Some("78.1-52020"));
+    assert_eq!(device.hardware_version.as_deref(), Some("1.20.1.6-2.0"));
+
+    let speaker = device.to_speaker("192.168.1.100".to_string());
+    assert_eq!(speaker.model_name, "Sonos One");
+    assert_eq!(speaker.software_version.as_deref(), Some("78.1-52020"));
+    assert_eq!(speaker.hardware_version.as_deref(), Some("1.20.1.6-2.0"));
+  }
+
+  #[test]
+  fn test_device_model_falls_back_to_display_name() {
+    let xml = include_str!("../../tests/fixtures/sonos_one_device_description.xml")
+      .replacen("<modelName>Sonos One</modelName>", "<modelName></modelName>", 1);
+
+    let device = Device::from_xml(&xml).unwrap();
+    assert_eq!(device.model(), "One");
+
+    let without_display_name = xml.replacen("<displayName>One</displayName>", "", 1);
+    let device = Device::from_xml(&without_display_name).unwrap();
+    assert_eq!(device.model(), "");
+    assert!(device.to_speaker("192.168.1.100".to_string()).model_name.is_empty());
+  }
+
+  #[test]
+  fn test_device_without_service_list() {
+    let xml = include_str!("../../tests/fixtures/sonos_one_device.xml");
+
+    let device = Device::from_xml(xml).unwrap();
+
+    assert!(device.supported_services().is_empty());
+  }
 }
@@ -1,59 +1,168 @@
 use super::device::{extract_ip_from_url, Device};
-use super::ssdp::SsdpClient;
+use super::ssdp::{m_search_request, parse_ssdp_response, SsdpClient, DEFAULT_MX, SSDP_MULTICAST_ADDR};
 use crate::error::{Result, SonosError};
-use crate::model::Speaker;
+use crate::model::{Speaker, SpeakerId};
+use futures_util::future::BoxFuture;
+use futures_util::stream::{FuturesUnordered, Stream, StreamExt};
 use std::collections::HashSet;
-use std::time::Duration;
+use std::io::ErrorKind;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+
+/// SSDP search target Sonos players respond to
+const ZONE_PLAYER_URN: &str = "urn:schemas-upnp-org:device:ZonePlayer:1";
+
+/// Where Sonos players serve their full device description
+const DEVICE_DESCRIPTION_PATH: &str = "/xml/device_description.xml";
+
+/// Tuning for SSDP discovery on unreliable networks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiscoveryConfig {
+    /// Number of M-SEARCH requests to send, spaced evenly across the timeout
+    pub retries: u8,
+    /// `MX:` header value, the number of seconds speakers may delay their response
+    pub mx: u8,
+    /// Total time spent listening for responses
+    pub timeout: Duration,
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            mx: DEFAULT_MX,
+            timeout: Duration::from_secs(3),
+        }
+    }
+}
 
 /// Discovery service for finding Sonos speakers on the network
 pub struct Discovery {
     timeout: Duration,
+    local_ip: Option<IpAddr>,
+    retries: u8,
+    mx: u8,
 }
 
 impl Discovery {
     /// Create a new discovery service with the specified timeout
     pub fn new(timeout: Duration) -> Self {
-        Self { timeout }
+        Self {
+            timeout,
+            local_ip: None,
+            retries: 1,
+            mx: DEFAULT_MX,
+        }
+    }
+
+    /// Create a discovery service with retry and MX settings
+    pub fn from_config(config: DiscoveryConfig) -> Self {
+        Self {
+            timeout: config.timeout,
+            local_ip: None,
+            retries: config.retries.max(1),
+            mx: config.mx,
+        }
+    }
+
+    /// Send SSDP searches from a specific local interface address
+    pub fn with_interface(mut self, local_ip: IpAddr) -> Self {
+        self.local_ip = Some(local_ip);
+        self
+    }
+
+    /// Create the SSDP client, bound to the configured interface if any
+    fn ssdp_client(&self) -> Result<SsdpClient> {
+        match self.local_ip {
+            Some(local_ip) => SsdpClient::bind(local_ip, self.timeout).map_err(|e| match e.kind() {
+                ErrorKind::AddrNotAvailable | ErrorKind::InvalidInput => SonosError::DiscoveryFailed(
+                    format!("{} is not a local IPv4 interface address: {}", local_ip, e),
+                ),
+                _ => SonosError::DiscoveryFailed(format!(
+                    "Failed to bind SSDP client to {}: {}",
+                    local_ip, e
+                )),
+            }),
+            None => SsdpClient::new(self.timeout).map_err(|e| {
+                SonosError::DiscoveryFailed(format!("Failed to create SSDP client: {}", e))
+            }),
+        }
     }
 
     /// Discover all Sonos speakers on the network
+    ///
+    /// The M-SEARCH is sent once per configured retry, spaced evenly across the
+    /// timeout, and speakers are deduplicated by UDN across all responses.
     pub fn discover_speakers(&self) -> Result<Vec<Speaker>> {
-        let client = SsdpClient::new(self.timeout).map_err(|e| {
-            SonosError::DiscoveryFailed(format!("Failed to create SSDP client: {}", e))
-        })?;
+        self.discover_speakers_at(SSDP_MULTICAST_ADDR)
+    }
 
-        let responses = client
-            .search("urn:schemas-upnp-org:device:ZonePlayer:1")
-            .map_err(|e| SonosError::DiscoveryFailed(format!("SSDP search failed: {}", e)))?;
+    /// Discover speakers, returning as soon as `min_count` distinct speakers are found
+    ///
+    /// Otherwise behaves like [`discover_speakers`](Self::discover_speakers) and
+    /// returns whatever was found once the timeout elapses, which may be fewer
+    /// than `min_count`.
+    pub fn discover_speakers_until(&self, min_count: usize) -> Result<Vec<Speaker>> {
+        self.discover_until_at(SSDP_MULTICAST_ADDR, min_count)
+    }
+
+    /// Discover speakers by sending the M-SEARCH requests to `search_addr`
+    fn discover_speakers_at(&self, search_addr: SocketAddr) -> Result<Vec<Speaker>> {
+        self.discover_until_at(search_addr, usize::MAX)
+    }
+
+    /// Search `search_addr` until the timeout elapses or `min_count` speakers are found
+    fn discover_until_at(&self, search_addr: SocketAddr, min_count: usize) -> Result<Vec<Speaker>> {
+        if min_count == 0 {
+            return Ok(Vec::new());
+        }
+
+        let client = self.ssdp_client()?;
+        let window = self.timeout / u32::from(self.retries);
 
         let mut speakers = Vec::new();
         let mut seen_locations = HashSet::new();
+        let mut seen_ids: HashSet<SpeakerId> = HashSet::new();
 
-        for response_result in responses {
-            match response_result {
-                Ok(response) => {
-                    // Avoid duplicate speakers from multiple responses
-                    if seen_locations.contains(&response.location) {
-                        continue;
-                    }
-                    seen_locations.insert(response.location.clone());
+        for _ in 0..self.retries {
+            client
+                .send_search(ZONE_PLAYER_URN, self.mx, search_addr)
+                .map_err(|e| SonosError::DiscoveryFailed(format!("SSDP search failed: {}", e)))?;
 
-                    // Filter out non-Sonos devices early based on SSDP response
-                    if !self.is_likely_sonos_device(&response) {
-                        continue;
-                    }
+            for response_result in client.responses_until(Instant::now() + window) {
+                match response_result {
+                    Ok(response) => {
+                        // Avoid duplicate speakers from multiple responses
+                        if seen_locations.contains(&response.location) {
+                            continue;
+                        }
+                        seen_locations.insert(response.location.clone());
+
+                        // Filter out non-Sonos devices early based on SSDP response
+                        if !self.is_likely_sonos_device(&response) {
+                            continue;
+                        }
 
-                    if let Some(ip) = extract_ip_from_url(&response.location) {
-                        match self.fetch_device_info(&response.location, ip) {
-                            Ok(speaker) => speakers.push(speaker),
-                            Err(_e) => {
-                                // Still might get some false positives, but much fewer now
+                        if let Some(ip) = extract_ip_from_url(&response.location) {
+                            match self.fetch_device_info(&response.location, ip) {
+                                Ok(speaker) => {
+                                    if seen_ids.insert(speaker.id.clone()) {
+                                        speakers.push(speaker);
+                                        if speakers.len() >= min_count {
+                                            return Ok(speakers);
+                                        }
+                                    }
+                                }
+                                Err(_e) => {
+                                    // Let a later retry fetch this location again
+                                    seen_locations.remove(&response.location);
+                                }
                             }
                         }
                     }
-                }
-                Err(_e) => {
-                    // Silently skip SSDP response errors - common on busy networks
+                    Err(_e) => {
+                        // Silently skip SSDP response errors - common on busy networks
+                    }
                 }
             }
         }
@@ -61,6 +170,27 @@ impl Discovery {
         Ok(speakers)
     }
 
+    /// Discover Sonos speakers asynchronously
+    ///
+    /// Speakers are yielded as soon as their device description has been fetched,
+    /// rather than after the whole discovery window. The stream ends once the
+    /// timeout has elapsed and every outstanding fetch has finished. It must be
+    /// polled from within a tokio runtime.
+    pub fn discover_speakers_stream(self) -> impl Stream<Item = Speaker> {
+        self.stream_from(SSDP_MULTICAST_ADDR)
+    }
+
+    /// Discover speakers by sending the M-SEARCH to `search_addr`
+    fn stream_from(self, search_addr: SocketAddr) -> impl Stream<Item = Speaker> {
+        futures_util::stream::once(AsyncSearch::start(self, search_addr))
+            .filter_map(|search| async move { search })
+            .flat_map(|search| {
+                futures_util::stream::unfold(search, |mut search| async move {
+                    search.next_speaker().await.map(|speaker| (speaker, search))
+                })
+            })
+    }
+
     /// Check if an SSDP response is likely from a Sonos device
     fn is_likely_sonos_device(&self, response: &super::ssdp::SsdpResponse) -> bool {
         // Check URN - Sonos devices use ZonePlayer
@@ -92,6 +222,9 @@ impl Discovery {
     }
 
     /// Fetch device information from a URL and convert to Speaker
+    ///
+    /// When the description leaves the model out, the full description at
+    /// `/xml/device_description.xml` is fetched to fill it in.
     fn fetch_device_info(&self, location: &str, ip_address: String) -> Result<Speaker> {
         let client = reqwest::blocking::Client::builder()
             .timeout(self.timeout)
@@ -100,36 +233,240 @@ impl Discovery {
                 SonosError::CommunicationError(format!("Failed to create HTTP client: {}", e))
             })?;
 
-        let response = client
-            .get(location)
-            .send()
-            .map_err(|e| SonosError::CommunicationError(format!("HTTP request failed: {}", e)))?;
+        let speaker = speaker_from_description(&fetch_description(&client, location)?, ip_address)?;
+        if !speaker.model_name.is_empty() {
+            return Ok(speaker);
+        }
 
-        if !response.status().is_success() {
-            return Err(SonosError::CommunicationError(format!(
-                "HTTP request failed with status: {}",
-                response.status()
-            )));
+        let Some(url) = device_description_url(location) else {
+            return Ok(speaker);
+        };
+        // Retry once; a speaker that is still booting can drop the first request
+        let probed = fetch_description(&client, &url)
+            .or_else(|_| fetch_description(&client, &url))
+            .and_then(|xml| Device::from_xml(&xml));
+        Ok(with_probed_model(speaker, probed))
+    }
+}
+
+/// GET a device description
+fn fetch_description(client: &reqwest::blocking::Client, url: &str) -> Result<String> {
+    let response = client
+        .get(url)
+        .send()
+        .map_err(|e| SonosError::CommunicationError(format!("HTTP request failed: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(SonosError::CommunicationError(format!(
+            "HTTP request failed with status: {}",
+            response.status()
+        )));
+    }
+
+    response.text().map_err(|e| {
+        SonosError::CommunicationError(format!("Failed to read response body: {}", e))
+    })
+}
+
+/// The full device description URL on the host serving `location`
+fn device_description_url(location: &str) -> Option<String> {
+    let (scheme, rest) = location.split_once("//")?;
+    let host = rest.split('/').next().filter(|host| !host.is_empty())?;
+    Some(format!("{}//{}{}", scheme, host, DEVICE_DESCRIPTION_PATH))
+}
+
+/// Fill in the model and versions `speaker` is missing from a probed description
+fn with_probed_model(mut speaker: Speaker, probed: Result<Device>) -> Speaker {
+    match probed {
+        Ok(device) => {
+            speaker.model_name = device.model().to_string();
+            speaker.software_version = speaker.software_version.or(device.software_version);
+            speaker.hardware_version = speaker.hardware_version.or(device.hardware_version);
         }
+        Err(e) => log::debug!("Failed to probe model of {}: {}", speaker.ip_address, e),
+    }
+    speaker
+}
+
+/// An in-progress async discovery: listens for SSDP responses until the
+/// deadline while fetching device descriptions concurrently
+struct AsyncSearch {
+    discovery: Discovery,
+    /// Dropped once the discovery window has closed
+    socket: Option<tokio::net::UdpSocket>,
+    deadline: tokio::time::Instant,
+    http: reqwest::Client,
+    seen_locations: HashSet<String>,
+    fetches: FuturesUnordered<BoxFuture<'static, Result<Speaker>>>,
+    buffer: [u8; 2048],
+}
+
+impl AsyncSearch {
+    /// Open the socket and send the M-SEARCH
+    async fn start(discovery: Discovery, search_addr: SocketAddr) -> Option<Self> {
+        let started = async {
+            let socket = discovery.ssdp_client()?.into_async().map_err(|e| {
+                SonosError::DiscoveryFailed(format!("Failed to create SSDP socket: {}", e))
+            })?;
 
-        let xml = response.text().map_err(|e| {
-            SonosError::CommunicationError(format!("Failed to read response body: {}", e))
-        })?;
+            socket
+                .send_to(m_search_request(ZONE_PLAYER_URN, discovery.mx).as_bytes(), search_addr)
+                .await
+                .map_err(|e| SonosError::DiscoveryFailed(format!("SSDP search failed: {}", e)))?;
 
-        let device = Device::from_xml(&xml)?;
+            let http = reqwest::Client::builder()
+                .timeout(discovery.timeout)
+                .build()
+                .map_err(|e| {
+                    SonosError::CommunicationError(format!("Failed to create HTTP client: {}", e))
+                })?;
 
-        // Verify this is actually a Sonos device
-        if !device.is_sonos_speaker() {
-            return Err(SonosError::DeviceNotFound(format!(
-                "Device at {} is not a Sonos speaker",
-                ip_address
+            Ok::<_, SonosError>((socket, http))
+        };
+
+        match started.await {
+            Ok((socket, http)) => Some(Self {
+                deadline: tokio::time::Instant::now() + discovery.timeout,
+                discovery,
+                socket: Some(socket),
+                http,
+                seen_locations: HashSet::new(),
+                fetches: FuturesUnordered::new(),
+                buffer: [0; 2048],
+            }),
+            Err(e) => {
+                log::warn!("Async discovery failed to start: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Wait for the next speaker whose device description has been fetched
+    async fn next_speaker(&mut self) -> Option<Speaker> {
+        loop {
+            if self.socket.is_none() && self.fetches.is_empty() {
+                return None;
+            }
+
+            tokio::select! {
+                received = recv_response(self.socket.as_ref(), &mut self.buffer) => {
+                    match received {
+                        Ok(size) => self.handle_response(size),
+                        Err(_e) => {
+                            // Silently skip SSDP response errors - common on busy networks
+                        }
+                    }
+                }
+                _ = tokio::time::sleep_until(self.deadline), if self.socket.is_some() => {
+                    self.socket = None;
+                }
+                Some(fetched) = self.fetches.next() => {
+                    match fetched {
+                        Ok(speaker) => return Some(speaker),
+                        Err(e) => log::debug!("Skipping discovered device: {}", e),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Queue a device description fetch for a new Sonos SSDP response
+    fn handle_response(&mut self, size: usize) {
+        let Ok(response_text) = std::str::from_utf8(&self.buffer[..size]) else {
+            return;
+        };
+        let Some(response) = parse_ssdp_response(response_text) else {
+            return;
+        };
+
+        // Avoid duplicate speakers from multiple responses
+        if !self.seen_locations.insert(response.location.clone()) {
+            return;
+        }
+
+        // Filter out non-Sonos devices early based on SSDP response
+        if !self.discovery.is_likely_sonos_device(&response) {
+            return;
+        }
+
+        if let Some(ip) = extract_ip_from_url(&response.location) {
+            self.fetches.push(Box::pin(fetch_device_info_async(
+                self.http.clone(),
+                response.location,
+                ip,
             )));
         }
+    }
+}
 
-        Ok(device.to_speaker(ip_address))
+/// Receive one datagram, or wait forever once the socket has been dropped
+async fn recv_response(
+    socket: Option<&tokio::net::UdpSocket>,
+    buffer: &mut [u8],
+) -> std::io::Result<usize> {
+    match socket {
+        Some(socket) => socket.recv(buffer).await,
+        None => std::future::pending().await,
     }
 }
 
+/// Async counterpart of `Discovery::fetch_device_info`
+async fn fetch_device_info_async(
+    client: reqwest::Client,
+    location: String,
+    ip_address: String,
+) -> Result<Speaker> {
+    let speaker = speaker_from_description(&fetch_description_async(&client, &location).await?, ip_address)?;
+    if !speaker.model_name.is_empty() {
+        return Ok(speaker);
+    }
+
+    let Some(url) = device_description_url(&location) else {
+        return Ok(speaker);
+    };
+    let probed = match fetch_description_async(&client, &url).await {
+        Ok(xml) => Ok(xml),
+        Err(_) => fetch_description_async(&client, &url).await,
+    }
+    .and_then(|xml| Device::from_xml(&xml));
+    Ok(with_probed_model(speaker, probed))
+}
+
+/// Async counterpart of `fetch_description`
+async fn fetch_description_async(client: &reqwest::Client, url: &str) -> Result<String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| SonosError::CommunicationError(format!("HTTP request failed: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(SonosError::CommunicationError(format!(
+            "HTTP request failed with status: {}",
+            response.status()
+        )));
+    }
+
+    response.text().await.map_err(|e| {
+        SonosError::CommunicationError(format!("Failed to read response body: {}", e))
+    })
+}
+
+/// Parse a device description and convert it to a Speaker if it is a Sonos device
+fn speaker_from_description(xml: &str, ip_address: String) -> Result<Speaker> {
+    let device = Device::from_xml(xml)?;
+
+    // Verify this is actually a Sonos device
+    if !device.is_sonos_speaker() {
+        return Err(SonosError::DeviceNotFound(format!(
+            "Device at {} is not a Sonos speaker",
+            ip_address
+        )));
+    }
+
+    Ok(device.to_speaker(ip_address))
+}
+
 /// Convenience function for quick speaker discovery with default timeout
 pub fn discover_speakers() -> Result<Vec<Speaker>> {
     let discovery = Discovery::new(Duration::from_secs(3));
@@ -142,6 +479,76 @@ pub fn discover_speakers_with_timeout(timeout: Duration) -> Result<Vec<Speaker>>
     discovery.discover_speakers()
 }
 
+/// Speaker discovery that stops early once `min_count` speakers have responded
+///
+/// Returns after `max_timeout` at the latest, with however many speakers were
+/// found by then. Handy for a quick "is anything here" check:
+///
+/// ```rust,no_run
+/// use sonos::transport::discovery::discover_speakers_until;
+/// use std::time::Duration;
+///
+/// let any_speaker = !discover_speakers_until(1, Duration::from_secs(3))?.is_empty();
+/// # Ok::<(), sonos::SonosError>(())
+/// ```
+pub fn discover_speakers_until(min_count: usize, max_timeout: Duration) -> Result<Vec<Speaker>> {
+    let discovery = Discovery::new(max_timeout);
+    discovery.discover_speakers_until(min_count)
+}
+
+/// Convenience function for speaker discovery from a specific local interface
+///
+/// Useful on multi-homed machines where the default route (a VPN or container
+/// bridge, for example) would otherwise swallow the SSDP multicast.
+pub fn discover_speakers_with_interface(local_ip: IpAddr, timeout: Duration) -> Result<Vec<Speaker>> {
+    let discovery = Discovery::new(timeout).with_interface(local_ip);
+    discovery.discover_speakers()
+}
+
+/// Speaker discovery with retries, for networks that drop multicast packets
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use sonos::transport::discovery::{discover_speakers_with_config, DiscoveryConfig};
+/// use std::time::Duration;
+///
+/// let speakers = discover_speakers_with_config(DiscoveryConfig {
+///     retries: 4,
+///     mx: 1,
+///     timeout: Duration::from_secs(4),
+/// })?;
+/// # Ok::<(), sonos::SonosError>(())
+/// ```
+pub fn discover_speakers_with_config(config: DiscoveryConfig) -> Result<Vec<Speaker>> {
+    let discovery = Discovery::from_config(config);
+    discovery.discover_speakers()
+}
+
+/// Async speaker discovery that yields each speaker as soon as it is found
+///
+/// The stream ends once `timeout` has elapsed and every outstanding device
+/// description fetch has completed. It must be polled from within a tokio runtime.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use futures_util::StreamExt;
+/// use sonos::transport::discovery::discover_speakers_async;
+/// use std::time::Duration;
+///
+/// # async fn run() {
+/// let speakers = discover_speakers_async(Duration::from_secs(3));
+/// futures_util::pin_mut!(speakers);
+/// while let Some(speaker) = speakers.next().await {
+///     println!("Found {} at {}", speaker.name, speaker.ip_address);
+/// }
+/// # }
+/// ```
+pub fn discover_speakers_async(timeout: Duration) -> impl Stream<Item = Speaker> {
+    Discovery::new(timeout).discover_speakers_stream()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::SpeakerId;
@@ -296,6 +703,37 @@ mod tests {
         assert!(result.is_ok() || result.is_err()); // Either outcome is valid without real devices
     }
 
+    #[test]
+    fn test_discover_speakers_with_interface_loopback() {
+        let local_ip: IpAddr = "127.0.0.1".parse().unwrap();
+        let result = discover_speakers_with_interface(local_ip, Duration::from_millis(100));
+        // Binding succeeds; no speakers answer on loopback, and a send failure is a DiscoveryFailed
+        match result {
+            Ok(speakers) => assert!(speakers.is_empty()),
+            Err(e) => assert!(matches!(e, SonosError::DiscoveryFailed(_))),
+        }
+    }
+
+    #[test]
+    fn test_discover_speakers_with_interface_rejects_non_local_ip() {
+        // TEST-NET-1 address is never assigned to a local interface
+        let remote_ip: IpAddr = "192.0.2.1".parse().unwrap();
+        let result = discover_speakers_with_interface(remote_ip, Duration::from_millis(100));
+        match result {
+            Err(SonosError::DiscoveryFailed(message)) => {
+                assert!(message.contains("not a local IPv4 interface"), "{}", message)
+            }
+            other => panic!("Expected DiscoveryFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_discover_speakers_with_interface_rejects_ipv6() {
+        let ipv6: IpAddr = "::1".parse().unwrap();
+        let result = discover_speakers_with_interface(ipv6, Duration::from_millis(100));
+        assert!(matches!(result, Err(SonosError::DiscoveryFailed(_))));
+    }
+
     #[test]
     fn test_fetch_device_info_invalid_url() {
         let discovery = Discovery::new(Duration::from_millis(100));
@@ -348,4 +786,282 @@ mod tests {
         };
         assert!(discovery.is_likely_sonos_device(&rincon_response));
     }
+
+    /// Serve device descriptions over HTTP; the second one is held back until `gate` fires
+    fn spawn_description_server(gate: std::sync::mpsc::Receiver<()>) -> u16 {
+        use std::io::{Read, Write};
+        use std::sync::{Arc, Mutex};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let gate = Arc::new(Mutex::new(gate));
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let gate = Arc::clone(&gate);
+                std::thread::spawn(move || {
+                    let mut request = [0u8; 2048];
+                    let size = stream.read(&mut request).unwrap_or(0);
+                    let request = String::from_utf8_lossy(&request[..size]);
+
+                    let body = if request.starts_with("GET /one.xml") {
+                        include_str!("../../tests/fixtures/sonos_one_device.xml")
+                    } else {
+                        let _ = gate.lock().unwrap().recv();
+                        include_str!("../../tests/fixtures/sonos_play1_device.xml")
+                    };
+
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = stream.write_all(response.as_bytes());
+                });
+            }
+        });
+
+        port
+    }
+
+    #[tokio::test]
+    async fn test_discover_speakers_stream_yields_incrementally() {
+        let (gate_sender, gate) = std::sync::mpsc::channel();
+        let http_port = spawn_description_server(gate);
+
+        // Fake SSDP responder: answers the M-SEARCH with two Sonos players
+        let responder = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let responder_addr = responder.local_addr().unwrap();
+        let responder_thread = std::thread::spawn(move || {
+            let mut buffer = [0u8; 2048];
+            let (size, client) = responder.recv_from(&mut buffer).unwrap();
+            let request = String::from_utf8_lossy(&buffer[..size]).to_string();
+
+            for (path, id) in [("one.xml", "RINCON_000E58A0123456"), ("play1.xml", "RINCON_B8E937654321")] {
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\n\
+                    LOCATION: http://127.0.0.1:{}/{}\r\n\
+                    ST: {}\r\n\
+                    USN: uuid:{}::{}\r\n\
+                    \r\n",
+                    http_port, path, ZONE_PLAYER_URN, id, ZONE_PLAYER_URN
+                );
+                responder.send_to(response.as_bytes(), client).unwrap();
+            }
+            request
+        });
+
+        let speakers = Discovery::new(Duration::from_millis(500)).stream_from(responder_addr);
+        futures_util::pin_mut!(speakers);
+
+        // The first speaker arrives while the second description is still being held back
+        let first = tokio::time::timeout(Duration::from_secs(5), speakers.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.name, "Living Room");
+        assert_eq!(first.ip_address, "127.0.0.1");
+
+        gate_sender.send(()).unwrap();
+        let second = tokio::time::timeout(Duration::from_secs(5), speakers.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.name, "Kitchen");
+
+        let end = tokio::time::timeout(Duration::from_secs(5), speakers.next())
+            .await
+            .unwrap();
+        assert!(end.is_none());
+
+        let request = responder_thread.join().unwrap();
+        assert!(request.starts_with("M-SEARCH * HTTP/1.1"));
+        assert!(request.contains(&format!("ST: {}", ZONE_PLAYER_URN)));
+    }
+
+    #[test]
+    fn test_discover_speakers_with_config_retries_after_dropped_search() {
+        let (_gate_sender, gate) = std::sync::mpsc::channel();
+        let http_port = spawn_description_server(gate);
+
+        // Fake SSDP responder: drops the first M-SEARCH, then answers each retry
+        let responder = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let responder_addr = responder.local_addr().unwrap();
+        let responder_thread = std::thread::spawn(move || {
+            let mut buffer = [0u8; 2048];
+            let mut requests = Vec::new();
+            for attempt in 0..3 {
+                let (size, client) = responder.recv_from(&mut buffer).unwrap();
+                requests.push(String::from_utf8_lossy(&buffer[..size]).to_string());
+                if attempt == 0 {
+                    continue;
+                }
+
+                // Each retry answers with a different location for the same player
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\n\
+                    LOCATION: http://127.0.0.1:{}/one.xml?attempt={}\r\n\
+                    ST: {}\r\n\
+                    USN: uuid:RINCON_000E58A0123456::{}\r\n\
+                    \r\n",
+                    http_port, attempt, ZONE_PLAYER_URN, ZONE_PLAYER_URN
+                );
+                responder.send_to(response.as_bytes(), client).unwrap();
+            }
+            requests
+        });
+
+        let discovery = Discovery::from_config(DiscoveryConfig {
+            retries: 3,
+            mx: 1,
+            timeout: Duration::from_millis(900),
+        });
+        let speakers = discovery.discover_speakers_at(responder_addr).unwrap();
+
+        assert_eq!(speakers.len(), 1);
+        assert_eq!(speakers[0].name, "Living Room");
+        assert_eq!(speakers[0].id, SpeakerId::new("uuid:RINCON_000E58A0123456"));
+
+        let requests = responder_thread.join().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests.iter().all(|request| request.contains("MX: 1\r\n")));
+    }
+
+    #[test]
+    fn test_discover_speakers_until_stops_at_min_count() {
+        use std::io::{Read, Write};
+
+        // Serve each fixture by path
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let http_port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut request = [0u8; 2048];
+                let size = stream.read(&mut request).unwrap_or(0);
+                let request = String::from_utf8_lossy(&request[..size]);
+
+                let body = if request.starts_with("GET /one.xml") {
+                    include_str!("../../tests/fixtures/sonos_one_device.xml")
+                } else if request.starts_with("GET /play1.xml") {
+                    include_str!("../../tests/fixtures/sonos_play1_device.xml")
+                } else {
+                    include_str!("../../tests/fixtures/minimal_sonos_device.xml")
+                };
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        // Fake SSDP responder: answers the M-SEARCH with three Sonos players
+        let responder = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let responder_addr = responder.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let mut buffer = [0u8; 2048];
+            let (_, client) = responder.recv_from(&mut buffer).unwrap();
+            for (path, id) in [
+                ("one.xml", "RINCON_000E58A0123456"),
+                ("play1.xml", "RINCON_B8E937654321"),
+                ("minimal.xml", "RINCON_MINIMAL123456"),
+            ] {
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\n\
+                    LOCATION: http://127.0.0.1:{}/{}\r\n\
+                    ST: {}\r\n\
+                    USN: uuid:{}::{}\r\n\
+                    \r\n",
+                    http_port, path, ZONE_PLAYER_URN, id, ZONE_PLAYER_URN
+                );
+                responder.send_to(response.as_bytes(), client).unwrap();
+            }
+        });
+
+        let timeout = Duration::from_secs(5);
+        let started = Instant::now();
+        let speakers = Discovery::new(timeout).discover_until_at(responder_addr, 2).unwrap();
+
+        assert!(started.elapsed() < timeout);
+        assert!((2..=3).contains(&speakers.len()));
+    }
+
+    #[test]
+    fn test_discover_speakers_until_zero_returns_immediately() {
+        let speakers = Discovery::new(Duration::from_secs(5))
+            .discover_until_at(SSDP_MULTICAST_ADDR, 0)
+            .unwrap();
+        assert!(speakers.is_empty());
+    }
+
+    #[test]
+    fn test_fetch_device_info_probes_missing_model() {
+        use std::io::{Read, Write};
+
+        // The SSDP location has no modelName; the full description fails once, then answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let http_port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let mut paths = Vec::new();
+            for (index, stream) in listener.incoming().take(3).enumerate() {
+                let mut stream = stream.unwrap();
+                let mut request = [0u8; 2048];
+                let size = stream.read(&mut request).unwrap_or(0);
+                let request = String::from_utf8_lossy(&request[..size]).to_string();
+                paths.push(request.split_whitespace().nth(1).unwrap_or_default().to_string());
+
+                let (status, body) = match index {
+                    0 => ("200 OK", include_str!("../../tests/fixtures/minimal_sonos_device.xml")
+                        .replace("<modelName>Sonos Test</modelName>", "")),
+                    1 => ("503 Service Unavailable", String::new()),
+                    _ => ("200 OK", include_str!("../../tests/fixtures/sonos_one_device_description.xml").to_string()),
+                };
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+            paths
+        });
+
+        let discovery = Discovery::new(Duration::from_secs(2));
+        let speaker = discovery
+            .fetch_device_info(&format!("http://127.0.0.1:{}/minimal.xml", http_port), "127.0.0.1".to_string())
+            .unwrap();
+
+        assert_eq!(speaker.id, SpeakerId::new("uuid:RINCON_MINIMAL123456"));
+        assert_eq!(speaker.model_name, "Sonos One");
+        assert_eq!(speaker.software_version.as_deref(), Some("78.1-52020"));
+        assert_eq!(speaker.hardware_version.as_deref(), Some("1.20.1.6-2.0"));
+        assert_eq!(
+            server.join().unwrap(),
+            vec!["/minimal.xml", "/xml/device_description.xml", "/xml/device_description.xml"]
+        );
+    }
+
+    #[test]
+    fn test_device_description_url() {
+        assert_eq!(
+            device_description_url("http://192.168.1.20:1400/status/description.xml").as_deref(),
+            Some("http://192.168.1.20:1400/xml/device_description.xml")
+        );
+        assert_eq!(device_description_url("not-a-url"), None);
+    }
+
+    #[test]
+    fn test_discovery_config_default() {
+        let config = DiscoveryConfig::default();
+        assert_eq!(config.retries, 3);
+        assert_eq!(config.mx, 2);
+        assert_eq!(config.timeout, Duration::from_secs(3));
+
+        let discovery = Discovery::from_config(DiscoveryConfig { retries: 0, ..config });
+        assert_eq!(discovery.retries, 1);
+    }
 }
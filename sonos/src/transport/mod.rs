@@ -1,4 +1,38 @@
 pub mod soap;
 pub mod ssdp;
 pub mod device;
-pub mod discovery;
\ No newline at end of file
+pub mod discovery;
+
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+
+/// `User-Agent` sent on SOAP and event subscription requests unless overridden
+pub const DEFAULT_USER_AGENT: &str = concat!("sonos-rs/", env!("CARGO_PKG_VERSION"));
+
+/// Longest wait between retries
+pub(crate) const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Exponential backoff for retry `attempt` (starting at 0), capped at [`MAX_BACKOFF`]
+pub(crate) fn capped_backoff(attempt: u32, base_duration: Duration) -> Duration {
+  let multiplier = 2_u64.saturating_pow(attempt);
+  let backoff_ms = (base_duration.as_millis() as u64).saturating_mul(multiplier);
+
+  // Cap to avoid excessive delays
+  Duration::from_millis(backoff_ms.min(MAX_BACKOFF.as_millis() as u64))
+}
+
+/// Headers sent on every request to a speaker: `user_agent` plus any `extra` headers
+pub(crate) fn request_headers(user_agent: &str, extra: &[(String, String)]) -> std::result::Result<HeaderMap, String> {
+  let mut headers = HeaderMap::new();
+  let value = HeaderValue::from_str(user_agent).map_err(|_| format!("Invalid User-Agent: {:?}", user_agent))?;
+  headers.insert(USER_AGENT, value);
+
+  for (name, value) in extra {
+    let header_name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| format!("Invalid header name: {:?}", name))?;
+    let header_value = HeaderValue::from_str(value).map_err(|_| format!("Invalid value for header {}: {:?}", name, value))?;
+    headers.insert(header_name, header_value);
+  }
+
+  Ok(headers)
+}
@@ -1,79 +1,247 @@
 use crate::error::Result;
 
+/// UPnP `Invalid Args` error code, also returned for actions a device doesn't implement
+pub const UPNP_INVALID_ARGS: u16 = 402;
+
+/// Meaning of the UPnP error codes Sonos devices commonly return, for faults
+/// that arrive without an `errorDescription`
+fn upnp_error_description(code: u16) -> Option<&'static str> {
+  let description = match code {
+    401 => "Invalid action",
+    402 => "Invalid args",
+    501 => "Action failed",
+    701 => "Transition not available",
+    702 => "No contents",
+    711 => "Illegal seek target",
+    712 => "Play mode not supported",
+    714 => "Illegal MIME-type",
+    718 => "Invalid InstanceID",
+    800 => "Command not supported or not a coordinator",
+    _ => return None,
+  };
+  Some(description)
+}
+
 pub struct SoapRequest {
   pub service_type: String,
   pub action: String,
   pub params: Vec<(String, String)>,
 }
 
+/// A SOAP action with its arguments, ready to be sent to a device.
+///
+/// Arguments keep the order they were added in, which Sonos devices require,
+/// and their values are XML-escaped when the envelope is built so DIDL-Lite
+/// metadata or URLs containing `&` travel as text.
+#[derive(Debug, Clone)]
+pub struct SoapAction {
+  service_type: String,
+  action: String,
+  args: Vec<(String, String)>,
+}
+
+impl SoapAction {
+  /// Start an `action` on the service identified by the `service_type` URN
+  pub fn new(service_type: impl Into<String>, action: impl Into<String>) -> Self {
+    Self {
+      service_type: service_type.into(),
+      action: action.into(),
+      args: Vec::new(),
+    }
+  }
+
+  /// Append an argument; values are escaped when the envelope is built
+  pub fn arg(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+    self.args.push((name.into(), value.into()));
+    self
+  }
+
+  /// Name of the action, e.g. `Play`
+  pub fn name(&self) -> &str {
+    &self.action
+  }
+
+  /// Value for the `SOAPACTION` header: the quoted `urn:...#Action`
+  pub fn soap_action_header(&self) -> String {
+    format!("\"{}#{}\"", self.service_type, self.action)
+  }
+
+  /// The full SOAP envelope to send as the request body
+  pub fn envelope(&self) -> String {
+    let mut args_xml = String::new();
+    for (name, value) in &self.args {
+      // Values such as DIDL-Lite metadata are XML themselves and must travel as text
+      args_xml.push_str(&format!("<{}>{}</{}>\n", name, quick_xml::escape::escape(value.as_str()), name));
+    }
+
+    format!(
+      "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+        <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+        s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\n\
+        <s:Body>\n\
+        <u:{} xmlns:u=\"{}\">\n\
+        {}\
+        </u:{}>\n\
+        </s:Body>\n\
+        </s:Envelope>",
+      self.action, self.service_type, args_xml, self.action
+    )
+  }
+}
+
+impl From<SoapRequest> for SoapAction {
+  fn from(request: SoapRequest) -> Self {
+    request
+      .params
+      .into_iter()
+      .fold(SoapAction::new(request.service_type, request.action), |action, (name, value)| {
+        action.arg(name, value)
+      })
+  }
+}
+
 pub struct SoapResponse {
   pub body: String,
 }
 
 pub struct SoapClient {
   http_client: reqwest::blocking::Client,
-  _timeout: std::time::Duration,
+  timeout: std::time::Duration,
+  retries: u8,
+  backoff: std::time::Duration,
 }
 
 impl SoapClient {
   pub fn new(timeout: std::time::Duration) -> Result<Self> {
-    let http_client = reqwest::blocking::Client::builder()
-      .timeout(timeout)
-      .build()
-      .map_err(|e| crate::error::SonosError::CommunicationError(e.to_string()))?;
+    Self::with_retry(timeout, 0, std::time::Duration::ZERO)
+  }
+
+  /// Retry idempotent actions up to `retries` times when the request can't be sent.
+  ///
+  /// Waits grow exponentially from `backoff`, capped at 30 seconds. Only query
+  /// actions (`Get*` and `Browse`) are retried; a state-changing action such as
+  /// `AddURIToQueue` might already have been applied when the connection dropped.
+  pub fn with_retry(timeout: std::time::Duration, retries: u8, backoff: std::time::Duration) -> Result<Self> {
+    let http_client = Self::build_http_client(timeout, super::DEFAULT_USER_AGENT, &[])?;
 
     Ok(Self {
       http_client,
-      _timeout: timeout,
+      timeout,
+      retries,
+      backoff,
     })
   }
 
+  /// Send `user_agent` instead of [`DEFAULT_USER_AGENT`](super::DEFAULT_USER_AGENT),
+  /// plus the `extra` headers, on every request.
+  pub fn with_headers(mut self, user_agent: &str, extra: &[(String, String)]) -> Result<Self> {
+    self.http_client = Self::build_http_client(self.timeout, user_agent, extra)?;
+    Ok(self)
+  }
+
+  fn build_http_client(
+    timeout: std::time::Duration,
+    user_agent: &str,
+    extra: &[(String, String)],
+  ) -> Result<reqwest::blocking::Client> {
+    let headers = super::request_headers(user_agent, extra).map_err(crate::error::SonosError::InvalidArgument)?;
+
+    reqwest::blocking::Client::builder()
+      .timeout(timeout)
+      .default_headers(headers)
+      .build()
+      .map_err(|e| crate::error::SonosError::CommunicationError(e.to_string()))
+  }
+
+  /// Whether `action` only reads state and is therefore safe to repeat
+  pub fn is_idempotent(action: &str) -> bool {
+    action.starts_with("Get") || action == "Browse"
+  }
+
   pub fn call(&self, device_url: &str, service_path: &str, request: SoapRequest) -> Result<SoapResponse> {
-    let url = format!("{}{}", device_url, service_path);
-    let body = Self::build_soap_envelope(&request);
+    self.send(device_url, service_path, &SoapAction::from(request))
+  }
 
-    let response = self
-      .http_client
-      .post(&url)
-      .header("Content-Type", "text/xml; charset=\"utf-8\"")
-      .header(
-        "SOAPACTION",
-        format!("{}#{}", request.service_type, request.action),
-      )
-      .body(body)
-      .send()
-      .map_err(|e| crate::error::SonosError::CommunicationError(e.to_string()))?;
+  /// Post `action` to the control URL at `service_path` on the device
+  pub fn send(&self, device_url: &str, service_path: &str, action: &SoapAction) -> Result<SoapResponse> {
+    let url = format!("{}{}", device_url, service_path);
+    let body = action.envelope();
+    let max_retries = if Self::is_idempotent(action.name()) { self.retries as u32 } else { 0 };
+
+    let mut attempt = 0;
+    let response = loop {
+      let result = self
+        .http_client
+        .post(&url)
+        .header("Content-Type", "text/xml; charset=\"utf-8\"")
+        .header("SOAPACTION", action.soap_action_header())
+        .body(body.clone())
+        .send();
+
+      match result {
+        Ok(response) => break response,
+        Err(e) if attempt < max_retries => {
+          let wait = super::capped_backoff(attempt, self.backoff);
+          log::debug!("{} to {} failed ({}), retrying in {:?}", action.name(), url, e, wait);
+          std::thread::sleep(wait);
+          attempt += 1;
+        }
+        Err(e) => return Err(crate::error::SonosError::CommunicationError(e.to_string())),
+      }
+    };
 
+    let status = response.status();
     let response_body = response
       .text()
       .map_err(|e| crate::error::SonosError::CommunicationError(e.to_string()))?;
 
     if response_body.contains("faultstring") {
-      let fault = Self::extract_fault_string(&response_body);
-      return Err(crate::error::SonosError::SoapFault(fault));
+      return Err(Self::parse_fault(&response_body));
+    }
+
+    if status == reqwest::StatusCode::SERVICE_UNAVAILABLE {
+      return Err(crate::error::SonosError::SatelliteSpeaker(format!(
+        "{} rejected by {}",
+        action.name(), device_url
+      )));
+    }
+
+    if !status.is_success() {
+      return Err(crate::error::SonosError::CommunicationError(format!(
+        "{} returned HTTP {}",
+        action.name(), status
+      )));
     }
 
     Ok(SoapResponse { body: response_body })
   }
 
-  fn build_soap_envelope(request: &SoapRequest) -> String {
-    let mut params_xml = String::new();
-    for (key, value) in &request.params {
-      params_xml.push_str(&format!("<{}>{}</{}>\n", key, value, key));
+  /// Fetch a plain HTTP page from the device, e.g. `/status/batterystatus`
+  pub fn get(&self, device_url: &str, path: &str) -> Result<String> {
+    let response = self
+      .http_client
+      .get(format!("{}{}", device_url, path))
+      .send()
+      .map_err(|e| crate::error::SonosError::CommunicationError(e.to_string()))?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::SERVICE_UNAVAILABLE {
+      return Err(crate::error::SonosError::SatelliteSpeaker(format!(
+        "GET {} rejected by {}",
+        path, device_url
+      )));
     }
 
-    format!(
-      "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
-        <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
-        s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\n\
-        <s:Body>\n\
-        <u:{} xmlns:u=\"{}\">\n\
-        {}\
-        </u:{}>\n\
-        </s:Body>\n\
-        </s:Envelope>",
-      request.action, request.service_type, params_xml, request.action
-    )
+    if !status.is_success() {
+      return Err(crate::error::SonosError::CommunicationError(format!(
+        "GET {} returned HTTP {}",
+        path, status
+      )));
+    }
+
+    response
+      .text()
+      .map_err(|e| crate::error::SonosError::CommunicationError(e.to_string()))
   }
 
   fn extract_fault_string(xml: &str) -> String {
@@ -84,6 +252,22 @@ impl SoapClient {
       .to_string()
   }
 
+  /// Turn a SOAP fault body into `UpnpFault` when it carries a UPnP error code,
+  /// otherwise into a plain `SoapFault`
+  pub fn parse_fault(xml: &str) -> crate::error::SonosError {
+    let code = Self::extract_xml_value(xml, "errorCode").and_then(|code| code.trim().parse::<u16>().ok());
+    let Some(code) = code else {
+      return crate::error::SonosError::SoapFault(Self::extract_fault_string(xml));
+    };
+
+    let description = Self::extract_xml_value(xml, "errorDescription")
+      .map(|description| description.trim().to_string())
+      .filter(|description| !description.is_empty())
+      .or_else(|| upnp_error_description(code).map(str::to_string))
+      .unwrap_or_else(|| Self::extract_fault_string(xml));
+    crate::error::SonosError::UpnpFault { code, description }
+  }
+
   pub fn extract_xml_value(xml: &str, tag: &str) -> Option<String> {
     let start_tag = format!("<{}>", tag);
     let end_tag = format!("</{}>", tag);
@@ -129,7 +313,7 @@ mod tests {
       ],
     };
 
-    let envelope = SoapClient::build_soap_envelope(&request);
+    let envelope = SoapAction::from(request).envelope();
 
     assert!(envelope.contains("<?xml version=\"1.0\""));
     assert!(envelope.contains("<u:SetVolume"));
@@ -139,6 +323,145 @@ mod tests {
     assert!(envelope.contains("</u:SetVolume>"));
   }
 
+  #[test]
+  fn test_build_soap_envelope_escapes_values() {
+    let request = SoapRequest {
+      service_type: "urn:schemas-upnp-org:service:AVTransport:1".to_string(),
+      action: "AddURIToQueue".to_string(),
+      params: vec![
+        ("EnqueuedURI".to_string(), "http://example.com/a.mp3?x=1&y=2".to_string()),
+        ("EnqueuedURIMetaData".to_string(), "<DIDL-Lite><item id=\"1\"/></DIDL-Lite>".to_string()),
+      ],
+    };
+
+    let envelope = SoapAction::from(request).envelope();
+
+    assert!(envelope.contains("<EnqueuedURI>http://example.com/a.mp3?x=1&amp;y=2</EnqueuedURI>"));
+    assert!(envelope.contains(
+      "<EnqueuedURIMetaData>&lt;DIDL-Lite&gt;&lt;item id=&quot;1&quot;/&gt;&lt;/DIDL-Lite&gt;</EnqueuedURIMetaData>"
+    ));
+  }
+
+  #[test]
+  fn test_soap_action_escapes_argument_values() {
+    let action = SoapAction::new("urn:schemas-upnp-org:service:AVTransport:1", "SetAVTransportURI")
+      .arg("InstanceID", "0")
+      .arg("CurrentURI", "http://example.com/a.mp3?x=1&y=2")
+      .arg("CurrentURIMetaData", "<dc:title>Tom & Jerry's \"Best\"</dc:title>");
+
+    let envelope = action.envelope();
+
+    assert!(envelope.contains("<CurrentURI>http://example.com/a.mp3?x=1&amp;y=2</CurrentURI>"));
+    assert!(envelope.contains(
+      "<CurrentURIMetaData>&lt;dc:title&gt;Tom &amp; Jerry&apos;s &quot;Best&quot;&lt;/dc:title&gt;</CurrentURIMetaData>"
+    ));
+    assert!(envelope.contains("<u:SetAVTransportURI xmlns:u=\"urn:schemas-upnp-org:service:AVTransport:1\">"));
+  }
+
+  #[test]
+  fn test_soap_action_keeps_argument_order() {
+    let envelope = SoapAction::new("urn:schemas-upnp-org:service:RenderingControl:1", "SetVolume")
+      .arg("InstanceID", "0")
+      .arg("Channel", "Master")
+      .arg("DesiredVolume", "50")
+      .envelope();
+
+    let instance = envelope.find("<InstanceID>").unwrap();
+    let channel = envelope.find("<Channel>").unwrap();
+    let volume = envelope.find("<DesiredVolume>").unwrap();
+    assert!(instance < channel && channel < volume);
+  }
+
+  #[test]
+  fn test_soap_action_header() {
+    let action = SoapAction::new("urn:schemas-upnp-org:service:AVTransport:1", "Play");
+
+    assert_eq!(action.soap_action_header(), "\"urn:schemas-upnp-org:service:AVTransport:1#Play\"");
+    assert_eq!(action.name(), "Play");
+  }
+
+  /// Drops the first `failures` connections without answering, then replies 200
+  fn spawn_flaky_server(failures: usize) -> (u16, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+    use std::io::{Read, Write};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let connections = std::sync::Arc::new(AtomicUsize::new(0));
+    let counter = connections.clone();
+
+    std::thread::spawn(move || {
+      for mut stream in listener.incoming().flatten() {
+        let mut buf = [0u8; 4096];
+        let _ = stream.read(&mut buf);
+        if counter.fetch_add(1, Ordering::SeqCst) < failures {
+          continue;
+        }
+        let body = "<s:Envelope><s:Body><u:GetVolumeResponse><CurrentVolume>12</CurrentVolume></u:GetVolumeResponse></s:Body></s:Envelope>";
+        let _ = stream.write_all(
+          format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body).as_bytes(),
+        );
+      }
+    });
+
+    (port, connections)
+  }
+
+  fn request(action: &str) -> SoapRequest {
+    SoapRequest {
+      service_type: "urn:schemas-upnp-org:service:RenderingControl:1".to_string(),
+      action: action.to_string(),
+      params: vec![("InstanceID".to_string(), "0".to_string())],
+    }
+  }
+
+  #[test]
+  fn test_idempotent_action_retries_until_success() {
+    let (port, connections) = spawn_flaky_server(2);
+    let client = SoapClient::with_retry(
+      std::time::Duration::from_secs(2),
+      3,
+      std::time::Duration::from_millis(10),
+    )
+    .unwrap();
+
+    let response = client
+      .call(&format!("http://127.0.0.1:{}", port), "/MediaRenderer/RenderingControl/Control", request("GetVolume"))
+      .unwrap();
+
+    assert_eq!(SoapClient::extract_xml_value(&response.body, "CurrentVolume"), Some("12".to_string()));
+    assert_eq!(connections.load(std::sync::atomic::Ordering::SeqCst), 3);
+  }
+
+  #[test]
+  fn test_non_idempotent_action_is_not_retried() {
+    let (port, connections) = spawn_flaky_server(2);
+    let client = SoapClient::with_retry(
+      std::time::Duration::from_secs(2),
+      3,
+      std::time::Duration::from_millis(10),
+    )
+    .unwrap();
+
+    let result = client.call(
+      &format!("http://127.0.0.1:{}", port),
+      "/MediaRenderer/AVTransport/Control",
+      request("AddURIToQueue"),
+    );
+
+    assert!(matches!(result, Err(crate::error::SonosError::CommunicationError(_))));
+    assert_eq!(connections.load(std::sync::atomic::Ordering::SeqCst), 1);
+  }
+
+  #[test]
+  fn test_is_idempotent() {
+    assert!(SoapClient::is_idempotent("GetVolume"));
+    assert!(SoapClient::is_idempotent("GetPositionInfo"));
+    assert!(SoapClient::is_idempotent("Browse"));
+    assert!(!SoapClient::is_idempotent("AddURIToQueue"));
+    assert!(!SoapClient::is_idempotent("Play"));
+  }
+
   #[test]
   fn test_extract_fault_string() {
     let fault_response = r#"<?xml version="1.0"?>
@@ -165,6 +488,60 @@ mod tests {
     assert_eq!(fault, "Unknown SOAP fault");
   }
 
+  #[test]
+  fn test_parse_fault_with_upnp_error() {
+    let fault_response = r#"<?xml version="1.0"?>
+      <s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/" s:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/">
+        <s:Body>
+          <s:Fault>
+            <faultcode>s:Client</faultcode>
+            <faultstring>UPnPError</faultstring>
+            <detail>
+              <UPnPError xmlns="urn:schemas-upnp-org:control-1-0">
+                <errorCode>701</errorCode>
+              </UPnPError>
+            </detail>
+          </s:Fault>
+        </s:Body>
+      </s:Envelope>"#;
+
+    match SoapClient::parse_fault(fault_response) {
+      crate::error::SonosError::UpnpFault { code, description } => {
+        assert_eq!(code, 701);
+        assert_eq!(description, "Transition not available");
+      }
+      other => panic!("Expected UpnpFault, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_parse_fault_prefers_error_description() {
+    let fault_response = "<s:Fault><faultstring>UPnPError</faultstring><detail><UPnPError><errorCode>718</errorCode><errorDescription>Invalid InstanceID 3</errorDescription></UPnPError></detail></s:Fault>";
+
+    assert!(matches!(
+      SoapClient::parse_fault(fault_response),
+      crate::error::SonosError::UpnpFault { code: 718, ref description } if description == "Invalid InstanceID 3"
+    ));
+  }
+
+  #[test]
+  fn test_parse_fault_without_error_code() {
+    let fault_response = "<s:Fault><faultcode>s:Client</faultcode><faultstring>Invalid Volume</faultstring></s:Fault>";
+
+    assert!(matches!(
+      SoapClient::parse_fault(fault_response),
+      crate::error::SonosError::SoapFault(ref fault) if fault == "Invalid Volume"
+    ));
+  }
+
+  #[test]
+  fn test_common_upnp_error_descriptions() {
+    assert_eq!(upnp_error_description(402), Some("Invalid args"));
+    assert_eq!(upnp_error_description(701), Some("Transition not available"));
+    assert_eq!(upnp_error_description(718), Some("Invalid InstanceID"));
+    assert_eq!(upnp_error_description(999), None);
+  }
+
   #[test]
   fn test_extract_xml_value() {
     let xml = "<response><volume>75</volume><muted>1</muted></response>";
@@ -202,7 +579,7 @@ mod tests {
       ],
     };
 
-    let envelope = SoapClient::build_soap_envelope(&request);
+    let envelope = SoapAction::from(request).envelope();
 
     assert!(envelope.contains("xmlns:u=\"urn:schemas-upnp-org:service:AVTransport:1\""));
     assert!(envelope.contains("<u:Play"));
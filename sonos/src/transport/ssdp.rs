@@ -1,7 +1,14 @@
-use std::net::UdpSocket;
-use std::time::Duration;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket};
+use std::time::{Duration, Instant};
 use std::io::{Error, ErrorKind};
 
+/// The SSDP multicast group and port M-SEARCH requests are sent to
+pub const SSDP_MULTICAST_ADDR: SocketAddr =
+  SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(239, 255, 255, 250), 1900));
+
+/// Default `MX:` value, the number of seconds devices may delay their response
+pub const DEFAULT_MX: u8 = 2;
+
 /// SSDP response containing device information
 #[derive(Debug, Clone, PartialEq)]
 pub struct SsdpResponse {
@@ -27,38 +34,88 @@ impl SsdpClient {
     Ok(Self { socket, timeout })
   }
 
+  /// Create an SSDP client whose socket is bound to a specific local interface,
+  /// so the M-SEARCH multicast leaves through that interface
+  pub fn bind(local_ip: IpAddr, timeout: Duration) -> Result<Self, Error> {
+    let IpAddr::V4(local_v4) = local_ip else {
+      return Err(Error::new(ErrorKind::InvalidInput, "SSDP discovery requires an IPv4 interface address"));
+    };
+
+    let socket = socket2::Socket::new(socket2::Domain::IPV4, socket2::Type::DGRAM, Some(socket2::Protocol::UDP))?;
+    socket.bind(&SocketAddr::new(local_ip, 0).into())?;
+    socket.set_multicast_if_v4(&local_v4)?;
+
+    let socket: UdpSocket = socket.into();
+    socket.set_read_timeout(Some(timeout))?;
+    socket.set_multicast_loop_v4(true)?;
+
+    Ok(Self { socket, timeout })
+  }
+
+  /// The local address the client socket is bound to
+  pub fn local_addr(&self) -> Result<SocketAddr, Error> {
+    self.socket.local_addr()
+  }
+
   /// Send an M-SEARCH request and return an iterator of responses
   pub fn search(&self, search_target: &str) -> Result<SsdpResponseIterator, Error> {
-    let request = format!(
-      "M-SEARCH * HTTP/1.1\r\n\
-        HOST: 239.255.255.250:1900\r\n\
-        MAN: \"ssdp:discover\"\r\n\
-        MX: 2\r\n\
-        ST: {}\r\n\
-        USER-AGENT: sonos-rs/1.0 UPnP/1.0\r\n\
-        \r\n",
-      search_target
-    );
-
-    self.socket.send_to(request.as_bytes(), "239.255.255.250:1900")?;
+    self.send_search(search_target, DEFAULT_MX, SSDP_MULTICAST_ADDR)?;
     
-    Ok(SsdpResponseIterator::new(&self.socket))
+    Ok(SsdpResponseIterator::new(&self.socket, None))
+  }
+
+  /// Send a single M-SEARCH request to `search_addr` without waiting for responses
+  pub fn send_search(&self, search_target: &str, mx: u8, search_addr: SocketAddr) -> Result<(), Error> {
+    let request = m_search_request(search_target, mx);
+
+    self.socket.send_to(request.as_bytes(), search_addr)?;
+
+    Ok(())
+  }
+
+  /// Iterate over responses received before `deadline`
+  pub fn responses_until(&self, deadline: Instant) -> SsdpResponseIterator<'_> {
+    SsdpResponseIterator::new(&self.socket, Some(deadline))
+  }
+
+  /// Convert into a non-blocking tokio socket for async discovery
+  ///
+  /// Must be called from within a tokio runtime.
+  pub fn into_async(self) -> Result<tokio::net::UdpSocket, Error> {
+    self.socket.set_nonblocking(true)?;
+    tokio::net::UdpSocket::from_std(self.socket)
   }
 }
 
+/// Build an M-SEARCH request for the given search target
+pub(crate) fn m_search_request(search_target: &str, mx: u8) -> String {
+  format!(
+    "M-SEARCH * HTTP/1.1\r\n\
+      HOST: 239.255.255.250:1900\r\n\
+      MAN: \"ssdp:discover\"\r\n\
+      MX: {}\r\n\
+      ST: {}\r\n\
+      USER-AGENT: sonos-rs/1.0 UPnP/1.0\r\n\
+      \r\n",
+    mx, search_target
+  )
+}
+
 /// Iterator for SSDP responses
 pub struct SsdpResponseIterator<'a> {
   socket: &'a UdpSocket,
   buffer: [u8; 2048],
   finished: bool,
+  deadline: Option<Instant>,
 }
 
 impl<'a> SsdpResponseIterator<'a> {
-  fn new(socket: &'a UdpSocket) -> Self {
+  fn new(socket: &'a UdpSocket, deadline: Option<Instant>) -> Self {
     Self {
       socket,
       buffer: [0; 2048],
       finished: false,
+      deadline,
     }
   }
 }
@@ -71,6 +128,18 @@ impl<'a> Iterator for SsdpResponseIterator<'a> {
         return None;
     }
 
+    // Bound the read so the iterator ends at the deadline even while responses keep arriving
+    if let Some(deadline) = self.deadline {
+      let remaining = deadline.saturating_duration_since(Instant::now());
+      if remaining.is_zero() {
+        self.finished = true;
+        return None;
+      }
+      if let Err(e) = self.socket.set_read_timeout(Some(remaining)) {
+        return Some(Err(e));
+      }
+    }
+
     match self.socket.recv_from(&mut self.buffer) {
       Ok((size, _)) => {
         match std::str::from_utf8(&self.buffer[..size]) {
@@ -102,7 +171,7 @@ impl<'a> Iterator for SsdpResponseIterator<'a> {
 }
 
 /// Parse an SSDP response from HTTP text
-fn parse_ssdp_response(response: &str) -> Option<SsdpResponse> {
+pub(crate) fn parse_ssdp_response(response: &str) -> Option<SsdpResponse> {
   let mut location = None;
   let mut urn = None;
   let mut usn = None;
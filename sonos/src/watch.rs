@@ -0,0 +1,64 @@
+//! One-call setup for monitoring every speaker on the network
+
+use std::time::Duration;
+
+use crate::error::{Result, SonosError};
+use crate::model::{Speaker, StateChange};
+use crate::streaming::{ActiveEventStream, EventStreamBuilder};
+use crate::transport::discovery::discover_speakers_with_timeout;
+
+/// Discover speakers and start streaming their events to `handler`
+///
+/// Listens for `timeout` for speakers to answer discovery, then starts an
+/// event stream over all of them with the default services. The returned
+/// stream keeps running until it is shut down or dropped, so hold on to it.
+///
+/// For a state cache, lifecycle callbacks or other services, use
+/// [`discover_speakers_with_timeout`] and [`EventStreamBuilder`] directly.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+///
+/// let stream = sonos::watch_system(Duration::from_secs(3), |event| {
+///     println!("{:?}", event);
+/// })?;
+///
+/// std::thread::sleep(Duration::from_secs(60));
+/// stream.shutdown()?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn watch_system<F>(timeout: Duration, handler: F) -> Result<ActiveEventStream>
+where
+    F: Fn(StateChange) + Send + Sync + 'static,
+{
+    let speakers = discover_speakers_with_timeout(timeout)?;
+    watch_speakers(speakers, handler, |builder| builder)
+}
+
+/// Start a stream over `speakers`, letting `configure` adjust the builder first
+///
+/// [`watch_system`] without the discovery step, for callers that already know
+/// their speakers. If no speaker could be subscribed to, the stream's own
+/// error comes back as [`SonosError::StreamFailed`].
+pub fn watch_speakers<F>(
+    speakers: Vec<Speaker>,
+    handler: F,
+    configure: impl FnOnce(EventStreamBuilder) -> EventStreamBuilder,
+) -> Result<ActiveEventStream>
+where
+    F: Fn(StateChange) + Send + Sync + 'static,
+{
+    if speakers.is_empty() {
+        return Err(SonosError::DeviceNotFound("No Sonos speakers found on the network".to_string()));
+    }
+
+    let builder = EventStreamBuilder::new(speakers)
+        .map_err(|e| SonosError::InvalidArgument(e.to_string()))?
+        .with_event_handler(handler);
+
+    configure(builder)
+        .start()
+        .map_err(SonosError::StreamFailed)
+}
@@ -35,6 +35,11 @@ pub mod xml_decode {
   {
     let encoded = String::deserialize(deserializer)?;
 
+    // Prefer the value as delivered when it is well-formed; see deserialize_nested_safe
+    if let Ok(result) = quick_xml::de::from_str(&strip_namespaces(&encoded)) {
+      return Ok(result);
+    }
+
     let decoded = decode_entities(&encoded);
 
     let cleaned_decoded = clean_xml(&decoded);
@@ -55,6 +60,12 @@ pub mod xml_decode {
       return Ok(None);
     }
 
+    // Attribute values arrive unescaped once already; only decode further when
+    // that is not enough, so escaped ampersands in URIs survive
+    if let Ok(result) = quick_xml::de::from_str(&strip_namespaces(&encoded)) {
+      return Ok(Some(result));
+    }
+
     let decoded = decode_entities(&encoded);
     let cleaned_decoded = clean_xml(&decoded);
 
@@ -95,7 +106,19 @@ pub mod xml_decode {
   }
 
   /// Strip all XML namespaces automatically - works with any XML structure
+  ///
+  /// Attribute values are escaped again on the way out, which the lenient
+  /// `decode_entities` path relies on.
   fn clean_xml(xml: &str) -> String {
+    rewrite_without_namespaces(xml, false)
+  }
+
+  /// Strip namespaces, copying attribute values exactly as they were escaped
+  fn strip_namespaces(xml: &str) -> String {
+    rewrite_without_namespaces(xml, true)
+  }
+
+  fn rewrite_without_namespaces(xml: &str, raw_attributes: bool) -> String {
     let mut reader = Reader::from_str(xml);
     reader.config_mut().trim_text(true);
     
@@ -108,7 +131,7 @@ pub mod xml_decode {
           let local_name = e.local_name();
           let name_str = std::str::from_utf8(local_name.as_ref()).unwrap();
           let mut elem = BytesStart::new(name_str);
-          copy_non_namespace_attributes(&e, &mut elem);
+          copy_non_namespace_attributes(&e, &mut elem, raw_attributes);
           writer.write_event(Event::Start(elem)).unwrap();
         }
         Ok(Event::End(e)) => {
@@ -121,7 +144,7 @@ pub mod xml_decode {
           let local_name = e.local_name();
           let name_str = std::str::from_utf8(local_name.as_ref()).unwrap();
           let mut elem = BytesStart::new(name_str);
-          copy_non_namespace_attributes(&e, &mut elem);
+          copy_non_namespace_attributes(&e, &mut elem, raw_attributes);
           writer.write_event(Event::Empty(elem)).unwrap();
         }
         Ok(Event::Eof) => break,
@@ -138,7 +161,7 @@ pub mod xml_decode {
   }
 
   /// Helper to copy attributes while filtering out namespace declarations
-  fn copy_non_namespace_attributes(source: &BytesStart, target: &mut BytesStart) {
+  fn copy_non_namespace_attributes(source: &BytesStart, target: &mut BytesStart, raw: bool) {
     for attr_result in source.attributes() {
       if let Ok(attr) = attr_result {
         let key = attr.key;
@@ -150,8 +173,12 @@ pub mod xml_decode {
           continue;
         }
         
-        let value_str = std::str::from_utf8(attr.value.as_ref()).unwrap();
-        target.push_attribute((key_str, value_str));
+        if raw {
+          target.push_attribute((key_str.as_bytes(), attr.value.as_ref()));
+        } else {
+          let value_str = std::str::from_utf8(attr.value.as_ref()).unwrap();
+          target.push_attribute((key_str, value_str));
+        }
       }
     }
   }
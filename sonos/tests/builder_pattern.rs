@@ -18,6 +18,9 @@ fn create_test_speaker(udn: &str, name: &str, ip: &str) -> Speaker {
         port: 1400,
         model_name: "PLAY:1".to_string(),
         satellites: vec![],
+        supported_services: vec![],
+        software_version: None,
+        hardware_version: None,
     }
 }
 
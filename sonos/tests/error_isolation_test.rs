@@ -83,6 +83,9 @@ mod integration_tests {
             port: 1400,
             model_name: "Test Model".to_string(),
             satellites: vec![],
+            supported_services: vec![],
+            software_version: None,
+            hardware_version: None,
         };
 
         // Verify speaker structure is correct
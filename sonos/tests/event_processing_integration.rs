@@ -1,4 +1,4 @@
-use sonos::model::{PlaybackState, Speaker, SpeakerId, StateChange, TrackInfo, TransportStatus};
+use sonos::model::{PlaybackState, Speaker, SpeakerId, StateChange, TrackInfo, TrackSourceKind, TransportStatus};
 use sonos::state::StateCache;
 use sonos::streaming::{EventStreamBuilder, LifecycleHandlers, ServiceType, StreamError};
 use std::sync::{Arc, Mutex};
@@ -9,13 +9,15 @@ use std::time::Duration;
 fn create_test_speaker(udn: &str, name: &str, ip: &str) -> Speaker {
     Speaker {
         id: SpeakerId::from_udn(udn),
-        udn: udn.to_string(),
         name: name.to_string(),
         room_name: format!("{} Room", name),
         ip_address: ip.to_string(),
         port: 1400,
         model_name: "PLAY:1".to_string(),
         satellites: vec![],
+        supported_services: vec![],
+        software_version: None,
+        hardware_version: None,
     }
 }
 
@@ -23,20 +25,21 @@ fn create_test_speaker(udn: &str, name: &str, ip: &str) -> Speaker {
 fn _create_test_events(speaker_id: SpeakerId) -> Vec<StateChange> {
     vec![
         StateChange::PlaybackStateChanged {
-            speaker_id,
+            speaker_id: speaker_id.clone(),
             state: PlaybackState::Playing,
         },
         StateChange::VolumeChanged {
-            speaker_id,
+            speaker_id: speaker_id.clone(),
             volume: 75,
         },
         StateChange::MuteChanged {
-            speaker_id,
+            speaker_id: speaker_id.clone(),
             muted: false,
         },
         StateChange::PositionChanged {
-            speaker_id,
-            position_ms: 45000,
+            speaker_id: speaker_id.clone(),
+            position: Duration::from_millis(45000),
+            duration: Duration::from_secs(180),
         },
         StateChange::TrackChanged {
             speaker_id,
@@ -46,6 +49,9 @@ fn _create_test_events(speaker_id: SpeakerId) -> Vec<StateChange> {
                 album: Some("Test Album".to_string()),
                 duration_ms: Some(180000),
                 uri: None,
+                album_art_uri: None,
+                source_kind: TrackSourceKind::Unknown,
+                duration: Some(Duration::from_secs(180)),
             }),
         },
     ]
@@ -121,12 +127,12 @@ fn test_lifecycle_callback_registration() {
 
     let lifecycle_handlers = LifecycleHandlers::new()
         .with_speaker_connected(move |speaker_id| {
-            connected_calls_clone.lock().unwrap().push(speaker_id);
             println!("Speaker connected: {:?}", speaker_id);
+            connected_calls_clone.lock().unwrap().push(speaker_id);
         })
         .with_speaker_disconnected(move |speaker_id| {
-            disconnected_calls_clone.lock().unwrap().push(speaker_id);
             println!("Speaker disconnected: {:?}", speaker_id);
+            disconnected_calls_clone.lock().unwrap().push(speaker_id);
         })
         .with_error(move |error| {
             error_calls_clone
@@ -467,15 +473,15 @@ fn test_state_cache_update_patterns() {
     // Test different update patterns that the event processing should handle
     let _test_events = vec![
         StateChange::PlaybackStateChanged {
-            speaker_id: speakers[0].id,
+            speaker_id: speakers[0].id.clone(),
             state: PlaybackState::Playing,
         },
         StateChange::VolumeChanged {
-            speaker_id: speakers[0].id,
+            speaker_id: speakers[0].id.clone(),
             volume: 50,
         },
         StateChange::MuteChanged {
-            speaker_id: speakers[1].id,
+            speaker_id: speakers[1].id.clone(),
             muted: true,
         },
     ];
@@ -17,6 +17,9 @@ fn test_non_blocking_event_processing() {
         port: 1400,
         model_name: "Test Model".to_string(),
         satellites: vec![],
+        supported_services: vec![],
+        software_version: None,
+        hardware_version: None,
     };
 
     // Counter to track events processed
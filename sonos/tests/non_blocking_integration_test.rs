@@ -19,6 +19,9 @@ fn test_event_processing_non_blocking_integration() {
         port: 1400,
         model_name: "Test Model".to_string(),
         satellites: vec![],
+        supported_services: vec![],
+        software_version: None,
+        hardware_version: None,
     };
 
     let state_cache = Arc::new(StateCache::new());
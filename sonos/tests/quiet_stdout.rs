@@ -0,0 +1,111 @@
+use sonos::streaming::{EventStreamBuilder, ServiceType};
+use sonos::{Speaker, SpeakerId};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::process::Command;
+use std::time::Duration;
+
+const CHILD_ENV: &str = "SONOS_QUIET_STDOUT_CHILD";
+const BEGIN_MARKER: &str = "<<subscribe-begin>>";
+const END_MARKER: &str = "<<subscribe-end>>";
+
+fn speaker(id: &str, port: u16) -> Speaker {
+    Speaker {
+        id: SpeakerId::new(id),
+        name: id.to_string(),
+        room_name: id.to_string(),
+        ip_address: "127.0.0.1".to_string(),
+        port,
+        model_name: "Test Model".to_string(),
+        satellites: vec![],
+        supported_services: vec![],
+        software_version: None,
+        hardware_version: None,
+    }
+}
+
+/// Accepts every SUBSCRIBE/UNSUBSCRIBE like a real speaker would
+fn spawn_subscribe_server() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf);
+            let _ = stream.write_all(
+                b"HTTP/1.1 200 OK\r\nSID: uuid:test-sid\r\nTIMEOUT: Second-1800\r\nContent-Length: 0\r\n\r\n",
+            );
+        }
+    });
+    port
+}
+
+fn closed_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+/// Runs a subscribe attempt against one reachable and one unreachable speaker
+/// between two markers, so the parent can check nothing else hit stdout.
+fn subscribe_attempt() {
+    let speakers = vec![
+        speaker("uuid:RINCON_REACHABLE::1", spawn_subscribe_server()),
+        speaker("uuid:RINCON_UNREACHABLE::1", closed_port()),
+    ];
+
+    println!("{}", BEGIN_MARKER);
+    std::io::stdout().flush().unwrap();
+
+    let result = EventStreamBuilder::new(speakers)
+        .unwrap()
+        .with_services(&[
+            ServiceType::AVTransport,
+            ServiceType::RenderingControl,
+            ServiceType::ZoneGroupTopology,
+        ])
+        .with_timeouts(Duration::from_secs(1800), Duration::from_millis(10))
+        .with_callback_ports(38450, 38460)
+        .start();
+    if let Ok(stream) = result {
+        let _ = stream.shutdown();
+    }
+
+    std::io::stdout().flush().unwrap();
+    println!("{}", END_MARKER);
+}
+
+#[test]
+fn test_subscribe_attempt_writes_nothing_to_stdout() {
+    if std::env::var_os(CHILD_ENV).is_some() {
+        subscribe_attempt();
+        return;
+    }
+
+    // Re-run this test in a child process without output capture so that any
+    // println! from the library ends up on the child's real stdout.
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args([
+            "test_subscribe_attempt_writes_nothing_to_stdout",
+            "--exact",
+            "--nocapture",
+            "--test-threads=1",
+        ])
+        .env(CHILD_ENV, "1")
+        .output()
+        .expect("failed to re-run test binary");
+    assert!(output.status.success(), "child test run failed: {:?}", output);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let begin = stdout.find(BEGIN_MARKER).expect("missing begin marker") + BEGIN_MARKER.len();
+    let end = stdout.find(END_MARKER).expect("missing end marker");
+    let between = stdout[begin..end].trim();
+
+    assert!(
+        between.is_empty(),
+        "subscribe attempt wrote to stdout:\n{}",
+        between
+    );
+}
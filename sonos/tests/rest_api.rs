@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use serde_json::{json, Value};
+use sonos::integrations::http::RestServer;
+use sonos::{Speaker, SpeakerId, StateCache};
+
+const SPEAKER_ID: &str = "RINCON_000E58A0123401400";
+
+/// A request captured by the mock speaker
+#[derive(Debug, Clone)]
+struct RecordedRequest {
+  headers: HashMap<String, String>,
+  body: String,
+}
+
+/// Minimal speaker that records each SOAP request and replies with a canned response
+struct MockSpeaker {
+  port: u16,
+  requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockSpeaker {
+  fn start(status: u16, response_body: &'static str) -> Self {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+
+    thread::spawn(move || {
+      for stream in listener.incoming() {
+        let Ok(mut stream) = stream else { break };
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+
+        let mut headers = HashMap::new();
+        loop {
+          let mut line = String::new();
+          reader.read_line(&mut line).unwrap();
+          let line = line.trim_end();
+          if line.is_empty() {
+            break;
+          }
+          if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+          }
+        }
+
+        let length = headers
+          .get("content-length")
+          .and_then(|v| v.parse::<usize>().ok())
+          .unwrap_or(0);
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+
+        recorded.lock().unwrap().push(RecordedRequest {
+          headers,
+          body: String::from_utf8_lossy(&body).to_string(),
+        });
+
+        let response = format!(
+          "HTTP/1.1 {} Status\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+          status,
+          response_body.len(),
+          response_body
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+      }
+    });
+
+    Self { port, requests }
+  }
+
+  fn speaker(&self) -> Speaker {
+    Speaker {
+      id: SpeakerId::new(SPEAKER_ID),
+      name: "Mock".to_string(),
+      room_name: "Mock Room".to_string(),
+      ip_address: "127.0.0.1".to_string(),
+      port: self.port,
+      model_name: "Sonos One".to_string(),
+      satellites: vec![],
+      supported_services: vec![],
+      software_version: None,
+      hardware_version: None,
+    }
+  }
+
+  fn requests(&self) -> Vec<RecordedRequest> {
+    self.requests.lock().unwrap().clone()
+  }
+}
+
+/// REST server over a cache holding `speakers`
+fn start_server(speakers: Vec<Speaker>) -> (RestServer, SocketAddr, StateCache) {
+  let cache = StateCache::new();
+  cache.initialize(speakers, vec![]);
+  let mut server = RestServer::new(cache.clone());
+  let address = server.start("127.0.0.1:0".parse().unwrap()).unwrap();
+  (server, address, cache)
+}
+
+fn get(address: SocketAddr, path: &str) -> (u16, Value) {
+  let response = reqwest::blocking::get(format!("http://{}{}", address, path)).unwrap();
+  let status = response.status().as_u16();
+  (status, serde_json::from_str(&response.text().unwrap()).unwrap_or(Value::Null))
+}
+
+fn post(address: SocketAddr, path: &str, body: &str) -> (u16, Value) {
+  let response = reqwest::blocking::Client::new()
+    .post(format!("http://{}{}", address, path))
+    .header("Content-Type", "application/json")
+    .body(body.to_string())
+    .send()
+    .unwrap();
+  let status = response.status().as_u16();
+  (status, serde_json::from_str(&response.text().unwrap()).unwrap_or(Value::Null))
+}
+
+#[test]
+fn test_get_speakers_lists_cached_speakers() {
+  let speaker = MockSpeaker::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let mut kitchen = speaker.speaker();
+  kitchen.id = SpeakerId::new("RINCON_347E5C0F2D3A01400");
+  kitchen.room_name = "Kitchen".to_string();
+  let (_server, address, _cache) = start_server(vec![speaker.speaker(), kitchen]);
+
+  let (status, body) = get(address, "/speakers");
+
+  assert_eq!(status, 200);
+  let rooms: Vec<&str> = body
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|s| s["speaker"]["room_name"].as_str().unwrap())
+    .collect();
+  assert_eq!(rooms, vec!["Kitchen", "Mock Room"]);
+  assert!(speaker.requests().is_empty());
+}
+
+#[test]
+fn test_get_speaker_state_returns_cached_state() {
+  let speaker = MockSpeaker::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let (_server, address, cache) = start_server(vec![speaker.speaker()]);
+  cache.update_volume(&SpeakerId::new(SPEAKER_ID), 25);
+
+  let (status, body) = get(address, &format!("/speakers/{}/state", SPEAKER_ID));
+
+  assert_eq!(status, 200);
+  assert_eq!(body["speaker"]["id"], SPEAKER_ID);
+  assert_eq!(body["volume"], 25);
+}
+
+#[test]
+fn test_unknown_speaker_is_not_found() {
+  let (_server, address, _cache) = start_server(vec![]);
+
+  let (status, body) = get(address, "/speakers/RINCON_FFFFFFFFFFFF01400/state");
+  assert_eq!(status, 404);
+  assert!(body["error"].as_str().unwrap().contains("RINCON_FFFFFFFFFFFF01400"));
+
+  let (status, _) = post(address, "/speakers/RINCON_FFFFFFFFFFFF01400/play", "");
+  assert_eq!(status, 404);
+}
+
+#[test]
+fn test_post_play_and_pause_control_speaker_and_update_state() {
+  let speaker = MockSpeaker::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let (_server, address, _cache) = start_server(vec![speaker.speaker()]);
+
+  let (status, body) = post(address, &format!("/speakers/{}/play", SPEAKER_ID), "");
+  assert_eq!(status, 200);
+  assert_eq!(body["playback_state"], json!("Playing"));
+
+  let (status, body) = post(address, &format!("/speakers/{}/pause", SPEAKER_ID), "");
+  assert_eq!(status, 200);
+  assert_eq!(body["playback_state"], json!("Paused"));
+
+  let actions: Vec<String> = speaker
+    .requests()
+    .iter()
+    .map(|r| r.headers.get("soapaction").unwrap().clone())
+    .collect();
+  assert_eq!(
+    actions,
+    vec![
+      "\"urn:schemas-upnp-org:service:AVTransport:1#Play\"",
+      "\"urn:schemas-upnp-org:service:AVTransport:1#Pause\"",
+    ]
+  );
+}
+
+#[test]
+fn test_post_volume_sets_speaker_volume() {
+  let speaker = MockSpeaker::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let (_server, address, _cache) = start_server(vec![speaker.speaker()]);
+
+  let (status, body) = post(address, &format!("/speakers/{}/volume", SPEAKER_ID), r#"{"volume": 30}"#);
+
+  assert_eq!(status, 200);
+  assert_eq!(body["volume"], 30);
+  let requests = speaker.requests();
+  assert_eq!(requests.len(), 1);
+  assert!(requests[0].body.contains("<DesiredVolume>30</DesiredVolume>"));
+}
+
+#[test]
+fn test_post_volume_without_volume_is_bad_request() {
+  let speaker = MockSpeaker::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let (_server, address, _cache) = start_server(vec![speaker.speaker()]);
+
+  let (status, body) = post(address, &format!("/speakers/{}/volume", SPEAKER_ID), r#"{"level": 30}"#);
+
+  assert_eq!(status, 400);
+  assert!(body["error"].is_string());
+  assert!(speaker.requests().is_empty());
+}
+
+#[test]
+fn test_unknown_action_is_not_found() {
+  let speaker = MockSpeaker::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let (_server, address, _cache) = start_server(vec![speaker.speaker()]);
+
+  let (status, _) = post(address, &format!("/speakers/{}/reboot", SPEAKER_ID), "");
+
+  assert_eq!(status, 404);
+  assert!(speaker.requests().is_empty());
+}
+
+#[test]
+fn test_speaker_fault_is_bad_gateway() {
+  let speaker = MockSpeaker::start(
+    500,
+    "<s:Envelope><s:Body><s:Fault><faultcode>s:Client</faultcode><faultstring>UPnPError</faultstring>\
+     <detail><UPnPError><errorCode>701</errorCode></UPnPError></detail></s:Fault></s:Body></s:Envelope>",
+  );
+  let (_server, address, cache) = start_server(vec![speaker.speaker()]);
+
+  let (status, body) = post(address, &format!("/speakers/{}/play", SPEAKER_ID), "");
+
+  assert_eq!(status, 502);
+  assert!(body["error"].is_string());
+  let state = cache.get_speaker(&SpeakerId::new(SPEAKER_ID)).unwrap();
+  assert_eq!(state.playback_state, sonos::PlaybackState::Stopped);
+}
+
+#[test]
+fn test_shutdown_stops_listening() {
+  let (mut server, address, _cache) = start_server(vec![]);
+
+  server.shutdown().unwrap();
+
+  assert!(reqwest::blocking::get(format!("http://{}/speakers", address)).is_err());
+}
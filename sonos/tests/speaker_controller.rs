@@ -0,0 +1,1356 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use sonos::group::{Group, SpeakerRef};
+use sonos::model::{Favorite, Playlist, PowerSource};
+use sonos::{GroupId, PlayMode, PlaybackState, SonosError, Speaker, SpeakerController, SpeakerId, Topology};
+
+/// A request captured by the mock SOAP server
+#[derive(Debug, Clone)]
+struct RecordedRequest {
+  path: String,
+  headers: HashMap<String, String>,
+  body: String,
+}
+
+/// Minimal HTTP server that records each request and replies with a canned response
+struct MockSoapServer {
+  port: u16,
+  requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockSoapServer {
+  fn start(status: u16, response_body: &'static str) -> Self {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+
+    thread::spawn(move || {
+      for stream in listener.incoming() {
+        let Ok(mut stream) = stream else { break };
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let path = request_line.split_whitespace().nth(1).unwrap_or("").to_string();
+
+        let mut headers = HashMap::new();
+        loop {
+          let mut line = String::new();
+          reader.read_line(&mut line).unwrap();
+          let line = line.trim_end();
+          if line.is_empty() {
+            break;
+          }
+          if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+          }
+        }
+
+        let length = headers
+          .get("content-length")
+          .and_then(|v| v.parse::<usize>().ok())
+          .unwrap_or(0);
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+
+        recorded.lock().unwrap().push(RecordedRequest {
+          path,
+          headers,
+          body: String::from_utf8_lossy(&body).to_string(),
+        });
+
+        let response = format!(
+          "HTTP/1.1 {} Status\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+          status,
+          response_body.len(),
+          response_body
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+      }
+    });
+
+    Self { port, requests }
+  }
+
+  fn speaker(&self) -> Speaker {
+    Speaker {
+      id: SpeakerId::new("uuid:RINCON_000E58A0123401400"),
+      name: "Mock".to_string(),
+      room_name: "Mock Room".to_string(),
+      ip_address: "127.0.0.1".to_string(),
+      port: self.port,
+      model_name: "Sonos One".to_string(),
+      satellites: vec![],
+      supported_services: vec![],
+      software_version: None,
+      hardware_version: None,
+    }
+  }
+
+  fn requests(&self) -> Vec<RecordedRequest> {
+    self.requests.lock().unwrap().clone()
+  }
+}
+
+const AV_TRANSPORT_URN: &str = "urn:schemas-upnp-org:service:AVTransport:1";
+
+#[test]
+fn test_play_sends_av_transport_action() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body><u:PlayResponse/></s:Body></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.play().unwrap();
+
+  let requests = server.requests();
+  assert_eq!(requests.len(), 1);
+  let request = &requests[0];
+  assert_eq!(request.path, "/MediaRenderer/AVTransport/Control");
+  assert_eq!(
+    request.headers.get("soapaction").unwrap(),
+    &format!("\"{}#Play\"", AV_TRANSPORT_URN)
+  );
+  assert!(request.body.contains(&format!("<u:Play xmlns:u=\"{}\">", AV_TRANSPORT_URN)));
+  assert!(request.body.contains("<InstanceID>0</InstanceID>"));
+  assert!(request.body.contains("<Speed>1</Speed>"));
+}
+
+#[test]
+fn test_transport_actions_use_expected_soap_actions() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.pause().unwrap();
+  controller.stop().unwrap();
+  controller.next().unwrap();
+  controller.previous().unwrap();
+
+  let actions: Vec<String> = server
+    .requests()
+    .iter()
+    .map(|r| r.headers.get("soapaction").unwrap().clone())
+    .collect();
+  assert_eq!(
+    actions,
+    vec![
+      format!("\"{}#Pause\"", AV_TRANSPORT_URN),
+      format!("\"{}#Stop\"", AV_TRANSPORT_URN),
+      format!("\"{}#Next\"", AV_TRANSPORT_URN),
+      format!("\"{}#Previous\"", AV_TRANSPORT_URN),
+    ]
+  );
+
+  for request in server.requests() {
+    assert!(request.body.contains("<InstanceID>0</InstanceID>"));
+    assert!(!request.body.contains("<Speed>"));
+  }
+}
+
+#[test]
+fn test_non_success_status_is_an_error() {
+  let server = MockSoapServer::start(500, "Internal Server Error");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  match controller.pause() {
+    Err(SonosError::CommunicationError(message)) => assert!(message.contains("500")),
+    other => panic!("Expected CommunicationError, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_service_unavailable_is_a_satellite_speaker() {
+  let server = MockSoapServer::start(503, "Service Unavailable");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  match controller.pause() {
+    Err(SonosError::SatelliteSpeaker(message)) => assert!(message.contains("Pause")),
+    other => panic!("Expected SatelliteSpeaker, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_soap_fault_is_surfaced() {
+  let server = MockSoapServer::start(
+    500,
+    "<s:Envelope><s:Body><s:Fault><faultcode>s:Client</faultcode><faultstring>UPnPError</faultstring></s:Fault></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  match controller.next() {
+    Err(SonosError::SoapFault(fault)) => assert_eq!(fault, "UPnPError"),
+    other => panic!("Expected SoapFault, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_set_group_volume_reads_then_sets_each_member() {
+  let server = MockSoapServer::start(
+    200,
+    "<s:Envelope><s:Body><u:GetVolumeResponse><CurrentVolume>20</CurrentVolume></u:GetVolumeResponse></s:Body></s:Envelope>",
+  );
+  let location = format!("http://127.0.0.1:{}/xml/device_description.xml", server.port);
+  let group = Group::new(
+    GroupId::new("RINCON_000E58A0123401400:1"),
+    SpeakerId::new("RINCON_000E58A0123401400"),
+    vec![
+      SpeakerRef::new(SpeakerId::new("RINCON_000E58A0123401400"), vec![]).with_location(location.clone()),
+      SpeakerRef::new(SpeakerId::new("RINCON_000E58A0567801400"), vec![]).with_location(location),
+    ],
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  let results = controller.set_group_volume(&group, 40).unwrap();
+  assert_eq!(results.len(), 2);
+  for (_, result) in &results {
+    assert_eq!(*result.as_ref().unwrap(), 40);
+  }
+
+  let requests = server.requests();
+  assert_eq!(requests.len(), 4);
+  assert!(requests.iter().all(|r| r.path == "/MediaRenderer/RenderingControl/Control"));
+  assert!(requests[0].headers["soapaction"].ends_with("#GetVolume\""));
+  assert!(requests[2].headers["soapaction"].ends_with("#SetVolume\""));
+  assert!(requests[2].body.contains("<DesiredVolume>40</DesiredVolume>"));
+}
+
+#[test]
+fn test_join_group_sets_rincon_uri() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller
+    .join_group(&SpeakerId::new("uuid:RINCON_5CAAFDAE58BD01400::1"))
+    .unwrap();
+
+  let requests = server.requests();
+  assert_eq!(requests.len(), 1);
+  assert_eq!(requests[0].path, "/MediaRenderer/AVTransport/Control");
+  assert_eq!(
+    requests[0].headers["soapaction"],
+    format!("\"{}#SetAVTransportURI\"", AV_TRANSPORT_URN)
+  );
+  assert!(requests[0]
+    .body
+    .contains("<CurrentURI>x-rincon:RINCON_5CAAFDAE58BD01400</CurrentURI>"));
+  assert!(requests[0].body.contains("<CurrentURIMetaData></CurrentURIMetaData>"));
+}
+
+#[test]
+fn test_transfer_coordinator_delegates_to_group_member() {
+  let server = MockSoapServer::start(200, include_str!("fixtures/get_zone_group_state_grouped_response.xml"));
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.transfer_coordinator(&SpeakerId::new("uuid:RINCON_B8E9375E0A1201400::1")).unwrap();
+
+  let requests = server.requests();
+  assert_eq!(requests.len(), 2);
+  assert_eq!(
+    requests[1].headers["soapaction"],
+    format!("\"{}#DelegateGroupCoordinationTo\"", AV_TRANSPORT_URN)
+  );
+  assert!(requests[1].body.contains("<NewCoordinator>RINCON_B8E9375E0A1201400</NewCoordinator>"));
+  assert!(requests[1].body.contains("<RejoinGroup>1</RejoinGroup>"));
+}
+
+#[test]
+fn test_transfer_coordinator_rejects_speaker_outside_group() {
+  let server = MockSoapServer::start(200, include_str!("fixtures/get_zone_group_state_grouped_response.xml"));
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert!(matches!(
+    controller.transfer_coordinator(&SpeakerId::new("RINCON_347E5C0F2D3A01400")),
+    Err(SonosError::InvalidArgument(_))
+  ));
+  assert!(matches!(
+    controller.transfer_coordinator(&SpeakerId::new("RINCON_UNKNOWN")),
+    Err(SonosError::InvalidArgument(_))
+  ));
+
+  // Only the topology lookups reached the speaker
+  let requests = server.requests();
+  assert_eq!(requests.len(), 2);
+  assert!(requests.iter().all(|request| request.path == "/ZoneGroupTopology/Control"));
+}
+
+#[test]
+fn test_leave_group_becomes_standalone_coordinator() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.leave_group().unwrap();
+
+  let requests = server.requests();
+  assert_eq!(
+    requests[0].headers["soapaction"],
+    format!("\"{}#BecomeCoordinatorOfStandaloneGroup\"", AV_TRANSPORT_URN)
+  );
+  assert!(requests[0].body.contains("<InstanceID>0</InstanceID>"));
+}
+
+#[test]
+fn test_join_group_surfaces_http_failure() {
+  let server = MockSoapServer::start(500, "Internal Server Error");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  let result = controller.join_group(&SpeakerId::new("RINCON_5CAAFDAE58BD01400"));
+  assert!(matches!(result, Err(SonosError::CommunicationError(_))));
+}
+
+#[test]
+fn test_seek_time_sends_rel_time_target() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.seek_time(Duration::from_secs(3725)).unwrap();
+
+  let requests = server.requests();
+  assert_eq!(requests.len(), 1);
+  assert_eq!(requests[0].headers["soapaction"], format!("\"{}#Seek\"", AV_TRANSPORT_URN));
+  assert!(requests[0].body.contains("<InstanceID>0</InstanceID>"));
+  assert!(requests[0].body.contains("<Unit>REL_TIME</Unit>"));
+  assert!(requests[0].body.contains("<Target>1:02:05</Target>"));
+}
+
+#[test]
+fn test_seek_track_sends_track_nr_target() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.seek_track(12).unwrap();
+
+  let requests = server.requests();
+  assert!(requests[0].body.contains("<Unit>TRACK_NR</Unit>"));
+  assert!(requests[0].body.contains("<Target>12</Target>"));
+}
+
+#[test]
+fn test_seek_track_rejects_zero_without_a_request() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert!(matches!(controller.seek_track(0), Err(SonosError::InvalidArgument(_))));
+  assert!(server.requests().is_empty());
+}
+
+#[test]
+fn test_seek_rejected_by_device_is_an_error() {
+  // 711 is the UPnP "Illegal seek target" error
+  let server = MockSoapServer::start(
+    500,
+    "<s:Envelope><s:Body><s:Fault><faultcode>s:Client</faultcode><faultstring>UPnPError</faultstring><detail><UPnPError><errorCode>711</errorCode></UPnPError></detail></s:Fault></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  let result = controller.seek_time(Duration::from_secs(36_000));
+  assert!(matches!(result, Err(SonosError::UpnpFault { code: 711, .. })));
+}
+
+#[test]
+fn test_set_play_mode_sends_sonos_mode_string() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.set_play_mode(PlayMode::ShuffleNoRepeat).unwrap();
+
+  let requests = server.requests();
+  assert_eq!(requests[0].headers["soapaction"], format!("\"{}#SetPlayMode\"", AV_TRANSPORT_URN));
+  assert!(requests[0].body.contains("<NewPlayMode>SHUFFLE_NOREPEAT</NewPlayMode>"));
+}
+
+#[test]
+fn test_set_crossfade_sends_flag() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.set_crossfade(true).unwrap();
+  controller.set_crossfade(false).unwrap();
+
+  let requests = server.requests();
+  assert!(requests[0].headers["soapaction"].ends_with("#SetCrossfadeMode\""));
+  assert!(requests[0].body.contains("<CrossfadeMode>1</CrossfadeMode>"));
+  assert!(requests[1].body.contains("<CrossfadeMode>0</CrossfadeMode>"));
+}
+
+#[test]
+fn test_get_crossfade_reads_crossfade_mode() {
+  let server = MockSoapServer::start(
+    200,
+    "<s:Envelope><s:Body><u:GetCrossfadeModeResponse><CrossfadeMode>1</CrossfadeMode></u:GetCrossfadeModeResponse></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert!(controller.get_crossfade().unwrap());
+  assert!(server.requests()[0].headers["soapaction"].ends_with("#GetCrossfadeMode\""));
+}
+
+#[test]
+fn test_get_mute_reads_current_mute() {
+  let server = MockSoapServer::start(
+    200,
+    "<s:Envelope><s:Body><u:GetMuteResponse><CurrentMute>1</CurrentMute></u:GetMuteResponse></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert!(controller.get_mute().unwrap());
+
+  let requests = server.requests();
+  assert_eq!(requests[0].path, "/MediaRenderer/RenderingControl/Control");
+  assert!(requests[0].headers["soapaction"].ends_with("#GetMute\""));
+  assert!(requests[0].body.contains("<Channel>Master</Channel>"));
+}
+
+#[test]
+fn test_set_mute_sends_desired_mute() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.set_mute(true).unwrap();
+  controller.set_mute(false).unwrap();
+
+  let requests = server.requests();
+  assert!(requests[0].headers["soapaction"].ends_with("#SetMute\""));
+  assert!(requests[0].body.contains("<Channel>Master</Channel>"));
+  assert!(requests[0].body.contains("<DesiredMute>1</DesiredMute>"));
+  assert!(requests[1].body.contains("<DesiredMute>0</DesiredMute>"));
+}
+
+#[test]
+fn test_requests_carry_default_user_agent() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.play().unwrap();
+
+  let requests = server.requests();
+  assert_eq!(requests[0].headers["user-agent"], sonos::transport::DEFAULT_USER_AGENT);
+  assert!(requests[0].headers["user-agent"].starts_with("sonos-rs/"));
+}
+
+#[test]
+fn test_requests_carry_custom_user_agent_and_headers() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker())
+    .unwrap()
+    .with_http_headers("home-dashboard/2.1", &[("X-Request-Source".to_string(), "kitchen-panel".to_string())])
+    .unwrap();
+
+  controller.play().unwrap();
+
+  let requests = server.requests();
+  assert_eq!(requests[0].headers["user-agent"], "home-dashboard/2.1");
+  assert_eq!(requests[0].headers["x-request-source"], "kitchen-panel");
+}
+
+#[test]
+fn test_invalid_header_is_rejected() {
+  let speaker = MockSoapServer::start(200, "").speaker();
+  let result = SpeakerController::new(&speaker)
+    .unwrap()
+    .with_http_headers("sonos-rs", &[("Bad Header".to_string(), "value".to_string())]);
+
+  assert!(matches!(result, Err(SonosError::InvalidArgument(_))));
+}
+
+#[test]
+fn test_get_volume_reads_current_volume() {
+  let server = MockSoapServer::start(
+    200,
+    "<s:Envelope><s:Body><u:GetVolumeResponse><CurrentVolume>42</CurrentVolume></u:GetVolumeResponse></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert_eq!(controller.get_volume().unwrap(), 42);
+
+  let requests = server.requests();
+  assert_eq!(requests[0].path, "/MediaRenderer/RenderingControl/Control");
+  assert!(requests[0].headers["soapaction"].ends_with("#GetVolume\""));
+  assert!(requests[0].body.contains("<Channel>Master</Channel>"));
+}
+
+#[test]
+fn test_set_volume_clamps_to_100() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.set_volume(42).unwrap();
+  controller.set_volume(150).unwrap();
+
+  let requests = server.requests();
+  assert!(requests.iter().all(|r| r.headers["soapaction"].ends_with("#SetVolume\"")));
+  assert!(requests[0].body.contains("<DesiredVolume>42</DesiredVolume>"));
+  assert!(requests[1].body.contains("<DesiredVolume>100</DesiredVolume>"));
+}
+
+#[test]
+fn test_adjust_volume_sends_signed_adjustment() {
+  let server = MockSoapServer::start(
+    200,
+    "<s:Envelope><s:Body><u:SetRelativeVolumeResponse><NewVolume>37</NewVolume></u:SetRelativeVolumeResponse></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert_eq!(controller.adjust_volume(-5).unwrap(), 37);
+  controller.adjust_volume(3).unwrap();
+
+  let requests = server.requests();
+  assert!(requests[0].headers["soapaction"].ends_with("#SetRelativeVolume\""));
+  assert!(requests[0].body.contains("<Channel>Master</Channel>"));
+  assert!(requests[0].body.contains("<Adjustment>-5</Adjustment>"));
+  assert!(requests[1].body.contains("<Adjustment>3</Adjustment>"));
+}
+
+#[test]
+fn test_adjust_volume_surfaces_device_errors() {
+  let server = MockSoapServer::start(
+    500,
+    "<s:Envelope><s:Body><s:Fault><faultcode>s:Client</faultcode><faultstring>UPnPError</faultstring><detail><UPnPError><errorCode>402</errorCode></UPnPError></detail></s:Fault></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert!(matches!(controller.adjust_volume(10), Err(SonosError::UpnpFault { code: 402, .. })));
+}
+
+#[test]
+fn test_set_bass_and_treble_send_desired_level() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.set_bass(-4).unwrap();
+  controller.set_treble(10).unwrap();
+
+  let requests = server.requests();
+  assert_eq!(requests.len(), 2);
+  assert!(requests.iter().all(|r| r.path == "/MediaRenderer/RenderingControl/Control"));
+  assert!(requests[0].headers["soapaction"].ends_with("#SetBass\""));
+  assert!(requests[0].body.contains("<DesiredBass>-4</DesiredBass>"));
+  assert!(requests[1].headers["soapaction"].ends_with("#SetTreble\""));
+  assert!(requests[1].body.contains("<DesiredTreble>10</DesiredTreble>"));
+}
+
+#[test]
+fn test_eq_levels_out_of_range_are_rejected_without_a_request() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert!(matches!(controller.set_bass(11), Err(SonosError::InvalidArgument(_))));
+  assert!(matches!(controller.set_treble(-11), Err(SonosError::InvalidArgument(_))));
+  assert!(server.requests().is_empty());
+}
+
+#[test]
+fn test_set_loudness_sends_master_channel_flag() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.set_loudness(true).unwrap();
+  controller.set_loudness(false).unwrap();
+
+  let requests = server.requests();
+  assert!(requests[0].headers["soapaction"].ends_with("#SetLoudness\""));
+  assert!(requests[0].body.contains("<Channel>Master</Channel>"));
+  assert!(requests[0].body.contains("<DesiredLoudness>1</DesiredLoudness>"));
+  assert!(requests[1].body.contains("<DesiredLoudness>0</DesiredLoudness>"));
+}
+
+#[test]
+fn test_set_night_mode_and_dialog_enhancement_use_set_eq() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.set_night_mode(true).unwrap();
+  controller.set_dialog_enhancement(false).unwrap();
+
+  let requests = server.requests();
+  assert!(requests.iter().all(|r| r.headers["soapaction"].ends_with("#SetEQ\"")));
+  assert!(requests[0].body.contains("<EQType>NightMode</EQType>"));
+  assert!(requests[0].body.contains("<DesiredValue>1</DesiredValue>"));
+  assert!(requests[1].body.contains("<EQType>DialogLevel</EQType>"));
+  assert!(requests[1].body.contains("<DesiredValue>0</DesiredValue>"));
+}
+
+#[test]
+fn test_set_night_mode_invalid_eq_type_is_unsupported() {
+  // Speakers without a home theater EQ reject the EQType with UPnP error 402
+  let server = MockSoapServer::start(
+    500,
+    "<s:Envelope><s:Body><s:Fault><faultcode>s:Client</faultcode><faultstring>UPnPError</faultstring><detail><UPnPError><errorCode>402</errorCode></UPnPError></detail></s:Fault></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert!(matches!(controller.set_night_mode(true), Err(SonosError::Unsupported(_))));
+  assert!(matches!(controller.set_dialog_enhancement(true), Err(SonosError::Unsupported(_))));
+}
+
+#[test]
+fn test_set_balance_maps_to_lf_and_rf_volume() {
+  let server = MockSoapServer::start(200, include_str!("fixtures/get_zone_group_state_stereo_pair_response.xml"));
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  for (balance, left, right) in [(-100, 100, 0), (-50, 100, 50), (0, 100, 100), (30, 70, 100), (100, 0, 100)] {
+    controller.set_balance(balance).unwrap();
+
+    let requests = server.requests();
+    let set_volume: Vec<_> = requests[requests.len() - 2..].iter().collect();
+    assert!(set_volume.iter().all(|r| r.headers["soapaction"].ends_with("#SetVolume\"")));
+    assert!(set_volume[0].body.contains("<Channel>LF</Channel>"));
+    assert!(set_volume[0].body.contains(&format!("<DesiredVolume>{}</DesiredVolume>", left)));
+    assert!(set_volume[1].body.contains("<Channel>RF</Channel>"));
+    assert!(set_volume[1].body.contains(&format!("<DesiredVolume>{}</DesiredVolume>", right)));
+  }
+  assert!(server.requests()[0].headers["soapaction"].ends_with("#GetZoneGroupState\""));
+}
+
+#[test]
+fn test_set_balance_single_speaker_is_unsupported() {
+  let server = MockSoapServer::start(200, include_str!("fixtures/get_zone_group_state_grouped_response.xml"));
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert!(matches!(controller.set_balance(20), Err(SonosError::Unsupported(_))));
+  assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn test_set_balance_rejects_out_of_range() {
+  let server = MockSoapServer::start(200, include_str!("fixtures/get_zone_group_state_stereo_pair_response.xml"));
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert!(matches!(controller.set_balance(101), Err(SonosError::InvalidArgument(_))));
+  assert!(matches!(controller.set_balance(-101), Err(SonosError::InvalidArgument(_))));
+  assert!(server.requests().is_empty());
+}
+
+#[test]
+fn test_set_sub_gain_and_enabled_use_set_eq() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.set_sub_gain(-15).unwrap();
+  controller.set_sub_gain(15).unwrap();
+  controller.set_sub_enabled(false).unwrap();
+
+  let requests = server.requests();
+  assert!(requests.iter().all(|r| r.headers["soapaction"].ends_with("#SetEQ\"")));
+  assert!(requests[0].body.contains("<EQType>SubGain</EQType>"));
+  assert!(requests[0].body.contains("<DesiredValue>-15</DesiredValue>"));
+  assert!(requests[1].body.contains("<DesiredValue>15</DesiredValue>"));
+  assert!(requests[2].body.contains("<EQType>SubEnable</EQType>"));
+  assert!(requests[2].body.contains("<DesiredValue>0</DesiredValue>"));
+}
+
+#[test]
+fn test_set_sub_gain_rejects_out_of_range() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert!(matches!(controller.set_sub_gain(16), Err(SonosError::InvalidArgument(_))));
+  assert!(matches!(controller.set_sub_gain(-16), Err(SonosError::InvalidArgument(_))));
+  assert!(server.requests().is_empty());
+}
+
+#[test]
+fn test_set_sub_without_sub_is_unsupported() {
+  let server = MockSoapServer::start(
+    500,
+    "<s:Envelope><s:Body><s:Fault><faultcode>s:Client</faultcode><faultstring>UPnPError</faultstring><detail><UPnPError><errorCode>402</errorCode></UPnPError></detail></s:Fault></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert!(matches!(controller.set_sub_gain(3), Err(SonosError::Unsupported(_))));
+  assert!(matches!(controller.set_sub_enabled(true), Err(SonosError::Unsupported(_))));
+}
+
+#[test]
+fn test_set_eq_other_faults_are_not_unsupported() {
+  let server = MockSoapServer::start(
+    500,
+    "<s:Envelope><s:Body><s:Fault><faultcode>s:Client</faultcode><faultstring>UPnPError</faultstring><detail><UPnPError><errorCode>501</errorCode></UPnPError></detail></s:Fault></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  match controller.set_night_mode(true) {
+    Err(SonosError::UpnpFault { code, description }) => {
+      assert_eq!(code, 501);
+      assert_eq!(description, "Action failed");
+    }
+    other => panic!("Expected UpnpFault, got {:?}", other),
+  }
+}
+
+#[test]
+fn test_set_sleep_timer_sends_duration_or_clears_it() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.set_sleep_timer(Some(Duration::from_secs(45 * 60))).unwrap();
+  controller.set_sleep_timer(None).unwrap();
+
+  let requests = server.requests();
+  assert_eq!(requests[0].headers["soapaction"], format!("\"{}#ConfigureSleepTimer\"", AV_TRANSPORT_URN));
+  assert!(requests[0].body.contains("<NewSleepTimerDuration>0:45:00</NewSleepTimerDuration>"));
+  assert!(requests[1].body.contains("<NewSleepTimerDuration></NewSleepTimerDuration>"));
+}
+
+#[test]
+fn test_set_sleep_timer_rejects_more_than_a_day() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  let result = controller.set_sleep_timer(Some(Duration::from_secs(24 * 60 * 60 + 1)));
+  assert!(matches!(result, Err(SonosError::InvalidArgument(_))));
+  assert!(server.requests().is_empty());
+}
+
+#[test]
+fn test_get_sleep_timer_reads_remaining_duration() {
+  let server = MockSoapServer::start(
+    200,
+    "<s:Envelope><s:Body><u:GetRemainingSleepTimerDurationResponse><RemainingSleepTimerDuration>0:12:30</RemainingSleepTimerDuration><CurrentSleepTimerGeneration>3</CurrentSleepTimerGeneration></u:GetRemainingSleepTimerDurationResponse></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert_eq!(controller.get_sleep_timer().unwrap(), Some(Duration::from_secs(750)));
+  assert!(server.requests()[0].headers["soapaction"].ends_with("#GetRemainingSleepTimerDuration\""));
+}
+
+#[test]
+fn test_get_sleep_timer_empty_means_no_timer() {
+  let server = MockSoapServer::start(
+    200,
+    "<s:Envelope><s:Body><u:GetRemainingSleepTimerDurationResponse><RemainingSleepTimerDuration></RemainingSleepTimerDuration><CurrentSleepTimerGeneration>0</CurrentSleepTimerGeneration></u:GetRemainingSleepTimerDurationResponse></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert_eq!(controller.get_sleep_timer().unwrap(), None);
+}
+
+#[test]
+fn test_position_info_reads_rel_time_and_duration() {
+  let server = MockSoapServer::start(
+    200,
+    "<s:Envelope><s:Body><u:GetPositionInfoResponse><Track>3</Track><TrackDuration>0:04:12</TrackDuration><RelTime>0:00:42</RelTime></u:GetPositionInfoResponse></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  let (position, duration) = controller.position_info().unwrap();
+  assert_eq!(position, Duration::from_secs(42));
+  assert_eq!(duration, Duration::from_secs(252));
+  assert_eq!(server.requests()[0].headers["soapaction"], format!("\"{}#GetPositionInfo\"", AV_TRANSPORT_URN));
+}
+
+#[test]
+fn test_get_queue_browses_q0_without_instance_id() {
+  let server = MockSoapServer::start(200, include_str!("fixtures/queue_browse_response.xml"));
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  let queue = controller.get_queue().unwrap();
+  assert_eq!(queue.len(), 3);
+  assert_eq!(queue[1].artist.as_deref(), Some("Simon & Garfunkel"));
+  assert_eq!(queue[2].album, None);
+
+  let requests = server.requests();
+  assert_eq!(requests.len(), 1);
+  assert_eq!(requests[0].path, "/MediaServer/ContentDirectory/Control");
+  assert_eq!(
+    requests[0].headers["soapaction"],
+    "\"urn:schemas-upnp-org:service:ContentDirectory:1#Browse\""
+  );
+  assert!(requests[0].body.contains("<ObjectID>Q:0</ObjectID>"));
+  assert!(requests[0].body.contains("<BrowseFlag>BrowseDirectChildren</BrowseFlag>"));
+  assert!(requests[0].body.contains("<StartingIndex>0</StartingIndex>"));
+  assert!(!requests[0].body.contains("InstanceID"));
+}
+
+#[test]
+fn test_get_queue_pages_until_total_matches() {
+  let server = MockSoapServer::start(
+    200,
+    "<s:Envelope><s:Body><u:BrowseResponse><Result>&lt;DIDL-Lite&gt;&lt;item id=&quot;Q:0/1&quot; parentID=&quot;Q:0&quot; restricted=&quot;true&quot;&gt;&lt;res duration=&quot;0:03:00&quot;&gt;http://example.com/song.mp3&lt;/res&gt;&lt;dc:title&gt;Song&lt;/dc:title&gt;&lt;upnp:class&gt;object.item.audioItem.musicTrack&lt;/upnp:class&gt;&lt;/item&gt;&lt;/DIDL-Lite&gt;</Result><NumberReturned>1</NumberReturned><TotalMatches>3</TotalMatches><UpdateID>1</UpdateID></u:BrowseResponse></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  let queue = controller.get_queue().unwrap();
+  assert_eq!(queue.len(), 3);
+
+  let starting_indexes: Vec<bool> = server
+    .requests()
+    .iter()
+    .enumerate()
+    .map(|(i, r)| r.body.contains(&format!("<StartingIndex>{}</StartingIndex>", i)))
+    .collect();
+  assert_eq!(starting_indexes, vec![true, true, true]);
+}
+
+#[test]
+fn test_get_queue_stops_when_a_page_is_empty() {
+  let server = MockSoapServer::start(
+    200,
+    "<s:Envelope><s:Body><u:BrowseResponse><Result>&lt;DIDL-Lite&gt;&lt;/DIDL-Lite&gt;</Result><NumberReturned>0</NumberReturned><TotalMatches>5</TotalMatches><UpdateID>1</UpdateID></u:BrowseResponse></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert!(controller.get_queue().unwrap().is_empty());
+  assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn test_get_favorites_browses_fv2() {
+  let server = MockSoapServer::start(200, include_str!("fixtures/favorites_browse_response.xml"));
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  let favorites = controller.get_favorites().unwrap();
+  assert_eq!(favorites.len(), 2);
+  assert_eq!(favorites[0].title, "BBC Radio 6 Music");
+  assert!(favorites[1].is_container());
+
+  let requests = server.requests();
+  assert_eq!(requests.len(), 1);
+  assert!(requests[0].body.contains("<ObjectID>FV:2</ObjectID>"));
+  assert!(!requests[0].body.contains("InstanceID"));
+}
+
+fn favorite(uri: &str, class: &str) -> Favorite {
+  Favorite {
+    id: "FV:2/1".to_string(),
+    title: "Favorite".to_string(),
+    uri: uri.to_string(),
+    metadata: r#"<DIDL-Lite><item id="F1"><dc:title>Favorite</dc:title></item></DIDL-Lite>"#.to_string(),
+    class: Some(class.to_string()),
+    album_art_uri: None,
+    description: None,
+  }
+}
+
+#[test]
+fn test_play_favorite_station_sets_uri_with_metadata_then_plays() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller
+    .play_favorite(&favorite("x-sonosapi-stream:s44491?sid=254", "object.item.audioItem.audioBroadcast"))
+    .unwrap();
+
+  let requests = server.requests();
+  let actions: Vec<&str> = requests.iter().map(|r| r.headers["soapaction"].as_str()).collect();
+  assert_eq!(
+    actions,
+    vec![
+      format!("\"{}#SetAVTransportURI\"", AV_TRANSPORT_URN),
+      format!("\"{}#Play\"", AV_TRANSPORT_URN),
+    ]
+  );
+  assert!(requests[0].body.contains("<CurrentURI>x-sonosapi-stream:s44491?sid=254</CurrentURI>"));
+  assert!(requests[0].body.contains("&lt;dc:title&gt;Favorite&lt;/dc:title&gt;"));
+}
+
+#[test]
+fn test_play_favorite_playlist_replaces_queue_and_plays_first_track() {
+  let server = MockSoapServer::start(
+    200,
+    "<s:Envelope><s:Body><u:AddURIToQueueResponse><NewQueueLength>20</NewQueueLength></u:AddURIToQueueResponse></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller
+    .play_favorite(&favorite("x-rincon-cpcontainer:1006206cspotify%3aplaylist%3a1?sid=12", "object.container.playlistContainer"))
+    .unwrap();
+
+  let requests = server.requests();
+  let actions: Vec<String> = requests
+    .iter()
+    .map(|r| r.headers["soapaction"].trim_matches('"').trim_start_matches(AV_TRANSPORT_URN).to_string())
+    .collect();
+  assert_eq!(actions, vec!["#RemoveAllTracksFromQueue", "#AddURIToQueue", "#SetAVTransportURI", "#Seek", "#Play"]);
+  assert!(requests[1]
+    .body
+    .contains("<EnqueuedURI>x-rincon-cpcontainer:1006206cspotify%3aplaylist%3a1?sid=12</EnqueuedURI>"));
+  assert!(requests[2]
+    .body
+    .contains("<CurrentURI>x-rincon-queue:RINCON_000E58A0123401400#0</CurrentURI>"));
+  assert!(requests[3].body.contains("<Target>1</Target>"));
+}
+
+#[test]
+fn test_get_playlists_browses_sq() {
+  let server = MockSoapServer::start(200, include_str!("fixtures/playlists_browse_response.xml"));
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  let playlists = controller.get_playlists().unwrap();
+  let titles: Vec<&str> = playlists.iter().map(|p| p.title.as_str()).collect();
+  assert_eq!(titles, vec!["Dinner Party", "Rock & Roll"]);
+
+  let requests = server.requests();
+  assert_eq!(requests.len(), 1);
+  assert!(requests[0].body.contains("<ObjectID>SQ:</ObjectID>"));
+}
+
+#[test]
+fn test_play_playlist_replaces_queue_and_plays_first_track() {
+  let server = MockSoapServer::start(
+    200,
+    "<s:Envelope><s:Body><u:AddURIToQueueResponse><NewQueueLength>12</NewQueueLength></u:AddURIToQueueResponse></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+  let playlist = Playlist {
+    id: "SQ:3".to_string(),
+    title: "Dinner Party".to_string(),
+    uri: "file:///jffs/settings/savedqueues.rsq#3".to_string(),
+    album_art_uri: None,
+  };
+
+  controller.play_playlist(&playlist).unwrap();
+
+  let requests = server.requests();
+  let actions: Vec<String> = requests
+    .iter()
+    .map(|r| r.headers["soapaction"].trim_matches('"').trim_start_matches(AV_TRANSPORT_URN).to_string())
+    .collect();
+  assert_eq!(actions, vec!["#RemoveAllTracksFromQueue", "#AddURIToQueue", "#SetAVTransportURI", "#Seek", "#Play"]);
+  assert!(requests[1]
+    .body
+    .contains("<EnqueuedURI>file:///jffs/settings/savedqueues.rsq#3</EnqueuedURI>"));
+  assert!(requests[3].body.contains("<Unit>TRACK_NR</Unit>"));
+  assert!(requests[3].body.contains("<Target>1</Target>"));
+}
+
+#[test]
+fn test_add_uri_to_queue_at_position_enqueues_as_next() {
+  let server = MockSoapServer::start(
+    200,
+    "<s:Envelope><s:Body><u:AddURIToQueueResponse><FirstTrackNumberEnqueued>2</FirstTrackNumberEnqueued><NumTracksAdded>1</NumTracksAdded><NewQueueLength>5</NewQueueLength></u:AddURIToQueueResponse></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  let length = controller
+    .add_uri_to_queue("http://example.com/a.mp3?x=1&y=2", "<DIDL-Lite/>", Some(2))
+    .unwrap();
+  assert_eq!(length, 5);
+
+  let requests = server.requests();
+  assert_eq!(requests[0].headers["soapaction"], format!("\"{}#AddURIToQueue\"", AV_TRANSPORT_URN));
+  let body = &requests[0].body;
+  assert!(body.contains("<EnqueuedURI>http://example.com/a.mp3?x=1&amp;y=2</EnqueuedURI>"));
+  assert!(body.contains("<EnqueuedURIMetaData>&lt;DIDL-Lite/&gt;</EnqueuedURIMetaData>"));
+  assert!(body.contains("<DesiredFirstTrackNumberEnqueued>2</DesiredFirstTrackNumberEnqueued>"));
+  assert!(body.contains("<EnqueueAsNext>1</EnqueueAsNext>"));
+}
+
+#[test]
+fn test_add_uri_to_queue_position_zero_appends() {
+  let server = MockSoapServer::start(
+    200,
+    "<s:Envelope><s:Body><u:AddURIToQueueResponse><NewQueueLength>8</NewQueueLength></u:AddURIToQueueResponse></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert_eq!(controller.add_uri_to_queue("http://example.com/a.mp3", "", Some(0)).unwrap(), 8);
+  assert_eq!(controller.add_uri_to_queue("http://example.com/a.mp3", "", None).unwrap(), 8);
+
+  for request in server.requests() {
+    assert!(request.body.contains("<DesiredFirstTrackNumberEnqueued>0</DesiredFirstTrackNumberEnqueued>"));
+    assert!(request.body.contains("<EnqueueAsNext>0</EnqueueAsNext>"));
+  }
+}
+
+#[test]
+fn test_clear_queue_removes_all_tracks() {
+  let server = MockSoapServer::start(
+    200,
+    "<s:Envelope><s:Body><u:RemoveAllTracksFromQueueResponse/></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.clear_queue().unwrap();
+
+  let requests = server.requests();
+  assert_eq!(requests.len(), 1);
+  assert_eq!(
+    requests[0].headers["soapaction"],
+    format!("\"{}#RemoveAllTracksFromQueue\"", AV_TRANSPORT_URN)
+  );
+  assert!(requests[0].body.contains("<InstanceID>0</InstanceID>"));
+}
+
+#[test]
+fn test_set_uri_sends_uri_and_escaped_metadata() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller
+    .set_uri(
+      "x-rincon-mp3radio://radio.example.com/live.mp3?a=1&b=2",
+      Some(r#"<DIDL-Lite><item id="R:0"><dc:title>Rock & Roll <Live></dc:title></item></DIDL-Lite>"#),
+    )
+    .unwrap();
+
+  let requests = server.requests();
+  assert_eq!(requests.len(), 1);
+  assert_eq!(
+    requests[0].headers["soapaction"],
+    format!("\"{}#SetAVTransportURI\"", AV_TRANSPORT_URN)
+  );
+  let body = &requests[0].body;
+  assert!(body.contains(
+    "<CurrentURI>x-rincon-mp3radio://radio.example.com/live.mp3?a=1&amp;b=2</CurrentURI>"
+  ));
+  assert!(body.contains(
+    "<CurrentURIMetaData>&lt;DIDL-Lite&gt;&lt;item id=&quot;R:0&quot;&gt;&lt;dc:title&gt;Rock &amp; Roll &lt;Live&gt;&lt;/dc:title&gt;&lt;/item&gt;&lt;/DIDL-Lite&gt;</CurrentURIMetaData>"
+  ));
+  // The metadata must not leak raw markup into the envelope
+  assert!(!body.contains("<DIDL-Lite>"));
+  assert!(!body.contains("Rock & Roll"));
+}
+
+#[test]
+fn test_set_uri_without_metadata_sends_empty_metadata() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller.set_uri("http://example.com/song.mp3", None).unwrap();
+  controller.play().unwrap();
+
+  let requests = server.requests();
+  assert_eq!(requests.len(), 2);
+  assert!(requests[0].body.contains("<CurrentURI>http://example.com/song.mp3</CurrentURI>"));
+  assert!(requests[0].body.contains("<CurrentURIMetaData></CurrentURIMetaData>"));
+  assert_eq!(requests[1].headers["soapaction"], format!("\"{}#Play\"", AV_TRANSPORT_URN));
+}
+
+#[test]
+fn test_play_line_in_sets_rincon_stream_uri_then_plays() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  controller
+    .play_line_in(&SpeakerId::new("uuid:RINCON_B8E9378C5F1E01400::1"))
+    .unwrap();
+
+  let requests = server.requests();
+  assert_eq!(requests.len(), 2);
+  assert_eq!(
+    requests[0].headers["soapaction"],
+    format!("\"{}#SetAVTransportURI\"", AV_TRANSPORT_URN)
+  );
+  assert!(requests[0]
+    .body
+    .contains("<CurrentURI>x-rincon-stream:RINCON_B8E9378C5F1E01400</CurrentURI>"));
+  assert_eq!(requests[1].headers["soapaction"], format!("\"{}#Play\"", AV_TRANSPORT_URN));
+}
+
+#[test]
+fn test_play_own_line_in_requires_line_in_model() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let one = server.speaker();
+  let controller = SpeakerController::new(&one).unwrap();
+
+  let result = controller.play_line_in(&one.id);
+  assert!(matches!(result, Err(SonosError::Unsupported(_))));
+  assert!(server.requests().is_empty());
+
+  let five = Speaker { model_name: "Sonos Play:5".to_string(), ..server.speaker() };
+  SpeakerController::new(&five).unwrap().play_line_in(&five.id).unwrap();
+  assert!(server.requests()[0]
+    .body
+    .contains("<CurrentURI>x-rincon-stream:RINCON_000E58A0123401400</CurrentURI>"));
+}
+
+#[test]
+fn test_play_tv_sets_htastream_uri_then_plays() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let beam = Speaker { model_name: "Sonos Beam".to_string(), ..server.speaker() };
+  let controller = SpeakerController::new(&beam).unwrap();
+
+  controller.play_tv().unwrap();
+
+  let requests = server.requests();
+  assert_eq!(requests.len(), 2);
+  assert!(requests[0]
+    .body
+    .contains("<CurrentURI>x-sonos-htastream:RINCON_000E58A0123401400:spdif</CurrentURI>"));
+  assert_eq!(requests[1].headers["soapaction"], format!("\"{}#Play\"", AV_TRANSPORT_URN));
+}
+
+#[test]
+fn test_play_tv_without_tv_input_is_unsupported() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body/></s:Envelope>");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert!(matches!(controller.play_tv(), Err(SonosError::Unsupported(_))));
+  assert!(server.requests().is_empty());
+}
+
+#[test]
+fn test_input_capabilities_follow_model() {
+  let server = MockSoapServer::start(200, "");
+  let model = |name: &str| Speaker { model_name: name.to_string(), ..server.speaker() };
+
+  assert!(model("Sonos Play:5").has_line_in());
+  assert!(model("Sonos Port").has_line_in());
+  assert!(!model("Sonos One").has_line_in());
+  assert!(model("Sonos Arc").has_tv_input());
+  assert!(model("Sonos Amp").has_tv_input());
+  assert!(model("Sonos Amp").has_line_in());
+  assert!(!model("Sonos Play:5").has_tv_input());
+}
+
+#[test]
+fn test_battery_status_reads_status_page() {
+  let server = MockSoapServer::start(200, include_str!("fixtures/battery_status.xml"));
+  let roam = Speaker { model_name: "Sonos Roam".to_string(), ..server.speaker() };
+  let controller = SpeakerController::new(&roam).unwrap();
+
+  let status = controller.battery_status().unwrap();
+  assert_eq!(status.level, 87);
+  assert_eq!(status.power_source, PowerSource::ChargingRing);
+  assert_eq!(status.health, "GREEN");
+  assert_eq!(server.requests()[0].path, "/status/batterystatus");
+}
+
+#[test]
+fn test_battery_status_non_portable_is_unsupported() {
+  let server = MockSoapServer::start(200, include_str!("fixtures/battery_status.xml"));
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert!(matches!(controller.battery_status(), Err(SonosError::Unsupported(_))));
+  assert!(server.requests().is_empty());
+}
+
+/// Serves a living room + kitchen group on `reachable_port` and an office group nobody answers for
+fn two_group_topology_server(reachable_port: u16) -> MockSoapServer {
+  let unreachable_port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+  MockSoapServer::start(
+    200,
+    zone_group_state_response(&[
+      ("RINCON_LIVING", &[("RINCON_LIVING", reachable_port), ("RINCON_KITCHEN", reachable_port)]),
+      ("RINCON_OFFICE", &[("RINCON_OFFICE", unreachable_port)]),
+    ]),
+  )
+}
+
+#[test]
+fn test_pause_all_targets_coordinators_and_reports_each_group() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body><u:PauseResponse/></s:Body></s:Envelope>");
+  let controller = SpeakerController::new(&two_group_topology_server(server.port).speaker()).unwrap();
+
+  let results = controller.pause_all().unwrap();
+
+  assert_eq!(results.len(), 2);
+  assert_eq!(results[0].0, GroupId::new("RINCON_LIVING:1"));
+  assert!(results[0].1.is_ok());
+  assert_eq!(results[1].0, GroupId::new("RINCON_OFFICE:1"));
+  assert!(matches!(results[1].1, Err(SonosError::CommunicationError(_))));
+
+  // One request for the living room coordinator, none for its member
+  let requests = server.requests();
+  assert_eq!(requests.len(), 1);
+  assert_eq!(requests[0].headers["soapaction"], format!("\"{}#Pause\"", AV_TRANSPORT_URN));
+}
+
+#[test]
+fn test_play_all_sends_play_to_coordinators() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body><u:PlayResponse/></s:Body></s:Envelope>");
+  let controller = SpeakerController::new(&two_group_topology_server(server.port).speaker()).unwrap();
+
+  let results = controller.play_all().unwrap();
+
+  assert!(results[0].1.is_ok());
+  assert!(results[1].1.is_err());
+  let requests = server.requests();
+  assert_eq!(requests.len(), 1);
+  assert!(requests[0].body.contains("<Speed>1</Speed>"));
+}
+
+#[test]
+fn test_pause_all_fails_when_topology_is_unreadable() {
+  let server = MockSoapServer::start(500, "Internal Server Error");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert!(matches!(controller.pause_all(), Err(SonosError::CommunicationError(_))));
+  assert_eq!(server.requests().len(), 1);
+  assert_eq!(server.requests()[0].path, "/ZoneGroupTopology/Control");
+}
+
+#[test]
+fn test_get_transport_state_parses_current_transport_state() {
+  let server = MockSoapServer::start(
+    200,
+    "<s:Envelope><s:Body><u:GetTransportInfoResponse><CurrentTransportState>TRANSITIONING</CurrentTransportState><CurrentTransportStatus>OK</CurrentTransportStatus><CurrentSpeed>1</CurrentSpeed></u:GetTransportInfoResponse></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert_eq!(controller.get_transport_state().unwrap(), PlaybackState::Transitioning);
+  assert_eq!(
+    server.requests()[0].headers["soapaction"],
+    format!("\"{}#GetTransportInfo\"", AV_TRANSPORT_URN)
+  );
+}
+
+#[test]
+fn test_get_transport_state_rejects_unknown_state() {
+  let server = MockSoapServer::start(
+    200,
+    "<s:Envelope><s:Body><u:GetTransportInfoResponse><CurrentTransportState>RECORDING</CurrentTransportState></u:GetTransportInfoResponse></s:Body></s:Envelope>",
+  );
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  assert!(matches!(controller.get_transport_state(), Err(SonosError::ParseError(_))));
+}
+
+#[test]
+fn test_get_topology_parses_zone_group_state() {
+  let server = MockSoapServer::start(200, include_str!("fixtures/get_zone_group_state_response.xml"));
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+
+  let topology = controller.get_topology().unwrap();
+
+  assert_eq!(topology.get_groups().len(), 3);
+  let requests = server.requests();
+  assert_eq!(requests[0].path, "/ZoneGroupTopology/Control");
+  assert_eq!(
+    requests[0].headers.get("soapaction").unwrap(),
+    "\"urn:schemas-upnp-org:service:ZoneGroupTopology:1#GetZoneGroupState\""
+  );
+}
+
+#[test]
+fn test_topology_from_speakers_skips_failing_speaker() {
+  let satellite = MockSoapServer::start(503, "Service Unavailable");
+  let player = MockSoapServer::start(200, include_str!("fixtures/get_zone_group_state_response.xml"));
+
+  let topology = Topology::from_speakers(&[satellite.speaker(), player.speaker()]).unwrap();
+
+  assert_eq!(topology.get_groups().len(), 3);
+  assert_eq!(satellite.requests().len(), 1);
+  assert_eq!(player.requests().len(), 1);
+}
+
+#[test]
+fn test_topology_from_speakers_reports_last_error() {
+  let satellite = MockSoapServer::start(503, "Service Unavailable");
+
+  assert!(matches!(
+    Topology::from_speakers(&[satellite.speaker()]),
+    Err(SonosError::SatelliteSpeaker(_))
+  ));
+  assert!(matches!(Topology::from_speakers(&[]), Err(SonosError::DeviceNotFound(_))));
+}
+
+/// The attributes every ZoneGroupMember carries besides its identity
+const MEMBER_ATTRIBUTES: &str = "Icon=\"\" Configuration=\"1\" SoftwareVersion=\"85.0-64200\" SWGen=\"2\" \
+  MinCompatibleVersion=\"84.0-00000\" LegacyCompatibleVersion=\"58.0-00000\" BootSeq=\"94\" TVConfigurationError=\"0\" \
+  HdmiCecAvailable=\"0\" WirelessMode=\"1\" WirelessLeafOnly=\"0\" ChannelFreq=\"5805\" BehindWifiExtender=\"0\" \
+  WifiEnabled=\"1\" EthLink=\"0\" Orientation=\"3\" RoomCalibrationState=\"4\" SecureRegState=\"3\" \
+  VoiceConfigState=\"2\" MicEnabled=\"0\" HeadphoneSwapActive=\"0\" AirPlayEnabled=\"1\" IdleState=\"0\" \
+  MoreInfo=\"\" SSLPort=\"1443\" HHSSLPort=\"1843\"";
+
+/// GetZoneGroupState response for `groups` of `(coordinator, [(member, port)])`
+fn zone_group_state_response(groups: &[(&str, &[(&str, u16)])]) -> &'static str {
+  let zone_groups: String = groups
+    .iter()
+    .map(|(coordinator, members)| {
+      let members: String = members
+        .iter()
+        .map(|(uuid, port)| {
+          format!(
+            "<ZoneGroupMember UUID=\"{}\" Location=\"http://127.0.0.1:{}/xml/device_description.xml\" ZoneName=\"{}\" {}/>",
+            uuid, port, uuid, MEMBER_ATTRIBUTES
+          )
+        })
+        .collect();
+      format!("<ZoneGroup Coordinator=\"{}\" ID=\"{}:1\">{}</ZoneGroup>", coordinator, coordinator, members)
+    })
+    .collect();
+  let state = format!("<ZoneGroupState><ZoneGroups>{}</ZoneGroups></ZoneGroupState>", zone_groups);
+  let escaped = state.replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;");
+
+  // The mock server wants a 'static body; leaking one string per test is fine
+  Box::leak(
+    format!(
+      "<s:Envelope><s:Body><u:GetZoneGroupStateResponse><ZoneGroupState>{}</ZoneGroupState></u:GetZoneGroupStateResponse></s:Body></s:Envelope>",
+      escaped
+    )
+    .into_boxed_str(),
+  )
+}
+
+#[test]
+fn test_set_volume_all_attempts_every_target() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body><u:SetVolumeResponse/></s:Body></s:Envelope>");
+  let unreachable_port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+  let topology_server = MockSoapServer::start(
+    200,
+    zone_group_state_response(&[
+      ("RINCON_LIVING", &[("RINCON_LIVING", server.port), ("RINCON_KITCHEN", server.port)]),
+      ("RINCON_OFFICE", &[("RINCON_OFFICE", unreachable_port)]),
+    ]),
+  );
+  let controller = SpeakerController::new(&topology_server.speaker()).unwrap();
+  let targets = vec![
+    (SpeakerId::new("RINCON_LIVING"), 10),
+    (SpeakerId::new("RINCON_KITCHEN"), 20),
+    (SpeakerId::new("RINCON_OFFICE"), 30),
+    (SpeakerId::new("RINCON_GARAGE"), 40),
+  ];
+
+  let results = controller.set_volume_all(&targets).unwrap();
+
+  let ids: Vec<&SpeakerId> = results.iter().map(|(id, _)| id).collect();
+  assert_eq!(ids, targets.iter().map(|(id, _)| id).collect::<Vec<_>>());
+  assert!(results[0].1.is_ok());
+  assert!(results[1].1.is_ok());
+  assert!(matches!(results[2].1, Err(SonosError::CommunicationError(_))));
+  assert!(matches!(results[3].1, Err(SonosError::DeviceNotFound(_))));
+
+  let mut volumes: Vec<String> = server
+    .requests()
+    .iter()
+    .map(|request| request.body.split("<DesiredVolume>").nth(1).unwrap().split('<').next().unwrap().to_string())
+    .collect();
+  volumes.sort();
+  assert_eq!(volumes, vec!["10", "20"]);
+}
+
+#[test]
+fn test_set_volume_all_returns_the_topology_error() {
+  // A satellite answers 503, which must come back as itself rather than a generic failure
+  let server = MockSoapServer::start(503, "Service Unavailable");
+  let controller = SpeakerController::new(&server.speaker()).unwrap();
+  let targets = vec![(SpeakerId::new("RINCON_LIVING"), 10), (SpeakerId::new("RINCON_KITCHEN"), 20)];
+
+  assert!(matches!(controller.set_volume_all(&targets), Err(SonosError::SatelliteSpeaker(_))));
+  assert_eq!(server.requests().len(), 1);
+  assert_eq!(server.requests()[0].path, "/ZoneGroupTopology/Control");
+}
+
+#[test]
+fn test_set_volume_all_reports_satellite_targets() {
+  let server = MockSoapServer::start(200, "<s:Envelope><s:Body><u:SetVolumeResponse/></s:Body></s:Envelope>");
+  // Bond a sub to the living room speaker
+  let satellite = format!(
+    "<Satellite UUID=\"RINCON_SUB\" Location=\"http://127.0.0.1:1/xml/device_description.xml\" \
+     ZoneName=\"Living Room\" Invisible=\"1\" HTSatChanMapSet=\"RINCON_LIVING:LF,RF;RINCON_SUB:SW\" \
+     ActiveZoneID=\"\" {}/>",
+    MEMBER_ATTRIBUTES
+  )
+  .replace('<', "&lt;")
+  .replace('>', "&gt;")
+  .replace('"', "&quot;");
+  let response = zone_group_state_response(&[("RINCON_LIVING", &[("RINCON_LIVING", server.port)])]).replacen(
+    "/&gt;",
+    &format!("&gt;{}&lt;/ZoneGroupMember&gt;", satellite),
+    1,
+  );
+  let topology_server = MockSoapServer::start(200, Box::leak(response.into_boxed_str()));
+  let controller = SpeakerController::new(&topology_server.speaker()).unwrap();
+  let targets = vec![(SpeakerId::new("RINCON_LIVING"), 10), (SpeakerId::new("RINCON_SUB"), 20)];
+
+  let results = controller.set_volume_all(&targets).unwrap();
+
+  assert!(results[0].1.is_ok());
+  assert!(matches!(results[1].1, Err(SonosError::SatelliteSpeaker(_))));
+  assert_eq!(server.requests().len(), 1);
+}
@@ -0,0 +1,84 @@
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+
+use sonos::mock::{MockSpeaker, MockSubscription};
+use sonos::watch::watch_speakers;
+use sonos::{ServiceType, SonosError, StateChange, StreamError};
+
+#[test]
+fn test_watch_speakers_rejects_empty_network() {
+    let result = watch_speakers(vec![], |_| {}, |builder| builder);
+
+    assert!(matches!(result, Err(SonosError::DeviceNotFound(_))));
+}
+
+#[test]
+fn test_watch_speakers_delivers_events_to_handler() {
+    let speaker = MockSpeaker::builder().id("uuid:RINCON_KITCHEN::1").name("Kitchen").build();
+    let template = MockSubscription::new(ServiceType::RenderingControl, speaker.id.clone())
+        .with_events(vec![StateChange::VolumeChanged { speaker_id: speaker.id.clone(), volume: 42 }]);
+    let (sender, receiver) = mpsc::channel();
+    let sender = Mutex::new(sender);
+
+    let stream = watch_speakers(
+        vec![speaker.clone()],
+        move |event| {
+            let _ = sender.lock().unwrap().send(event);
+        },
+        |builder| {
+            builder
+                .with_callback_bind_address(IpAddr::V4(Ipv4Addr::LOCALHOST))
+                .with_subscription_factory(MockSubscription::factory(template))
+        },
+    )
+    .unwrap();
+
+    let rendering_control = stream
+        .subscriptions()
+        .into_iter()
+        .find(|info| info.service_type == ServiceType::RenderingControl)
+        .unwrap();
+    let url = format!("{}/callback/{}", stream.base_callback_url().unwrap(), rendering_control.id);
+    reqwest::blocking::Client::new()
+        .request(reqwest::Method::from_bytes(b"NOTIFY").unwrap(), url)
+        .header("NT", "upnp:event")
+        .header("NTS", "upnp:propchange")
+        .header("SID", "uuid:mock-sid")
+        .header("SEQ", "0")
+        .body("<e:propertyset/>")
+        .send()
+        .unwrap();
+
+    match receiver.recv_timeout(Duration::from_secs(2)).unwrap() {
+        StateChange::VolumeChanged { speaker_id, volume } => {
+            assert_eq!(speaker_id, speaker.id);
+            assert_eq!(volume, 42);
+        }
+        other => panic!("Expected VolumeChanged, got {:?}", other),
+    }
+
+    stream.shutdown().unwrap();
+}
+
+#[test]
+fn test_watch_speakers_keeps_stream_error_when_every_speaker_fails() {
+    let speakers = vec![
+        MockSpeaker::builder().id("uuid:RINCON_KITCHEN::1").build(),
+        MockSpeaker::builder().id("uuid:RINCON_OFFICE::1").build(),
+    ];
+    let template = MockSubscription::new(ServiceType::AVTransport, speakers[0].id.clone()).failing_subscribe();
+
+    let result = watch_speakers(speakers, |_| {}, |builder| {
+        builder
+            .with_callback_bind_address(IpAddr::V4(Ipv4Addr::LOCALHOST))
+            .with_timeouts(Duration::from_secs(1800), Duration::from_millis(10))
+            .with_subscription_factory(MockSubscription::factory(template))
+    });
+
+    match result {
+        Err(SonosError::StreamFailed(StreamError::AllSpeakersFailed(failures))) => assert_eq!(failures.len(), 2),
+        Err(other) => panic!("Expected AllSpeakersFailed, got {:?}", other),
+        Ok(_) => panic!("Expected the stream to fail"),
+    }
+}
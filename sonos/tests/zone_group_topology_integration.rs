@@ -16,6 +16,9 @@ fn test_zone_group_topology_integration() -> Result<(), Box<dyn std::error::Erro
         port: 1400,
         udn: "uuid:RINCON_123456789::1".to_string(),
         satellites: vec![],
+        supported_services: vec![],
+        software_version: None,
+        hardware_version: None,
     };
 
     // Create StateCache
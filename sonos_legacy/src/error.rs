@@ -9,7 +9,8 @@ pub enum SonosError {
   NetworkTimeout,
   NetworkError(String),
   InvalidVolume(u8),
-  NotCoordinator(String)
+  NotCoordinator(String),
+  GroupJoinFailed(Vec<String>)
 }
 
 impl fmt::Display for SonosError {
@@ -22,7 +23,8 @@ impl fmt::Display for SonosError {
       SonosError::NetworkTimeout => write!(f, "Network request timed out"),
       SonosError::NetworkError(msg) => write!(f, "Network error: {}", msg),
       SonosError::InvalidVolume(volume) => write!(f, "Invalid volume level: {} (must be 0-100)", volume),
-      SonosError::NotCoordinator(ip) => write!(f, "Device ({}) is not the zone coordinator", ip)
+      SonosError::NotCoordinator(ip) => write!(f, "Device ({}) is not the zone coordinator", ip),
+      SonosError::GroupJoinFailed(speakers) => write!(f, "Failed to join speakers to the group ({})", speakers.join(", "))
     }
   }
 }
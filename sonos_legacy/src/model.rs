@@ -67,8 +67,11 @@ pub enum Action {
   Stop,
   GetVolume,
   GetGroupVolume,
+  SetRelativeGroupVolume,
   SetVolume,
   SetRelativeVolume,
+  GetMute,
+  SetMute,
   GetZoneGroupState,
   GetTransportInfo,
   SetAVTransportURI,
@@ -92,8 +95,11 @@ impl Action {
       Action::Stop => "Stop",
       Action::GetVolume => "GetVolume",
       Action::GetGroupVolume => "GetGroupVolume",
+      Action::SetRelativeGroupVolume => "SetRelativeGroupVolume",
       Action::SetVolume => "SetVolume",
       Action::SetRelativeVolume => "SetRelativeVolume",
+      Action::GetMute => "GetMute",
+      Action::SetMute => "SetMute",
       Action::GetZoneGroupState => "GetZoneGroupState",
       Action::GetTransportInfo => "GetTransportInfo",
       Action::SetAVTransportURI => "SetAVTransportURI",
@@ -114,8 +120,11 @@ impl Action {
       Action::GetVolume
       | Action::SetVolume
       | Action::SetRelativeVolume
+      | Action::GetMute
+      | Action::SetMute
       => Service::rendering_control(),
       Action::GetGroupVolume
+      | Action::SetRelativeGroupVolume
       => Service::group_rendering_control(),
       Action::GetZoneGroupState
       => Service::zone_group_topology(),
@@ -150,3 +159,4 @@ impl PlayState {
     }
   }
 }
+
@@ -130,6 +130,48 @@ impl SpeakerController {
         self.parse_element_u8(&response, "NewVolume")
     }
 
+    /// Adjust the volume of the whole group led by the coordinator at `ip`
+    ///
+    /// Every member moves by the same relative amount; returns the new group volume.
+    pub fn adjust_group_volume(&self, ip: &str, adjustment: i8) -> Result<u8, SonosError> {
+        let payload = format!(
+            "<InstanceID>0</InstanceID><Adjustment>{}</Adjustment>",
+            adjustment
+        );
+        let response = self
+            .client
+            .send_action(ip, Action::SetRelativeGroupVolume, &payload)?;
+        self.parse_element_u8(&response, "NewVolume")
+    }
+
+    /// Whether the speaker is muted
+    pub fn get_mute(&self, ip: &str) -> Result<bool, SonosError> {
+        let payload = "<InstanceID>0</InstanceID><Channel>Master</Channel>";
+        let response = self.client.send_action(ip, Action::GetMute, payload)?;
+        let muted = self.client.get_child_element_text(&response, "CurrentMute")?;
+        Ok(muted == "1")
+    }
+
+    /// Mute or unmute the speaker
+    pub fn set_mute(&self, ip: &str, muted: bool) -> Result<(), SonosError> {
+        let payload = format!(
+            "<InstanceID>0</InstanceID><Channel>Master</Channel><DesiredMute>{}</DesiredMute>",
+            if muted { 1 } else { 0 }
+        );
+        self.client.send_action(ip, Action::SetMute, &payload)?;
+        Ok(())
+    }
+
+    /// Join the speaker at `ip` to the group led by the coordinator with `coordinator_uuid`
+    pub fn join_group(&self, ip: &str, coordinator_uuid: &str) -> Result<(), SonosError> {
+        let payload = format!(
+            "<InstanceID>0</InstanceID><CurrentURI>x-rincon:{}</CurrentURI><CurrentURIMetaData></CurrentURIMetaData>",
+            coordinator_uuid
+        );
+        self.client.send_action(ip, Action::SetAVTransportURI, &payload)?;
+        Ok(())
+    }
+
     fn is_coordinator(&self, ip: &str) -> Result<bool, SonosError> {
         let payload = "<InstanceID>0</InstanceID>";
         let response = self
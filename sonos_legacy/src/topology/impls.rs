@@ -107,6 +107,64 @@ impl ZoneGroupMember {
   pub fn get_uuid(&self) -> String {
     self.uuid.clone()
   }
+
+  /// URL of the device description, e.g. `http://192.168.4.65:1400/xml/device_description.xml`
+  pub fn get_location(&self) -> &str {
+    &self.location
+  }
 }
 
 // impl Satellite {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Satellite, VanishedDevice, VanishedDevices};
+
+  fn member(uuid: &str, ip: &str, zone_name: &str) -> ZoneGroupMember {
+    ZoneGroupMember {
+      uuid: uuid.to_string(),
+      location: format!("http://{}:1400/xml/device_description.xml", ip),
+      zone_name: zone_name.to_string(),
+      software_version: "56.0-76060".to_string(),
+      configuration: "1".to_string(),
+      icon: String::new(),
+      satellites: Vec::new(),
+    }
+  }
+
+  #[test]
+  fn test_topology_serde_round_trip() {
+    let mut living_room = member("RINCON_123456", "192.168.1.100", "Living Room");
+    living_room.satellites.push(Satellite {
+      uuid: "RINCON_SAT123".to_string(),
+      location: "http://192.168.1.101:1400/xml/device_description.xml".to_string(),
+      zone_name: "Living Room".to_string(),
+      software_version: "56.0-76060".to_string(),
+    });
+    let topology = Topology {
+      zone_groups: vec![ZoneGroup {
+        coordinator: "RINCON_123456".to_string(),
+        id: "RINCON_123456:1234567890".to_string(),
+        members: vec![living_room, member("RINCON_789012", "192.168.1.102", "Kitchen")],
+      }],
+      vanished_devices: Some(VanishedDevices {
+        devices: vec![VanishedDevice {
+          uuid: "RINCON_VANISHED".to_string(),
+          zone_name: "Old Speaker".to_string(),
+          reason: "powered off".to_string(),
+        }],
+      }),
+    };
+
+    let json = serde_json::to_string(&topology).unwrap();
+    let restored: Topology = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(format!("{:?}", restored), format!("{:?}", topology));
+    assert_eq!(restored.get_groups()[0].get_coordinator().get_ip(), "192.168.1.100");
+    assert_eq!(
+      restored.get_groups()[0].get_speakers()[1].get_location(),
+      "http://192.168.1.102:1400/xml/device_description.xml"
+    );
+  }
+}
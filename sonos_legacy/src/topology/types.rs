@@ -1,5 +1,7 @@
+use serde_derive::{Deserialize, Serialize};
+
 /// Complete topology information for the Sonos system
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Topology {
     /// List of active zone groups in the system
     pub zone_groups: Vec<ZoneGroup>,
@@ -8,7 +10,7 @@ pub struct Topology {
 }
 
 /// Represents a Sonos zone group containing one or more speakers
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ZoneGroup {
     /// UUID of the coordinator speaker for this group
     pub coordinator: String,
@@ -19,7 +21,7 @@ pub struct ZoneGroup {
 }
 
 /// Represents a speaker (zone group member) in the Sonos system
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ZoneGroupMember {
     /// Unique identifier for this speaker
     pub uuid: String,
@@ -38,7 +40,7 @@ pub struct ZoneGroupMember {
 }
 
 /// Represents a satellite speaker (e.g., surround speakers in a home theater setup)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Satellite {
     /// Unique identifier for this satellite speaker
     pub uuid: String,
@@ -51,14 +53,14 @@ pub struct Satellite {
 }
 
 /// Container for speakers that are no longer available on the network
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VanishedDevices {
     /// List of devices that have disappeared from the network
     pub devices: Vec<VanishedDevice>,
 }
 
 /// Represents a speaker that was previously discovered but is no longer available
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VanishedDevice {
     /// Unique identifier for this vanished speaker
     pub uuid: String,